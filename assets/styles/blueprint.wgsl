@@ -0,0 +1,78 @@
+// Blueprint style: outlines only, drawn in a single ink color on a sparse dot grid
+// Example of the style shader contract (see "Custom ASCII Styles" in the README)
+
+struct Uniforms {
+    tex_width: u32,
+    tex_height: u32,
+    cols: u32,
+    rows: u32,
+    edge_threshold: u32,
+    exposure: f32,
+    gamma: f32,
+    _padding: f32,
+};
+
+@group(0) @binding(0)
+var direction_texture: texture_2d<f32>;  // R=direction, G=edge_flag, B=luminance, A=linear depth
+
+@group(0) @binding(1)
+var<uniform> uniforms: Uniforms;
+
+@group(0) @binding(2)
+var<storage, read_write> output: array<u32>;
+
+@group(0) @binding(3)
+var color_texture: texture_2d<f32>;
+
+const CHAR_SPACE: u32 = 0u;
+const CHAR_DOT: u32 = 1u;
+const CHAR_EDGE_VERTICAL: u32 = 10u;
+
+const INK: vec3<f32> = vec3<f32>(0.55, 0.8, 1.0);
+const GRID: vec3<f32> = vec3<f32>(0.2, 0.3, 0.5);
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let col = gid.x;
+    let row = gid.y;
+    if (col >= uniforms.cols || row >= uniforms.rows) {
+        return;
+    }
+
+    let tile_width = f32(uniforms.tex_width) / f32(uniforms.cols);
+    let tile_height = f32(uniforms.tex_height) / f32(uniforms.rows);
+
+    // Vote on edge direction over a 4x4 grid of samples
+    var votes = array<u32, 4>(0u, 0u, 0u, 0u);
+    var edge_pixels: u32 = 0u;
+    for (var sy = 0u; sy < 4u; sy++) {
+        for (var sx = 0u; sx < 4u; sx++) {
+            let px = i32((f32(col) + (f32(sx) + 0.5) / 4.0) * tile_width);
+            let py = i32((f32(row) + (f32(sy) + 0.5) / 4.0) * tile_height);
+            let data = textureLoad(direction_texture, vec2<i32>(px, py), 0);
+            let direction = i32(data.r);
+            if (data.g > 0.5 && direction >= 0 && direction <= 3) {
+                votes[direction] += 1u;
+                edge_pixels += 1u;
+            }
+        }
+    }
+
+    var char_index = CHAR_SPACE;
+    var color = GRID;
+    if (edge_pixels >= uniforms.edge_threshold) {
+        var best = 0;
+        for (var i = 1; i < 4; i++) {
+            if (votes[i] > votes[best]) {
+                best = i;
+            }
+        }
+        char_index = CHAR_EDGE_VERTICAL + u32(best);
+        color = INK;
+    } else if (col % 4u == 0u && row % 2u == 0u) {
+        char_index = CHAR_DOT;
+    }
+
+    let rgb = vec3<u32>(clamp(color * 255.0, vec3<f32>(0.0), vec3<f32>(255.0)));
+    output[row * uniforms.cols + col] = (rgb.r << 24u) | (rgb.g << 16u) | (rgb.b << 8u) | char_index;
+}
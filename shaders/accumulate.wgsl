@@ -0,0 +1,33 @@
+// Still-Frame Accumulation Pass
+// Blends each jittered render of an unchanging scene into the running average of the ones
+// before it, so a still frame converges to a supersampled image
+
+struct Uniforms {
+    width: u32,
+    height: u32,
+    weight: f32,             // Share of the new render (1 / samples so far, 1 = restart)
+    _padding: u32,
+};
+
+@group(0) @binding(0)
+var scene_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var history_texture: texture_2d<f32>;
+
+@group(0) @binding(2)
+var output_texture: texture_storage_2d<rgba32float, write>;
+
+@group(0) @binding(3)
+var<uniform> uniforms: Uniforms;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= uniforms.width || gid.y >= uniforms.height) {
+        return;
+    }
+    let coords = vec2<i32>(gid.xy);
+    let scene = textureLoad(scene_texture, coords, 0);
+    let history = textureLoad(history_texture, coords, 0);
+    textureStore(output_texture, coords, mix(history, scene, uniforms.weight));
+}
@@ -1,6 +1,7 @@
 // Final ASCII Pass with Tile Voting
 // Samples direction texture across each character cell
-// Votes on dominant edge direction
+// Votes on dominant edge direction, then again over the surrounding 3x3 cells so runs of edge
+// characters stay continuous and agree on direction
 // Outputs character index + packed RGB color
 
 struct Uniforms {
@@ -11,11 +12,19 @@ struct Uniforms {
     edge_threshold: u32,     // Min edge pixels to use edge char (e.g., 2 out of 16 samples)
     exposure: f32,           // Luminance boost (e.g., 1.5)
     gamma: f32,              // Contrast curve (e.g., 0.8)
-    _padding: f32,
+    depth_weight: f32,       // How much nearness replaces luminance (0 = off)
+    depth_near: f32,         // View distance at full nearness
+    depth_far: f32,          // View distance at zero nearness
+    clip_near: f32,          // Projection near plane (fitted to the scene), where linear depth is 0
+    clip_far: f32,           // Projection far plane, where linear depth is 1
+    suppress_background: u32, // 1 = far-depth pixels count as black and never vote for edges
+    fill_levels: u32,        // Luminance bands for toon fills (0 = continuous)
+    ink_outlines: u32,       // 1 = cells drawn by outline votes are colored black
+    _pad0: u32,
 };
 
 @group(0) @binding(0)
-var direction_texture: texture_2d<f32>;  // R=direction, G=edge_flag, B=luminance, A=depth
+var direction_texture: texture_2d<f32>;  // R=direction, G=edge_flag (2 = outline), B=luminance, A=linear depth
 
 @group(0) @binding(1)
 var<uniform> uniforms: Uniforms;
@@ -33,32 +42,124 @@ var color_texture: texture_2d<f32>;  // Original rendered color
 // 12: Diagonal edge /
 // 13: Diagonal edge \
 
+// 0 at depth_far (and the background), 1 at depth_near
+fn nearness(depth: f32) -> f32 {
+    let distance = mix(uniforms.clip_near, uniforms.clip_far, depth);
+    return saturate((uniforms.depth_far - distance) / (uniforms.depth_far - uniforms.depth_near));
+}
+
 const CHAR_EDGE_VERTICAL: u32 = 10u;
 const CHAR_EDGE_HORIZONTAL: u32 = 11u;
 const CHAR_EDGE_DIAG_FWD: u32 = 12u;
 const CHAR_EDGE_DIAG_BACK: u32 = 13u;
 
-// Fill factors for each character (0-1, how much of the cell the character covers)
-// Characters: ' ', '.', ';', 'c', 'o', 'P', 'O', '?', '@', '#'
-const CHAR_FILL: array<f32, 14> = array<f32, 14>(
-    0.01,  // 0: space (nearly invisible, avoid div by 0)
-    0.08,  // 1: .
-    0.12,  // 2: ;
-    0.30,  // 3: c
-    0.40,  // 4: o
-    0.55,  // 5: P
-    0.60,  // 6: O
-    0.50,  // 7: ?
-    0.75,  // 8: @
-    0.85,  // 9: #
-    0.45,  // 10: | (vertical edge)
-    0.45,  // 11: - (horizontal edge)
-    0.40,  // 12: / (diagonal)
-    0.40,  // 13: \ (diagonal)
+// Character ramp: the ink coverage of each fill character scaled so the densest is 1, and how
+// much of the cell each character covers (by character index; 10-13 are the edges)
+struct Ramp {
+    levels: array<f32, 10>,
+    fill: array<f32, 14>,
+};
+
+@group(0) @binding(4)
+var<storage, read> ramp: Ramp;
+
+// Fill character whose coverage is nearest the luminance (ties go to the lower index), so
+// unevenly inked characters don't band the way evenly spaced indices do
+fn nearest_fill(luminance: f32) -> u32 {
+    var best: u32 = 0u;
+    var best_distance: f32 = 2.0;
+    for (var i = 0u; i < 10u; i++) {
+        let distance = abs(ramp.levels[i] - luminance);
+        if (distance < best_distance) {
+            best = i;
+            best_distance = distance;
+        }
+    }
+    return best;
+}
+
+// Snap a luminance to the nearest of `fill_levels` evenly spaced bands, 0 to 1 (toon fills)
+fn band(luminance: f32) -> f32 {
+    if (uniforms.fill_levels < 2u) {
+        return luminance;
+    }
+    let steps = f32(uniforms.fill_levels - 1u);
+    return round(saturate(luminance) * steps) / steps;
+}
+
+// Samples per tile side, shared by the cell's own pass and the neighborhood pass
+const SAMPLES_PER_SIDE: u32 = 4u;
+
+// Pixel coordinates of sample (sx, sy) in a tile
+fn sample_coords(col: i32, row: i32, sx: u32, sy: u32) -> vec2<i32> {
+    let tile_width = f32(uniforms.tex_width) / f32(uniforms.cols);
+    let tile_height = f32(uniforms.tex_height) / f32(uniforms.rows);
+    let step_x = tile_width / f32(SAMPLES_PER_SIDE);
+    let step_y = tile_height / f32(SAMPLES_PER_SIDE);
+    return vec2<i32>(
+        i32(f32(col) * tile_width + (f32(sx) + 0.5) * step_x),
+        i32(f32(row) * tile_height + (f32(sy) + 0.5) * step_y),
+    );
+}
+
+// Whether a direction texel is an edge that may vote (background never votes when suppressed)
+fn votes_for_edge(data: vec4<f32>) -> bool {
+    let background = uniforms.suppress_background == 1u && data.a >= 1.0;
+    let direction = i32(data.r);
+    return data.g > 0.5 && !background && direction >= 0 && direction <= 3;
+}
+
+// Edge votes per direction [|, -, \, /] for a tile (zero outside the grid)
+fn tile_votes(col: i32, row: i32) -> vec4<u32> {
+    var votes = vec4<u32>(0u);
+    if (col < 0 || row < 0 || col >= i32(uniforms.cols) || row >= i32(uniforms.rows)) {
+        return votes;
+    }
+    for (var sy = 0u; sy < SAMPLES_PER_SIDE; sy++) {
+        for (var sx = 0u; sx < SAMPLES_PER_SIDE; sx++) {
+            let coords = sample_coords(col, row, sx, sy);
+            if (coords.x >= i32(uniforms.tex_width) || coords.y >= i32(uniforms.tex_height)) {
+                continue;
+            }
+            let data = textureLoad(direction_texture, coords, 0);
+            if (votes_for_edge(data)) {
+                votes[i32(data.r)] += 1u;
+            }
+        }
+    }
+    return votes;
+}
+
+// Index of the largest vote (-1 when there are none); ties go to the lower index
+fn dominant(votes: vec4<u32>) -> i32 {
+    var best: u32 = 0u;
+    var index: i32 = -1;
+    for (var i = 0; i < 4; i++) {
+        if (votes[i] > best) {
+            best = votes[i];
+            index = i;
+        }
+    }
+    return index;
+}
+
+// Whether a tile alone has enough votes to be an edge character, in the given direction
+fn is_edge_tile(votes: vec4<u32>, direction: i32) -> bool {
+    let total = votes.x + votes.y + votes.z + votes.w;
+    return total >= uniforms.edge_threshold && dominant(votes) == direction;
+}
+
+// Cell step along a run of each edge direction (0-3 = | - \ /, see EDGE_CHARS in output.rs):
+// | runs vertically, - horizontally, \ down-right, / up-right
+const RUN_STEP: array<vec2<i32>, 4> = array<vec2<i32>, 4>(
+    vec2<i32>(0, 1),
+    vec2<i32>(1, 0),
+    vec2<i32>(1, 1),
+    vec2<i32>(1, -1),
 );
 
-// One thread per ASCII character cell
-@compute @workgroup_size(1, 1)
+// One thread per ASCII character cell, 8x8 cells per workgroup (CELL_WORKGROUP_SIZE)
+@compute @workgroup_size(8, 8)
 fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     let tile_col = gid.x;
     let tile_row = gid.y;
@@ -68,39 +169,33 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
         return;
     }
 
-    // Calculate tile bounds in pixels
-    let tile_width = f32(uniforms.tex_width) / f32(uniforms.cols);
-    let tile_height = f32(uniforms.tex_height) / f32(uniforms.rows);
-    let tile_start_x = f32(tile_col) * tile_width;
-    let tile_start_y = f32(tile_row) * tile_height;
+    let col = i32(tile_col);
+    let row = i32(tile_row);
 
     // Vote counts for each direction: [vertical, horizontal, diag_fwd, diag_back]
-    var direction_votes = array<u32, 4>(0u, 0u, 0u, 0u);
-    var total_edge_pixels: u32 = 0u;
+    var direction_votes = vec4<u32>(0u);
     var luminance_sum: f32 = 0.0;
     var color_sum: vec3<f32> = vec3<f32>(0.0);
     var sample_count: u32 = 0u;
+    var outline_votes: u32 = 0u;
 
-    // Sample 4x4 grid within the tile
-    let samples_x = 4u;
-    let samples_y = 4u;
-    let step_x = tile_width / f32(samples_x);
-    let step_y = tile_height / f32(samples_y);
-
-    for (var sy = 0u; sy < samples_y; sy++) {
-        for (var sx = 0u; sx < samples_x; sx++) {
-            let px = i32(tile_start_x + (f32(sx) + 0.5) * step_x);
-            let py = i32(tile_start_y + (f32(sy) + 0.5) * step_y);
+    // Pass 1: sample a 4x4 grid within the tile
+    for (var sy = 0u; sy < SAMPLES_PER_SIDE; sy++) {
+        for (var sx = 0u; sx < SAMPLES_PER_SIDE; sx++) {
+            let pixel_coords = sample_coords(col, row, sx, sy);
 
             // Bounds check
-            if (px >= 0 && px < i32(uniforms.tex_width) && py >= 0 && py < i32(uniforms.tex_height)) {
-                let pixel_coords = vec2<i32>(px, py);
+            if (pixel_coords.x < i32(uniforms.tex_width) && pixel_coords.y < i32(uniforms.tex_height)) {
                 let data = textureLoad(direction_texture, pixel_coords, 0);
                 let color = textureLoad(color_texture, pixel_coords, 0).rgb;
 
-                let direction = i32(data.r);
-                let is_edge = data.g > 0.5;
-                let luminance = data.b;
+                // Nothing was drawn where depth is still at the clear value
+                let background = uniforms.suppress_background == 1u && data.a >= 1.0;
+
+                var luminance = mix(data.b, nearness(data.a), uniforms.depth_weight);
+                if (background) {
+                    luminance = 0.0;
+                }
 
                 // Accumulate luminance and color
                 luminance_sum += luminance;
@@ -108,24 +203,41 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
                 sample_count += 1u;
 
                 // Vote for edge direction
-                if (is_edge && direction >= 0 && direction <= 3) {
-                    direction_votes[direction] += 1u;
-                    total_edge_pixels += 1u;
+                if (votes_for_edge(data)) {
+                    direction_votes[i32(data.r)] += 1u;
+                    if (data.g > 1.5) {
+                        outline_votes += 1u;
+                    }
                 }
             }
         }
     }
+    let total_edge_pixels = direction_votes.x + direction_votes.y + direction_votes.z + direction_votes.w;
 
-    // Find dominant direction
-    var max_votes: u32 = 0u;
-    var dominant_dir: i32 = -1;
-
-    for (var i = 0; i < 4; i++) {
-        if (direction_votes[i] > max_votes) {
-            max_votes = direction_votes[i];
-            dominant_dir = i;
+    // Pass 2: vote again over the 3x3 cell neighborhood (own votes count double), so a diagonal
+    // doesn't flip between / and | as it crosses cell boundaries
+    var neighborhood_votes = direction_votes * 2u;
+    for (var dy = -1; dy <= 1; dy++) {
+        for (var dx = -1; dx <= 1; dx++) {
+            if (dx != 0 || dy != 0) {
+                neighborhood_votes += tile_votes(col + dx, row + dy);
+            }
         }
     }
+    var dominant_dir = -1;
+    if (total_edge_pixels > 0u) {
+        // Only directions this cell saw itself can win
+        dominant_dir = dominant(select(vec4<u32>(0u), neighborhood_votes, direction_votes > vec4<u32>(0u)));
+    }
+
+    // A weak cell between two edge cells of its direction continues the run instead of
+    // breaking it with a fill character
+    var bridges_run = false;
+    if (dominant_dir >= 0 && total_edge_pixels < uniforms.edge_threshold) {
+        let step = RUN_STEP[dominant_dir];
+        bridges_run = is_edge_tile(tile_votes(col + step.x, row + step.y), dominant_dir)
+            && is_edge_tile(tile_votes(col - step.x, row - step.y), dominant_dir);
+    }
 
     var char_index: u32;
     var avg_color: vec3<f32> = vec3<f32>(0.5);
@@ -135,10 +247,21 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
         avg_color = color_sum / f32(sample_count);
     }
 
+    // Toon fills: the color keeps its hue at its band's brightness
+    if (uniforms.fill_levels >= 2u) {
+        let color_luminance = dot(avg_color, vec3<f32>(0.2126, 0.7152, 0.0722));
+        avg_color = saturate(avg_color * band(color_luminance) / max(color_luminance, 0.001));
+    }
+
     // Check if we have enough edge votes
-    if (total_edge_pixels >= uniforms.edge_threshold && max_votes > 0u) {
+    if (dominant_dir >= 0 && (total_edge_pixels >= uniforms.edge_threshold || bridges_run)) {
         // Use edge character based on dominant direction
         char_index = CHAR_EDGE_VERTICAL + u32(dominant_dir);
+
+        // Mostly outline: drawn in ink rather than the surface color
+        if (uniforms.ink_outlines == 1u && outline_votes * 2u >= total_edge_pixels) {
+            avg_color = vec3<f32>(0.0);
+        }
     } else {
         // Use fill character based on average luminance
         var avg_luminance: f32 = 0.0;
@@ -147,15 +270,15 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
         }
 
         // Apply exposure and gamma
-        avg_luminance = saturate(pow(avg_luminance * uniforms.exposure, uniforms.gamma));
+        avg_luminance = band(saturate(pow(avg_luminance * uniforms.exposure, uniforms.gamma)));
 
-        // Map to character index (0-9)
-        char_index = u32(clamp(avg_luminance * 10.0, 0.0, 9.0));
+        // Pick the fill character with the nearest ink coverage
+        char_index = nearest_fill(avg_luminance);
     }
 
     // Compensate color for character fill factor
     // Darker characters (low fill) need brighter colors to achieve the same perceived brightness
-    let fill_factor = CHAR_FILL[char_index];
+    let fill_factor = ramp.fill[char_index];
     // Boost = 1/fill, but clamped to avoid extreme values
     // We use sqrt to soften the compensation (full compensation would be too aggressive)
     let boost = min(1.0 / sqrt(fill_factor), 3.0);
@@ -0,0 +1,52 @@
+// Bounding box shader - draws unlit line geometry using the scene uniforms
+
+struct Uniforms {
+    mvp: mat4x4<f32>,
+    model: mat4x4<f32>,
+    light_dir: vec4<f32>,
+    lighting_mode: u32,
+    light_intensity: f32,
+    _pad2: u32,
+    _pad3: u32,
+    camera_pos: vec4<f32>,
+    prev_mvp: mat4x4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) color: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec3<f32>,
+    // Clip position this frame and last frame, for motion vectors
+    @location(1) current_clip: vec4<f32>,
+    @location(2) previous_clip: vec4<f32>,
+};
+
+// Color, and screen movement since the previous frame (as a fraction of the screen)
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @location(1) motion: vec2<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = uniforms.mvp * vec4<f32>(in.position, 1.0);
+    out.current_clip = out.clip_position;
+    out.previous_clip = uniforms.prev_mvp * vec4<f32>(in.position, 1.0);
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> FragmentOutput {
+    let delta = in.current_clip.xy / in.current_clip.w - in.previous_clip.xy / in.previous_clip.w;
+    return FragmentOutput(vec4<f32>(in.color, 1.0), delta * vec2<f32>(0.5, -0.5));
+}
@@ -3,40 +3,88 @@ struct Uniforms {
     model: mat4x4<f32>,
     light_dir: vec4<f32>,
     lighting_mode: u32,
-    _pad1: u32,
+    light_intensity: f32,
     _pad2: u32,
     _pad3: u32,
+    // World-space camera position (w unused)
+    camera_pos: vec4<f32>,
+    // Last frame's model-view-projection, for motion vectors
+    prev_mvp: mat4x4<f32>,
 };
 
 @group(0) @binding(0)
 var<uniform> uniforms: Uniforms;
 
+// Material of the sub-mesh being drawn
+struct Material {
+    base_color: vec4<f32>,
+    // Draw list index + 1, for the part ID target (0 = not a model part)
+    part_id: u32,
+    // Nonzero for the selected part
+    highlight: u32,
+};
+
+@group(1) @binding(0)
+var<uniform> material: Material;
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) normal: vec3<f32>,
     @location(2) color: vec3<f32>,
 };
 
+// Per-instance transform (columns of a 4x4 matrix), applied before the model matrix
+struct InstanceInput {
+    @location(3) col0: vec4<f32>,
+    @location(4) col1: vec4<f32>,
+    @location(5) col2: vec4<f32>,
+    @location(6) col3: vec4<f32>,
+};
+
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) world_normal: vec3<f32>,
     @location(1) color: vec3<f32>,
     @location(2) world_pos: vec3<f32>,
+    // Clip position this frame and last frame, for motion vectors
+    @location(3) current_clip: vec4<f32>,
+    @location(4) previous_clip: vec4<f32>,
 };
 
+// Color, and screen movement since the previous frame
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @location(1) motion: vec2<f32>,
+    @location(2) part_id: u32,
+};
+
+// Screen movement from the previous clip position to the current one, as a fraction of the
+// screen (x right, y down)
+fn motion_vector(in: VertexOutput) -> vec2<f32> {
+    let delta = in.current_clip.xy / in.current_clip.w - in.previous_clip.xy / in.previous_clip.w;
+    return delta * vec2<f32>(0.5, -0.5);
+}
+
 @vertex
-fn vs_main(in: VertexInput) -> VertexOutput {
+fn vs_main(in: VertexInput, instance: InstanceInput) -> VertexOutput {
+    let instance_matrix = mat4x4<f32>(instance.col0, instance.col1, instance.col2, instance.col3);
+    let local_pos = instance_matrix * vec4<f32>(in.position, 1.0);
+    let local_normal = instance_matrix * vec4<f32>(in.normal, 0.0);
+
     var out: VertexOutput;
-    out.clip_position = uniforms.mvp * vec4<f32>(in.position, 1.0);
+    out.clip_position = uniforms.mvp * local_pos;
+    out.current_clip = out.clip_position;
+    out.previous_clip = uniforms.prev_mvp * local_pos;
     // Transform normal by model matrix (assuming no non-uniform scaling)
-    out.world_normal = (uniforms.model * vec4<f32>(in.normal, 0.0)).xyz;
-    out.color = in.color;
-    out.world_pos = (uniforms.model * vec4<f32>(in.position, 1.0)).xyz;
+    out.world_normal = (uniforms.model * local_normal).xyz;
+    out.color = in.color * material.base_color.rgb;
+    out.world_pos = (uniforms.model * local_pos).xyz;
     return out;
 }
 
 // Lighting mode values:
 // 0 = Flat, 1 = Diffuse, 2 = Specular, 3 = Toon, 4 = Gradient, 5 = Normals
+// (6 = X-Ray is drawn by fs_xray_back and fs_xray_front instead; fs_main lights it as Diffuse)
 
 // Calculate diffuse lighting (shared by multiple modes)
 fn calc_diffuse(normal: vec3<f32>) -> f32 {
@@ -53,7 +101,7 @@ fn calc_diffuse(normal: vec3<f32>) -> f32 {
     let hemisphere_factor = dot(normal, up) * 0.5 + 0.5;
     let ambient = mix(0.2, 0.4, hemisphere_factor);
 
-    return ambient + key_diffuse * 0.5 + fill_diffuse + rim_diffuse;
+    return ambient + key_diffuse * 0.5 * uniforms.light_intensity + fill_diffuse + rim_diffuse;
 }
 
 // Calculate specular highlight
@@ -61,7 +109,7 @@ fn calc_specular(normal: vec3<f32>, view_dir: vec3<f32>) -> f32 {
     let key_light_dir = normalize(uniforms.light_dir.xyz);
     let half_dir = normalize(key_light_dir + view_dir);
     let spec = pow(max(dot(normal, half_dir), 0.0), 32.0);
-    return spec * 0.5;
+    return spec * 0.5 * uniforms.light_intensity;
 }
 
 // Quantize for toon shading
@@ -91,7 +139,7 @@ fn hsv2rgb(h: f32, s: f32, v: f32) -> vec3<f32> {
 }
 
 @fragment
-fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+fn fs_main(in: VertexOutput) -> FragmentOutput {
     let normal = normalize(in.world_normal);
 
     // View direction (assuming camera at origin looking at -Z)
@@ -132,5 +180,42 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         final_color = in.color * lighting;
     }
 
-    return vec4<f32>(final_color, 1.0);
+    return FragmentOutput(vec4<f32>(highlight(final_color, normal, in.world_pos), 1.0), motion_vector(in), material.part_id);
+}
+
+// The selected part is tinted, most strongly around its outline so its edges stand out
+const HIGHLIGHT_COLOR: vec3<f32> = vec3<f32>(1.0, 0.7, 0.1);
+const HIGHLIGHT_TINT: f32 = 0.3;
+
+fn highlight(color: vec3<f32>, normal: vec3<f32>, world_pos: vec3<f32>) -> vec3<f32> {
+    if (material.highlight == 0u) {
+        return color;
+    }
+    let view_dir = normalize(uniforms.camera_pos.xyz - world_pos);
+    let rim = 1.0 - abs(dot(normal, view_dir));
+    return mix(color, HIGHLIGHT_COLOR, mix(HIGHLIGHT_TINT, 1.0, rim * rim));
+}
+
+// X-ray: back faces are drawn first, opaque and dimmed, so the far side of the shell and any
+// inner parts show; front faces are then blended over them, clearest face-on and most opaque
+// at grazing angles so the silhouette stays readable
+const XRAY_BACK_DIM: f32 = 0.45;
+const XRAY_FRONT_ALPHA: f32 = 0.2;
+const XRAY_RIM_ALPHA: f32 = 0.85;
+
+@fragment
+fn fs_xray_back(in: VertexOutput) -> FragmentOutput {
+    // Light the side of the face that is turned toward the camera
+    let normal = -normalize(in.world_normal);
+    let color = in.color * calc_diffuse(normal) * XRAY_BACK_DIM;
+    return FragmentOutput(vec4<f32>(highlight(color, normal, in.world_pos), 1.0), motion_vector(in), material.part_id);
+}
+
+@fragment
+fn fs_xray_front(in: VertexOutput) -> FragmentOutput {
+    let normal = normalize(in.world_normal);
+    let view_dir = normalize(uniforms.camera_pos.xyz - in.world_pos);
+    let rim = 1.0 - abs(dot(normal, view_dir));
+    let alpha = mix(XRAY_FRONT_ALPHA, XRAY_RIM_ALPHA, rim * rim);
+    return FragmentOutput(vec4<f32>(highlight(in.color * calc_diffuse(normal), normal, in.world_pos), alpha), motion_vector(in), material.part_id);
 }
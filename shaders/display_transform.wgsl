@@ -0,0 +1,84 @@
+// Display Transform Pass
+// Converts the linear HDR scene color to display-referred sRGB before edge detection and
+// character/color quantization, optionally through an ACES filmic tonemap, and optionally
+// motion-blurred by accumulating the scene along each pixel's motion vector
+
+struct Uniforms {
+    width: u32,
+    height: u32,
+    tonemap: u32,            // 0 = clamp, 1 = ACES
+    motion_blur: f32,        // Shutter: fraction of the motion since the last frame blurred over (0 = off)
+};
+
+@group(0) @binding(0)
+var scene_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var output_texture: texture_storage_2d<rgba8unorm, write>;
+
+@group(0) @binding(2)
+var<uniform> uniforms: Uniforms;
+
+@group(0) @binding(3)
+var motion_texture: texture_2d<f32>;  // Screen movement since the last frame, as a fraction of the screen
+
+// Scene samples accumulated along the motion vector
+const BLUR_SAMPLES: i32 = 8;
+
+// Scene color averaged along the stretch of the motion vector the shutter covers, centered on
+// the pixel
+fn motion_blurred(coords: vec2<i32>) -> vec4<f32> {
+    let size = vec2<f32>(f32(uniforms.width), f32(uniforms.height));
+    let motion = textureLoad(motion_texture, coords, 0).xy * size * uniforms.motion_blur;
+    if (dot(motion, motion) < 0.25) {
+        return textureLoad(scene_texture, coords, 0);
+    }
+    let max_coords = vec2<i32>(i32(uniforms.width) - 1, i32(uniforms.height) - 1);
+    var sum = vec4<f32>(0.0);
+    for (var i: i32 = 0; i < BLUR_SAMPLES; i++) {
+        let offset = motion * (f32(i) / f32(BLUR_SAMPLES - 1) - 0.5);
+        let sample_coords = clamp(coords + vec2<i32>(round(offset)), vec2<i32>(0), max_coords);
+        sum += textureLoad(scene_texture, sample_coords, 0);
+    }
+    return sum / f32(BLUR_SAMPLES);
+}
+
+// ACES filmic curve (Narkowicz fit), maps [0, inf) to [0, 1)
+fn aces(x: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return clamp((x * (a * x + b)) / (x * (c * x + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Linear to sRGB transfer function (IEC 61966-2-1)
+fn linear_to_srgb(linear: vec3<f32>) -> vec3<f32> {
+    let low = linear * 12.92;
+    let high = 1.055 * pow(linear, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, linear <= vec3<f32>(0.0031308));
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= uniforms.width || gid.y >= uniforms.height) {
+        return;
+    }
+    let coords = vec2<i32>(gid.xy);
+    var scene: vec4<f32>;
+    if (uniforms.motion_blur > 0.0) {
+        scene = motion_blurred(coords);
+    } else {
+        scene = textureLoad(scene_texture, coords, 0);
+    }
+
+    var color = max(scene.rgb, vec3<f32>(0.0));
+    if (uniforms.tonemap == 1u) {
+        color = aces(color);
+    } else {
+        color = min(color, vec3<f32>(1.0));
+    }
+
+    textureStore(output_texture, coords, vec4<f32>(linear_to_srgb(color), scene.a));
+}
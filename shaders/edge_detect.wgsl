@@ -1,16 +1,25 @@
 // Edge Detection Pass
 // Combines depth discontinuities + normal discontinuities + DoG luminance edges
-// Following AcerolaFX approach
+// Following AcerolaFX approach, or runs Canny on luminance alone
+// Part IDs add object outlines and can keep shading edges off the inside of each object
 
 struct Uniforms {
     width: u32,
     height: u32,
-    depth_threshold: f32,    // Depth discontinuity threshold (default 0.02)
+    depth_threshold: f32,    // Depth discontinuity threshold, as a fraction of the clip range (default 0.08)
     normal_threshold: f32,   // Normal discontinuity threshold (default 0.5)
     dog_threshold: f32,      // DoG edge threshold (default 0.02)
     use_depth: u32,          // Enable depth edges (1 = true)
     use_normals: u32,        // Enable normal edges (1 = true)
     use_dog: u32,            // Enable DoG edges (1 = true)
+    use_canny: u32,          // Enable Canny luminance edges (1 = true)
+    canny_low: f32,          // Canny hysteresis low threshold (gradient magnitude)
+    canny_high: f32,         // Canny hysteresis high threshold (gradient magnitude)
+    clip_near: f32,          // Projection near plane (fitted to the scene), to linearize depth
+    clip_far: f32,           // Projection far plane
+    depth_world_units: u32,  // Depth threshold is in world units instead (1 = true)
+    use_ids: u32,            // Enable edges where the part ID changes (1 = true)
+    interior_edges: u32,     // Keep normal and luminance edges inside a single part (1 = true)
 };
 
 @group(0) @binding(0)
@@ -25,6 +34,9 @@ var output_texture: texture_storage_2d<rgba32float, write>;
 @group(0) @binding(3)
 var<uniform> uniforms: Uniforms;
 
+@group(0) @binding(4)
+var part_id_texture: texture_2d<u32>;
+
 // Luminance coefficients (Rec. 709)
 const LUMA_R: f32 = 0.2126;
 const LUMA_G: f32 = 0.7152;
@@ -35,10 +47,21 @@ fn get_luminance(color: vec3<f32>) -> f32 {
     return dot(color, vec3<f32>(LUMA_R, LUMA_G, LUMA_B));
 }
 
-// Sample depth at coordinates (with bounds check)
+// Sample linear depth at coordinates (with bounds check): view distance mapped to 0 at the near
+// plane and 1 at the far plane, with the background at exactly 1
 fn sample_depth(coords: vec2<i32>) -> f32 {
     let clamped = clamp(coords, vec2<i32>(0), vec2<i32>(i32(uniforms.width) - 1, i32(uniforms.height) - 1));
-    return textureLoad(depth_texture, clamped, 0);
+    let depth = textureLoad(depth_texture, clamped, 0);
+    let near = uniforms.clip_near;
+    let far = uniforms.clip_far;
+    let distance = near * far / (far - depth * (far - near));
+    return select(saturate((distance - near) / (far - near)), 1.0, depth >= 1.0);
+}
+
+// Sample the part ID at coordinates (with bounds check): part index + 1, 0 where no part was drawn
+fn sample_part_id(coords: vec2<i32>) -> u32 {
+    let clamped = clamp(coords, vec2<i32>(0), vec2<i32>(i32(uniforms.width) - 1, i32(uniforms.height) - 1));
+    return textureLoad(part_id_texture, clamped, 0).r;
 }
 
 // Sample color at coordinates (with bounds check)
@@ -92,6 +115,91 @@ fn calculate_dog(coords: vec2<i32>) -> f32 {
     return abs(avg3x3 - avg5x5);
 }
 
+// Canny needs smoothed luminance over a 9x9 window: Gaussian (7x7 valid) -> gradients (5x5 valid)
+// -> non-maximum suppression (3x3 valid) -> hysteresis at the center
+const CANNY_RAW: i32 = 9;
+
+// Index into a square window of the given size, with (0, 0) at its center
+fn window_index(x: i32, y: i32, size: i32) -> i32 {
+    let half = size / 2;
+    return (y + half) * size + (x + half);
+}
+
+// Single-pass Canny: a weak pixel counts only when a neighbor is strong, which approximates
+// full hysteresis without the iterative flood fill
+fn calculate_canny(coords: vec2<i32>) -> f32 {
+    var raw: array<f32, 81>;
+    for (var y: i32 = -4; y <= 4; y++) {
+        for (var x: i32 = -4; x <= 4; x++) {
+            raw[window_index(x, y, CANNY_RAW)] = get_luminance(sample_color(coords + vec2<i32>(x, y)));
+        }
+    }
+
+    // 3x3 Gaussian (1 2 1 / 2 4 2 / 1 2 1) over the inner 7x7
+    var smoothed: array<f32, 49>;
+    for (var y: i32 = -3; y <= 3; y++) {
+        for (var x: i32 = -3; x <= 3; x++) {
+            var sum: f32 = 0.0;
+            for (var dy: i32 = -1; dy <= 1; dy++) {
+                for (var dx: i32 = -1; dx <= 1; dx++) {
+                    let weight = f32((2 - abs(dx)) * (2 - abs(dy)));
+                    sum += weight * raw[window_index(x + dx, y + dy, CANNY_RAW)];
+                }
+            }
+            smoothed[window_index(x, y, 7)] = sum / 16.0;
+        }
+    }
+
+    // Sobel gradients over the inner 5x5
+    var magnitude: array<f32, 25>;
+    var gradient: array<vec2<f32>, 25>;
+    for (var y: i32 = -2; y <= 2; y++) {
+        for (var x: i32 = -2; x <= 2; x++) {
+            let nw = smoothed[window_index(x - 1, y - 1, 7)];
+            let n  = smoothed[window_index(x, y - 1, 7)];
+            let ne = smoothed[window_index(x + 1, y - 1, 7)];
+            let w  = smoothed[window_index(x - 1, y, 7)];
+            let e  = smoothed[window_index(x + 1, y, 7)];
+            let sw = smoothed[window_index(x - 1, y + 1, 7)];
+            let s  = smoothed[window_index(x, y + 1, 7)];
+            let se = smoothed[window_index(x + 1, y + 1, 7)];
+            let g = vec2<f32>(-nw - 2.0 * w - sw + ne + 2.0 * e + se, -nw - 2.0 * n - ne + sw + 2.0 * s + se);
+            gradient[window_index(x, y, 5)] = g;
+            magnitude[window_index(x, y, 5)] = length(g);
+        }
+    }
+
+    // Non-maximum suppression over the inner 3x3: keep pixels that peak along their gradient
+    var strong = false;
+    var center_weak = false;
+    for (var y: i32 = -1; y <= 1; y++) {
+        for (var x: i32 = -1; x <= 1; x++) {
+            let mag = magnitude[window_index(x, y, 5)];
+            if (mag < uniforms.canny_low) {
+                continue;
+            }
+            let g = gradient[window_index(x, y, 5)] / mag;
+            let step = vec2<i32>(round(g));
+            let ahead = magnitude[window_index(x + step.x, y + step.y, 5)];
+            let behind = magnitude[window_index(x - step.x, y - step.y, 5)];
+            if (mag < ahead || mag < behind) {
+                continue;
+            }
+            if (x == 0 && y == 0) {
+                center_weak = true;
+            }
+            if (mag >= uniforms.canny_high) {
+                strong = true;
+            }
+        }
+    }
+
+    if (center_weak && strong) {
+        return 1.0;
+    }
+    return 0.0;
+}
+
 @compute @workgroup_size(16, 16)
 fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     let coords = vec2<i32>(gid.xy);
@@ -108,8 +216,26 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     let center_color = sample_color(coords);
     let center_lum = get_luminance(center_color);
 
+    // A pixel whose 8 neighbors all belong to its own part is inside that object
+    let center_id = sample_part_id(coords);
+    var id_boundary = false;
+    for (var dy: i32 = -1; dy <= 1; dy++) {
+        for (var dx: i32 = -1; dx <= 1; dx++) {
+            if (sample_part_id(coords + vec2<i32>(dx, dy)) != center_id) {
+                id_boundary = true;
+            }
+        }
+    }
+    // Inside a part, only depth edges (self-occlusion) are kept unless interior edges are on
+    let shading_edges = uniforms.interior_edges == 1u || center_id == 0u || id_boundary;
+
+    // === Object ID edge detection ===
+    if (uniforms.use_ids == 1u && id_boundary) {
+        edge_strength = 1.0;
+    }
+
     // === Depth-based edge detection ===
-    if (uniforms.use_depth == 1u) {
+    if (uniforms.use_depth == 1u && edge_strength < 1.0) {
         // Sample 8 neighbors for depth
         let depth_n  = sample_depth(coords + vec2<i32>(0, -1));
         let depth_s  = sample_depth(coords + vec2<i32>(0, 1));
@@ -131,13 +257,15 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
         depth_sum += abs(depth_se - center_depth);
         depth_sum += abs(depth_sw - center_depth);
 
-        if (depth_sum > uniforms.depth_threshold) {
+        // Linear depth spans the clip range, so scaling by it gives view distance
+        let depth_scale = select(1.0, uniforms.clip_far - uniforms.clip_near, uniforms.depth_world_units == 1u);
+        if (depth_sum * depth_scale > uniforms.depth_threshold) {
             edge_strength = 1.0;
         }
     }
 
     // === Normal-based edge detection ===
-    if (uniforms.use_normals == 1u && edge_strength < 1.0) {
+    if (uniforms.use_normals == 1u && shading_edges && edge_strength < 1.0) {
         let center_normal = calculate_normal(coords);
 
         // Sample neighbor normals
@@ -159,13 +287,18 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     }
 
     // === DoG-based edge detection ===
-    if (uniforms.use_dog == 1u && edge_strength < 1.0) {
+    if (uniforms.use_dog == 1u && shading_edges && edge_strength < 1.0) {
         let dog = calculate_dog(coords);
         if (dog > uniforms.dog_threshold) {
             edge_strength = 1.0;
         }
     }
 
-    // Output: R = edge strength, G = luminance, B = depth, A = unused
+    // === Canny luminance edge detection ===
+    if (uniforms.use_canny == 1u && shading_edges && edge_strength < 1.0) {
+        edge_strength = calculate_canny(coords);
+    }
+
+    // Output: R = edge strength, G = luminance, B = linear depth, A = unused
     textureStore(output_texture, coords, vec4<f32>(edge_strength, center_lum, center_depth, 1.0));
 }
@@ -0,0 +1,49 @@
+// Label Occlusion Pass
+// Tests each projected label anchor against the depth buffer around where it lands, for labels
+// that fade out as the model turns in front of them
+// Outputs the fraction of taps with nothing in front of the anchor (1 = in full view)
+
+struct Uniforms {
+    tex_width: u32,
+    tex_height: u32,
+    count: u32,
+    spread: f32,             // Pixels between taps (about a cell)
+};
+
+@group(0) @binding(0)
+var depth_texture: texture_depth_2d;
+
+@group(0) @binding(1)
+var<uniform> uniforms: Uniforms;
+
+// Anchor position on the texture (0 to 1, y down) and the depth of its nearest point
+@group(0) @binding(2)
+var<storage, read> anchors: array<vec4<f32>>;
+
+@group(0) @binding(3)
+var<storage, read_write> output: array<f32>;
+
+// Taps per side, centered on the anchor
+const TAPS: i32 = 3;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= uniforms.count) {
+        return;
+    }
+
+    let anchor = anchors[gid.x];
+    let size = vec2<f32>(f32(uniforms.tex_width), f32(uniforms.tex_height));
+    let center = anchor.xy * size;
+    var clear = 0.0;
+    for (var ty = 0; ty < TAPS; ty++) {
+        for (var tx = 0; tx < TAPS; tx++) {
+            let offset = vec2<f32>(f32(tx - TAPS / 2), f32(ty - TAPS / 2)) * uniforms.spread;
+            let coords = vec2<i32>(clamp(center + offset, vec2<f32>(0.0), size - 1.0));
+            if (textureLoad(depth_texture, coords, 0) >= anchor.z) {
+                clear += 1.0;
+            }
+        }
+    }
+    output[gid.x] = clear / f32(TAPS * TAPS);
+}
@@ -0,0 +1,196 @@
+// Game of Life on a torus - the board is stepped on the GPU, then written straight into the
+// model's vertex buffer: dead cells flush with the surface (glowing briefly after dying) and
+// live cells raised as blocks
+
+struct Uniforms {
+    cols: u32,               // Cells around the ring (the board wraps both ways, like the torus)
+    rows: u32,               // Cells around the tube
+    seed: u32,               // Soup being seeded (seed_main)
+    _pad0: u32,
+    ring_radius: f32,        // Ring center to tube center
+    tube_radius: f32,
+    cell_height: f32,        // How far live cells stand out from the surface
+    _pad1: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+// Boards two generations back, one back, and the one being written (rotated between steps);
+// 1 = alive. The mesh pass reads the newest board from `current`
+@group(0) @binding(1)
+var<storage, read> before_last: array<u32>;
+
+@group(0) @binding(2)
+var<storage, read> current: array<u32>;
+
+@group(0) @binding(3)
+var<storage, read_write> next: array<u32>;
+
+// Generations since each cell last died (for the fading trail)
+@group(0) @binding(4)
+var<storage, read_write> since_death: array<f32>;
+
+// Live cells on the board written, and cells that differ from two generations back (0 means a
+// still life or a period-2 oscillator)
+struct Stats {
+    population: atomic<u32>,
+    changed: atomic<u32>,
+};
+
+@group(0) @binding(5)
+var<storage, read_write> stats: Stats;
+
+// Model vertices as raw floats: position, normal, color (9 per vertex); a quad of the surface
+// per cell first, then a box of 6 quads per cell
+@group(0) @binding(6)
+var<storage, read_write> vertices: array<f32>;
+
+// Fraction of cells alive in a fresh random soup
+const SEED_DENSITY: f32 = 0.32;
+// Generations a dead cell's trail takes to fade
+const TRAIL_GENERATIONS: f32 = 6.0;
+
+const LIVE_COLOR: vec3<f32> = vec3<f32>(0.55, 1.0, 0.45);
+const SURFACE_COLOR: vec3<f32> = vec3<f32>(0.12, 0.14, 0.2);
+const TRAIL_COLOR: vec3<f32> = vec3<f32>(0.2, 0.45, 0.3);
+
+const TAU: f32 = 6.28318530718;
+
+fn cell_index(col: i32, row: i32) -> u32 {
+    let cols = i32(uniforms.cols);
+    let rows = i32(uniforms.rows);
+    return u32(((row % rows + rows) % rows) * cols + (col % cols + cols) % cols);
+}
+
+// Fill `next` with a random soup (deterministic: the nth soup is always the same)
+@compute @workgroup_size(8, 8)
+fn seed_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= uniforms.cols || gid.y >= uniforms.rows) {
+        return;
+    }
+    let i = gid.y * uniforms.cols + gid.x;
+    var h = (i ^ (uniforms.seed * 0x9e3779b9u)) * 0x85ebca6bu;
+    h ^= h >> 13u;
+    h *= 0xc2b2ae35u;
+    h ^= h >> 16u;
+    let alive = f32(h & 0xffffu) / 65536.0 < SEED_DENSITY;
+    next[i] = u32(alive);
+    since_death[i] = TRAIL_GENERATIONS;
+    if (alive) {
+        atomicAdd(&stats.population, 1u);
+    }
+}
+
+@compute @workgroup_size(8, 8)
+fn step_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= uniforms.cols || gid.y >= uniforms.rows) {
+        return;
+    }
+    let col = i32(gid.x);
+    let row = i32(gid.y);
+    let i = gid.y * uniforms.cols + gid.x;
+
+    var neighbors = 0u;
+    for (var dr: i32 = -1; dr <= 1; dr++) {
+        for (var dc: i32 = -1; dc <= 1; dc++) {
+            if (dr != 0 || dc != 0) {
+                neighbors += current[cell_index(col + dc, row + dr)];
+            }
+        }
+    }
+    let was_alive = current[i] == 1u;
+    let alive = neighbors == 3u || (was_alive && neighbors == 2u);
+    next[i] = u32(alive);
+
+    if (was_alive && !alive) {
+        since_death[i] = 0.0;
+    } else {
+        since_death[i] += 1.0;
+    }
+    if (alive) {
+        atomicAdd(&stats.population, 1u);
+    }
+    if (u32(alive) != before_last[i]) {
+        atomicAdd(&stats.changed, 1u);
+    }
+}
+
+fn write_vertex(index: u32, position: vec3<f32>, normal: vec3<f32>, color: vec3<f32>) {
+    let base = index * 9u;
+    vertices[base] = position.x;
+    vertices[base + 1u] = position.y;
+    vertices[base + 2u] = position.z;
+    vertices[base + 3u] = normal.x;
+    vertices[base + 4u] = normal.y;
+    vertices[base + 5u] = normal.z;
+    vertices[base + 6u] = color.x;
+    vertices[base + 7u] = color.y;
+    vertices[base + 8u] = color.z;
+}
+
+// Point at angle u around the ring and v around the tube, `radius` from the tube's center
+fn torus_point(u: f32, v: f32, radius: f32) -> vec3<f32> {
+    let r = uniforms.ring_radius + radius * cos(v);
+    return vec3<f32>(r * cos(u), radius * sin(v), r * sin(u));
+}
+
+// The same quad corners and box face order as MeshBuilder::push_quad and push_box, so the vertex
+// buffer keeps the layout the index buffer was built for
+@compute @workgroup_size(8, 8)
+fn mesh_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= uniforms.cols || gid.y >= uniforms.rows) {
+        return;
+    }
+    let i = gid.y * uniforms.cols + gid.x;
+    let du = TAU / f32(uniforms.cols);
+    let dv = TAU / f32(uniforms.rows);
+    let u0 = f32(gid.x) * du;
+    let v0 = f32(gid.y) * dv;
+    let uc = u0 + du / 2.0;
+    let vc = v0 + dv / 2.0;
+    let normal = vec3<f32>(cos(vc) * cos(uc), sin(vc), cos(vc) * sin(uc));
+
+    // Surface quad, glowing for a few generations after its cell dies
+    let glow = max(1.0 - since_death[i] / TRAIL_GENERATIONS, 0.0);
+    let surface_color = mix(SURFACE_COLOR, TRAIL_COLOR, glow);
+    let tube = uniforms.tube_radius;
+    write_vertex(4u * i, torus_point(u0, v0, tube), normal, surface_color);
+    write_vertex(4u * i + 1u, torus_point(u0, v0 + dv, tube), normal, surface_color);
+    write_vertex(4u * i + 2u, torus_point(u0 + du, v0 + dv, tube), normal, surface_color);
+    write_vertex(4u * i + 3u, torus_point(u0 + du, v0, tube), normal, surface_color);
+
+    // Box standing on the cell, slightly smaller than it so neighbors stay distinguishable;
+    // dead cells collapse it to a point, which draws nothing
+    let alive = f32(current[i]);
+    let along_ring = vec3<f32>(-sin(uc), 0.0, cos(uc));
+    let rotation = mat3x3<f32>(along_ring, normal, cross(along_ring, normal));
+    let center = torus_point(uc, vc, tube + uniforms.cell_height / 2.0);
+    let half = vec3<f32>(
+        (uniforms.ring_radius + tube * cos(vc)) * du * 0.42,
+        uniforms.cell_height / 2.0,
+        tube * dv * 0.42,
+    ) * alive;
+
+    // Each face: normal axis and two tangent axes chosen so (u x v) = normal
+    var faces = array<mat3x3<f32>, 6>(
+        mat3x3<f32>(vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 1.0, 0.0), vec3<f32>(0.0, 0.0, 1.0)),
+        mat3x3<f32>(vec3<f32>(-1.0, 0.0, 0.0), vec3<f32>(0.0, 0.0, 1.0), vec3<f32>(0.0, 1.0, 0.0)),
+        mat3x3<f32>(vec3<f32>(0.0, 1.0, 0.0), vec3<f32>(0.0, 0.0, 1.0), vec3<f32>(1.0, 0.0, 0.0)),
+        mat3x3<f32>(vec3<f32>(0.0, -1.0, 0.0), vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 0.0, 1.0)),
+        mat3x3<f32>(vec3<f32>(0.0, 0.0, 1.0), vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 1.0, 0.0)),
+        mat3x3<f32>(vec3<f32>(0.0, 0.0, -1.0), vec3<f32>(0.0, 1.0, 0.0), vec3<f32>(1.0, 0.0, 0.0)),
+    );
+    let box_base = 4u * uniforms.cols * uniforms.rows + 24u * i;
+    for (var f = 0u; f < 6u; f++) {
+        let face = faces[f];
+        let c = face[0] * half;
+        let a = face[1] * half;
+        let b = face[2] * half;
+        let face_normal = rotation * face[0];
+        write_vertex(box_base + 4u * f, center + rotation * (c - a - b), face_normal, LIVE_COLOR);
+        write_vertex(box_base + 4u * f + 1u, center + rotation * (c + a - b), face_normal, LIVE_COLOR);
+        write_vertex(box_base + 4u * f + 2u, center + rotation * (c + a + b), face_normal, LIVE_COLOR);
+        write_vertex(box_base + 4u * f + 3u, center + rotation * (c - a + b), face_normal, LIVE_COLOR);
+    }
+}
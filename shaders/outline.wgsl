@@ -0,0 +1,158 @@
+// Outline Dilation Pass
+// Marks silhouette pixels (where the model meets the background or another part) with their edge
+// direction, then spreads them over an ellipse a set number of character cells across, so
+// outlines come out thicker than the interior detail edges
+// Three entry points: mark -> spread_x -> spread_y (separable, like a box blur)
+
+struct Uniforms {
+    width: u32,
+    height: u32,
+    radius_x: f32,           // Spread to each side in pixels
+    radius_y: f32,
+};
+
+@group(0) @binding(0)
+var direction_texture: texture_2d<f32>;  // From sobel_edges: R=direction, G=edge flag, B=luminance, A=linear depth
+
+@group(0) @binding(1)
+var part_id_texture: texture_2d<u32>;    // Part index + 1, 0 where no part was drawn
+
+@group(0) @binding(2)
+var source_texture: texture_2d<f32>;     // The previous step's output
+
+@group(0) @binding(3)
+var output_texture: texture_storage_2d<rgba32float, write>;
+
+@group(0) @binding(4)
+var<uniform> uniforms: Uniforms;
+
+const PI: f32 = 3.14159265359;
+
+// Surface value of the background, which no part ID reaches
+const BACKGROUND: u32 = 0xffffffffu;
+
+fn clamp_coords(coords: vec2<i32>) -> vec2<i32> {
+    return clamp(coords, vec2<i32>(0), vec2<i32>(i32(uniforms.width) - 1, i32(uniforms.height) - 1));
+}
+
+// Which surface a pixel shows: its part ID, or BACKGROUND where nothing was drawn
+fn surface(coords: vec2<i32>) -> u32 {
+    let clamped = clamp_coords(coords);
+    if (textureLoad(direction_texture, clamped, 0).a >= 1.0) {
+        return BACKGROUND;
+    }
+    return textureLoad(part_id_texture, clamped, 0).r;
+}
+
+// Quantize a gradient to an edge direction (0=| 1=- 2=/ 3=\), with sobel_edges' thresholds
+fn quantize_direction(gx: f32, gy: f32) -> f32 {
+    let theta = atan2(gy, gx);
+    let abs_theta = abs(theta) / PI;
+    if (abs_theta < 0.05 || abs_theta > 0.9) {
+        return 0.0;
+    }
+    if (abs_theta > 0.45 && abs_theta < 0.55) {
+        return 1.0;
+    }
+    if (abs_theta < 0.45) {
+        return select(2.0, 3.0, theta > 0.0);
+    }
+    return select(3.0, 2.0, theta > 0.0);
+}
+
+// Silhouette mask: R = direction (-1 if none), G = 1 on silhouette pixels
+// Only the drawn side of a boundary is marked; the direction runs along the boundary of the
+// pixel's own surface, so it holds where the two sides sit at the same depth
+@compute @workgroup_size(16, 16)
+fn mark(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let coords = vec2<i32>(gid.xy);
+    if (coords.x >= i32(uniforms.width) || coords.y >= i32(uniforms.height)) {
+        return;
+    }
+
+    let center = surface(coords);
+    var boundary = false;
+    var gx: f32 = 0.0;
+    var gy: f32 = 0.0;
+    for (var dy: i32 = -1; dy <= 1; dy++) {
+        for (var dx: i32 = -1; dx <= 1; dx++) {
+            // Sobel over "same surface as the center"
+            let same = select(0.0, 1.0, surface(coords + vec2<i32>(dx, dy)) == center);
+            gx += f32(dx * (2 - abs(dy))) * same;
+            gy += f32(dy * (2 - abs(dx))) * same;
+            if (same < 0.5) {
+                boundary = true;
+            }
+        }
+    }
+
+    if (boundary && center != BACKGROUND) {
+        textureStore(output_texture, coords, vec4<f32>(quantize_direction(gx, gy), 1.0, 0.0, 0.0));
+    } else {
+        textureStore(output_texture, coords, vec4<f32>(-1.0, 0.0, 0.0, 0.0));
+    }
+}
+
+// Nearest silhouette pixel along the row: R = its direction, G = 1 if found, B = its distance
+@compute @workgroup_size(16, 16)
+fn spread_x(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let coords = vec2<i32>(gid.xy);
+    if (coords.x >= i32(uniforms.width) || coords.y >= i32(uniforms.height)) {
+        return;
+    }
+
+    let radius = i32(uniforms.radius_x);
+    var result = vec4<f32>(-1.0, 0.0, 0.0, 0.0);
+    var best = radius + 1;
+    for (var dx: i32 = -radius; dx <= radius; dx++) {
+        let sample_coords = coords + vec2<i32>(dx, 0);
+        if (sample_coords.x < 0 || sample_coords.x >= i32(uniforms.width)) {
+            continue;
+        }
+        let mask = textureLoad(source_texture, sample_coords, 0);
+        if (mask.g > 0.5 && abs(dx) < best) {
+            best = abs(dx);
+            result = vec4<f32>(mask.r, 1.0, f32(best), 0.0);
+        }
+    }
+    textureStore(output_texture, coords, result);
+}
+
+// Nearest silhouette pixel within the ellipse, written over the direction texture as an edge in
+// its direction, flagged 2 so the ASCII pass can tell outlines from detail edges (luminance and
+// depth stay the pixel's own)
+@compute @workgroup_size(16, 16)
+fn spread_y(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let coords = vec2<i32>(gid.xy);
+    if (coords.x >= i32(uniforms.width) || coords.y >= i32(uniforms.height)) {
+        return;
+    }
+
+    let radius = i32(uniforms.radius_y);
+    let reach = vec2<f32>(uniforms.radius_x, uniforms.radius_y) + 0.5;
+    var direction: f32 = -1.0;
+    var best: f32 = 1.0;
+    for (var dy: i32 = -radius; dy <= radius; dy++) {
+        let sample_coords = coords + vec2<i32>(0, dy);
+        if (sample_coords.y < 0 || sample_coords.y >= i32(uniforms.height)) {
+            continue;
+        }
+        let row = textureLoad(source_texture, sample_coords, 0);
+        if (row.g < 0.5) {
+            continue;
+        }
+        let offset = vec2<f32>(row.b, f32(dy)) / reach;
+        let distance = dot(offset, offset);
+        if (distance <= best) {
+            best = distance;
+            direction = row.r;
+        }
+    }
+
+    let base = textureLoad(direction_texture, coords, 0);
+    if (direction >= 0.0) {
+        textureStore(output_texture, coords, vec4<f32>(direction, 2.0, base.b, base.a));
+    } else {
+        textureStore(output_texture, coords, base);
+    }
+}
@@ -0,0 +1,48 @@
+// Raw Preview Pass
+// Averages the display-transformed scene color over each preview cell, without any ASCII
+// conversion, for the picture-in-picture preview
+// Outputs packed RGB in the same 0xRRGGBBCC layout as the ASCII pass (character 0)
+
+struct Uniforms {
+    tex_width: u32,
+    tex_height: u32,
+    cols: u32,
+    rows: u32,
+};
+
+@group(0) @binding(0)
+var color_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var<uniform> uniforms: Uniforms;
+
+@group(0) @binding(2)
+var<storage, read_write> output: array<u32>;
+
+// Samples per cell side
+const SAMPLES: u32 = 4u;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= uniforms.cols || gid.y >= uniforms.rows) {
+        return;
+    }
+
+    let cell_width = f32(uniforms.tex_width) / f32(uniforms.cols);
+    let cell_height = f32(uniforms.tex_height) / f32(uniforms.rows);
+    var sum = vec3<f32>(0.0);
+    for (var sy = 0u; sy < SAMPLES; sy++) {
+        for (var sx = 0u; sx < SAMPLES; sx++) {
+            let px = u32((f32(gid.x) + (f32(sx) + 0.5) / f32(SAMPLES)) * cell_width);
+            let py = u32((f32(gid.y) + (f32(sy) + 0.5) / f32(SAMPLES)) * cell_height);
+            let coords = vec2<i32>(vec2<u32>(min(px, uniforms.tex_width - 1u), min(py, uniforms.tex_height - 1u)));
+            sum += textureLoad(color_texture, coords, 0).rgb;
+        }
+    }
+    let color = saturate(sum / f32(SAMPLES * SAMPLES));
+
+    let r = u32(color.r * 255.0);
+    let g = u32(color.g * 255.0);
+    let b = u32(color.b * 255.0);
+    output[gid.y * uniforms.cols + gid.x] = (r << 24u) | (g << 16u) | (b << 8u);
+}
@@ -0,0 +1,246 @@
+// Raymarched signed-distance-field scenes - a fullscreen triangle that sphere-traces each pixel's
+// ray through the object, writing color and depth just like the rasterized model
+
+struct Uniforms {
+    inv_view_proj: mat4x4<f32>,
+    view_proj: mat4x4<f32>,
+    model: mat4x4<f32>,
+    inv_model: mat4x4<f32>,
+    prev_mvp: mat4x4<f32>,   // Last frame's model-view-projection, for motion vectors
+    light_dir: vec4<f32>,
+    scene: u32,              // 0 = Mandelbulb, 1 = Gyroid, 2 = Rounded boxes
+    lighting_mode: u32,
+    light_intensity: f32,
+    time: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Fullscreen triangle: (-1, -1), (3, -1), (-1, 3)
+    let x = f32(i32(vertex_index & 1u) * 4 - 1);
+    let y = f32(i32(vertex_index >> 1u) * 4 - 1);
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(x, y, 0.0, 1.0);
+    out.ndc = vec2<f32>(x, y);
+    return out;
+}
+
+const MAX_STEPS: i32 = 160;
+// Every scene fits in this sphere (object space), so rays start and stop at its surface
+const BOUND_RADIUS: f32 = 1.2;
+// Hit distance, relative to the distance travelled (coarser far away)
+const HIT_EPSILON: f32 = 0.0004;
+
+// Smooth minimum: unions that blend over a distance of k
+fn smin(a: f32, b: f32, k: f32) -> f32 {
+    let h = clamp(0.5 + 0.5 * (b - a) / k, 0.0, 1.0);
+    return mix(b, a, h) - k * h * (1.0 - h);
+}
+
+fn sd_round_box(p: vec3<f32>, half: vec3<f32>, radius: f32) -> f32 {
+    let q = abs(p) - half + vec3<f32>(radius);
+    return length(max(q, vec3<f32>(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0) - radius;
+}
+
+// Power-8 Mandelbulb: (distance estimate, orbit trap for coloring), scaled to about 0.8 units
+const BULB_SCALE: f32 = 0.7;
+
+fn mandelbulb(p: vec3<f32>) -> vec2<f32> {
+    let c = p / BULB_SCALE;
+    // The power slowly breathes so the surface keeps changing
+    let power = 8.0 + sin(uniforms.time * 0.2) * 1.0;
+    var z = c;
+    var dr = 1.0;
+    var r = length(z);
+    var trap = 1e10;
+    for (var i = 0; i < 8; i++) {
+        r = max(length(z), 1e-6);
+        if (r > 2.0) {
+            break;
+        }
+        let theta = acos(clamp(z.z / r, -1.0, 1.0)) * power;
+        let phi = atan2(z.y, z.x) * power;
+        dr = pow(r, power - 1.0) * power * dr + 1.0;
+        z = pow(r, power) * vec3<f32>(sin(theta) * cos(phi), sin(phi) * sin(theta), cos(theta)) + c;
+        trap = min(trap, r);
+    }
+    return vec2<f32>(0.5 * log(r) * r / dr * BULB_SCALE, trap);
+}
+
+// Gyroid sheet carved to a sphere, its phase drifting over time
+fn gyroid(p: vec3<f32>) -> f32 {
+    let frequency = 7.0;
+    let q = p * frequency + vec3<f32>(uniforms.time * 0.4);
+    let sheet = abs(dot(sin(q), cos(q.zxy))) / frequency - 0.03;
+    // Scaled down: the gyroid expression overestimates distance
+    return max(length(p) - 0.85, sheet * 0.6);
+}
+
+// A rounded cube with three rounded bars through it, blended together
+fn rounded_boxes(p: vec3<f32>) -> f32 {
+    var d = sd_round_box(p, vec3<f32>(0.42), 0.1);
+    d = smin(d, sd_round_box(p, vec3<f32>(0.8, 0.14, 0.14), 0.07), 0.12);
+    d = smin(d, sd_round_box(p, vec3<f32>(0.14, 0.8, 0.14), 0.07), 0.12);
+    d = smin(d, sd_round_box(p, vec3<f32>(0.14, 0.14, 0.8), 0.07), 0.12);
+    // Hollow out a sphere so the bars show through the cube's faces
+    return max(d, 0.52 - length(p));
+}
+
+// Distance from an object-space point to the selected scene's surface
+fn scene_distance(p: vec3<f32>) -> f32 {
+    if (uniforms.scene == 0u) {
+        return mandelbulb(p).x;
+    } else if (uniforms.scene == 1u) {
+        return gyroid(p);
+    }
+    return rounded_boxes(p);
+}
+
+// Base color of the surface at an object-space point
+fn scene_color(p: vec3<f32>) -> vec3<f32> {
+    if (uniforms.scene == 0u) {
+        let trap = clamp(mandelbulb(p).y, 0.0, 1.0);
+        return mix(vec3<f32>(0.95, 0.55, 0.2), vec3<f32>(0.35, 0.55, 0.95), trap);
+    } else if (uniforms.scene == 1u) {
+        return vec3<f32>(0.4, 0.85, 0.6) + p * 0.25;
+    }
+    // Bars and cube in different colors, blending where they meet
+    let bar = clamp((max(abs(p.x), max(abs(p.y), abs(p.z))) - 0.5) * 4.0, 0.0, 1.0);
+    return mix(vec3<f32>(0.85, 0.85, 0.9), vec3<f32>(0.95, 0.4, 0.3), bar);
+}
+
+// Surface normal from the distance gradient (tetrahedral samples)
+fn scene_normal(p: vec3<f32>) -> vec3<f32> {
+    let e = vec2<f32>(1.0, -1.0) * 0.0007;
+    return normalize(
+        e.xyy * scene_distance(p + e.xyy) +
+        e.yyx * scene_distance(p + e.yyx) +
+        e.yxy * scene_distance(p + e.yxy) +
+        e.xxx * scene_distance(p + e.xxx)
+    );
+}
+
+// Lighting, as in cube.wgsl
+fn calc_diffuse(normal: vec3<f32>) -> f32 {
+    let key_light_dir = normalize(uniforms.light_dir.xyz);
+    let key_diffuse = max(dot(normal, key_light_dir), 0.0);
+
+    let fill_light_dir = normalize(vec3<f32>(-0.5, 0.3, -0.7));
+    let fill_diffuse = max(dot(normal, fill_light_dir), 0.0) * 0.4;
+
+    let rim_light_dir = normalize(vec3<f32>(0.0, 0.0, -1.0));
+    let rim_diffuse = max(dot(normal, rim_light_dir), 0.0) * 0.3;
+
+    let up = vec3<f32>(0.0, 1.0, 0.0);
+    let hemisphere_factor = dot(normal, up) * 0.5 + 0.5;
+    let ambient = mix(0.2, 0.4, hemisphere_factor);
+
+    return ambient + key_diffuse * 0.5 * uniforms.light_intensity + fill_diffuse + rim_diffuse;
+}
+
+fn calc_specular(normal: vec3<f32>, view_dir: vec3<f32>) -> f32 {
+    let key_light_dir = normalize(uniforms.light_dir.xyz);
+    let half_dir = normalize(key_light_dir + view_dir);
+    let spec = pow(max(dot(normal, half_dir), 0.0), 32.0);
+    return spec * 0.5 * uniforms.light_intensity;
+}
+
+fn toon_shade(intensity: f32) -> f32 {
+    if (intensity > 0.95) { return 1.0; }
+    else if (intensity > 0.5) { return 0.7; }
+    else if (intensity > 0.25) { return 0.4; }
+    else { return 0.2; }
+}
+
+fn hsv2rgb(h: f32, s: f32, v: f32) -> vec3<f32> {
+    let k = vec3<f32>(1.0, 2.0 / 3.0, 1.0 / 3.0);
+    let p = abs(fract(vec3<f32>(h) + k) * 6.0 - vec3<f32>(3.0));
+    return v * mix(vec3<f32>(1.0), clamp(p - vec3<f32>(1.0), vec3<f32>(0.0), vec3<f32>(1.0)), s);
+}
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @location(1) motion: vec2<f32>,  // Screen movement since the previous frame (x right, y down)
+    @builtin(frag_depth) depth: f32,
+};
+
+@fragment
+fn fs_main(in: VertexOutput) -> FragmentOutput {
+    // World-space ray through the pixel, then into object space so the model rotation applies
+    let near = uniforms.inv_view_proj * vec4<f32>(in.ndc, 0.0, 1.0);
+    let far = uniforms.inv_view_proj * vec4<f32>(in.ndc, 1.0, 1.0);
+    let eye = near.xyz / near.w;
+    let origin = (uniforms.inv_model * vec4<f32>(eye, 1.0)).xyz;
+    let dir = normalize((uniforms.inv_model * vec4<f32>(far.xyz / far.w - eye, 0.0)).xyz);
+
+    // Clip the ray to the bounding sphere
+    let b = dot(origin, dir);
+    let c = dot(origin, origin) - BOUND_RADIUS * BOUND_RADIUS;
+    let h = b * b - c;
+    if (h < 0.0) {
+        discard;
+    }
+    var t = max(-b - sqrt(h), 0.0);
+    let t_exit = -b + sqrt(h);
+
+    var hit = false;
+    var steps = 0;
+    for (; steps < MAX_STEPS; steps++) {
+        let d = scene_distance(origin + dir * t);
+        if (d < HIT_EPSILON * max(t, 1.0)) {
+            hit = true;
+            break;
+        }
+        t += d;
+        if (t > t_exit) {
+            break;
+        }
+    }
+    if (!hit) {
+        discard;
+    }
+
+    let p = origin + dir * t;
+    let world_pos = (uniforms.model * vec4<f32>(p, 1.0)).xyz;
+    let normal = normalize((uniforms.model * vec4<f32>(scene_normal(p), 0.0)).xyz);
+    let view_dir = normalize(eye - world_pos);
+    let color = scene_color(p);
+    // Rays that needed many steps graze crevices: darken them as cheap ambient occlusion
+    let occlusion = 1.0 - f32(steps) / f32(MAX_STEPS) * 0.6;
+
+    var final_color: vec3<f32>;
+    let mode = uniforms.lighting_mode;
+    if (mode == 0u) {
+        final_color = color;
+    } else if (mode == 2u) {
+        final_color = color * calc_diffuse(normal) * occlusion + vec3<f32>(calc_specular(normal, view_dir));
+    } else if (mode == 3u) {
+        final_color = color * toon_shade(calc_diffuse(normal));
+        let edge_factor = 1.0 - pow(1.0 - abs(dot(normal, view_dir)), 2.0);
+        final_color = final_color * mix(0.3, 1.0, edge_factor);
+    } else if (mode == 4u) {
+        let hue = fract(world_pos.y * 0.3 + 0.5);
+        final_color = hsv2rgb(hue, 0.8, 0.9) * (calc_diffuse(normal) * 0.5 + 0.5) * occlusion;
+    } else if (mode == 5u) {
+        final_color = normal * 0.5 + vec3<f32>(0.5);
+    } else {
+        final_color = color * calc_diffuse(normal) * occlusion;
+    }
+
+    let clip = uniforms.view_proj * vec4<f32>(world_pos, 1.0);
+    let previous_clip = uniforms.prev_mvp * vec4<f32>(p, 1.0);
+    var out: FragmentOutput;
+    out.color = vec4<f32>(final_color, 1.0);
+    out.motion = (clip.xy / clip.w - previous_clip.xy / previous_clip.w) * vec2<f32>(0.5, -0.5);
+    out.depth = clip.z / clip.w;
+    return out;
+}
@@ -27,7 +27,56 @@ var skybox_texture: texture_2d<f32>;
 @group(0) @binding(1)
 var skybox_sampler: sampler;
 
+// Image shown before the last change, crossfaded into the current one
+@group(0) @binding(3)
+var previous_texture: texture_2d<f32>;
+
+struct SkyboxUniforms {
+    fade: f32,                  // 0 = previous image, 1 = current image
+    has_previous: u32,          // 0 = fade in from black
+    offset: f32,                // Horizontal scroll in turns (auto-rotation + sidecar offset)
+    projection: u32,            // 0 = stretch, 1 = equirectangular panorama
+    previous_offset: f32,
+    previous_projection: u32,
+    intensity: f32,             // Brightness multiplier
+    blur: f32,                  // Mip level bias (0 = sharp)
+};
+
+@group(0) @binding(4)
+var<uniform> skybox: SkyboxUniforms;
+
+// Texture coordinates for a screen position
+fn skybox_uv(uv: vec2<f32>, offset: f32, projection: u32) -> vec2<f32> {
+    if (projection == 1u) {
+        // Panorama: a 90 degree window (a quarter of the width, half the height)
+        return vec2<f32>(offset + (uv.x - 0.5) * 0.25, 0.5 + (uv.y - 0.5) * 0.5);
+    }
+    return vec2<f32>(uv.x + offset, uv.y);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(skybox_texture, skybox_sampler, in.uv);
+    let current_uv = skybox_uv(in.uv, skybox.offset, skybox.projection);
+    let current = textureSampleBias(skybox_texture, skybox_sampler, current_uv, skybox.blur);
+    var previous = vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    if (skybox.has_previous == 1u) {
+        let previous_uv = skybox_uv(in.uv, skybox.previous_offset, skybox.previous_projection);
+        previous = textureSampleBias(previous_texture, skybox_sampler, previous_uv, skybox.blur);
+    }
+    let color = mix(previous, current, skybox.fade);
+    return vec4<f32>(color.rgb * skybox.intensity, color.a);
+}
+
+// Two-color vertical gradient, drawn with the same fullscreen triangle
+struct GradientUniforms {
+    top: vec4<f32>,
+    bottom: vec4<f32>,
+};
+
+@group(0) @binding(2)
+var<uniform> gradient: GradientUniforms;
+
+@fragment
+fn fs_gradient(in: VertexOutput) -> @location(0) vec4<f32> {
+    return mix(gradient.top, gradient.bottom, clamp(in.uv.y, 0.0, 1.0));
 }
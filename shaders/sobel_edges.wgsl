@@ -1,15 +1,16 @@
 // Sobel Edge Direction Pass
-// Calculates edge direction from luminance gradients
+// Calculates edge direction from luminance (or depth) gradients
 // Only processes pixels marked as edges
 
 struct Uniforms {
     width: u32,
     height: u32,
-    _padding: vec2<u32>,
+    use_depth_gradient: u32,  // Orient edges by depth instead of luminance (1 = true)
+    _padding: u32,
 };
 
 @group(0) @binding(0)
-var edge_texture: texture_2d<f32>;  // From edge_detect: R=edge, G=luminance, B=depth
+var edge_texture: texture_2d<f32>;  // From edge_detect: R=edge, G=luminance, B=linear depth
 
 @group(0) @binding(1)
 var output_texture: texture_storage_2d<rgba32float, write>;
@@ -19,10 +20,11 @@ var<uniform> uniforms: Uniforms;
 
 const PI: f32 = 3.14159265359;
 
-// Sample luminance from edge texture (stored in G channel)
-fn sample_lum(coords: vec2<i32>) -> f32 {
+// Sample the value whose gradient orients edges: luminance (G channel) or depth (B channel)
+fn sample_gradient_source(coords: vec2<i32>) -> f32 {
     let clamped = clamp(coords, vec2<i32>(0), vec2<i32>(i32(uniforms.width) - 1, i32(uniforms.height) - 1));
-    return textureLoad(edge_texture, clamped, 0).g;
+    let data = textureLoad(edge_texture, clamped, 0);
+    return select(data.g, data.b, uniforms.use_depth_gradient == 1u);
 }
 
 // Sample edge strength (stored in R channel)
@@ -58,14 +60,14 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
         // [nw] [n ] [ne]
         // [w ] [c ] [e ]
         // [sw] [s ] [se]
-        let lum_nw = sample_lum(coords + vec2<i32>(-1, -1));
-        let lum_n  = sample_lum(coords + vec2<i32>(0, -1));
-        let lum_ne = sample_lum(coords + vec2<i32>(1, -1));
-        let lum_w  = sample_lum(coords + vec2<i32>(-1, 0));
-        let lum_e  = sample_lum(coords + vec2<i32>(1, 0));
-        let lum_sw = sample_lum(coords + vec2<i32>(-1, 1));
-        let lum_s  = sample_lum(coords + vec2<i32>(0, 1));
-        let lum_se = sample_lum(coords + vec2<i32>(1, 1));
+        let lum_nw = sample_gradient_source(coords + vec2<i32>(-1, -1));
+        let lum_n  = sample_gradient_source(coords + vec2<i32>(0, -1));
+        let lum_ne = sample_gradient_source(coords + vec2<i32>(1, -1));
+        let lum_w  = sample_gradient_source(coords + vec2<i32>(-1, 0));
+        let lum_e  = sample_gradient_source(coords + vec2<i32>(1, 0));
+        let lum_sw = sample_gradient_source(coords + vec2<i32>(-1, 1));
+        let lum_s  = sample_gradient_source(coords + vec2<i32>(0, 1));
+        let lum_se = sample_gradient_source(coords + vec2<i32>(1, 1));
 
         // Sobel kernels
         // Gx (horizontal gradient - detects vertical edges):
@@ -111,6 +113,6 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
         }
     }
 
-    // Output: R = direction (0-3, or -1 if not edge), G = edge flag, B = luminance, A = depth
+    // Output: R = direction (0-3, or -1 if not edge), G = edge flag, B = luminance, A = linear depth
     textureStore(output_texture, coords, vec4<f32>(direction, edge_flag, luminance, depth));
 }
@@ -0,0 +1,64 @@
+// Starfield background - instanced quads drifting towards the camera
+// Each instance is one star; the quad corners are generated from the vertex index
+
+struct Uniforms {
+    time: f32,
+    aspect: f32,
+    speed: f32,
+    _padding: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+struct StarInput {
+    @location(0) seed: vec3<f32>,    // x, y in [-1, 1], z = depth phase in [0, 1)
+    @location(1) params: vec2<f32>,  // x = speed multiplier, y = brightness
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) brightness: f32,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32, star: StarInput) -> VertexOutput {
+    // Two triangles: (0,1,2) and (0,2,3) of the quad corners
+    // Corners: 0 = (-1,-1), 1 = (1,-1), 2 = (1,1), 3 = (-1,1)
+    let corner_ids = array<u32, 6>(0u, 1u, 2u, 0u, 2u, 3u);
+    var ids = corner_ids;
+    let corner_id = ids[vertex_index];
+    let corner = vec2<f32>(
+        select(-1.0, 1.0, corner_id == 1u || corner_id == 2u),
+        select(-1.0, 1.0, corner_id >= 2u),
+    );
+
+    // Depth cycles from far (1) to near (0) and wraps around
+    let z = 1.0 - fract(star.seed.z + uniforms.time * uniforms.speed * star.params.x);
+    let depth = mix(0.05, 1.0, z);
+
+    // Perspective divide pushes stars outward as they approach
+    let center = star.seed.xy / depth;
+    let size = min(0.006 / depth, 0.03);
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(
+        center.x + corner.x * size / uniforms.aspect,
+        center.y + corner.y * size,
+        0.0,
+        1.0,
+    );
+    out.uv = corner;
+    // Fade in from the distance so stars don't pop into view
+    out.brightness = star.params.y * (1.0 - z);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Soft round falloff, blended additively over the background
+    let falloff = 1.0 - smoothstep(0.4, 1.0, length(in.uv));
+    let intensity = in.brightness * falloff;
+    return vec4<f32>(vec3<f32>(intensity), 1.0);
+}
@@ -0,0 +1,54 @@
+// Stereogram Depth Pass
+// Averages the scene's nearness over each cell from the depth buffer, for the random-character
+// stereogram output mode (the pattern itself is laid out on the CPU, row by row)
+// Outputs nearness as 0-255 per cell (0 = background or the far end of the depth window)
+
+struct Uniforms {
+    tex_width: u32,
+    tex_height: u32,
+    cols: u32,
+    rows: u32,
+    depth_near: f32,         // View distance mapped to full nearness
+    depth_far: f32,          // View distance mapped to zero nearness
+    clip_near: f32,          // Projection near plane
+    clip_far: f32,           // Projection far plane
+};
+
+@group(0) @binding(0)
+var depth_texture: texture_depth_2d;
+
+@group(0) @binding(1)
+var<uniform> uniforms: Uniforms;
+
+@group(0) @binding(2)
+var<storage, read_write> output: array<u32>;
+
+// Samples per cell side
+const SAMPLES: u32 = 4u;
+
+// Linear view distance mapped into the depth window, 1 at its near end
+fn nearness(depth: f32) -> f32 {
+    let distance = uniforms.clip_near * uniforms.clip_far
+        / (uniforms.clip_far - depth * (uniforms.clip_far - uniforms.clip_near));
+    return saturate((uniforms.depth_far - distance) / (uniforms.depth_far - uniforms.depth_near));
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= uniforms.cols || gid.y >= uniforms.rows) {
+        return;
+    }
+
+    let cell_width = f32(uniforms.tex_width) / f32(uniforms.cols);
+    let cell_height = f32(uniforms.tex_height) / f32(uniforms.rows);
+    var sum = 0.0;
+    for (var sy = 0u; sy < SAMPLES; sy++) {
+        for (var sx = 0u; sx < SAMPLES; sx++) {
+            let px = u32((f32(gid.x) + (f32(sx) + 0.5) / f32(SAMPLES)) * cell_width);
+            let py = u32((f32(gid.y) + (f32(sy) + 0.5) / f32(SAMPLES)) * cell_height);
+            let coords = vec2<i32>(vec2<u32>(min(px, uniforms.tex_width - 1u), min(py, uniforms.tex_height - 1u)));
+            sum += nearness(textureLoad(depth_texture, coords, 0));
+        }
+    }
+    output[gid.y * uniforms.cols + gid.x] = u32(saturate(sum / f32(SAMPLES * SAMPLES)) * 255.0);
+}
@@ -0,0 +1,130 @@
+// Water ripple simulation - a damped wave equation on a height grid, stepped in place on the GPU,
+// then written straight into the model's vertex buffer so the standard pipeline draws the surface
+
+struct Uniforms {
+    grid: u32,               // Grid points along each side
+    step: u32,               // Simulation step being computed (seeds the raindrops)
+    half_size: f32,          // Half the width of the surface
+    tilt: f32,               // Rotation about X (radians) tipping the far edge up towards the camera
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+// Heights two steps back, one step back, and the step being written (rotated between steps);
+// the mesh pass reads the newest heights from `current`
+@group(0) @binding(1)
+var<storage, read> previous: array<f32>;
+
+@group(0) @binding(2)
+var<storage, read> current: array<f32>;
+
+@group(0) @binding(3)
+var<storage, read_write> next: array<f32>;
+
+// Model vertices as raw floats: position, normal, color (9 per vertex); the front face of the
+// sheet fills the first grid * grid vertices and its back face the next grid * grid
+@group(0) @binding(4)
+var<storage, read_write> vertices: array<f32>;
+
+// Squared wave speed in cells per step (the explicit scheme is stable below 0.5)
+const WAVE_SPEED_SQ: f32 = 0.3;
+const DAMPING: f32 = 0.996;
+// A raindrop lands every this many steps
+const DROP_INTERVAL: u32 = 24u;
+const DROP_RADIUS: f32 = 3.5;
+const DROP_DEPTH: f32 = 0.9;
+// World-space height of a unit wave
+const HEIGHT_SCALE: f32 = 0.05;
+
+const DEEP_COLOR: vec3<f32> = vec3<f32>(0.08, 0.3, 0.6);
+const CREST_COLOR: vec3<f32> = vec3<f32>(0.75, 0.92, 1.0);
+
+fn hash(n: u32) -> u32 {
+    var h = n * 0x9e3779b9u;
+    h ^= h >> 16u;
+    h *= 0x85ebca6bu;
+    h ^= h >> 13u;
+    h *= 0xc2b2ae35u;
+    h ^= h >> 16u;
+    return h;
+}
+
+@compute @workgroup_size(8, 8)
+fn step_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let n = uniforms.grid;
+    if (gid.x >= n || gid.y >= n) {
+        return;
+    }
+    let i = gid.y * n + gid.x;
+
+    // The rim is held still, so ripples reflect off the edges of the pool
+    if (gid.x == 0u || gid.y == 0u || gid.x == n - 1u || gid.y == n - 1u) {
+        next[i] = 0.0;
+        return;
+    }
+
+    let laplacian = current[i - 1u] + current[i + 1u] + current[i - n] + current[i + n] - 4.0 * current[i];
+    var height = (2.0 * current[i] - previous[i] + WAVE_SPEED_SQ * laplacian) * DAMPING;
+
+    // A raindrop pushes down a smooth dimple at a pseudo-random point
+    if (uniforms.step % DROP_INTERVAL == 0u) {
+        let seed = hash(uniforms.step / DROP_INTERVAL);
+        let margin = 4u;
+        let span = n - 2u * margin;
+        let center = vec2<f32>(f32(margin + seed % span), f32(margin + (seed >> 16u) % span));
+        let d = distance(vec2<f32>(gid.xy), center);
+        if (d < DROP_RADIUS) {
+            height -= DROP_DEPTH * (0.5 + 0.5 * cos(d / DROP_RADIUS * 3.14159265));
+        }
+    }
+    next[i] = height;
+}
+
+fn height_at(x: u32, y: u32) -> f32 {
+    let n = uniforms.grid;
+    return current[min(y, n - 1u) * n + min(x, n - 1u)] * HEIGHT_SCALE;
+}
+
+fn write_vertex(index: u32, position: vec3<f32>, normal: vec3<f32>, color: vec3<f32>) {
+    let base = index * 9u;
+    vertices[base] = position.x;
+    vertices[base + 1u] = position.y;
+    vertices[base + 2u] = position.z;
+    vertices[base + 3u] = normal.x;
+    vertices[base + 4u] = normal.y;
+    vertices[base + 5u] = normal.z;
+    vertices[base + 6u] = color.x;
+    vertices[base + 7u] = color.y;
+    vertices[base + 8u] = color.z;
+}
+
+@compute @workgroup_size(8, 8)
+fn mesh_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let n = uniforms.grid;
+    if (gid.x >= n || gid.y >= n) {
+        return;
+    }
+    let cell = 2.0 * uniforms.half_size / f32(n - 1u);
+    let height = height_at(gid.x, gid.y);
+
+    // Central differences (one-sided at the rim) for the slope
+    let dx = (height_at(gid.x + 1u, gid.y) - height_at(max(gid.x, 1u) - 1u, gid.y)) / (2.0 * cell);
+    let dz = (height_at(gid.x, gid.y + 1u) - height_at(gid.x, max(gid.y, 1u) - 1u)) / (2.0 * cell);
+    let flat_position = vec3<f32>(f32(gid.x) * cell - uniforms.half_size, height, f32(gid.y) * cell - uniforms.half_size);
+    let flat_normal = normalize(vec3<f32>(-dx, 1.0, -dz));
+
+    let c = cos(uniforms.tilt);
+    let s = sin(uniforms.tilt);
+    let tilt = mat3x3<f32>(vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, c, s), vec3<f32>(0.0, -s, c));
+    let position = tilt * flat_position;
+    let normal = tilt * flat_normal;
+
+    // Crests catch the light as foam, troughs stay deep blue
+    let crest = clamp(height / HEIGHT_SCALE * 1.5 + 0.3, 0.0, 1.0);
+    let color = mix(DEEP_COLOR, CREST_COLOR, crest * crest);
+
+    let i = gid.y * n + gid.x;
+    write_vertex(i, position, normal, color);
+    write_vertex(n * n + i, position, -normal, color);
+}
@@ -0,0 +1,166 @@
+use anyhow::{anyhow, Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{SampleFormat, SizedSample, Stream, StreamConfig};
+use std::sync::{Arc, Mutex};
+
+use super::AudioLevels;
+
+/// Upper edge of the bass band (Hz)
+const BASS_CUTOFF: f32 = 250.0;
+
+/// Upper edge of the mid band (Hz); everything above is treble
+const MID_CUTOFF: f32 = 4000.0;
+
+/// Per-update decay of the auto-gain peak, so quiet passages still move the visuals
+const PEAK_DECAY: f32 = 0.995;
+
+/// Peak floor so silence doesn't get amplified into noise
+const MIN_PEAK: f32 = 0.002;
+
+/// How quickly levels rise and fall (fraction of the gap closed per update)
+const ATTACK: f32 = 0.6;
+const RELEASE: f32 = 0.15;
+
+/// Splits a mono signal into bass/mid/treble with one-pole filters and accumulates band energy
+struct BandSplitter {
+    bass_coeff: f32,
+    mid_coeff: f32,
+    bass_lp: f32,
+    mid_lp: f32,
+    /// Sum of squared samples per band since the last read
+    energy: [f32; 3],
+    samples: u32,
+}
+
+impl BandSplitter {
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            bass_coeff: one_pole_coeff(BASS_CUTOFF, sample_rate),
+            mid_coeff: one_pole_coeff(MID_CUTOFF, sample_rate),
+            bass_lp: 0.0,
+            mid_lp: 0.0,
+            energy: [0.0; 3],
+            samples: 0,
+        }
+    }
+
+    fn push(&mut self, sample: f32) {
+        self.bass_lp += (sample - self.bass_lp) * self.bass_coeff;
+        self.mid_lp += (sample - self.mid_lp) * self.mid_coeff;
+        let bands = [self.bass_lp, self.mid_lp - self.bass_lp, sample - self.mid_lp];
+        for (energy, band) in self.energy.iter_mut().zip(bands) {
+            *energy += band * band;
+        }
+        self.samples += 1;
+    }
+
+    /// RMS per band since the last call (None if no samples arrived)
+    fn take_rms(&mut self) -> Option<[f32; 3]> {
+        if self.samples == 0 {
+            return None;
+        }
+        let n = self.samples as f32;
+        let rms = self.energy.map(|e| (e / n).sqrt());
+        self.energy = [0.0; 3];
+        self.samples = 0;
+        Some(rms)
+    }
+}
+
+/// Smoothing coefficient of a one-pole low-pass filter
+fn one_pole_coeff(cutoff: f32, sample_rate: u32) -> f32 {
+    1.0 - (-2.0 * std::f32::consts::PI * cutoff / sample_rate as f32).exp()
+}
+
+/// Captures the default input device and tracks bass/mid/treble levels
+/// The stream stops when this is dropped
+pub struct AudioInput {
+    _stream: Stream,
+    splitter: Arc<Mutex<BandSplitter>>,
+    peaks: [f32; 3],
+    levels: [f32; 3],
+    device_name: String,
+}
+
+impl AudioInput {
+    /// Open the default input device (microphone or loopback/monitor source)
+    pub fn open() -> Result<Self> {
+        let host = cpal::default_host();
+        let device = host
+            .default_input_device()
+            .ok_or_else(|| anyhow!("No audio input device found"))?;
+        let device_name = device.name().unwrap_or_else(|_| "Unknown".to_string());
+        let supported = device
+            .default_input_config()
+            .with_context(|| format!("Failed to query audio input {}", device_name))?;
+        let config: StreamConfig = supported.config();
+        let splitter = Arc::new(Mutex::new(BandSplitter::new(config.sample_rate.0)));
+
+        let stream = match supported.sample_format() {
+            SampleFormat::F32 => build_stream::<f32>(&device, &config, splitter.clone()),
+            SampleFormat::I16 => build_stream::<i16>(&device, &config, splitter.clone()),
+            SampleFormat::U16 => build_stream::<u16>(&device, &config, splitter.clone()),
+            other => return Err(anyhow!("Unsupported audio sample format {:?}", other)),
+        }
+        .with_context(|| format!("Failed to open audio input {}", device_name))?;
+        stream.play().context("Failed to start audio capture")?;
+
+        Ok(Self {
+            _stream: stream,
+            splitter,
+            peaks: [MIN_PEAK; 3],
+            levels: [0.0; 3],
+            device_name,
+        })
+    }
+
+    pub fn device_name(&self) -> &str {
+        &self.device_name
+    }
+
+    /// Fold in the audio captured since the last call and return the smoothed levels
+    pub fn update(&mut self) -> AudioLevels {
+        let rms = self.splitter.lock().ok().and_then(|mut s| s.take_rms());
+        if let Some(rms) = rms {
+            for ((peak, level), rms) in self.peaks.iter_mut().zip(self.levels.iter_mut()).zip(rms) {
+                // Auto-gain: normalize against a slowly decaying peak
+                *peak = (*peak * PEAK_DECAY).max(rms).max(MIN_PEAK);
+                let target = (rms / *peak).clamp(0.0, 1.0);
+                let rate = if target > *level { ATTACK } else { RELEASE };
+                *level += (target - *level) * rate;
+            }
+        }
+        AudioLevels {
+            bass: self.levels[0],
+            mid: self.levels[1],
+            treble: self.levels[2],
+        }
+    }
+}
+
+/// Build an input stream that mixes each frame down to mono and feeds the band splitter
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    splitter: Arc<Mutex<BandSplitter>>,
+) -> Result<Stream>
+where
+    T: SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    let channels = config.channels.max(1) as usize;
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            if let Ok(mut splitter) = splitter.lock() {
+                for frame in data.chunks(channels) {
+                    let sum: f32 = frame.iter().map(|&s| cpal::Sample::to_sample::<f32>(s)).sum();
+                    splitter.push(sum / frame.len() as f32);
+                }
+            }
+        },
+        |err| log::warn!("Audio input error: {}", err),
+        None,
+    )?;
+    Ok(stream)
+}
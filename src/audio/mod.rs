@@ -0,0 +1,58 @@
+#[cfg(feature = "audio")]
+mod capture;
+
+#[cfg(feature = "audio")]
+pub use capture::AudioInput;
+
+/// Stand-in for builds without the `audio` feature; it can never be opened
+#[cfg(not(feature = "audio"))]
+pub enum AudioInput {}
+
+#[cfg(not(feature = "audio"))]
+impl AudioInput {
+    pub fn open() -> anyhow::Result<Self> {
+        Err(anyhow::anyhow!(
+            "--audio is not available in this build (rebuild with --features audio)"
+        ))
+    }
+
+    pub fn device_name(&self) -> &str {
+        match *self {}
+    }
+
+    pub fn update(&mut self) -> AudioLevels {
+        match *self {}
+    }
+}
+
+/// Normalized band energies in 0..1 (all zero = no modulation)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AudioLevels {
+    pub bass: f32,
+    pub mid: f32,
+    pub treble: f32,
+}
+
+impl AudioLevels {
+    /// Extra rotation speed from the bass, as a multiple of the configured speed
+    pub fn spin_boost(&self) -> f32 {
+        self.bass * 2.0
+    }
+
+    /// Key light multiplier driven by the mids
+    pub fn light_intensity(&self) -> f32 {
+        1.0 + self.mid * 1.5
+    }
+
+    /// Exposure multiplier driven by the treble
+    pub fn exposure_scale(&self) -> f32 {
+        1.0 + self.treble * 0.8
+    }
+
+    /// Compact level meter for the status bar, e.g. "B▆ M▃ T▁"
+    pub fn meter(&self) -> String {
+        const BARS: &[char] = &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let bar = |level: f32| BARS[((level.clamp(0.0, 1.0) * (BARS.len() - 1) as f32).round()) as usize];
+        format!("B{} M{} T{}", bar(self.bass), bar(self.mid), bar(self.treble))
+    }
+}
@@ -0,0 +1,159 @@
+//! Character ramp calibration: measures how much of a terminal cell each ramp character inks in
+//! the user's font, for the `calibrate` subcommand, so the fill characters can be reordered and
+//! weighted to match (the AcerolaFX order is wrong for many fonts)
+
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::cli::CalibrateArgs;
+use crate::gpu::{CharRamp, RAMP_CHARS};
+use crate::terminal::{ASCII_RAMP, EDGE_CHARS};
+
+/// Monospace fonts tried when no `--font` is given (common terminal defaults)
+const DEFAULT_FONTS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
+    "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSansMono.ttf",
+    "/System/Library/Fonts/Menlo.ttc",
+    "/System/Library/Fonts/SFNSMono.ttf",
+    "C:\\Windows\\Fonts\\consola.ttf",
+    "C:\\Windows\\Fonts\\CascadiaMono.ttf",
+];
+
+/// Sample grid over one cell (columns, rows), about as fine as a large terminal font
+const SAMPLES: (usize, usize) = (48, 96);
+
+/// Line segments each curve is flattened into
+const CURVE_STEPS: usize = 8;
+
+/// Glyph outline flattened into closed polygons, in font units
+struct Polygons {
+    contours: Vec<Vec<(f32, f32)>>,
+}
+
+impl Polygons {
+    fn last(&self) -> (f32, f32) {
+        self.contours.last().and_then(|contour| contour.last()).copied().unwrap_or((0.0, 0.0))
+    }
+
+    fn push(&mut self, point: (f32, f32)) {
+        if let Some(contour) = self.contours.last_mut() {
+            contour.push(point);
+        }
+    }
+}
+
+impl ttf_parser::OutlineBuilder for Polygons {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.contours.push(vec![(x, y)]);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push((x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last();
+        for step in 1..=CURVE_STEPS {
+            let t = step as f32 / CURVE_STEPS as f32;
+            let u = 1.0 - t;
+            self.push((u * u * x0 + 2.0 * u * t * x1 + t * t * x, u * u * y0 + 2.0 * u * t * y1 + t * t * y));
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x0, y0) = self.last();
+        for step in 1..=CURVE_STEPS {
+            let t = step as f32 / CURVE_STEPS as f32;
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            self.push((a * x0 + b * x1 + c * x2 + d * x, a * y0 + b * y1 + c * y2 + d * y));
+        }
+    }
+
+    fn close(&mut self) {}
+}
+
+/// Fraction of the cell (advance width by ascender-to-descender height) inked by a glyph,
+/// sampled on a grid with the nonzero winding rule so overlapping strokes count once
+fn glyph_coverage(face: &ttf_parser::Face, c: char) -> f32 {
+    let Some(glyph) = face.glyph_index(c) else {
+        return 0.0;
+    };
+    let mut outline = Polygons { contours: Vec::new() };
+    if face.outline_glyph(glyph, &mut outline).is_none() {
+        return 0.0;
+    }
+
+    let width = face.glyph_hor_advance(glyph).unwrap_or(face.units_per_em()) as f32;
+    let (top, bottom) = (face.ascender() as f32, face.descender() as f32);
+    let (cols, rows) = SAMPLES;
+    let mut inked = 0;
+    let mut crossings: Vec<(f32, i32)> = Vec::new();
+    for row in 0..rows {
+        let y = top - (row as f32 + 0.5) / rows as f32 * (top - bottom);
+        // Where each edge crosses this row, and which way it winds
+        crossings.clear();
+        for contour in &outline.contours {
+            for (i, &(x0, y0)) in contour.iter().enumerate() {
+                let (x1, y1) = contour[(i + 1) % contour.len()];
+                if (y0 <= y) != (y1 <= y) {
+                    let x = x0 + (y - y0) / (y1 - y0) * (x1 - x0);
+                    crossings.push((x, if y1 > y0 { 1 } else { -1 }));
+                }
+            }
+        }
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for col in 0..cols {
+            let x = (col as f32 + 0.5) / cols as f32 * width;
+            let winding: i32 = crossings.iter().take_while(|&&(cx, _)| cx < x).map(|&(_, dir)| dir).sum();
+            if winding != 0 {
+                inked += 1;
+            }
+        }
+    }
+    inked as f32 / (cols * rows) as f32
+}
+
+/// Ink coverage of every ramp character in a font, in cell index order
+fn measure_coverage(font_data: &[u8], font_path: &Path) -> Result<[f32; RAMP_CHARS]> {
+    let face = ttf_parser::Face::parse(font_data, 0)
+        .map_err(|e| anyhow!("Failed to parse font {}: {}", font_path.display(), e))?;
+    let chars: Vec<char> = ASCII_RAMP.iter().chain(EDGE_CHARS).copied().collect();
+    if let Some(missing) = chars.iter().find(|&&c| c != ' ' && face.glyph_index(c).is_none()) {
+        return Err(anyhow!("Font {} has no {:?} glyph", font_path.display(), missing));
+    }
+    Ok(std::array::from_fn(|i| glyph_coverage(&face, chars[i])))
+}
+
+/// Run the `calibrate` subcommand: measure the font's ramp coverage and print it with the
+/// dark-to-bright order it gives, ready to paste into the config file
+pub fn run(args: &CalibrateArgs) -> Result<()> {
+    let font_path = match &args.font {
+        Some(path) => path.clone(),
+        None => DEFAULT_FONTS
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.exists())
+            .ok_or_else(|| anyhow!("No default monospace font found; pass your terminal's font with --font FILE"))?,
+    };
+    let font_data = std::fs::read(&font_path).with_context(|| format!("Failed to read font {}", font_path.display()))?;
+    let coverage = measure_coverage(&font_data, &font_path)?;
+
+    println!("Ink coverage in {}:", font_path.display());
+    for (c, value) in ASCII_RAMP.iter().chain(EDGE_CHARS).zip(&coverage) {
+        println!("  '{}'  {:5.1}%", c, value * 100.0);
+    }
+    let order: String = CharRamp::from_coverage(&coverage).order().iter().map(|&i| ASCII_RAMP[i as usize]).collect();
+    let default_order: String = ASCII_RAMP.iter().collect();
+    if order == default_order {
+        println!("\nDark-to-bright order {:?} matches the default ramp", order);
+    } else {
+        println!("\nDark-to-bright order {:?} (default {:?})", order, default_order);
+    }
+
+    let values: Vec<String> = coverage.iter().map(|value| format!("{:.4}", value)).collect();
+    println!("\nAdd this line to your config file to use it:\n");
+    println!("ramp_coverage = [{}]", values.join(", "));
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use std::path::Path;
+
+/// Seconds a caption takes to fade in or out
+const FADE_TIME: f32 = 0.4;
+
+/// Presets are bound to F1..F9
+pub const MAX_PRESETS: usize = 9;
+
+struct Caption {
+    text: String,
+    /// Animation time the caption appeared
+    shown_at: f32,
+    /// Animation time it starts fading out (None = until replaced or hidden)
+    hide_at: Option<f32>,
+}
+
+impl Caption {
+    fn opacity(&self, now: f32) -> f32 {
+        let fade_in = (now - self.shown_at) / FADE_TIME;
+        let fade_out = self.hide_at.map_or(1.0, |t| (t + FADE_TIME - now) / FADE_TIME);
+        fade_in.min(fade_out).clamp(0.0, 1.0)
+    }
+}
+
+/// Bottom-center caption with fade in/out, shown from hotkey presets or demo scripts
+/// Times are animation-clock seconds so fades are reproducible with --deterministic
+#[derive(Default)]
+pub struct Captions {
+    current: Option<Caption>,
+    /// Captions from the presets file, indexed by hotkey
+    presets: Vec<String>,
+    /// Preset currently shown, so pressing its key again hides it
+    active_preset: Option<usize>,
+}
+
+impl Captions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load presets: one caption per line, `#` starts a comment, `\n` breaks a line
+    /// A missing file just means no presets
+    pub fn load_presets(&mut self, path: &Path) {
+        self.presets = std::fs::read_to_string(path)
+            .map(|text| {
+                text.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .take(MAX_PRESETS)
+                    .map(|line| line.replace("\\n", "\n"))
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    /// Show `text`, fading out after `duration` seconds if given
+    pub fn show(&mut self, text: &str, now: f32, duration: Option<f32>) {
+        self.active_preset = None;
+        if text.is_empty() {
+            self.hide(now);
+            return;
+        }
+        self.current = Some(Caption {
+            text: text.to_string(),
+            shown_at: now,
+            hide_at: duration.map(|d| now + d.max(0.0)),
+        });
+    }
+
+    /// Start fading out the current caption
+    pub fn hide(&mut self, now: f32) {
+        self.active_preset = None;
+        if let Some(ref mut caption) = self.current {
+            caption.hide_at = Some(caption.hide_at.map_or(now, |t| t.min(now)));
+        }
+    }
+
+    /// Show preset `index`, or hide it if it is already showing
+    pub fn toggle_preset(&mut self, index: usize, now: f32) {
+        if self.active_preset == Some(index) {
+            self.hide(now);
+        } else if let Some(text) = self.presets.get(index).cloned() {
+            self.show(&text, now, None);
+            self.active_preset = Some(index);
+        }
+    }
+
+    /// The caption to draw and its opacity (0..1), dropping it once fully faded out
+    pub fn visible(&mut self, now: f32) -> Option<(&str, f32)> {
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|c| c.hide_at.is_some_and(|t| now >= t + FADE_TIME))
+        {
+            self.current = None;
+        }
+        self.current
+            .as_ref()
+            .map(|caption| (caption.text.as_str(), caption.opacity(now)))
+    }
+}
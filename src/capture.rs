@@ -0,0 +1,212 @@
+//! Seamless loop capture: renders exactly one rotation period and writes it as a looping GIF or
+//! an ANSI animation
+
+use anyhow::{anyhow, Context, Result};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, Rgba, RgbaImage};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::gpu::{RotationMode, TumbleRates};
+use crate::terminal::{edge_ink, half_block_edge, RenderMode};
+
+/// Frames per second of the capture (the terminal's update rate)
+const CAPTURE_FPS: f32 = 30.0;
+
+/// GIF (and PNG export) pixels per terminal cell (a 5x7 glyph with spacing, half-block pixels are 6x6)
+pub const CELL_WIDTH: u32 = 6;
+pub const CELL_HEIGHT: u32 = 12;
+/// Rows above the glyph in each GIF cell
+const GLYPH_TOP: u32 = 3;
+
+/// Text color of plain ASCII frames in the GIF
+const PLAIN_COLOR: Rgba<u8> = Rgba([204, 204, 204, 255]);
+
+/// NeuQuant speed for the GIF palette (1 = best quality, 30 = fastest)
+const GIF_QUANTIZE_SPEED: i32 = 20;
+
+/// 5x7 bitmaps for the fill ramp (` .;coPO?@#`) and edge characters (`| - \ /`), in cell index
+/// order, five bits per row with the leftmost pixel highest
+const GLYPHS: [[u8; 7]; 14] = [
+    [0, 0, 0, 0, 0, 0, 0],
+    [0, 0, 0, 0, 0, 0b01100, 0b01100],
+    [0, 0b01100, 0b01100, 0, 0b01100, 0b00100, 0b01000],
+    [0, 0, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110],
+    [0, 0, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110],
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0, 0b00100],
+    [0b01110, 0b10001, 0b10111, 0b10101, 0b10111, 0b10000, 0b01110],
+    [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010],
+    [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+    [0, 0, 0, 0b11111, 0, 0, 0],
+    [0b10000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00001, 0b00001],
+    [0b00001, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b10000],
+];
+
+/// `ascii-3d-loop-<unix time>.gif` in the working directory
+pub fn default_path() -> PathBuf {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    PathBuf::from(format!("ascii-3d-loop-{}.gif", now))
+}
+
+/// One rotation period being rendered frame by frame
+pub struct LoopCapture {
+    path: PathBuf,
+    mode: RenderMode,
+    /// Animation time of the first frame
+    start: f32,
+    /// Seconds between frames (the period divided evenly)
+    step: f32,
+    total: usize,
+    frames: Vec<(Vec<u32>, u32, u32)>,
+    /// A request for the next frame is in flight
+    pub awaiting: bool,
+}
+
+impl LoopCapture {
+    /// Plan a capture of one period of `rotation` (Tumble composed from `tumble`) starting at
+    /// animation time `start`
+    pub fn new(path: PathBuf, rotation: RotationMode, speed: f32, tumble: TumbleRates, mode: RenderMode, start: f32) -> Result<Self> {
+        let period = rotation
+            .loop_period(speed, tumble)
+            .ok_or_else(|| anyhow!("{} rotation doesn't loop", rotation.name()))?;
+        // A whole number of frames spread over exactly one period: frame N would equal frame 0
+        let total = ((period * CAPTURE_FPS).round() as usize).max(2);
+        Ok(Self {
+            path,
+            mode,
+            start,
+            step: period / total as f32,
+            total,
+            frames: Vec::with_capacity(total),
+            awaiting: false,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Animation time to render next
+    pub fn next_time(&self) -> f32 {
+        self.start + self.frames.len() as f32 * self.step
+    }
+
+    /// Add the frame rendered for `next_time`
+    pub fn push(&mut self, cells: &[u32], cols: u32, rows: u32) {
+        self.frames.push((cells.to_vec(), cols, rows));
+        self.awaiting = false;
+    }
+
+    /// (frames captured, frames in the loop)
+    pub fn progress(&self) -> (usize, usize) {
+        (self.frames.len(), self.total)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.frames.len() >= self.total
+    }
+
+    /// Write the loop: a GIF for `.gif` paths, otherwise ANSI text frames (each starting with a
+    /// cursor-home sequence) made by `to_text`
+    pub fn write(&self, to_text: impl Fn(&[u32], u32, u32) -> String) -> Result<()> {
+        let gif = self
+            .path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("gif"));
+        let file = File::create(&self.path).with_context(|| format!("Failed to create {}", self.path.display()))?;
+        let mut writer = BufWriter::new(file);
+        if gif {
+            let mut encoder = GifEncoder::new_with_speed(writer, GIF_QUANTIZE_SPEED);
+            encoder.set_repeat(Repeat::Infinite)?;
+            let delay = Delay::from_numer_denom_ms((self.step * 1000.0).round() as u32, 1);
+            for (cells, cols, rows) in &self.frames {
+                let image = rasterize(cells, *cols, *rows, self.mode);
+                encoder.encode_frame(Frame::from_parts(image, 0, 0, delay))?;
+            }
+        } else {
+            writer.write_all(b"\x1b[2J")?;
+            for (cells, cols, rows) in &self.frames {
+                writer.write_all(b"\x1b[H")?;
+                writer.write_all(to_text(cells, *cols, *rows).as_bytes())?;
+            }
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Draw a frame's cells as an image: glyphs in their cell color (ASCII modes) or colored
+/// half-cell blocks (half-block modes, with edge glyphs over the blended cell) on black
+pub fn rasterize(cells: &[u32], cols: u32, rows: u32, mode: RenderMode) -> RgbaImage {
+    let unpack = |packed: u32| {
+        let color = Rgba([(packed >> 24) as u8, (packed >> 16) as u8, (packed >> 8) as u8, 255]);
+        (color, (packed & 0xFF) as usize)
+    };
+    if mode.is_half_block() {
+        // Two pixel rows per terminal cell: each becomes a square half of the cell
+        let half = CELL_HEIGHT / 2;
+        let mut image = RgbaImage::from_fn(cols * CELL_WIDTH, rows * half, |x, y| {
+            let index = ((y / half) * cols + x / CELL_WIDTH) as usize;
+            cells.get(index).map_or(Rgba([0, 0, 0, 255]), |&packed| unpack(packed).0)
+        });
+        if mode == RenderMode::HalfBlockEdges {
+            for row in 0..rows / 2 {
+                for col in 0..cols {
+                    let pixel = |r: u32| cells.get((r * cols + col) as usize).copied().unwrap_or(0);
+                    let (top, bottom) = (pixel(row * 2), pixel(row * 2 + 1));
+                    let Some(edge) = half_block_edge(top as u8, bottom as u8) else {
+                        continue;
+                    };
+                    let background = blend_packed(top, bottom);
+                    for y in 0..CELL_HEIGHT {
+                        for x in 0..CELL_WIDTH {
+                            image.put_pixel(col * CELL_WIDTH + x, row * CELL_HEIGHT + y, background);
+                        }
+                    }
+                    let ink = edge_ink((background[0], background[1], background[2]));
+                    draw_glyph(&mut image, edge as usize, col, row, Rgba([ink.0, ink.1, ink.2, 255]));
+                }
+            }
+        }
+        return image;
+    }
+
+    let mut image = RgbaImage::from_pixel(cols * CELL_WIDTH, rows * CELL_HEIGHT, Rgba([0, 0, 0, 255]));
+    for row in 0..rows {
+        for col in 0..cols {
+            let Some(&packed) = cells.get((row * cols + col) as usize) else {
+                continue;
+            };
+            let (color, index) = unpack(packed);
+            let color = if mode == RenderMode::PlainAscii { PLAIN_COLOR } else { color };
+            draw_glyph(&mut image, index, col, row, color);
+        }
+    }
+    image
+}
+
+/// Draw the glyph for a cell index into the cell at (col, row)
+fn draw_glyph(image: &mut RgbaImage, index: usize, col: u32, row: u32, color: Rgba<u8>) {
+    let Some(glyph) = GLYPHS.get(index) else {
+        return;
+    };
+    for (gy, bits) in glyph.iter().enumerate() {
+        for gx in 0..5 {
+            if bits & (0b10000 >> gx) != 0 {
+                image.put_pixel(col * CELL_WIDTH + gx, row * CELL_HEIGHT + GLYPH_TOP + gy as u32, color);
+            }
+        }
+    }
+}
+
+/// Average color of two packed cells
+fn blend_packed(a: u32, b: u32) -> Rgba<u8> {
+    let avg = |shift: u32| ((((a >> shift) & 0xFF) + ((b >> shift) & 0xFF)) / 2) as u8;
+    Rgba([avg(24), avg(16), avg(8), 255])
+}
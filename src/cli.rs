@@ -0,0 +1,332 @@
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+
+use crate::terminal::RenderMode;
+use crate::tile::TileView;
+
+const USAGE: &str = "\
+Usage: ascii-3d [OPTIONS]
+       ascii-3d [--config FILE] convert [CONVERT OPTIONS] IMAGE...
+       ascii-3d [--config FILE] play RECORDING
+       ascii-3d calibrate [--font FILE]
+       ascii-3d gallery [--manifest URL] [--list]
+
+Options:
+  --audio          Audio-reactive mode: bass, mid and treble from the default
+                   input device drive rotation, lighting and exposure
+                   (requires building with --features audio)
+  --config FILE    Read settings from FILE instead of the default location
+                   (~/.config/ascii-3d/config.toml)
+  --deterministic  Step the animation clock by frame index, freeze live data,
+                   and load models synchronously so runs are reproducible
+  --loop FILE      Where K writes its one-rotation loop capture (.gif for a
+                   GIF, anything else for ANSI frames); default
+                   ascii-3d-loop-<time>.gif
+  --model-url URL  Download a .glb, .gltf, .obj or .zip model (with curl,
+                   cached in ~/.cache/ascii-3d/models) and show it
+  --quality N      Supersampling: 1, 2, or 4 times the base pixels per cell
+                   (higher costs GPU time but gives cleaner edges)
+  --record FILE    Record the frames shown to FILE (.a3d: the exact cell
+                   grids, delta-compressed), replayed with `ascii-3d play FILE`
+  --screensaver    Screensaver for tmux lock-command or idle hooks: loop the
+                   built-in scenes (or --script) full screen with no status
+                   bar, and exit on any key
+  --script FILE    Run a Rhai demo script (set_model, set_text, set_lighting,
+                   orbit, wait, caption) to drive the demo unattended
+  --stats FILE     Write per-frame timings, resolution changes and GPU info to
+                   FILE on exit (JSON for .json, CSV otherwise); M writes it
+                   at any time
+  --shader-dir DIR Load the ASCII pipeline shaders from DIR and recompile them
+                   whenever they change (compile errors are shown on screen)
+  --tile VIEW      Show the scene from a fixed front, top, side, or perspective
+                   camera, in step with the other --tile instances on this
+                   machine (e.g. one per tmux pane)
+  --video SOURCE   Show live video behind the model: webcam, webcam:N, or a
+                   video file or URL (decoded by ffmpeg, which must be installed)
+  --video-only     With --video, show the video alone without the model
+  --weld           Merge duplicate vertices in loaded models (smooths normals
+                   and saves GPU memory on OBJ files split per face)
+  -h, --help       Print this help
+
+Convert: print images as ASCII art through the same GPU edge/ASCII pipeline
+(convert video by extracting its frames to images first)
+  --cols N         Output width in characters (default: terminal width, or 80)
+  --mode MODE      plain, color (default), half-block, or half-block-edges
+  --output FILE    Write to FILE instead of standard output
+
+Play: replay an .a3d recording at its original timing (Space pauses, Esc quits)
+
+Calibrate: measure how much ink each ramp character has in your terminal's font
+and print a ramp_coverage line for the config file
+  --font FILE      The font to measure (default: DejaVu Sans Mono, Menlo, or
+                   Consolas, whichever is installed)
+
+Gallery: download a curated set of CC0 models and skyboxes into assets/models
+and assets/skyboxes (with curl); ones already there are skipped
+  --manifest URL   The manifest to read, a URL or a file (default: the list
+                   kept in the project's repository)
+  --list           Show the manifest's entries without downloading";
+
+/// Command-line options
+#[derive(Clone, Debug, Default)]
+pub struct CliArgs {
+    /// Modulate the scene from the default audio input
+    pub audio: bool,
+    /// Config file overriding the default location
+    pub config: Option<PathBuf>,
+    /// Reproducible output: fixed time step, no wall clock, no adaptive behavior
+    pub deterministic: bool,
+    /// File the K loop capture is written to
+    pub loop_file: Option<PathBuf>,
+    /// Model downloaded and shown at startup
+    pub model_url: Option<String>,
+    /// Supersampling factor overriding the config file
+    pub quality: Option<u32>,
+    /// File the shown frames are recorded to
+    pub record: Option<PathBuf>,
+    /// Full-screen attract playlist that exits on any key
+    pub screensaver: bool,
+    /// Rhai script driving the demo
+    pub script: Option<PathBuf>,
+    /// Session statistics file written on exit and with the M key
+    pub stats: Option<PathBuf>,
+    /// Directory to load and hot-reload WGSL shaders from
+    pub shader_dir: Option<PathBuf>,
+    /// Fixed camera of a tiled instance synchronized with the others
+    pub tile: Option<TileView>,
+    /// Webcam, video file, or stream shown as the background
+    pub video: Option<String>,
+    /// Hide the model so only the video is converted
+    pub video_only: bool,
+    /// Weld duplicate vertices on import
+    pub weld: bool,
+    /// Run the image converter instead of the demo
+    pub convert: Option<ConvertArgs>,
+    /// Replay a recording instead of running the demo
+    pub play: Option<PathBuf>,
+    /// Measure a font's ramp coverage instead of running the demo
+    pub calibrate: Option<CalibrateArgs>,
+    /// Download the asset gallery instead of running the demo
+    pub gallery: Option<GalleryArgs>,
+}
+
+/// Options of the `gallery` subcommand
+#[derive(Clone, Debug, Default)]
+pub struct GalleryArgs {
+    /// Manifest URL or file (None = the project's curated list)
+    pub manifest: Option<String>,
+    /// Print the entries instead of downloading them
+    pub list: bool,
+}
+
+/// Options of the `calibrate` subcommand
+#[derive(Clone, Debug, Default)]
+pub struct CalibrateArgs {
+    /// Font file to measure (None = the first installed default)
+    pub font: Option<PathBuf>,
+}
+
+/// Options of the `convert` subcommand
+#[derive(Clone, Debug)]
+pub struct ConvertArgs {
+    /// Images to convert, printed in order
+    pub inputs: Vec<PathBuf>,
+    /// Output width in terminal columns (None = terminal width)
+    pub cols: Option<u16>,
+    pub mode: RenderMode,
+    /// File written instead of standard output
+    pub output: Option<PathBuf>,
+}
+
+impl CliArgs {
+    /// Parse the process arguments
+    /// Returns Ok(None) when help was requested and printed
+    pub fn parse() -> Result<Option<Self>> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Option<Self>> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--audio" => parsed.audio = true,
+                "--config" => {
+                    let file = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--config requires a file\n\n{}", USAGE))?;
+                    parsed.config = Some(PathBuf::from(file));
+                }
+                "--deterministic" => parsed.deterministic = true,
+                "--loop" => {
+                    let file = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--loop requires a file\n\n{}", USAGE))?;
+                    parsed.loop_file = Some(PathBuf::from(file));
+                }
+                "--model-url" => {
+                    let url = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--model-url requires a URL\n\n{}", USAGE))?;
+                    parsed.model_url = Some(url);
+                }
+                "--quality" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--quality requires 1, 2, or 4\n\n{}", USAGE))?;
+                    parsed.quality = match value.as_str() {
+                        "1" | "2" | "4" => value.parse().ok(),
+                        _ => return Err(anyhow!("--quality must be 1, 2, or 4, not {:?}", value)),
+                    };
+                }
+                "--record" => {
+                    let file = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--record requires a file\n\n{}", USAGE))?;
+                    parsed.record = Some(PathBuf::from(file));
+                }
+                "--screensaver" => parsed.screensaver = true,
+                "--script" => {
+                    let file = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--script requires a file\n\n{}", USAGE))?;
+                    parsed.script = Some(PathBuf::from(file));
+                }
+                "--stats" => {
+                    let file = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--stats requires a file\n\n{}", USAGE))?;
+                    parsed.stats = Some(PathBuf::from(file));
+                }
+                "--shader-dir" => {
+                    let dir = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--shader-dir requires a directory\n\n{}", USAGE))?;
+                    parsed.shader_dir = Some(PathBuf::from(dir));
+                }
+                "--tile" => {
+                    let view = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--tile requires front, top, side, or perspective\n\n{}", USAGE))?;
+                    parsed.tile = Some(TileView::from_name(&view)?);
+                }
+                "--video" => {
+                    let source = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--video requires webcam, webcam:N, or a file\n\n{}", USAGE))?;
+                    parsed.video = Some(source);
+                }
+                "--video-only" => parsed.video_only = true,
+                "--weld" => parsed.weld = true,
+                "convert" => {
+                    parsed.convert = Some(ConvertArgs::parse_from(args.by_ref())?);
+                }
+                "calibrate" => {
+                    parsed.calibrate = Some(CalibrateArgs::parse_from(args.by_ref())?);
+                }
+                "gallery" => {
+                    parsed.gallery = Some(GalleryArgs::parse_from(args.by_ref())?);
+                }
+                "play" => {
+                    let file = args
+                        .next()
+                        .ok_or_else(|| anyhow!("play requires a recording\n\n{}", USAGE))?;
+                    parsed.play = Some(PathBuf::from(file));
+                }
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    return Ok(None);
+                }
+                other => return Err(anyhow!("Unknown argument {:?}\n\n{}", other, USAGE)),
+            }
+        }
+        if parsed.video_only && parsed.video.is_none() {
+            return Err(anyhow!("--video-only requires --video\n\n{}", USAGE));
+        }
+        Ok(Some(parsed))
+    }
+}
+
+impl CalibrateArgs {
+    /// Parse the arguments after `calibrate`
+    fn parse_from(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--font" => {
+                    let file = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--font requires a file\n\n{}", USAGE))?;
+                    parsed.font = Some(PathBuf::from(file));
+                }
+                other => return Err(anyhow!("Unknown calibrate argument {:?}\n\n{}", other, USAGE)),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+impl GalleryArgs {
+    /// Parse the arguments after `gallery`
+    fn parse_from(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--manifest" => {
+                    let source = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--manifest requires a URL or file\n\n{}", USAGE))?;
+                    parsed.manifest = Some(source);
+                }
+                "--list" => parsed.list = true,
+                other => return Err(anyhow!("Unknown gallery argument {:?}\n\n{}", other, USAGE)),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+impl ConvertArgs {
+    /// Parse the arguments after `convert`
+    fn parse_from(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self {
+            inputs: Vec::new(),
+            cols: None,
+            mode: RenderMode::ColoredAscii,
+            output: None,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--cols" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--cols requires a number\n\n{}", USAGE))?;
+                    parsed.cols = match value.parse::<u16>() {
+                        Ok(cols) if cols > 0 => Some(cols),
+                        _ => return Err(anyhow!("--cols must be a positive number, not {:?}", value)),
+                    };
+                }
+                "--mode" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--mode requires plain, color, half-block, or half-block-edges\n\n{}", USAGE))?;
+                    parsed.mode = RenderMode::from_name(&value)?;
+                }
+                "--output" => {
+                    let file = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--output requires a file\n\n{}", USAGE))?;
+                    parsed.output = Some(PathBuf::from(file));
+                }
+                other if other.starts_with("--") => {
+                    return Err(anyhow!("Unknown convert argument {:?}\n\n{}", other, USAGE))
+                }
+                input => parsed.inputs.push(PathBuf::from(input)),
+            }
+        }
+        if parsed.inputs.is_empty() {
+            return Err(anyhow!("convert requires at least one image\n\n{}", USAGE));
+        }
+        Ok(parsed)
+    }
+}
@@ -0,0 +1,377 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::gpu::{CharRamp, EdgeAlgorithm, OrbitCamera, Tonemap, TumbleRates, MAX_OUTLINE_WIDTH, RAMP_CHARS};
+use crate::terminal::{Charset, ColorAdjust, ColorDepth, ColorVision, HalfBlock, PanelCorner};
+use crate::theme::Theme;
+
+/// File name inside the per-user config directory
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Settings read from the user's config file
+#[derive(Clone, Debug)]
+pub struct ConfigFile {
+    pub theme: Theme,
+    /// Colors the terminal supports (None = detect from the environment)
+    pub color_depth: Option<ColorDepth>,
+    /// Saturation bias when matching colors to a 256/16-color palette
+    pub chroma_boost: f32,
+    /// Starting saturation/brightness/contrast of cell colors (5-0 adjust them live)
+    pub color_adjust: ColorAdjust,
+    /// Depth mixed into ASCII luminance so closer surfaces are brighter (0 = off)
+    pub depth_weight: f32,
+    /// Curve applied to the linear scene color before it is encoded to sRGB
+    pub tonemap: Tonemap,
+    /// Motion blur shutter: the fraction of each pixel's motion since the last frame it is
+    /// blurred along (0 = off)
+    pub motion_blur: f32,
+    /// Average jittered renders into a supersampled frame while the view holds still
+    pub accumulate: bool,
+    /// Cap the rotation speed, leave out the drifting rotation modes, and draw fill characters in
+    /// place of edge characters, for viewers sensitive to motion
+    pub reduced_motion: bool,
+    /// Edge detector used to place line characters
+    pub edge_algorithm: EdgeAlgorithm,
+    /// Depth edge threshold in world units (None = a fraction of the fitted clip range)
+    pub depth_edge_threshold: Option<f32>,
+    /// Outline each model part where it meets another part or the background
+    pub object_edges: bool,
+    /// Keep normal and luminance edges inside a model part (off leaves only depth edges there)
+    pub interior_edges: bool,
+    /// Silhouette thickness in character cells (1 = as detected)
+    pub outline_width: u32,
+    /// Draw thick outlines in black ink instead of the surface color
+    pub ink_outlines: bool,
+    /// Luminance bands fills are snapped to, for toon fills (0 = continuous)
+    pub fill_levels: u32,
+    /// Fill character order and coverage (from `ramp_coverage`, as measured by `ascii-3d calibrate`)
+    pub ramp: CharRamp,
+    /// Leave background cells blank in Plain ASCII mode
+    pub suppress_background: bool,
+    /// Start with double-wide ASCII cells (each cell two terminal columns wide, so cells are square)
+    pub double_wide: bool,
+    /// Characters allowed in the terminal and in exports
+    pub charset: Charset,
+    /// Half-block mode glyph (None = detect from the environment)
+    pub half_block: Option<HalfBlock>,
+    /// Skybox auto-rotation in degrees per second (0 = still)
+    pub skybox_rotation: f32,
+    /// Per-axis speeds the Tumble rotation mode composes (from a `[tumble]` table)
+    pub tumble: TumbleRates,
+    /// Camera path of the Orbit rotation mode (from an `[orbit]` table)
+    pub orbit: OrbitCamera,
+    /// Draw the status bar (off gives the whole terminal to the model, for clean captures)
+    pub status_bar: bool,
+    /// Capture the mouse so clicking a part of the model selects it
+    pub mouse: bool,
+    /// Screen corner of the GPU info panel
+    pub gpu_panel: PanelCorner,
+    /// Single-line GPU info panel
+    pub gpu_panel_compact: bool,
+    /// Supersampling factor on the base pixels per cell: 1, 2, or 4
+    pub quality: u32,
+    /// Merge duplicate vertices when loading models
+    pub weld: bool,
+    /// Bake ambient occlusion into model vertex colors on load
+    pub ambient_occlusion: bool,
+    /// Crease angle in degrees for normals generated on load (None = smooth everywhere)
+    pub crease_angle: Option<f32>,
+}
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            color_depth: None,
+            chroma_boost: 1.0,
+            color_adjust: ColorAdjust::default(),
+            depth_weight: 0.0,
+            tonemap: Tonemap::default(),
+            motion_blur: 0.0,
+            accumulate: false,
+            reduced_motion: false,
+            edge_algorithm: EdgeAlgorithm::default(),
+            depth_edge_threshold: None,
+            object_edges: false,
+            interior_edges: true,
+            outline_width: 1,
+            ink_outlines: false,
+            fill_levels: 0,
+            ramp: CharRamp::default(),
+            suppress_background: false,
+            double_wide: false,
+            charset: Charset::default(),
+            half_block: None,
+            skybox_rotation: 0.0,
+            tumble: TumbleRates::default(),
+            orbit: OrbitCamera::default(),
+            status_bar: true,
+            mouse: false,
+            gpu_panel: PanelCorner::default(),
+            gpu_panel_compact: false,
+            quality: 1,
+            weld: false,
+            crease_angle: None,
+            ambient_occlusion: false,
+        }
+    }
+}
+
+/// Default config file location: `$XDG_CONFIG_HOME/ascii-3d/config.toml`,
+/// `~/.config/ascii-3d/config.toml`, or `%APPDATA%\ascii-3d\config.toml` on Windows
+pub fn default_config_path() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    base.map(|dir| dir.join("ascii-3d").join(CONFIG_FILE_NAME))
+}
+
+impl ConfigFile {
+    /// Read the config file; a missing file gives the defaults
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read config file {}", path.display()))
+            }
+        };
+        Self::parse(&text).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let table: toml::Table = text.parse()?;
+        let mut config = Self::default();
+        // A preset goes first so the file's own keys override it
+        if let Some(preset) = table.get("preset") {
+            let name = preset.as_str().ok_or_else(|| anyhow!("preset must be \"comic\""))?;
+            config.apply_preset(name)?;
+        }
+        // Applied once every key is read, whichever order `ramp_coverage` and `theme` come in
+        let mut high_contrast = false;
+        for (key, value) in &table {
+            match key.as_str() {
+                "theme" => {
+                    // `theme = "nord"` is shorthand for a built-in theme without overrides
+                    config.theme = match value {
+                        toml::Value::String(name) => {
+                            let mut table = toml::Table::new();
+                            table.insert("base".to_string(), toml::Value::String(name.clone()));
+                            Theme::from_table(&table)?
+                        }
+                        toml::Value::Table(table) => Theme::from_table(table)?,
+                        _ => return Err(anyhow!("theme must be a name or a [theme] table")),
+                    };
+                }
+                "color_depth" => {
+                    // Integers are accepted too: `color_depth = 256`
+                    let name = match value {
+                        toml::Value::String(name) => name.clone(),
+                        toml::Value::Integer(n) => n.to_string(),
+                        _ => return Err(anyhow!("color_depth must be \"auto\", \"truecolor\", 256, or 16")),
+                    };
+                    config.color_depth = match name.as_str() {
+                        "auto" => None,
+                        name => Some(ColorDepth::from_name(name)?),
+                    };
+                }
+                "chroma_boost" => config.chroma_boost = number_in(key, value, 0.0, 4.0)?,
+                "saturation" => config.color_adjust.saturation = number_in(key, value, 0.0, 4.0)?,
+                "brightness" => config.color_adjust.brightness = number_in(key, value, 0.1, 4.0)?,
+                "contrast" => config.color_adjust.contrast = number_in(key, value, 0.1, 4.0)?,
+                "color_vision" => {
+                    let name = value
+                        .as_str()
+                        .ok_or_else(|| anyhow!("color_vision must be \"normal\", \"deuteranopia\", or \"protanopia\""))?;
+                    config.color_adjust.vision = ColorVision::from_name(name)?;
+                }
+                "high_contrast" => {
+                    high_contrast = value.as_bool().ok_or_else(|| anyhow!("high_contrast must be true or false"))?;
+                }
+                "skybox_rotation" => config.skybox_rotation = number_in(key, value, -90.0, 90.0)?,
+                "tumble" => {
+                    // Axes left out of the table don't turn: `tumble = { y = 1.0, x = 0.3 }`
+                    let table = value.as_table().ok_or_else(|| anyhow!("tumble must be a table of x, y, and z speeds"))?;
+                    let mut tumble = TumbleRates { x: 0.0, y: 0.0, z: 0.0 };
+                    for (axis, speed) in table {
+                        let slot = match axis.as_str() {
+                            "x" => &mut tumble.x,
+                            "y" => &mut tumble.y,
+                            "z" => &mut tumble.z,
+                            other => return Err(anyhow!("Unknown tumble axis {:?} (expected x, y, or z)", other)),
+                        };
+                        *slot = number_in(&format!("tumble.{}", axis), speed, -5.0, 5.0)?;
+                    }
+                    config.tumble = tumble;
+                }
+                "orbit" => {
+                    let table = value.as_table().ok_or_else(|| anyhow!("orbit must be a table of radius, height, and target"))?;
+                    for (field, value) in table {
+                        match field.as_str() {
+                            "radius" => config.orbit.radius = number_in("orbit.radius", value, 0.5, 50.0)?,
+                            "height" => config.orbit.height = number_in("orbit.height", value, -50.0, 50.0)?,
+                            "target" => {
+                                let message = || anyhow!("orbit.target must be 3 numbers [x, y, z]");
+                                let values = value.as_array().filter(|values| values.len() == 3).ok_or_else(message)?;
+                                for (axis, value) in values.iter().enumerate() {
+                                    config.orbit.target[axis] = number_in("orbit.target", value, -10.0, 10.0)?;
+                                }
+                            }
+                            other => return Err(anyhow!("Unknown orbit setting {:?} (expected radius, height, or target)", other)),
+                        }
+                    }
+                }
+                "depth_weight" => config.depth_weight = number_in(key, value, 0.0, 1.0)?,
+                "tonemap" => {
+                    let name = value.as_str().ok_or_else(|| anyhow!("tonemap must be \"none\" or \"aces\""))?;
+                    config.tonemap = Tonemap::from_name(name)?;
+                }
+                "motion_blur" => config.motion_blur = number_in(key, value, 0.0, 1.0)?,
+                "accumulate" => {
+                    config.accumulate = value.as_bool().ok_or_else(|| anyhow!("accumulate must be true or false"))?;
+                }
+                "reduced_motion" => {
+                    config.reduced_motion = value.as_bool().ok_or_else(|| anyhow!("reduced_motion must be true or false"))?;
+                }
+                "edge_algorithm" => {
+                    let name = value
+                        .as_str()
+                        .ok_or_else(|| anyhow!("edge_algorithm must be \"hybrid\", \"canny\", or \"depth\""))?;
+                    config.edge_algorithm = EdgeAlgorithm::from_name(name)?;
+                }
+                "depth_edge_threshold" => config.depth_edge_threshold = Some(number_in(key, value, 0.001, 10.0)?),
+                "object_edges" => {
+                    config.object_edges = value.as_bool().ok_or_else(|| anyhow!("object_edges must be true or false"))?;
+                }
+                "interior_edges" => {
+                    config.interior_edges = value.as_bool().ok_or_else(|| anyhow!("interior_edges must be true or false"))?;
+                }
+                "preset" => {}
+                "ink_outlines" => {
+                    config.ink_outlines = value.as_bool().ok_or_else(|| anyhow!("ink_outlines must be true or false"))?;
+                }
+                "fill_levels" => {
+                    config.fill_levels = match value.as_integer() {
+                        Some(n @ (0 | 2..=10)) => n as u32,
+                        _ => return Err(anyhow!("fill_levels must be 0 (continuous) or from 2 to 10")),
+                    };
+                }
+                "outline_width" => {
+                    config.outline_width = match value.as_integer() {
+                        Some(n) if (1..=MAX_OUTLINE_WIDTH as i64).contains(&n) => n as u32,
+                        _ => return Err(anyhow!("outline_width must be a whole number of cells from 1 to {}", MAX_OUTLINE_WIDTH)),
+                    };
+                }
+                "ramp_coverage" => {
+                    let message = || anyhow!("ramp_coverage must be {} numbers between 0 and 1 (see `ascii-3d calibrate`)", RAMP_CHARS);
+                    let values = value.as_array().filter(|values| values.len() == RAMP_CHARS).ok_or_else(message)?;
+                    let mut coverage = [0.0; RAMP_CHARS];
+                    for (slot, value) in coverage.iter_mut().zip(values) {
+                        *slot = number_in(key, value, 0.0, 1.0)?;
+                    }
+                    config.ramp = CharRamp::from_coverage(&coverage);
+                }
+                "charset" => {
+                    let name = value
+                        .as_str()
+                        .ok_or_else(|| anyhow!("charset must be \"unicode\", \"cp437\", or \"ascii-only\""))?;
+                    config.charset = Charset::from_name(name)?;
+                }
+                "half_block" => {
+                    let name = value
+                        .as_str()
+                        .ok_or_else(|| anyhow!("half_block must be \"auto\", \"upper\", \"lower\", or \"full\""))?;
+                    config.half_block = match name {
+                        "auto" => None,
+                        name => Some(HalfBlock::from_name(name)?),
+                    };
+                }
+                "suppress_background" => {
+                    config.suppress_background = value
+                        .as_bool()
+                        .ok_or_else(|| anyhow!("suppress_background must be true or false"))?;
+                }
+                "double_wide" => {
+                    config.double_wide = value
+                        .as_bool()
+                        .ok_or_else(|| anyhow!("double_wide must be true or false"))?;
+                }
+                "mouse" => {
+                    config.mouse = value
+                        .as_bool()
+                        .ok_or_else(|| anyhow!("mouse must be true or false"))?;
+                }
+                "status_bar" => {
+                    config.status_bar = value
+                        .as_bool()
+                        .ok_or_else(|| anyhow!("status_bar must be true or false"))?;
+                }
+                "gpu_panel" => {
+                    let name = value.as_str().ok_or_else(|| {
+                        anyhow!("gpu_panel must be \"top-left\", \"top-right\", \"bottom-left\", or \"bottom-right\"")
+                    })?;
+                    config.gpu_panel = PanelCorner::from_name(name)?;
+                }
+                "gpu_panel_compact" => {
+                    config.gpu_panel_compact = value
+                        .as_bool()
+                        .ok_or_else(|| anyhow!("gpu_panel_compact must be true or false"))?;
+                }
+                "quality" => {
+                    config.quality = match value.as_integer() {
+                        Some(n @ (1 | 2 | 4)) => n as u32,
+                        _ => return Err(anyhow!("quality must be 1, 2, or 4")),
+                    };
+                }
+                "crease_angle" => config.crease_angle = Some(number_in(key, value, 0.0, 180.0)?),
+                "ambient_occlusion" => {
+                    config.ambient_occlusion = value
+                        .as_bool()
+                        .ok_or_else(|| anyhow!("ambient_occlusion must be true or false"))?;
+                }
+                "weld" => {
+                    config.weld = value.as_bool().ok_or_else(|| anyhow!("weld must be true or false"))?;
+                }
+                other => return Err(anyhow!("Unknown setting {:?}", other)),
+            }
+        }
+        if high_contrast {
+            config.ramp = config.ramp.high_contrast();
+        }
+        config.theme = config.theme.for_color_vision(config.color_adjust.vision);
+        Ok(config)
+    }
+
+    /// Set the settings a `preset` bundles
+    fn apply_preset(&mut self, name: &str) -> Result<()> {
+        match name {
+            // Thick inked silhouettes around flat toon fills, with no shading lines inside objects
+            "comic" => {
+                self.object_edges = true;
+                self.interior_edges = false;
+                self.outline_width = 3;
+                self.ink_outlines = true;
+                self.fill_levels = 4;
+            }
+            other => return Err(anyhow!("Unknown preset {:?} (expected \"comic\")", other)),
+        }
+        Ok(())
+    }
+}
+
+/// A float or integer setting within `min..=max`
+fn number_in(key: &str, value: &toml::Value, min: f32, max: f32) -> Result<f32> {
+    let number = match value {
+        toml::Value::Float(f) => *f as f32,
+        toml::Value::Integer(n) => *n as f32,
+        _ => return Err(anyhow!("{} must be a number", key)),
+    };
+    if !(min..=max).contains(&number) {
+        return Err(anyhow!("{} must be between {} and {}", key, min, max));
+    }
+    Ok(number)
+}
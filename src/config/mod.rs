@@ -1,5 +1,9 @@
+mod file;
+mod onboarding;
 mod state;
 mod ui;
 
-pub use state::{get_skybox_display_name, ConfigState};
+pub use file::{default_config_path, ConfigFile};
+pub use onboarding::{run_onboarding, StarterAssets};
+pub use state::{get_skybox_display_name, get_style_display_name, ConfigState, SkyboxSidecar, GRADIENT_PRESETS};
 pub use ui::run_config_ui;
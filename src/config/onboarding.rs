@@ -0,0 +1,237 @@
+use anyhow::{Context, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame, Terminal,
+};
+use std::io::stdout;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::terminal::{Charset, TerminalRenderer};
+use crate::theme::Theme;
+
+/// Controls explained on the first page (a subset of the README's table)
+const CONTROLS: &[(&str, &str)] = &[
+    ("W/A/S/D", "Rotate the model"),
+    ("Q/E", "Zoom out/in"),
+    ("R", "Back to auto-rotation"),
+    ("1-4, Tab", "Render modes"),
+    ("L / O", "Lighting / rotation modes"),
+    ("C", "Configuration menu (models, scenes, skyboxes)"),
+    ("X", "Export the frame"),
+    ("Space", "Hide the status bar and GPU panel"),
+    ("Esc", "Quit"),
+];
+
+/// What the wizard puts in `assets/models` before the demo starts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StarterAssets {
+    /// Download the curated CC0 asset gallery
+    Gallery,
+    /// Write a cube, cylinder, and sphere (no download needed)
+    Primitives,
+    /// Only the built-in scenes
+    None,
+}
+
+const ASSET_CHOICES: &[(StarterAssets, &str)] = &[
+    (StarterAssets::Gallery, "Download the sample gallery (CC0 models and a skybox; needs curl)"),
+    (StarterAssets::Primitives, "Generate primitive shapes (cube, cylinder, sphere)"),
+    (StarterAssets::None, "Skip: start with the built-in scenes only"),
+];
+
+/// Settings the last page sets: config key and its values, the first being the default
+const SETTINGS: &[(&str, &str, &[&str])] = &[
+    ("Color depth", "color_depth", &["auto", "truecolor", "256", "16"]),
+    ("Reduced motion", "reduced_motion", &["false", "true"]),
+    ("High-contrast ramp", "high_contrast", &["false", "true"]),
+    ("Color vision", "color_vision", &["normal", "deuteranopia", "protanopia"]),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Page {
+    Controls,
+    Assets,
+    Settings,
+}
+
+struct Wizard {
+    page: Page,
+    /// Highlighted asset choice
+    asset: usize,
+    /// Highlighted setting row
+    setting: usize,
+    /// Chosen value of each setting
+    values: [usize; SETTINGS.len()],
+}
+
+impl Wizard {
+    /// Handle a key; Some(finished) when the wizard is done (false = skipped with Esc)
+    fn handle_key(&mut self, code: KeyCode) -> Option<bool> {
+        match (self.page, code) {
+            (_, KeyCode::Esc) => return Some(false),
+            (Page::Controls, KeyCode::Enter) => self.page = Page::Assets,
+            (Page::Assets, KeyCode::Up) => self.asset = self.asset.saturating_sub(1),
+            (Page::Assets, KeyCode::Down) => self.asset = (self.asset + 1).min(ASSET_CHOICES.len() - 1),
+            (Page::Assets, KeyCode::Enter) => self.page = Page::Settings,
+            (Page::Assets, KeyCode::Backspace) => self.page = Page::Controls,
+            (Page::Settings, KeyCode::Up) => self.setting = self.setting.saturating_sub(1),
+            (Page::Settings, KeyCode::Down) => self.setting = (self.setting + 1).min(SETTINGS.len() - 1),
+            (Page::Settings, KeyCode::Left) => {
+                let count = SETTINGS[self.setting].2.len();
+                self.values[self.setting] = (self.values[self.setting] + count - 1) % count;
+            }
+            (Page::Settings, KeyCode::Right) => {
+                self.values[self.setting] = (self.values[self.setting] + 1) % SETTINGS[self.setting].2.len();
+            }
+            (Page::Settings, KeyCode::Enter) => return Some(true),
+            (Page::Settings, KeyCode::Backspace) => self.page = Page::Assets,
+            _ => {}
+        }
+        None
+    }
+
+    /// The initial config file: the chosen settings, with the ones left at their default
+    /// commented out so later defaults still apply
+    fn config_text(&self) -> String {
+        let mut text = String::from("# Written by the first-run setup; see the README's Config File section for every setting\n");
+        for (&(_, key, values), &value) in SETTINGS.iter().zip(&self.values) {
+            let chosen = values[value];
+            // Strings are quoted, except the booleans and numbers TOML reads as such
+            let literal = if chosen.parse::<f64>().is_ok() || chosen == "true" || chosen == "false" {
+                chosen.to_string()
+            } else {
+                format!("{:?}", chosen)
+            };
+            let comment = if value == 0 { "# " } else { "" };
+            text.push_str(&format!("{}{} = {}\n", comment, key, literal));
+        }
+        text
+    }
+}
+
+/// Run the first-run wizard full screen, then write the initial config file to `path`
+/// Skipping with Esc writes the defaults, so the wizard isn't shown again
+pub fn run_onboarding(path: &Path, theme: &Theme, charset: Charset) -> Result<StarterAssets> {
+    let mut wizard = Wizard {
+        page: Page::Controls,
+        asset: 0,
+        setting: 0,
+        values: [0; SETTINGS.len()],
+    };
+    let finished = {
+        // The renderer owns raw mode and the alternate screen, restored when it is dropped
+        let _screen = TerminalRenderer::new()?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+        terminal.clear()?;
+        loop {
+            terminal.draw(|f| draw_onboarding(f, &wizard, theme, charset))?;
+            if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        if let Some(finished) = wizard.handle_key(key.code) {
+                            break finished;
+                        }
+                    }
+                }
+            }
+        }
+    };
+    if !finished {
+        wizard.values = [0; SETTINGS.len()];
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(path, wizard.config_text()).with_context(|| format!("Failed to write {}", path.display()))?;
+    eprintln!("Config written to {}", path.display());
+    Ok(if finished { ASSET_CHOICES[wizard.asset].0 } else { StarterAssets::None })
+}
+
+fn draw_onboarding(f: &mut Frame, wizard: &Wizard, theme: &Theme, charset: Charset) {
+    let area = f.area();
+    f.render_widget(Clear, area);
+
+    let popup_width = 70.min(area.width.saturating_sub(4));
+    let popup_height = 20.min(area.height.saturating_sub(2));
+    let popup_area = Rect::new(
+        (area.width.saturating_sub(popup_width)) / 2,
+        (area.height.saturating_sub(popup_height)) / 2,
+        popup_width,
+        popup_height,
+    );
+    let (step, title, hint) = match wizard.page {
+        Page::Controls => (1, "Controls", "Enter: next   Esc: skip setup"),
+        Page::Assets => (2, "Something to look at", "Up/Down: choose   Enter: next   Backspace: back   Esc: skip setup"),
+        Page::Settings => (3, "Display", "Up/Down: setting   Left/Right: change   Enter: finish   Backspace: back"),
+    };
+    let block = Block::default()
+        .title(format!(" Welcome to ascii-3d: {} ({}/3) ", title, step))
+        .borders(Borders::ALL)
+        .border_set(charset.border_set())
+        .border_style(Style::default().fg(theme.border));
+    f.render_widget(block, popup_area);
+
+    let inner = Rect::new(
+        popup_area.x + 2,
+        popup_area.y + 1,
+        popup_area.width.saturating_sub(4),
+        popup_area.height.saturating_sub(2),
+    );
+    let chunks = Layout::vertical([
+        Constraint::Length(2), // Introduction
+        Constraint::Min(1),    // Page content
+        Constraint::Length(1), // Key hints
+    ])
+    .split(inner);
+
+    let intro = match wizard.page {
+        Page::Controls => "No config file or models were found, so here is a quick tour. These keys work while the demo runs:",
+        Page::Assets => "assets/models is empty. The built-in scenes always work; pick what else to start with:",
+        Page::Settings => "Saved to the config file (each can be changed there later):",
+    };
+    f.render_widget(
+        Paragraph::new(intro).style(Style::default().fg(theme.label)).wrap(Wrap { trim: true }),
+        chunks[0],
+    );
+
+    let selected = Style::default().fg(theme.focus).add_modifier(Modifier::REVERSED);
+    let lines: Vec<Line> = match wizard.page {
+        Page::Controls => CONTROLS
+            .iter()
+            .map(|(keys, action)| Line::styled(format!("  {:<10} {}", keys, action), Style::default().fg(theme.value)))
+            .collect(),
+        Page::Assets => ASSET_CHOICES
+            .iter()
+            .enumerate()
+            .map(|(i, (_, label))| {
+                if i == wizard.asset {
+                    Line::styled(format!("> {}", label), selected)
+                } else {
+                    Line::styled(format!("  {}", label), Style::default().fg(theme.inactive))
+                }
+            })
+            .collect(),
+        Page::Settings => SETTINGS
+            .iter()
+            .zip(&wizard.values)
+            .enumerate()
+            .map(|(i, (&(label, _, values), &value))| {
+                let text = format!("  {:<20} < {} >", label, values[value]);
+                if i == wizard.setting {
+                    Line::styled(text, selected)
+                } else {
+                    Line::styled(text, Style::default().fg(theme.inactive))
+                }
+            })
+            .collect(),
+    };
+    f.render_widget(Paragraph::new(lines), chunks[1]);
+    f.render_widget(Paragraph::new(hint).style(Style::default().fg(theme.dim)), chunks[2]);
+}
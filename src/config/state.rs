@@ -1,9 +1,26 @@
-use crate::gpu::{LightingMode, RotationMode};
+use anyhow::{anyhow, Context, Result};
+use crate::gpu::{BackgroundStyle, LightingMode, RotationMode, SkyboxProjection};
+use crate::scene::{BuiltinScene, InstancePattern, MarqueePath};
+use crate::timeline::Playback;
 use std::path::{Path, PathBuf};
 
 /// Supported skybox image extensions
 const SKYBOX_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "bmp"];
 
+/// Skybox file names that say nothing about the image; the folder name is shown instead
+const GENERIC_SKYBOX_NAMES: &[&str] = &["skybox", "sky", "panorama", "background", "image", "texture"];
+
+/// File extension of style shaders
+const STYLE_EXTENSION: &str = "wgsl";
+
+/// Named gradient background presets (name, top color, bottom color)
+pub const GRADIENT_PRESETS: &[(&str, [f32; 3], [f32; 3])] = &[
+    ("Dusk", [0.05, 0.02, 0.12], [0.45, 0.18, 0.10]),
+    ("Ocean", [0.00, 0.03, 0.10], [0.05, 0.30, 0.35]),
+    ("Forest", [0.02, 0.06, 0.03], [0.20, 0.32, 0.10]),
+    ("Steel", [0.01, 0.01, 0.02], [0.25, 0.27, 0.30]),
+];
+
 /// Configuration state for the demo
 #[derive(Clone)]
 pub struct ConfigState {
@@ -11,16 +28,52 @@ pub struct ConfigState {
     pub model_path: Option<PathBuf>,
     /// List of available model files
     pub available_models: Vec<PathBuf>,
+    /// Models downloaded by URL, listed after the model directory's files
+    pub downloaded_models: Vec<PathBuf>,
+    /// Built-in scene shown instead of the model file (None = use model_path)
+    pub scene: Option<BuiltinScene>,
+    /// String extruded by the 3D text scene
+    pub text: String,
     /// Current rotation mode
     pub rotation_mode: RotationMode,
-    /// Rotation speed multiplier (0.1 to 3.0)
+    /// Animation playback speed multiplier (0.1 to 3.0)
     pub rotation_speed: f32,
+    /// Animation playback direction
+    pub playback: Playback,
     /// Current lighting mode
     pub lighting_mode: LightingMode,
     /// Currently selected skybox path (None = solid color background)
     pub skybox_path: Option<PathBuf>,
     /// List of available skybox images
     pub available_skyboxes: Vec<PathBuf>,
+    /// Skybox brightness (0.0 to 1.0)
+    pub skybox_intensity: f32,
+    /// Skybox blur (0.0 = sharp to 1.0 = strongest)
+    pub skybox_blur: f32,
+    /// Style shader replacing the final ASCII pass (None = built-in style)
+    pub style_path: Option<PathBuf>,
+    /// List of available style shaders
+    pub available_styles: Vec<PathBuf>,
+    /// Procedural background drawn behind the model
+    pub background: BackgroundStyle,
+    /// Gradient background top color (linear RGB)
+    pub gradient_top: [f32; 3],
+    /// Gradient background bottom color (linear RGB)
+    pub gradient_bottom: [f32; 3],
+    /// Arrangement for drawing multiple copies of the model
+    pub instance_pattern: InstancePattern,
+    /// Number of copies drawn by the instance pattern (1 to 64)
+    pub instance_count: u32,
+    /// Path of the text marquee circling the model
+    pub marquee: MarqueePath,
+    /// Text shown by the marquee
+    pub marquee_text: String,
+    /// Sub-mesh names of the loaded model
+    pub part_names: Vec<String>,
+    /// Sub-meshes hidden in the parts list (parallel to `part_names`)
+    pub hidden_parts: Vec<bool>,
+    /// Sub-mesh drawn alone, overriding `hidden_parts`
+    pub solo_part: Option<usize>,
 }
 
 impl Default for ConfigState {
@@ -28,11 +81,29 @@ impl Default for ConfigState {
         Self {
             model_path: None,
             available_models: Vec::new(),
+            downloaded_models: Vec::new(),
+            scene: None,
+            text: "ASCII 3D".to_string(),
             rotation_mode: RotationMode::default(),
             rotation_speed: 1.0,
+            playback: Playback::default(),
             lighting_mode: LightingMode::default(),
             skybox_path: None,
             available_skyboxes: Vec::new(),
+            skybox_intensity: 1.0,
+            skybox_blur: 0.0,
+            style_path: None,
+            available_styles: Vec::new(),
+            background: BackgroundStyle::default(),
+            gradient_top: GRADIENT_PRESETS[0].1,
+            gradient_bottom: GRADIENT_PRESETS[0].2,
+            instance_pattern: InstancePattern::default(),
+            instance_count: 8,
+            marquee: MarqueePath::default(),
+            marquee_text: "GREETINGS FROM THE TERMINAL".to_string(),
+            part_names: Vec::new(),
+            hidden_parts: Vec::new(),
+            solo_part: None,
         }
     }
 }
@@ -44,7 +115,7 @@ impl ConfigState {
 
     /// Refresh the list of available models from the given directory
     pub fn refresh_models(&mut self, models_dir: &std::path::Path) {
-        self.available_models = crate::model::discover_models(models_dir);
+        self.available_models = self.with_downloads(crate::model::discover_models(models_dir));
 
         // If no model is selected and models are available, select the first one
         if self.model_path.is_none() && !self.available_models.is_empty() {
@@ -59,6 +130,28 @@ impl ConfigState {
         }
     }
 
+    /// Add a model downloaded by URL to the list and select it
+    pub fn add_downloaded_model(&mut self, path: PathBuf) {
+        if !self.downloaded_models.contains(&path) {
+            self.downloaded_models.push(path.clone());
+        }
+        if !self.available_models.contains(&path) {
+            self.available_models.push(path.clone());
+        }
+        self.scene = None;
+        self.model_path = Some(path);
+    }
+
+    /// A scanned model list followed by the downloaded models it doesn't already hold
+    fn with_downloads(&self, mut models: Vec<PathBuf>) -> Vec<PathBuf> {
+        for path in &self.downloaded_models {
+            if !models.contains(path) {
+                models.push(path.clone());
+            }
+        }
+        models
+    }
+
     /// Get the index of the currently selected model
     pub fn selected_model_index(&self) -> Option<usize> {
         self.model_path
@@ -73,11 +166,109 @@ impl ConfigState {
         }
     }
 
+    /// Number of entries in the model list (built-in scenes followed by model files)
+    pub fn model_entry_count(&self) -> usize {
+        BuiltinScene::all().len() + self.available_models.len()
+    }
+
+    /// Get the model list index of the current selection (scene or model file)
+    pub fn selected_entry_index(&self) -> Option<usize> {
+        match self.scene {
+            Some(scene) => BuiltinScene::all().iter().position(|&s| s == scene),
+            None => self
+                .selected_model_index()
+                .map(|i| i + BuiltinScene::all().len()),
+        }
+    }
+
+    /// Select a built-in scene or model file by name (case-insensitive)
+    /// Model files match by display name or file name, with or without the extension
+    pub fn select_by_name(&mut self, name: &str) -> bool {
+        if let Some(&scene) = BuiltinScene::all()
+            .iter()
+            .find(|s| s.name().eq_ignore_ascii_case(name))
+        {
+            self.scene = Some(scene);
+            return true;
+        }
+
+        let matches = |path: &PathBuf| {
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            let stem = path.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+            [crate::model::get_model_display_name(path).as_str(), file_name, stem]
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(name))
+        };
+        match self.available_models.iter().find(|p| matches(p)).cloned() {
+            Some(path) => {
+                self.scene = None;
+                self.model_path = Some(path);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Select a model list entry by index (built-in scenes come first)
+    pub fn select_entry(&mut self, index: usize) {
+        let scenes = BuiltinScene::all();
+        if index < scenes.len() {
+            self.scene = Some(scenes[index]);
+        } else if index - scenes.len() < self.available_models.len() {
+            self.scene = None;
+            self.select_model(index - scenes.len());
+        }
+    }
+
     /// Adjust rotation speed (clamped to 0.1 - 3.0)
     pub fn adjust_speed(&mut self, delta: f32) {
         self.rotation_speed = (self.rotation_speed + delta).clamp(0.1, 3.0);
     }
 
+    /// Adjust skybox brightness (clamped to 0.0 - 1.0)
+    pub fn adjust_skybox_intensity(&mut self, delta: f32) {
+        self.skybox_intensity = (self.skybox_intensity + delta).clamp(0.0, 1.0);
+    }
+
+    /// Adjust skybox blur (clamped to 0.0 - 1.0)
+    pub fn adjust_skybox_blur(&mut self, delta: f32) {
+        self.skybox_blur = (self.skybox_blur + delta).clamp(0.0, 1.0);
+    }
+
+    /// Adjust instance count (clamped to 1 - 64)
+    pub fn adjust_instance_count(&mut self, delta: i32) {
+        self.instance_count = (self.instance_count as i32 + delta).clamp(1, 64) as u32;
+    }
+
+    /// Replace the parts list for a newly loaded model, showing every part
+    pub fn set_parts(&mut self, names: Vec<String>) {
+        self.hidden_parts = vec![false; names.len()];
+        self.part_names = names;
+        self.solo_part = None;
+    }
+
+    /// Hide a shown part or show a hidden one
+    pub fn toggle_part_hidden(&mut self, index: usize) {
+        if let Some(hidden) = self.hidden_parts.get_mut(index) {
+            *hidden = !*hidden;
+        }
+    }
+
+    /// Draw only this part, or go back to the hidden flags if it is already solo
+    pub fn toggle_part_solo(&mut self, index: usize) {
+        if index < self.part_names.len() {
+            self.solo_part = if self.solo_part == Some(index) { None } else { Some(index) };
+        }
+    }
+
+    /// Whether each part is drawn
+    pub fn part_visibility(&self) -> Vec<bool> {
+        match self.solo_part {
+            Some(solo) => (0..self.part_names.len()).map(|i| i == solo).collect(),
+            None => self.hidden_parts.iter().map(|&hidden| !hidden).collect(),
+        }
+    }
+
     /// Refresh the list of available skyboxes from the given directory
     pub fn refresh_skyboxes(&mut self, skyboxes_dir: &Path) {
         self.available_skyboxes = discover_skyboxes(skyboxes_dir);
@@ -90,6 +281,27 @@ impl ConfigState {
         }
     }
 
+    /// Re-scan the model and skybox directories for files added or removed while running,
+    /// keeping the current selections; returns the display names of the new entries
+    pub fn rescan_assets(&mut self, models_dir: &Path, skyboxes_dir: &Path) -> Vec<String> {
+        let models = self.with_downloads(crate::model::discover_models(models_dir));
+        let skyboxes = discover_skyboxes(skyboxes_dir);
+        let added = models
+            .iter()
+            .filter(|p| !self.available_models.contains(p))
+            .map(|p| crate::model::get_model_display_name(p))
+            .chain(
+                skyboxes
+                    .iter()
+                    .filter(|p| !self.available_skyboxes.contains(p))
+                    .map(|p| get_skybox_display_name(p)),
+            )
+            .collect();
+        self.available_models = models;
+        self.available_skyboxes = skyboxes;
+        added
+    }
+
     /// Get the index of the currently selected skybox (0 = None)
     pub fn selected_skybox_index(&self) -> usize {
         match &self.skybox_path {
@@ -111,29 +323,152 @@ impl ConfigState {
             self.skybox_path = Some(self.available_skyboxes[index - 1].clone());
         }
     }
+
+    /// Refresh the list of available style shaders from the given directory
+    pub fn refresh_styles(&mut self, styles_dir: &Path) {
+        self.available_styles = discover_styles(styles_dir);
+
+        // If current style is not in list, fall back to the built-in style
+        if let Some(ref path) = self.style_path {
+            if !self.available_styles.contains(path) {
+                self.style_path = None;
+            }
+        }
+    }
+
+    /// Get the index of the currently selected style (0 = built-in)
+    pub fn selected_style_index(&self) -> usize {
+        self.style_path
+            .as_ref()
+            .and_then(|path| self.available_styles.iter().position(|s| s == path))
+            .map(|i| i + 1)
+            .unwrap_or(0)
+    }
+
+    /// Select style by index (0 = built-in, 1+ = style shader index)
+    pub fn select_style(&mut self, index: usize) {
+        if index == 0 {
+            self.style_path = None;
+        } else if index <= self.available_styles.len() {
+            self.style_path = Some(self.available_styles[index - 1].clone());
+        }
+    }
 }
 
-/// Discover skybox images in a directory
+/// Discover style shaders in a directory
+fn discover_styles(dir: &Path) -> Vec<PathBuf> {
+    let mut styles: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.is_file()
+                        && p.extension()
+                            .and_then(|e| e.to_str())
+                            .is_some_and(|e| e.eq_ignore_ascii_case(STYLE_EXTENSION))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    styles.sort();
+    styles
+}
+
+/// Discover skybox images in a directory and its subdirectories
 fn discover_skyboxes(dir: &Path) -> Vec<PathBuf> {
     let mut skyboxes = Vec::new();
-    if let Ok(entries) = std::fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                    if SKYBOX_EXTENSIONS.contains(&ext.to_lowercase().as_str()) {
-                        skyboxes.push(path);
-                    }
+    discover_skyboxes_recursive(dir, &mut skyboxes);
+    skyboxes.sort_by_key(|p| get_skybox_display_name(p));
+    skyboxes
+}
+
+fn discover_skyboxes_recursive(dir: &Path, skyboxes: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            discover_skyboxes_recursive(&path, skyboxes);
+        } else if path.is_file() {
+            if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+                if SKYBOX_EXTENSIONS.contains(&ext.to_lowercase().as_str()) {
+                    skyboxes.push(path);
                 }
             }
         }
     }
-    skyboxes.sort();
-    skyboxes
 }
 
 /// Get a display name for a skybox path
+/// Images with a generic name ("Nebula/skybox.png") are named after their folder,
+/// others in a subfolder are shown as "folder/name"
 pub fn get_skybox_display_name(path: &Path) -> String {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown");
+    let folder = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|s| s.to_str())
+        .filter(|f| !f.eq_ignore_ascii_case("skyboxes"));
+
+    match folder {
+        Some(folder) if GENERIC_SKYBOX_NAMES.iter().any(|g| stem.eq_ignore_ascii_case(g)) => folder.to_string(),
+        Some(folder) => format!("{}/{}", folder, stem),
+        None => stem.to_string(),
+    }
+}
+
+/// Per-skybox settings from an optional sidecar file next to the image
+/// (`nebula.png` reads `nebula.toml`)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SkyboxSidecar {
+    pub projection: SkyboxProjection,
+    /// Horizontal offset in degrees
+    pub rotation: f32,
+}
+
+impl SkyboxSidecar {
+    /// Read the sidecar for a skybox image; a missing file gives the defaults
+    pub fn load(image: &Path) -> Result<Self> {
+        let path = image.with_extension("toml");
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Self::parse(&text).with_context(|| format!("Invalid skybox settings {}", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let table: toml::Table = text.parse()?;
+        let mut sidecar = Self::default();
+        for (key, value) in &table {
+            match key.as_str() {
+                "projection" => {
+                    sidecar.projection = match value.as_str() {
+                        Some("stretch") => SkyboxProjection::Stretch,
+                        Some("panorama") | Some("equirectangular") => SkyboxProjection::Panorama,
+                        _ => return Err(anyhow!("projection must be \"stretch\" or \"panorama\"")),
+                    };
+                }
+                "rotation" => {
+                    sidecar.rotation = match value {
+                        toml::Value::Float(f) => *f as f32,
+                        toml::Value::Integer(n) => *n as f32,
+                        _ => return Err(anyhow!("rotation must be a number of degrees")),
+                    };
+                }
+                other => return Err(anyhow!("Unknown setting {:?}", other)),
+            }
+        }
+        Ok(sidecar)
+    }
+}
+
+/// Get a display name for a style shader path
+pub fn get_style_display_name(path: &Path) -> String {
     path.file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("unknown")
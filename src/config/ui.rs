@@ -4,35 +4,74 @@ use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
     Frame, Terminal,
 };
 use std::io::Stdout;
 use std::time::Duration;
 
-use super::{get_skybox_display_name, ConfigState};
-use crate::gpu::{LightingMode, RotationMode};
+use super::{get_skybox_display_name, get_style_display_name, ConfigState, GRADIENT_PRESETS};
+use crate::gpu::{BackgroundStyle, LightingMode, RotationMode};
 use crate::model::get_model_display_name;
+use crate::scene::{BuiltinScene, InstancePattern, MarqueePath};
+use crate::terminal::Charset;
+use crate::theme::Theme;
+use crate::timeline::Playback;
+
+/// Maximum length of the 3D text string
+const MAX_TEXT_LEN: usize = 32;
+
+/// Maximum length of the marquee text
+const MAX_MARQUEE_LEN: usize = 64;
+
+/// Background selector entries: each style, with the gradient expanded per preset
+fn background_options() -> Vec<(BackgroundStyle, Option<usize>)> {
+    BackgroundStyle::all()
+        .iter()
+        .flat_map(|&style| match style {
+            BackgroundStyle::Gradient => (0..GRADIENT_PRESETS.len()).map(|i| (style, Some(i))).collect(),
+            _ => vec![(style, None)],
+        })
+        .collect()
+}
 
 /// Which section of the UI is currently focused
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Focus {
     Models,
+    Parts,
+    Text,
     Rotation,
     Lighting,
     Skybox,
+    SkyboxIntensity,
+    SkyboxBlur,
+    Background,
+    Style,
+    Instances,
+    Marquee,
     Speed,
+    Playback,
     Buttons,
 }
 
 impl Focus {
     fn next(self) -> Self {
         match self {
-            Focus::Models => Focus::Rotation,
+            Focus::Models => Focus::Parts,
+            Focus::Parts => Focus::Text,
+            Focus::Text => Focus::Rotation,
             Focus::Rotation => Focus::Lighting,
             Focus::Lighting => Focus::Skybox,
-            Focus::Skybox => Focus::Speed,
-            Focus::Speed => Focus::Buttons,
+            Focus::Skybox => Focus::SkyboxIntensity,
+            Focus::SkyboxIntensity => Focus::SkyboxBlur,
+            Focus::SkyboxBlur => Focus::Background,
+            Focus::Background => Focus::Style,
+            Focus::Style => Focus::Instances,
+            Focus::Instances => Focus::Marquee,
+            Focus::Marquee => Focus::Speed,
+            Focus::Speed => Focus::Playback,
+            Focus::Playback => Focus::Buttons,
             Focus::Buttons => Focus::Models,
         }
     }
@@ -40,11 +79,20 @@ impl Focus {
     fn prev(self) -> Self {
         match self {
             Focus::Models => Focus::Buttons,
-            Focus::Rotation => Focus::Models,
+            Focus::Parts => Focus::Models,
+            Focus::Text => Focus::Parts,
+            Focus::Rotation => Focus::Text,
             Focus::Lighting => Focus::Rotation,
             Focus::Skybox => Focus::Lighting,
-            Focus::Speed => Focus::Skybox,
-            Focus::Buttons => Focus::Speed,
+            Focus::SkyboxIntensity => Focus::Skybox,
+            Focus::SkyboxBlur => Focus::SkyboxIntensity,
+            Focus::Background => Focus::SkyboxBlur,
+            Focus::Style => Focus::Background,
+            Focus::Instances => Focus::Style,
+            Focus::Marquee => Focus::Instances,
+            Focus::Speed => Focus::Marquee,
+            Focus::Playback => Focus::Speed,
+            Focus::Buttons => Focus::Playback,
         }
     }
 }
@@ -54,10 +102,17 @@ struct ConfigUI {
     config: ConfigState,
     focus: Focus,
     model_list_state: ListState,
+    part_list_state: ListState,
     rotation_index: usize,
     lighting_index: usize,
     skybox_index: usize,
+    background_index: usize,
+    style_index: usize,
+    instance_index: usize,
+    marquee_index: usize,
+    playback_index: usize,
     button_index: usize, // 0 = Apply, 1 = Cancel
+    error: Option<String>, // Shown as a popup until dismissed
 }
 
 impl ConfigUI {
@@ -74,30 +129,108 @@ impl ConfigUI {
 
         let skybox_index = config.selected_skybox_index();
 
+        let background_index = background_options()
+            .iter()
+            .position(|&(style, preset)| {
+                style == config.background
+                    && preset.is_none_or(|i| {
+                        GRADIENT_PRESETS[i].1 == config.gradient_top
+                            && GRADIENT_PRESETS[i].2 == config.gradient_bottom
+                    })
+            })
+            .or_else(|| {
+                background_options()
+                    .iter()
+                    .position(|&(style, _)| style == config.background)
+            })
+            .unwrap_or(0);
+
+        let style_index = config.selected_style_index();
+
+        let instance_index = InstancePattern::all()
+            .iter()
+            .position(|&p| p == config.instance_pattern)
+            .unwrap_or(0);
+
+        let marquee_index = MarqueePath::all()
+            .iter()
+            .position(|&p| p == config.marquee)
+            .unwrap_or(0);
+
+        let playback_index = Playback::all()
+            .iter()
+            .position(|&p| p == config.playback)
+            .unwrap_or(0);
+
         let mut model_list_state = ListState::default();
-        model_list_state.select(config.selected_model_index());
+        model_list_state.select(config.selected_entry_index());
+
+        let mut part_list_state = ListState::default();
+        part_list_state.select((!config.part_names.is_empty()).then_some(0));
 
         Self {
             config,
             focus: Focus::Models,
             model_list_state,
+            part_list_state,
             rotation_index,
             lighting_index,
             skybox_index,
+            background_index,
+            style_index,
+            instance_index,
+            marquee_index,
+            playback_index,
             button_index: 0,
+            error: None,
         }
     }
 
     fn handle_key(&mut self, key: KeyCode) -> Option<bool> {
+        // Any key dismisses the error popup
+        if self.error.take().is_some() {
+            return None;
+        }
+
         match key {
             KeyCode::Esc => return Some(false), // Cancel
             KeyCode::Tab => self.focus = self.focus.next(),
             KeyCode::BackTab => self.focus = self.focus.prev(),
-            KeyCode::Enter => {
-                if self.focus == Focus::Buttons {
-                    return Some(self.button_index == 0); // Apply or Cancel
+            KeyCode::Enter if self.focus == Focus::Buttons => {
+                return Some(self.button_index == 0); // Apply or Cancel
+            }
+            KeyCode::Char(c)
+                if self.focus == Focus::Text && self.config.text.chars().count() < MAX_TEXT_LEN =>
+            {
+                self.config.text.push(c);
+            }
+            KeyCode::Backspace if self.focus == Focus::Text => {
+                self.config.text.pop();
+            }
+            KeyCode::Char(c)
+                if self.focus == Focus::Marquee && self.config.marquee_text.chars().count() < MAX_MARQUEE_LEN =>
+            {
+                self.config.marquee_text.push(c);
+            }
+            KeyCode::Backspace if self.focus == Focus::Marquee => {
+                self.config.marquee_text.pop();
+            }
+            KeyCode::Char(' ') if self.focus == Focus::Parts => {
+                if let Some(i) = self.part_list_state.selected() {
+                    self.config.toggle_part_hidden(i);
+                }
+            }
+            KeyCode::Char('s') | KeyCode::Char('S') if self.focus == Focus::Parts => {
+                if let Some(i) = self.part_list_state.selected() {
+                    self.config.toggle_part_solo(i);
                 }
             }
+            KeyCode::Char('+') | KeyCode::Char('=') if self.focus == Focus::Instances => {
+                self.config.adjust_instance_count(1);
+            }
+            KeyCode::Char('-') if self.focus == Focus::Instances => {
+                self.config.adjust_instance_count(-1);
+            }
             KeyCode::Up => self.move_up(),
             KeyCode::Down => self.move_down(),
             KeyCode::Left => self.move_left(),
@@ -113,21 +246,22 @@ impl ConfigUI {
                 if let Some(i) = self.model_list_state.selected() {
                     if i > 0 {
                         self.model_list_state.select(Some(i - 1));
-                        self.config.select_model(i - 1);
+                        self.config.select_entry(i - 1);
                     }
                 }
             }
-            Focus::Rotation => {
-                if self.rotation_index > 0 {
-                    self.rotation_index -= 1;
-                    self.config.rotation_mode = RotationMode::all()[self.rotation_index];
+            Focus::Parts => {
+                if let Some(i) = self.part_list_state.selected().filter(|&i| i > 0) {
+                    self.part_list_state.select(Some(i - 1));
                 }
             }
-            Focus::Lighting => {
-                if self.lighting_index > 0 {
-                    self.lighting_index -= 1;
-                    self.config.lighting_mode = LightingMode::all()[self.lighting_index];
-                }
+            Focus::Rotation if self.rotation_index > 0 => {
+                self.rotation_index -= 1;
+                self.config.rotation_mode = RotationMode::all()[self.rotation_index];
+            }
+            Focus::Lighting if self.lighting_index > 0 => {
+                self.lighting_index -= 1;
+                self.config.lighting_mode = LightingMode::all()[self.lighting_index];
             }
             Focus::Skybox => {
                 let total = self.config.available_skyboxes.len() + 1; // +1 for "None"
@@ -140,6 +274,31 @@ impl ConfigUI {
                     self.config.select_skybox(self.skybox_index);
                 }
             }
+            Focus::Background => {
+                let total = background_options().len();
+                self.background_index = (self.background_index + total - 1) % total;
+                self.apply_background();
+            }
+            Focus::Style => {
+                let total = self.config.available_styles.len() + 1; // +1 for built-in
+                self.style_index = (self.style_index + total - 1) % total;
+                self.config.select_style(self.style_index);
+            }
+            Focus::Instances => {
+                let total = InstancePattern::all().len();
+                self.instance_index = (self.instance_index + total - 1) % total;
+                self.config.instance_pattern = InstancePattern::all()[self.instance_index];
+            }
+            Focus::Marquee => {
+                let total = MarqueePath::all().len();
+                self.marquee_index = (self.marquee_index + total - 1) % total;
+                self.config.marquee = MarqueePath::all()[self.marquee_index];
+            }
+            Focus::Playback => {
+                let total = Playback::all().len();
+                self.playback_index = (self.playback_index + total - 1) % total;
+                self.config.playback = Playback::all()[self.playback_index];
+            }
             _ => {}
         }
     }
@@ -148,26 +307,29 @@ impl ConfigUI {
         match self.focus {
             Focus::Models => {
                 if let Some(i) = self.model_list_state.selected() {
-                    if i + 1 < self.config.available_models.len() {
+                    if i + 1 < self.config.model_entry_count() {
                         self.model_list_state.select(Some(i + 1));
-                        self.config.select_model(i + 1);
+                        self.config.select_entry(i + 1);
                     }
-                } else if !self.config.available_models.is_empty() {
+                } else if self.config.model_entry_count() > 0 {
                     self.model_list_state.select(Some(0));
-                    self.config.select_model(0);
+                    self.config.select_entry(0);
                 }
             }
-            Focus::Rotation => {
-                if self.rotation_index + 1 < RotationMode::all().len() {
-                    self.rotation_index += 1;
-                    self.config.rotation_mode = RotationMode::all()[self.rotation_index];
+            Focus::Parts => {
+                if let Some(i) = self.part_list_state.selected() {
+                    if i + 1 < self.config.part_names.len() {
+                        self.part_list_state.select(Some(i + 1));
+                    }
                 }
             }
-            Focus::Lighting => {
-                if self.lighting_index + 1 < LightingMode::all().len() {
-                    self.lighting_index += 1;
-                    self.config.lighting_mode = LightingMode::all()[self.lighting_index];
-                }
+            Focus::Rotation if self.rotation_index + 1 < RotationMode::all().len() => {
+                self.rotation_index += 1;
+                self.config.rotation_mode = RotationMode::all()[self.rotation_index];
+            }
+            Focus::Lighting if self.lighting_index + 1 < LightingMode::all().len() => {
+                self.lighting_index += 1;
+                self.config.lighting_mode = LightingMode::all()[self.lighting_index];
             }
             Focus::Skybox => {
                 let total = self.config.available_skyboxes.len() + 1; // +1 for "None"
@@ -180,17 +342,54 @@ impl ConfigUI {
                     self.config.select_skybox(self.skybox_index);
                 }
             }
+            Focus::Background => {
+                self.background_index = (self.background_index + 1) % background_options().len();
+                self.apply_background();
+            }
+            Focus::Style => {
+                self.style_index = (self.style_index + 1) % (self.config.available_styles.len() + 1);
+                self.config.select_style(self.style_index);
+            }
+            Focus::Instances => {
+                self.instance_index = (self.instance_index + 1) % InstancePattern::all().len();
+                self.config.instance_pattern = InstancePattern::all()[self.instance_index];
+            }
+            Focus::Marquee => {
+                self.marquee_index = (self.marquee_index + 1) % MarqueePath::all().len();
+                self.config.marquee = MarqueePath::all()[self.marquee_index];
+            }
+            Focus::Playback => {
+                self.playback_index = (self.playback_index + 1) % Playback::all().len();
+                self.config.playback = Playback::all()[self.playback_index];
+            }
             _ => {}
         }
     }
 
+    /// Copy the selected background entry into the config
+    fn apply_background(&mut self) {
+        let (style, preset) = background_options()[self.background_index];
+        self.config.background = style;
+        if let Some(i) = preset {
+            self.config.gradient_top = GRADIENT_PRESETS[i].1;
+            self.config.gradient_bottom = GRADIENT_PRESETS[i].2;
+        }
+    }
+
     fn move_left(&mut self) {
         match self.focus {
             Focus::Speed => self.config.adjust_speed(-0.1),
+            Focus::SkyboxIntensity => self.config.adjust_skybox_intensity(-0.1),
+            Focus::SkyboxBlur => self.config.adjust_skybox_blur(-0.1),
             Focus::Buttons => self.button_index = 0,
             Focus::Rotation => self.move_up(),
             Focus::Lighting => self.move_up(),
             Focus::Skybox => self.move_up(),
+            Focus::Background => self.move_up(),
+            Focus::Style => self.move_up(),
+            Focus::Instances => self.move_up(),
+            Focus::Marquee => self.move_up(),
+            Focus::Playback => self.move_up(),
             _ => {}
         }
     }
@@ -198,25 +397,37 @@ impl ConfigUI {
     fn move_right(&mut self) {
         match self.focus {
             Focus::Speed => self.config.adjust_speed(0.1),
+            Focus::SkyboxIntensity => self.config.adjust_skybox_intensity(0.1),
+            Focus::SkyboxBlur => self.config.adjust_skybox_blur(0.1),
             Focus::Buttons => self.button_index = 1,
             Focus::Rotation => self.move_down(),
             Focus::Lighting => self.move_down(),
             Focus::Skybox => self.move_down(),
+            Focus::Background => self.move_down(),
+            Focus::Style => self.move_down(),
+            Focus::Instances => self.move_down(),
+            Focus::Marquee => self.move_down(),
+            Focus::Playback => self.move_down(),
             _ => {}
         }
     }
 }
 
 /// Run the config UI, blocking until user applies or cancels
+/// If `error` is set it is shown in a popup over the UI until a key is pressed
 /// Returns Some(config) if applied, None if cancelled
 pub fn run_config_ui(
     terminal: &mut Terminal<ratatui::backend::CrosstermBackend<Stdout>>,
     config: ConfigState,
+    error: Option<String>,
+    theme: &Theme,
+    charset: Charset,
 ) -> Result<Option<ConfigState>> {
     let mut ui = ConfigUI::new(config);
+    ui.error = error;
 
     loop {
-        terminal.draw(|f| draw_config_ui(f, &mut ui))?;
+        terminal.draw(|f| draw_config_ui(f, &mut ui, theme, charset))?;
 
         if event::poll(Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
@@ -234,7 +445,7 @@ pub fn run_config_ui(
     }
 }
 
-fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
+fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI, theme: &Theme, charset: Charset) {
     let area = f.area();
 
     // Clear the screen
@@ -242,7 +453,7 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
 
     // Calculate centered popup area (taller to accommodate new sections)
     let popup_width = 70.min(area.width.saturating_sub(4));
-    let popup_height = 28.min(area.height.saturating_sub(2));
+    let popup_height = 44.min(area.height.saturating_sub(2));
     let popup_x = (area.width.saturating_sub(popup_width)) / 2;
     let popup_y = (area.height.saturating_sub(popup_height)) / 2;
     let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
@@ -251,7 +462,8 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
     let block = Block::default()
         .title(" Configuration ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_set(charset.border_set())
+        .border_style(Style::default().fg(theme.border));
     f.render_widget(block, popup_area);
 
     // Inner area
@@ -262,18 +474,33 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
         popup_area.height.saturating_sub(2),
     );
 
-    // Layout: Models list, Rotation, Lighting, Skybox, Speed, Buttons
+    // Layout: Models list, Parts list, Text, Rotation, Lighting, Skybox, Background, Style, Instances, Marquee, Animation, Buttons
     let chunks = Layout::vertical([
         Constraint::Length(1),  // Model label
         Constraint::Length(5),  // Model list
+        Constraint::Length(1),  // Parts label
+        Constraint::Length(5),  // Parts list
+        Constraint::Length(1),  // Text label
+        Constraint::Length(1),  // Text input
         Constraint::Length(1),  // Rotation label
         Constraint::Length(2),  // Rotation options
         Constraint::Length(1),  // Lighting label
         Constraint::Length(2),  // Lighting options
         Constraint::Length(1),  // Skybox label
         Constraint::Length(1),  // Skybox selector
-        Constraint::Length(1),  // Speed label
+        Constraint::Length(1),  // Skybox brightness slider
+        Constraint::Length(1),  // Skybox blur slider
+        Constraint::Length(1),  // Background label
+        Constraint::Length(1),  // Background selector
+        Constraint::Length(1),  // Style label
+        Constraint::Length(1),  // Style selector
+        Constraint::Length(1),  // Instances label
+        Constraint::Length(1),  // Instances selector
+        Constraint::Length(1),  // Marquee label
+        Constraint::Length(1),  // Marquee path and text
+        Constraint::Length(1),  // Animation label
         Constraint::Length(1),  // Speed slider
+        Constraint::Length(1),  // Playback selector
         Constraint::Min(1),     // Spacer
         Constraint::Length(1),  // Buttons
     ])
@@ -281,46 +508,112 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
 
     // Model section
     let model_style = if ui.focus == Focus::Models {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.focus)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(theme.label)
     };
     f.render_widget(
         Paragraph::new("Model:").style(model_style),
         chunks[0],
     );
 
-    let model_items: Vec<ListItem> = ui
-        .config
-        .available_models
+    // Built-in scenes are listed first, in brackets
+    let model_items: Vec<ListItem> = BuiltinScene::all()
         .iter()
-        .map(|p| {
+        .map(|scene| ListItem::new(format!("  [{}]", scene.name())))
+        .chain(ui.config.available_models.iter().map(|p| {
             let name = get_model_display_name(p);
             ListItem::new(format!("  {}", name))
-        })
+        }))
         .collect();
 
     let model_list = List::new(model_items)
-        .block(Block::default().borders(Borders::ALL).border_style(
+        .block(Block::default().borders(Borders::ALL).border_set(charset.border_set()).border_style(
             if ui.focus == Focus::Models {
-                Style::default().fg(Color::Yellow)
+                Style::default().fg(theme.focus)
             } else {
-                Style::default().fg(Color::DarkGray)
+                Style::default().fg(theme.dim)
             },
         ))
         .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
 
     f.render_stateful_widget(model_list, chunks[1], &mut ui.model_list_state);
 
+    // Parts of the loaded model
+    let parts_style = if ui.focus == Focus::Parts {
+        Style::default().fg(theme.focus)
+    } else {
+        Style::default().fg(theme.label)
+    };
+    f.render_widget(
+        Paragraph::new("Parts of loaded model: (space to hide, s to solo)").style(parts_style),
+        chunks[2],
+    );
+
+    let part_items: Vec<ListItem> = if ui.config.part_names.is_empty() {
+        vec![ListItem::new("  (no parts)").style(Style::default().fg(theme.inactive))]
+    } else {
+        let visible = ui.config.part_visibility();
+        ui.config
+            .part_names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let check = if ui.config.hidden_parts[i] { ' ' } else { 'x' };
+                let solo = if ui.config.solo_part == Some(i) { " (solo)" } else { "" };
+                let style = if visible[i] {
+                    Style::default()
+                } else {
+                    Style::default().fg(theme.inactive)
+                };
+                ListItem::new(format!("  [{}] {}{}", check, name, solo)).style(style)
+            })
+            .collect()
+    };
+
+    let part_list = List::new(part_items)
+        .block(Block::default().borders(Borders::ALL).border_set(charset.border_set()).border_style(
+            if ui.focus == Focus::Parts {
+                Style::default().fg(theme.focus)
+            } else {
+                Style::default().fg(theme.dim)
+            },
+        ))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    f.render_stateful_widget(part_list, chunks[3], &mut ui.part_list_state);
+
+    // Text section
+    let text_style = if ui.focus == Focus::Text {
+        Style::default().fg(theme.focus)
+    } else {
+        Style::default().fg(theme.label)
+    };
+    f.render_widget(
+        Paragraph::new("3D Text: (type to edit)").style(text_style),
+        chunks[4],
+    );
+
+    let text_display_style = if ui.focus == Focus::Text {
+        Style::default().fg(theme.value).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.inactive)
+    };
+    let cursor = if ui.focus == Focus::Text { "_" } else { "" };
+    f.render_widget(
+        Paragraph::new(format!("  {}{}", ui.config.text, cursor)).style(text_display_style),
+        chunks[5],
+    );
+
     // Rotation section
     let rotation_style = if ui.focus == Focus::Rotation {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.focus)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(theme.label)
     };
     f.render_widget(
         Paragraph::new("Rotation Mode: (arrows to select)").style(rotation_style),
-        chunks[2],
+        chunks[6],
     );
 
     let rotation_modes: Vec<Span> = RotationMode::all()
@@ -330,9 +623,9 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
             let selected = i == ui.rotation_index;
             let prefix = if selected { ">" } else { " " };
             let style = if selected {
-                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+                Style::default().fg(theme.selection).add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Gray)
+                Style::default().fg(theme.inactive)
             };
             Span::styled(format!("{}{:<8}", prefix, mode.name()), style)
         })
@@ -342,17 +635,17 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
     let row2: Vec<Span> = rotation_modes.iter().skip(3).cloned().collect();
 
     let rotation_text = vec![Line::from(row1), Line::from(row2)];
-    f.render_widget(Paragraph::new(rotation_text), chunks[3]);
+    f.render_widget(Paragraph::new(rotation_text), chunks[7]);
 
     // Lighting section
     let lighting_style = if ui.focus == Focus::Lighting {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.focus)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(theme.label)
     };
     f.render_widget(
         Paragraph::new("Lighting Mode: (arrows to select)").style(lighting_style),
-        chunks[4],
+        chunks[8],
     );
 
     let lighting_modes: Vec<Span> = LightingMode::all()
@@ -362,9 +655,9 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
             let selected = i == ui.lighting_index;
             let prefix = if selected { ">" } else { " " };
             let style = if selected {
-                Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+                Style::default().fg(theme.selection_alt).add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Gray)
+                Style::default().fg(theme.inactive)
             };
             Span::styled(format!("{}{:<10}", prefix, mode.name()), style)
         })
@@ -374,17 +667,17 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
     let lrow2: Vec<Span> = lighting_modes.iter().skip(3).cloned().collect();
 
     let lighting_text = vec![Line::from(lrow1), Line::from(lrow2)];
-    f.render_widget(Paragraph::new(lighting_text), chunks[5]);
+    f.render_widget(Paragraph::new(lighting_text), chunks[9]);
 
     // Skybox section
     let skybox_style = if ui.focus == Focus::Skybox {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.focus)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(theme.label)
     };
     f.render_widget(
         Paragraph::new("Skybox: (arrows to cycle)").style(skybox_style),
-        chunks[6],
+        chunks[10],
     );
 
     // Skybox selector display
@@ -397,9 +690,9 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
     };
 
     let skybox_display_style = if ui.focus == Focus::Skybox {
-        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        Style::default().fg(theme.value).add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(Color::Gray)
+        Style::default().fg(theme.inactive)
     };
 
     let total_skyboxes = ui.config.available_skyboxes.len() + 1;
@@ -411,23 +704,157 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
     );
     f.render_widget(
         Paragraph::new(skybox_text).style(skybox_display_style),
-        chunks[7],
+        chunks[11],
+    );
+
+    // Skybox brightness and blur sliders
+    for (row, (focus, name, value)) in [
+        (Focus::SkyboxIntensity, "Brightness", ui.config.skybox_intensity),
+        (Focus::SkyboxBlur, "Blur", ui.config.skybox_blur),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let label = format!("  {:<11}{:>4.0}% ", name, value * 100.0);
+        let slider_width = chunks[12 + row].width.saturating_sub(label.len() as u16 + 2) as usize;
+        let filled = (value * slider_width as f32).round() as usize;
+        let slider_style = if ui.focus == focus {
+            Style::default().fg(theme.value).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.inactive)
+        };
+        f.render_widget(
+            Paragraph::new(format!(
+                "{}[{}{}]",
+                label,
+                "=".repeat(filled),
+                " ".repeat(slider_width.saturating_sub(filled))
+            ))
+            .style(slider_style),
+            chunks[12 + row],
+        );
+    }
+
+    // Background section
+    let background_style = if ui.focus == Focus::Background {
+        Style::default().fg(theme.focus)
+    } else {
+        Style::default().fg(theme.label)
+    };
+    f.render_widget(
+        Paragraph::new("Background: (arrows to cycle)").style(background_style),
+        chunks[14],
+    );
+
+    let background_display_style = if ui.focus == Focus::Background {
+        Style::default().fg(theme.value).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.inactive)
+    };
+    let background_text = match background_options()[ui.background_index] {
+        (style, Some(preset)) => format!("  < {} ({}) >", style.name(), GRADIENT_PRESETS[preset].0),
+        (style, None) => format!("  < {} >", style.name()),
+    };
+    f.render_widget(
+        Paragraph::new(background_text).style(background_display_style),
+        chunks[15],
+    );
+
+    // Style section
+    let style_style = if ui.focus == Focus::Style {
+        Style::default().fg(theme.focus)
+    } else {
+        Style::default().fg(theme.label)
+    };
+    f.render_widget(
+        Paragraph::new("ASCII Style: (arrows to cycle, from assets/styles)").style(style_style),
+        chunks[16],
     );
 
-    // Speed section
-    let speed_style = if ui.focus == Focus::Speed {
-        Style::default().fg(Color::Yellow)
+    let style_display_style = if ui.focus == Focus::Style {
+        Style::default().fg(theme.value).add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(theme.inactive)
+    };
+    let style_name = match ui.style_index {
+        0 => "Default".to_string(),
+        i => get_style_display_name(&ui.config.available_styles[i - 1]),
     };
     f.render_widget(
-        Paragraph::new(format!("Speed: {:.1}x (arrows to adjust)", ui.config.rotation_speed))
+        Paragraph::new(format!(
+            "  < {} > ({}/{})",
+            style_name,
+            ui.style_index + 1,
+            ui.config.available_styles.len() + 1
+        ))
+        .style(style_display_style),
+        chunks[17],
+    );
+
+    // Instances section
+    let instances_style = if ui.focus == Focus::Instances {
+        Style::default().fg(theme.focus)
+    } else {
+        Style::default().fg(theme.label)
+    };
+    f.render_widget(
+        Paragraph::new("Instances: (arrows to cycle, +/- to change count)").style(instances_style),
+        chunks[18],
+    );
+
+    let instances_display_style = if ui.focus == Focus::Instances {
+        Style::default().fg(theme.value).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.inactive)
+    };
+    let instance_pattern = InstancePattern::all()[ui.instance_index];
+    let instances_text = if instance_pattern == InstancePattern::Single {
+        format!("  < {} >", instance_pattern.name())
+    } else {
+        format!("  < {} > x{}", instance_pattern.name(), ui.config.instance_count)
+    };
+    f.render_widget(
+        Paragraph::new(instances_text).style(instances_display_style),
+        chunks[19],
+    );
+
+    // Marquee section
+    let marquee_style = if ui.focus == Focus::Marquee {
+        Style::default().fg(theme.focus)
+    } else {
+        Style::default().fg(theme.label)
+    };
+    f.render_widget(
+        Paragraph::new("Marquee: (arrows to cycle path, type to edit text)").style(marquee_style),
+        chunks[20],
+    );
+
+    let marquee_display_style = if ui.focus == Focus::Marquee {
+        Style::default().fg(theme.value).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.inactive)
+    };
+    let cursor = if ui.focus == Focus::Marquee { "_" } else { "" };
+    f.render_widget(
+        Paragraph::new(format!("  < {} > {}{}", ui.config.marquee.name(), ui.config.marquee_text, cursor))
+            .style(marquee_display_style),
+        chunks[21],
+    );
+
+    // Animation section
+    let speed_style = if matches!(ui.focus, Focus::Speed | Focus::Playback) {
+        Style::default().fg(theme.focus)
+    } else {
+        Style::default().fg(theme.label)
+    };
+    f.render_widget(
+        Paragraph::new(format!("Animation: {:.1}x (arrows to adjust speed and playback)", ui.config.rotation_speed))
             .style(speed_style),
-        chunks[8],
+        chunks[22],
     );
 
     // Speed slider
-    let slider_width = chunks[9].width.saturating_sub(2) as usize;
+    let slider_width = chunks[23].width.saturating_sub(2) as usize;
     let speed_normalized = ((ui.config.rotation_speed - 0.1) / 2.9).clamp(0.0, 1.0);
     let filled = (speed_normalized * slider_width as f32) as usize;
     let slider = format!(
@@ -436,22 +863,33 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
         " ".repeat(slider_width.saturating_sub(filled))
     );
     let slider_style = if ui.focus == Focus::Speed {
-        Style::default().fg(Color::Cyan)
+        Style::default().fg(theme.value)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme.dim)
     };
-    f.render_widget(Paragraph::new(slider).style(slider_style), chunks[9]);
+    f.render_widget(Paragraph::new(slider).style(slider_style), chunks[23]);
+
+    // Playback selector
+    let playback_style = if ui.focus == Focus::Playback {
+        Style::default().fg(theme.value).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.inactive)
+    };
+    f.render_widget(
+        Paragraph::new(format!("  < {} >", ui.config.playback.name())).style(playback_style),
+        chunks[24],
+    );
 
     // Buttons
     let apply_style = if ui.focus == Focus::Buttons && ui.button_index == 0 {
-        Style::default().fg(Color::Black).bg(Color::Green)
+        Style::default().fg(Color::Black).bg(theme.ok)
     } else {
-        Style::default().fg(Color::Green)
+        Style::default().fg(theme.ok)
     };
     let cancel_style = if ui.focus == Focus::Buttons && ui.button_index == 1 {
-        Style::default().fg(Color::Black).bg(Color::Red)
+        Style::default().fg(Color::Black).bg(theme.error)
     } else {
-        Style::default().fg(Color::Red)
+        Style::default().fg(theme.error)
     };
 
     let buttons = Line::from(vec![
@@ -460,5 +898,52 @@ fn draw_config_ui(f: &mut Frame, ui: &mut ConfigUI) {
         Span::raw("    "),
         Span::styled(" Cancel ", cancel_style),
     ]);
-    f.render_widget(Paragraph::new(buttons), chunks[11]);
+    f.render_widget(Paragraph::new(buttons), chunks[26]);
+
+    if let Some(ref message) = ui.error {
+        draw_error_popup(f, popup_area, message, theme, charset);
+    }
+}
+
+/// Draw an error message centered over the config popup
+fn draw_error_popup(f: &mut Frame, parent: Rect, message: &str, theme: &Theme, charset: Charset) {
+    let width = parent.width.saturating_sub(6).max(20).min(parent.width);
+    // Borders, blank line, and hint take 4 rows; wrap the message to estimate the rest
+    let text_width = width.saturating_sub(4).max(1) as usize;
+    let text_rows: usize = message
+        .lines()
+        .map(|line| line.chars().count().div_ceil(text_width).max(1))
+        .sum();
+    let height = (text_rows as u16 + 4).min(parent.height);
+    let area = Rect::new(
+        parent.x + (parent.width - width) / 2,
+        parent.y + (parent.height - height) / 2,
+        width,
+        height,
+    );
+
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .title(" Error ")
+        .borders(Borders::ALL)
+        .border_set(charset.border_set())
+        .border_style(Style::default().fg(theme.error));
+
+    let mut lines: Vec<Line> = message
+        .lines()
+        .map(|line| Line::from(Span::styled(line.to_string(), Style::default().fg(theme.label))))
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Press any key to choose another file",
+        Style::default().fg(theme.dim),
+    )));
+
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .style(Style::default().bg(theme.popup_bg)),
+        area,
+    );
 }
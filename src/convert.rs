@@ -0,0 +1,196 @@
+//! Image-to-ASCII conversion: runs still images through the edge/Sobel/ASCII compute pipeline
+//! in place of the 3D render, for the `convert` subcommand
+
+use anyhow::{anyhow, Context, Result};
+use std::io::Write;
+
+use crate::cli::ConvertArgs;
+use crate::config::ConfigFile;
+use crate::gpu::{request_device, AsciiPipeline, CharRamp, EdgeAlgorithm};
+use crate::terminal::{frame_to_text, ColorDepth, HalfBlock, Palette, TextStyle};
+
+/// Terminal columns when neither `--cols` nor a terminal size is available
+const DEFAULT_COLS: u16 = 80;
+
+/// Width / height of a terminal character cell
+const CELL_ASPECT: f32 = 0.5;
+
+/// Feeds images into an ASCII pipeline on its own GPU device
+pub struct ImageConverter {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    /// Created on the first conversion, once the grid size is known
+    pipeline: Option<AsciiPipeline>,
+    edge_algorithm: EdgeAlgorithm,
+    ramp: CharRamp,
+}
+
+impl ImageConverter {
+    pub fn new(edge_algorithm: EdgeAlgorithm, ramp: CharRamp) -> Result<Self> {
+        let (device, queue, _) = pollster::block_on(request_device())?;
+        Ok(Self { device, queue, pipeline: None, edge_algorithm, ramp })
+    }
+
+    /// Convert an image to packed cells (`0xRRGGBBCC`, as from the 3D view) on a cols x rows grid
+    /// The image is resized to `px_x` x `px_y` pixels per cell first so edge thresholds behave the
+    /// same as on a render
+    pub fn convert(&mut self, image: &image::RgbaImage, cols: u32, rows: u32, px_x: u32, px_y: u32) -> Result<Vec<u32>> {
+        let (width, height) = (cols * px_x, rows * px_y);
+        let max_dim = self.device.limits().max_texture_dimension_2d;
+        if width > max_dim || height > max_dim {
+            return Err(anyhow!("{}x{} cells need a {}x{} texture, over the GPU limit of {}", cols, rows, width, height, max_dim));
+        }
+        let resized = image::imageops::resize(image, width, height, image::imageops::FilterType::Triangle);
+
+        // Images are sRGB-encoded; the sRGB view decodes to the linear color the pipeline expects
+        let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+        let color_texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Convert Image Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &color_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            resized.as_raw(),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+
+        // No geometry: depth is cleared to far, so only luminance edges are found
+        let depth_texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Convert Depth Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        // Nothing moves: textures start zeroed
+        let motion_texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Convert Motion Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rg16Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        // No parts either: every pixel is background
+        let part_id_texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Convert Part ID Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R32Uint,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let color_view = color_texture.create_view(&Default::default());
+        let depth_view = depth_texture.create_view(&Default::default());
+        let motion_view = motion_texture.create_view(&Default::default());
+        let part_id_view = part_id_texture.create_view(&Default::default());
+
+        let pipeline = match self.pipeline.as_mut() {
+            Some(pipeline) => {
+                pipeline.resize(&self.device, cols, rows, width, height);
+                pipeline
+            }
+            None => {
+                let mut pipeline = AsciiPipeline::new(&self.device, cols, rows, width, height)?;
+                // A photo is already exposed; keep its tones as they are
+                pipeline.set_exposure(1.0);
+                pipeline.set_gamma(1.0);
+                pipeline.set_edge_algorithm(self.edge_algorithm);
+                pipeline.set_ramp(self.ramp);
+                self.pipeline.insert(pipeline)
+            }
+        };
+        pipeline.update_bind_groups(&self.device, &self.queue, &color_view, &depth_view, &motion_view, &part_id_view);
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Convert Encoder"),
+        });
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Clear Depth Pass"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pipeline.dispatch(&mut encoder);
+        pipeline.copy_to_staging(&mut encoder);
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        pollster::block_on(pipeline.read_results(&self.device))
+    }
+}
+
+/// Run the `convert` subcommand: print (or write) each input image as ASCII art, in order
+/// Video can be converted by extracting its frames to images first
+pub fn run(args: &ConvertArgs, config: &ConfigFile) -> Result<()> {
+    let term_cols = args
+        .cols
+        .or_else(|| crossterm::terminal::size().ok().map(|(cols, _)| cols))
+        .unwrap_or(DEFAULT_COLS)
+        .max(1);
+    let palette = Palette::new(config.color_depth.unwrap_or_else(ColorDepth::detect), config.chroma_boost);
+    let style = TextStyle {
+        palette: &palette,
+        charset: config.charset,
+        half_block: config.half_block.unwrap_or_else(HalfBlock::detect),
+    };
+
+    let mut converter = ImageConverter::new(config.edge_algorithm, config.ramp)?;
+    let mut frames = Vec::with_capacity(args.inputs.len());
+    for path in &args.inputs {
+        let image = image::open(path)
+            .with_context(|| format!("Failed to read image {}", path.display()))?
+            .to_rgba8();
+
+        // Keep the picture's aspect ratio on tall terminal cells
+        let aspect = image.height() as f32 / image.width().max(1) as f32;
+        let term_rows = ((term_cols as f32 * aspect * CELL_ASPECT).round() as u16).max(1);
+        let (cols, rows, px_x, px_y) = crate::get_pipeline_dims(term_cols, term_rows, args.mode, 1);
+
+        let mut cells = converter.convert(&image, cols, rows, px_x, px_y)?;
+        config.color_adjust.apply(&mut cells);
+        frames.push(frame_to_text(&cells, cols, rows, args.mode, (u16::MAX, u16::MAX), &style));
+    }
+    // A blank line between frames
+    let output = frames.join("\n");
+
+    match &args.output {
+        Some(path) => std::fs::write(path, output).with_context(|| format!("Failed to write {}", path.display())),
+        None => {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(output.as_bytes())?;
+            stdout.flush()?;
+            Ok(())
+        }
+    }
+}
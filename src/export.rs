@@ -0,0 +1,292 @@
+//! Export dialog (X): every way to copy or save the current frame, in one place
+
+use anyhow::{anyhow, Context, Result};
+use arboard::{Clipboard, ImageData};
+use crossterm::event::KeyCode;
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::capture::{rasterize, CELL_HEIGHT, CELL_WIDTH};
+use crate::render_thread::FrameResult;
+use crate::terminal::{edge_char, edge_ink, half_block_edge, RenderMode, TerminalRenderer};
+
+/// Where an export goes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportTarget {
+    ClipboardText,
+    ClipboardImage,
+    Text,
+    Ansi,
+    Png,
+    Svg,
+    GifLoop,
+}
+
+impl ExportTarget {
+    const ALL: [ExportTarget; 7] = [
+        ExportTarget::ClipboardText,
+        ExportTarget::ClipboardImage,
+        ExportTarget::Text,
+        ExportTarget::Ansi,
+        ExportTarget::Png,
+        ExportTarget::Svg,
+        ExportTarget::GifLoop,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            ExportTarget::ClipboardText => "Clipboard: text with colors",
+            ExportTarget::ClipboardImage => "Clipboard: image",
+            ExportTarget::Text => "Plain text (.txt)",
+            ExportTarget::Ansi => "ANSI art (.ans)",
+            ExportTarget::Png => "Image (.png)",
+            ExportTarget::Svg => "Vector image (.svg)",
+            ExportTarget::GifLoop => "One rotation, looping (.gif)",
+        }
+    }
+
+    /// File extension, None for clipboard targets
+    fn extension(&self) -> Option<&'static str> {
+        match self {
+            ExportTarget::ClipboardText | ExportTarget::ClipboardImage => None,
+            ExportTarget::Text => Some("txt"),
+            ExportTarget::Ansi => Some("ans"),
+            ExportTarget::Png => Some("png"),
+            ExportTarget::Svg => Some("svg"),
+            ExportTarget::GifLoop => Some("gif"),
+        }
+    }
+}
+
+/// What a key press in the dialog asks for
+pub enum DialogAction {
+    None,
+    Close,
+    /// Export to the target (and file, for file targets)
+    Export(ExportTarget, Option<PathBuf>),
+}
+
+/// Destination list and file name entry
+pub struct ExportDialog {
+    selected: usize,
+    /// File name without the extension, which follows the selected target
+    stem: String,
+}
+
+impl ExportDialog {
+    pub fn new() -> Self {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            selected: 0,
+            stem: format!("ascii-3d-{}", now),
+        }
+    }
+
+    fn target(&self) -> ExportTarget {
+        ExportTarget::ALL[self.selected]
+    }
+
+    /// Up/Down choose a destination, typing edits the file name, Enter exports, Esc closes
+    pub fn handle_key(&mut self, code: KeyCode) -> DialogAction {
+        let count = ExportTarget::ALL.len();
+        match code {
+            KeyCode::Esc => return DialogAction::Close,
+            KeyCode::Up => self.selected = (self.selected + count - 1) % count,
+            KeyCode::Down | KeyCode::Tab => self.selected = (self.selected + 1) % count,
+            KeyCode::Backspace => {
+                self.stem.pop();
+            }
+            KeyCode::Char(c) if !c.is_control() => self.stem.push(c),
+            KeyCode::Enter => {
+                let target = self.target();
+                return match target.extension() {
+                    None => DialogAction::Export(target, None),
+                    Some(_) if self.stem.trim().is_empty() => DialogAction::None,
+                    Some(ext) => DialogAction::Export(target, Some(PathBuf::from(format!("{}.{}", self.stem.trim(), ext)))),
+                };
+            }
+            _ => {}
+        }
+        DialogAction::None
+    }
+
+    /// Dialog lines, with the index of the selected one
+    pub fn lines(&self) -> (Vec<String>, usize) {
+        let mut lines: Vec<String> = ExportTarget::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, target)| format!("{} {}", if i == self.selected { ">" } else { " " }, target.label()))
+            .collect();
+        lines.push(String::new());
+        lines.push(match self.target().extension() {
+            Some(ext) => format!("File: {}_.{}", self.stem, ext),
+            None => "File: (copied to the clipboard)".to_string(),
+        });
+        lines.push("Up/Down: choose  Type: file name  Enter: export  Esc: close".to_string());
+        (lines, self.selected)
+    }
+}
+
+/// Export a frame drawn in `mode` to a clipboard or file target (the GIF loop is captured by
+/// the caller); returns the message to show
+pub fn export_frame(target: ExportTarget, path: Option<&Path>, frame: &FrameResult, mode: RenderMode, term: &TerminalRenderer) -> Result<String> {
+    let (cells, cols, rows) = (&frame.cells, frame.cols, frame.rows);
+    let file = || path.ok_or_else(|| anyhow!("No file name"));
+    match target {
+        ExportTarget::ClipboardText => {
+            let text = term.frame_to_ansi_string(cells, cols, rows, mode);
+            Clipboard::new()?.set_text(text)?;
+            Ok("Frame copied to the clipboard".to_string())
+        }
+        ExportTarget::ClipboardImage => {
+            let image = rasterize(cells, cols, rows, mode);
+            Clipboard::new()?.set_image(ImageData {
+                width: image.width() as usize,
+                height: image.height() as usize,
+                bytes: Cow::Owned(image.into_raw()),
+            })?;
+            Ok("Frame image copied to the clipboard".to_string())
+        }
+        ExportTarget::Text => {
+            if mode.is_half_block() {
+                return Err(anyhow!("Plain text needs an ASCII render mode (1 or 2)"));
+            }
+            let path = file()?;
+            let text = term.frame_to_ansi_string(cells, cols, rows, RenderMode::PlainAscii);
+            std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(format!("Frame written to {}", path.display()))
+        }
+        ExportTarget::Ansi => {
+            let path = file()?;
+            let text = term.frame_to_ansi_string(cells, cols, rows, mode);
+            std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(format!("Frame written to {}", path.display()))
+        }
+        ExportTarget::Png => {
+            let path = file()?;
+            rasterize(cells, cols, rows, mode)
+                .save(path)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(format!("Frame written to {}", path.display()))
+        }
+        ExportTarget::Svg => {
+            let path = file()?;
+            let text = term.frame_to_ansi_string(cells, cols, rows, RenderMode::PlainAscii);
+            let svg = frame_to_svg(cells, cols, rows, mode, &text);
+            std::fs::write(path, svg).with_context(|| format!("Failed to write {}", path.display()))?;
+            Ok(format!("Frame written to {}", path.display()))
+        }
+        ExportTarget::GifLoop => Err(anyhow!("GIF loops are captured over several frames")),
+    }
+}
+
+/// SVG of a frame on a black background: monospace text colored per cell (runs of one color
+/// share a `<tspan>`), or colored rectangles in the half-block modes
+/// `plain` is the frame as plain text, so characters match the configured charset
+fn frame_to_svg(cells: &[u32], cols: u32, rows: u32, mode: RenderMode, plain: &str) -> String {
+    let color = |packed: u32| format!("#{:06x}", packed >> 8);
+    let (width, height) = if mode.is_half_block() {
+        (cols * CELL_WIDTH, rows * CELL_HEIGHT / 2)
+    } else {
+        (cols * CELL_WIDTH, rows * CELL_HEIGHT)
+    };
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n<rect width=\"100%\" height=\"100%\" fill=\"#000\"/>\n",
+        w = width,
+        h = height
+    );
+
+    if mode.is_half_block() {
+        let half = CELL_HEIGHT / 2;
+        for row in 0..rows {
+            for col in 0..cols {
+                if let Some(&packed) = cells.get((row * cols + col) as usize) {
+                    let _ = writeln!(
+                        svg,
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                        col * CELL_WIDTH,
+                        row * half,
+                        CELL_WIDTH,
+                        half,
+                        color(packed)
+                    );
+                }
+            }
+        }
+        // Edge characters in contrasting ink over the blended cell
+        if mode == RenderMode::HalfBlockEdges {
+            for row in 0..rows / 2 {
+                for col in 0..cols {
+                    let pixel = |r: u32| cells.get((r * cols + col) as usize).copied().unwrap_or(0);
+                    let (top, bottom) = (pixel(row * 2), pixel(row * 2 + 1));
+                    let Some(edge) = half_block_edge(top as u8, bottom as u8) else {
+                        continue;
+                    };
+                    let avg = |shift: u32| ((((top >> shift) & 0xFF) + ((bottom >> shift) & 0xFF)) / 2) as u8;
+                    let background = (avg(24), avg(16), avg(8));
+                    let ink = edge_ink(background);
+                    let (x, y) = (col * CELL_WIDTH, row * CELL_HEIGHT);
+                    let _ = writeln!(
+                        svg,
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#{:02x}{:02x}{:02x}\"/>",
+                        x, y, CELL_WIDTH, CELL_HEIGHT, background.0, background.1, background.2
+                    );
+                    let _ = writeln!(
+                        svg,
+                        "<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" fill=\"#{:02x}{:02x}{:02x}\">{}</text>",
+                        x,
+                        y + CELL_HEIGHT - CELL_HEIGHT / 4,
+                        CELL_WIDTH as f32 / 0.6,
+                        ink.0,
+                        ink.1,
+                        ink.2,
+                        edge_char(edge)
+                    );
+                }
+            }
+        }
+    } else {
+        let _ = writeln!(
+            svg,
+            "<g font-family=\"monospace\" font-size=\"{}\" xml:space=\"preserve\">",
+            CELL_WIDTH as f32 / 0.6
+        );
+        for (row, line) in plain.lines().enumerate() {
+            let fill_at = |col: usize| match mode {
+                RenderMode::PlainAscii => "#ccc".to_string(),
+                _ => cells.get(row * cols as usize + col).map_or("#ccc".to_string(), |&packed| color(packed)),
+            };
+            let y = (row as u32 + 1) * CELL_HEIGHT - CELL_HEIGHT / 4;
+            let _ = write!(svg, "<text y=\"{}\">", y);
+            let chars: Vec<char> = line.chars().collect();
+            let mut col = 0;
+            while col < chars.len() {
+                if chars[col] == ' ' {
+                    col += 1;
+                    continue;
+                }
+                // One tspan per run of a single color
+                let (start, fill) = (col, fill_at(col));
+                let mut run = String::new();
+                while col < chars.len() && chars[col] != ' ' && fill_at(col) == fill {
+                    match chars[col] {
+                        '<' => run.push_str("&lt;"),
+                        '>' => run.push_str("&gt;"),
+                        '&' => run.push_str("&amp;"),
+                        c => run.push(c),
+                    }
+                    col += 1;
+                }
+                let _ = write!(svg, "<tspan x=\"{}\" fill=\"{}\">{}</tspan>", start as u32 * CELL_WIDTH, fill, run);
+            }
+            svg.push_str("</text>\n");
+        }
+        svg.push_str("</g>\n");
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
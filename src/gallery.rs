@@ -0,0 +1,160 @@
+//! Asset gallery: downloads the curated CC0 models and skyboxes listed in a manifest into the
+//! assets directories, for the `gallery` subcommand, so a fresh checkout has something to show
+//! (entries already downloaded are skipped, so running it again fetches only what's new)
+
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::cli::GalleryArgs;
+use crate::model::{download, fetch_text, url_file_name};
+
+/// Manifest read when no `--manifest` is given: the curated list kept in this repository, fetched
+/// from GitHub so new entries reach existing builds
+pub const DEFAULT_MANIFEST: &str =
+    "https://raw.githubusercontent.com/tannerharkin/ascii-3d-terminal-demo/main/assets/gallery.toml";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AssetKind {
+    Model,
+    Skybox,
+}
+
+impl AssetKind {
+    /// Directory the demo lists this kind of asset from
+    fn dir(self) -> &'static str {
+        match self {
+            AssetKind::Model => crate::MODELS_DIR,
+            AssetKind::Skybox => crate::SKYBOXES_DIR,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            AssetKind::Model => "model",
+            AssetKind::Skybox => "skybox",
+        }
+    }
+}
+
+/// One `[[model]]` or `[[skybox]]` table of the manifest
+struct GalleryEntry {
+    kind: AssetKind,
+    name: String,
+    url: String,
+    /// File name in the assets directory (the URL's last path segment unless the entry sets `file`)
+    file: String,
+    license: String,
+    author: Option<String>,
+    /// Skybox projection written to the image's sidecar TOML ("panorama" for 360° images)
+    projection: Option<String>,
+}
+
+impl GalleryEntry {
+    fn path(&self) -> PathBuf {
+        Path::new(self.kind.dir()).join(&self.file)
+    }
+
+    /// "Name (license, author)" for progress and listings
+    fn credit(&self) -> String {
+        match &self.author {
+            Some(author) => format!("{} ({}, {})", self.name, self.license, author),
+            None => format!("{} ({})", self.name, self.license),
+        }
+    }
+}
+
+/// Parse a manifest: `[[model]]` and `[[skybox]]` tables with `name`, `url`, and `license`, and
+/// optionally `author`, `file`, and (skyboxes) `projection`
+fn parse_manifest(text: &str) -> Result<Vec<GalleryEntry>> {
+    let table: toml::Table = text.parse()?;
+    let mut entries = Vec::new();
+    for (key, value) in &table {
+        let kind = match key.as_str() {
+            "model" => AssetKind::Model,
+            "skybox" => AssetKind::Skybox,
+            other => return Err(anyhow!("Unknown manifest section {:?} (expected [[model]] or [[skybox]])", other)),
+        };
+        let items = value
+            .as_array()
+            .ok_or_else(|| anyhow!("{} must be a list of [[{}]] tables", key, key))?;
+        for item in items {
+            let item = item
+                .as_table()
+                .ok_or_else(|| anyhow!("{} must be a list of [[{}]] tables", key, key))?;
+            let field = |name: &str| item.get(name).and_then(|value| value.as_str()).map(str::to_string);
+            let name = field("name").ok_or_else(|| anyhow!("A [[{}]] entry has no name", key))?;
+            let url = field("url").ok_or_else(|| anyhow!("{} has no url", name))?;
+            let license = field("license").ok_or_else(|| anyhow!("{} has no license", name))?;
+            let file = field("file")
+                .or_else(|| url_file_name(&url).map(str::to_string))
+                .ok_or_else(|| anyhow!("{} needs a file name (its url doesn't end in one)", name))?;
+            // The file lands in the assets directory; a path could put it anywhere
+            if file.contains(['/', '\\']) || file.starts_with('.') {
+                return Err(anyhow!("{} has an invalid file name {:?}", name, file));
+            }
+            entries.push(GalleryEntry {
+                kind,
+                name,
+                url,
+                file,
+                license,
+                author: field("author"),
+                projection: field("projection"),
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// Run the `gallery` subcommand: download the manifest's new entries (or list them with `--list`)
+pub fn run(args: &GalleryArgs) -> Result<()> {
+    let source = args.manifest.as_deref().unwrap_or(DEFAULT_MANIFEST);
+    let text = if source.starts_with("http://") || source.starts_with("https://") {
+        fetch_text(source)?
+    } else {
+        std::fs::read_to_string(source).with_context(|| format!("Failed to read manifest {}", source))?
+    };
+    let entries = parse_manifest(&text).with_context(|| format!("Invalid gallery manifest {}", source))?;
+
+    if args.list {
+        for entry in &entries {
+            let state = if entry.path().is_file() { "downloaded" } else { "new" };
+            println!("{:<7} {:<11} {}", entry.kind.name(), state, entry.credit());
+        }
+        return Ok(());
+    }
+
+    let mut added = 0;
+    let mut present = 0;
+    let mut failed = Vec::new();
+    for entry in &entries {
+        let path = entry.path();
+        if path.is_file() {
+            present += 1;
+            continue;
+        }
+        let dir = Path::new(entry.kind.dir());
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        eprintln!("{}", entry.credit());
+        // One broken link shouldn't cost the rest of the gallery
+        match download(&entry.url, &path) {
+            Ok(()) => {
+                if let Some(ref projection) = entry.projection {
+                    let sidecar = path.with_extension("toml");
+                    std::fs::write(&sidecar, format!("projection = {:?}\n", projection))
+                        .with_context(|| format!("Failed to write {}", sidecar.display()))?;
+                }
+                added += 1;
+            }
+            Err(e) => {
+                eprintln!("{:#}", e);
+                failed.push(entry.name.as_str());
+            }
+        }
+    }
+    println!("{} new, {} already downloaded", added, present);
+    if !failed.is_empty() {
+        return Err(anyhow!("Failed to download {}", failed.join(", ")));
+    }
+    Ok(())
+}
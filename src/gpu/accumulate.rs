@@ -0,0 +1,196 @@
+use bytemuck::{Pod, Zeroable};
+
+use super::pipeline::PIXEL_WORKGROUP_SIZE;
+
+/// Renders averaged into a still frame before it counts as converged (later ones are skipped)
+pub const MAX_SAMPLES: u32 = 64;
+
+/// Running average format (more precision than the render target, so late samples still count)
+const HISTORY_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba32Float;
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct AccumulateUniforms {
+    width: u32,
+    height: u32,
+    weight: f32,
+    _padding: u32,
+}
+
+/// Sub-pixel offset of sample `sample` (in pixels, within half a pixel of the center): the
+/// Halton (2, 3) sequence, which covers the pixel evenly however many samples are taken
+/// The first sample is unjittered, so a frame that never converges looks as it would without
+pub(super) fn jitter(sample: u32) -> (f32, f32) {
+    if sample == 0 {
+        return (0.0, 0.0);
+    }
+    (halton(sample, 2) - 0.5, halton(sample, 3) - 0.5)
+}
+
+fn halton(mut index: u32, base: u32) -> f32 {
+    let mut fraction = 1.0;
+    let mut result = 0.0;
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
+
+/// Averages jittered renders of a still scene into one of two history textures, reading the
+/// other (they swap roles each sample)
+pub(super) struct AccumulatePass {
+    pipeline: wgpu::ComputePipeline,
+    layout: wgpu::BindGroupLayout,
+    uniform_buffer: wgpu::Buffer,
+    history: [(wgpu::Texture, wgpu::TextureView); 2],
+    width: u32,
+    height: u32,
+}
+
+impl AccumulatePass {
+    pub fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Accumulate Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/accumulate.wgsl").into()),
+        });
+
+        let texture = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Accumulate Bind Group Layout"),
+            entries: &[
+                texture(0),
+                texture(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: HISTORY_FORMAT,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Accumulate Pipeline Layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Accumulate Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Accumulate Uniform Buffer"),
+            size: std::mem::size_of::<AccumulateUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline,
+            layout,
+            uniform_buffer,
+            history: std::array::from_fn(|_| create_history_texture(device, width, height)),
+            width,
+            height,
+        }
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        if (width, height) != (self.width, self.height) {
+            self.history = std::array::from_fn(|_| create_history_texture(device, width, height));
+            self.width = width;
+            self.height = height;
+        }
+    }
+
+    /// History texture `index` (0 or 1)
+    pub fn view(&self, index: usize) -> &wgpu::TextureView {
+        &self.history[index].1
+    }
+
+    /// Blend `scene_view` into history texture `target` with share `weight`, over the other one
+    pub fn encode(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        scene_view: &wgpu::TextureView,
+        target: usize,
+        weight: f32,
+    ) {
+        let uniforms = AccumulateUniforms {
+            width: self.width,
+            height: self.height,
+            weight,
+            _padding: 0,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Accumulate Bind Group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(scene_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(self.view(1 - target)) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(self.view(target)) },
+                wgpu::BindGroupEntry { binding: 3, resource: self.uniform_buffer.as_entire_binding() },
+            ],
+        });
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Accumulate Pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(self.width.div_ceil(PIXEL_WORKGROUP_SIZE), self.height.div_ceil(PIXEL_WORKGROUP_SIZE), 1);
+    }
+}
+
+fn create_history_texture(device: &wgpu::Device, width: u32, height: u32) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Accumulation History Texture"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: HISTORY_FORMAT,
+        usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
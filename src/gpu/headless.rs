@@ -1,8 +1,16 @@
 use anyhow::Result;
 use bytemuck::{Pod, Zeroable};
 use glam::{Mat4, Vec3};
+use std::cell::{Cell, RefCell};
 use wgpu::util::DeviceExt;
 
+use super::accumulate::{jitter, AccumulatePass, MAX_SAMPLES};
+use super::labels::LabelAnchor;
+use super::life::LifePass;
+use super::sdf::{SdfPass, SdfScene, SDF_BOUND_RADIUS};
+use super::wave::WavePass;
+use crate::model::bounding_radius;
+
 /// Vertex type for 3D models
 /// Matches the layout expected by the shader
 #[repr(C)]
@@ -13,6 +21,31 @@ pub struct Vertex {
     pub color: [f32; 3],
 }
 
+/// Surface properties shared by one sub-mesh, applied on top of its vertex colors
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Material {
+    /// Multiplied into the vertex colors
+    pub base_color: [f32; 3],
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self { base_color: [1.0; 3] }
+    }
+}
+
+/// Contiguous index range for one sub-mesh (OBJ object or glTF primitive), drawn as its own call
+#[derive(Clone, Copy, Debug)]
+pub struct MeshRange {
+    pub first_index: u32,
+    pub index_count: u32,
+    /// Bounding box center of the sub-mesh in normalized model space
+    pub centroid: [f32; 3],
+    /// Half the bounding box diagonal of the sub-mesh
+    pub extent: f32,
+    pub material: Material,
+}
+
 /// Rotation mode for the rendered model
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum RotationMode {
@@ -23,6 +56,7 @@ pub enum RotationMode {
     AxisZ,
     Tumble,
     Orbit,
+    Physics,
 }
 
 impl RotationMode {
@@ -34,6 +68,7 @@ impl RotationMode {
             RotationMode::AxisZ => "Z Axis",
             RotationMode::Tumble => "Tumble",
             RotationMode::Orbit => "Orbit",
+            RotationMode::Physics => "Physics",
         }
     }
 
@@ -45,8 +80,105 @@ impl RotationMode {
             RotationMode::AxisZ,
             RotationMode::Tumble,
             RotationMode::Orbit,
+            RotationMode::Physics,
         ]
     }
+
+    /// Cycle to the next rotation mode (wraps around)
+    pub fn next(&self) -> RotationMode {
+        let all = Self::all();
+        let index = all.iter().position(|m| m == self).unwrap_or(0);
+        all[(index + 1) % all.len()]
+    }
+
+    /// Whether the model drifts unpredictably (several axes at once, or bouncing), which reduced
+    /// motion leaves out
+    pub fn drifts(&self) -> bool {
+        matches!(self, RotationMode::Tumble | RotationMode::Physics)
+    }
+
+    /// Seconds until the pose repeats exactly at `speed` with the Tumble mode composed from
+    /// `tumble` (matching `render_with_rotation`), or None for modes that never change or never repeat
+    pub fn loop_period(&self, speed: f32, tumble: TumbleRates) -> Option<f32> {
+        use std::f32::consts::TAU;
+        match self {
+            RotationMode::AxisX | RotationMode::AxisY | RotationMode::AxisZ => Some(TAU / speed),
+            RotationMode::Tumble => tumble.loop_turns().map(|turns| turns as f32 * TAU / speed),
+            RotationMode::Orbit => Some(2.0 * TAU / speed),
+            RotationMode::Static | RotationMode::Physics => None,
+        }
+    }
+}
+
+/// Camera path of the Orbit rotation mode: a circle of `radius` around `target`, `height` above it,
+/// at half the rotation speed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitCamera {
+    pub radius: f32,
+    pub height: f32,
+    pub target: Vec3,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self { radius: CAMERA_DISTANCE, height: 1.5, target: Vec3::ZERO }
+    }
+}
+
+impl OrbitCamera {
+    /// Camera position at `angle` (time times speed)
+    pub fn eye(&self, angle: f32) -> Vec3 {
+        let angle = angle * 0.5;
+        self.target + Vec3::new(self.radius * angle.cos(), self.height, self.radius * angle.sin())
+    }
+
+    /// Same path with the camera `scale` times as far from the target (radius and height, so it
+    /// looks down at the same angle)
+    pub fn zoomed(self, scale: f32) -> Self {
+        Self { radius: self.radius * scale, height: self.height * scale, ..self }
+    }
+}
+
+/// Most turns a Tumble composition may take to come back to its starting pose and still count
+/// as a loop
+const MAX_TUMBLE_TURNS: u32 = 100;
+
+/// Angular speed of each axis the Tumble mode composes (radians per second at 1x), applied
+/// Y first, then X, then Z
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TumbleRates {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Default for TumbleRates {
+    fn default() -> Self {
+        Self { x: 0.5, y: 0.7, z: 0.3 }
+    }
+}
+
+impl TumbleRates {
+    /// Model rotation at `angle` (time times speed)
+    pub fn rotation(&self, angle: f32) -> Mat4 {
+        Mat4::from_rotation_y(angle * self.y) * Mat4::from_rotation_x(angle * self.x) * Mat4::from_rotation_z(angle * self.z)
+    }
+
+    /// Fewest turns at 1 radian per second after which every axis is back to a whole turn
+    /// (10 for the default 0.7, 0.5 and 0.3), or None if they don't line up within
+    /// `MAX_TUMBLE_TURNS` or nothing turns
+    fn loop_turns(&self) -> Option<u32> {
+        let rates = [self.x, self.y, self.z];
+        if rates.iter().all(|&rate| rate == 0.0) {
+            return None;
+        }
+        (1..=MAX_TUMBLE_TURNS).find(|&turns| {
+            rates.iter().all(|&rate| {
+                let axis_turns = rate * turns as f32;
+                (axis_turns - axis_turns.round()).abs() < 1e-3
+            })
+        })
+    }
 }
 
 /// Lighting mode for rendering
@@ -59,6 +191,7 @@ pub enum LightingMode {
     Toon,          // Cel-shaded (quantized)
     Gradient,      // Height-based coloring
     Normals,       // Show normals as color
+    XRay,          // Dimmed back faces under translucent front faces (two passes)
 }
 
 impl LightingMode {
@@ -70,6 +203,7 @@ impl LightingMode {
             LightingMode::Toon => "Toon",
             LightingMode::Gradient => "Gradient",
             LightingMode::Normals => "Normals",
+            LightingMode::XRay => "X-Ray",
         }
     }
 
@@ -81,9 +215,17 @@ impl LightingMode {
             LightingMode::Toon,
             LightingMode::Gradient,
             LightingMode::Normals,
+            LightingMode::XRay,
         ]
     }
 
+    /// Cycle to the next lighting mode (wraps around)
+    pub fn next(&self) -> LightingMode {
+        let all = Self::all();
+        let index = all.iter().position(|m| m == self).unwrap_or(0);
+        all[(index + 1) % all.len()]
+    }
+
     pub fn to_u32(self) -> u32 {
         match self {
             LightingMode::Flat => 0,
@@ -92,10 +234,193 @@ impl LightingMode {
             LightingMode::Toon => 3,
             LightingMode::Gradient => 4,
             LightingMode::Normals => 5,
+            LightingMode::XRay => 6,
+        }
+    }
+}
+
+/// Procedural background drawn behind the model (a skybox image, if set, is drawn first)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BackgroundStyle {
+    #[default]
+    Solid,
+    Starfield,
+    Gradient,
+}
+
+impl BackgroundStyle {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackgroundStyle::Solid => "Solid",
+            BackgroundStyle::Starfield => "Starfield",
+            BackgroundStyle::Gradient => "Gradient",
+        }
+    }
+
+    pub fn all() -> &'static [BackgroundStyle] {
+        &[
+            BackgroundStyle::Solid,
+            BackgroundStyle::Starfield,
+            BackgroundStyle::Gradient,
+        ]
+    }
+}
+
+/// Top and bottom colors for the gradient background
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct GradientUniforms {
+    top: [f32; 4],
+    bottom: [f32; 4],
+}
+
+/// How a skybox image is mapped onto the screen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SkyboxProjection {
+    /// The whole image stretched over the screen
+    #[default]
+    Stretch,
+    /// An equirectangular panorama, viewed through a 90° window
+    Panorama,
+}
+
+impl SkyboxProjection {
+    fn to_u32(self) -> u32 {
+        match self {
+            SkyboxProjection::Stretch => 0,
+            SkyboxProjection::Panorama => 1,
+        }
+    }
+}
+
+/// Skybox crossfade state and scroll offsets
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct SkyboxUniforms {
+    /// 0 = previous image only, 1 = current image only
+    fade: f32,
+    /// 1 if there is a previous image to fade from (otherwise fade in from black)
+    has_previous: u32,
+    /// Horizontal texture offset of the current image in turns
+    offset: f32,
+    /// `SkyboxProjection` of the current image
+    projection: u32,
+    previous_offset: f32,
+    previous_projection: u32,
+    /// Brightness multiplier (1 = as loaded)
+    intensity: f32,
+    /// Mip level bias (0 = sharp)
+    blur: f32,
+}
+
+/// Seconds a skybox change takes to crossfade
+const SKYBOX_FADE_TIME: f32 = 1.0;
+
+/// Mip levels of blur at the strongest skybox blur setting
+const SKYBOX_MAX_BLUR: f32 = 6.0;
+
+/// A skybox image uploaded to the GPU
+struct SkyboxImage {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    projection: SkyboxProjection,
+    /// Fixed horizontal offset in turns, added to the auto-rotation
+    rotation_offset: f32,
+}
+
+/// Number of particles in the starfield background
+const STAR_COUNT: usize = 400;
+
+/// Distance from the camera to the origin for the built-in camera poses (before the camera is
+/// fitted to a model)
+pub const CAMERA_DISTANCE: f32 = 4.0;
+
+/// Vertical field of view of the camera (radians)
+const FIELD_OF_VIEW: f32 = std::f32::consts::FRAC_PI_4;
+
+/// Room left around a fitted model's bounding sphere (1 = touching the edges of the view)
+const FRAME_MARGIN: f32 = 1.1;
+
+/// Camera distance at which a bounding sphere of `radius` around the origin fills a view of
+/// `aspect` (width / height) whichever way the model turns
+pub fn framing_distance(radius: f32, aspect: f32) -> f32 {
+    let half_y = FIELD_OF_VIEW / 2.0;
+    let half_x = (half_y.tan() * aspect).atan();
+    radius * FRAME_MARGIN / half_y.min(half_x).sin()
+}
+
+/// Scene color target: linear and unclamped, converted for display by the ASCII pipeline
+const RENDER_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Motion vector target: each pixel's screen movement since the previous frame, as a fraction of
+/// the render size (x right, y down), written by the model pass alongside the color
+const MOTION_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rg16Float;
+
+/// Motion target of the model pass pipelines
+pub(super) const MOTION_TARGET: wgpu::ColorTargetState = wgpu::ColorTargetState {
+    format: MOTION_FORMAT,
+    blend: None,
+    write_mask: wgpu::ColorWrites::ALL,
+};
+
+/// Part ID target: which sub-mesh covers each pixel, as its index in the draw list plus one
+/// (0 = the background, or anything that isn't a model part), written by the model pass
+const PART_ID_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
+/// Part ID target of the model pipelines
+const PART_ID_TARGET: wgpu::ColorTargetState = wgpu::ColorTargetState {
+    format: PART_ID_FORMAT,
+    blend: None,
+    write_mask: wgpu::ColorWrites::ALL,
+};
+
+/// Part ID target of the other pipelines in the model pass, which leave it cleared
+pub(super) const NO_PART_ID_TARGET: wgpu::ColorTargetState = wgpu::ColorTargetState {
+    write_mask: wgpu::ColorWrites::empty(),
+    ..PART_ID_TARGET
+};
+
+/// Direction of the key light (world space)
+pub(super) const LIGHT_DIR: [f32; 4] = [0.5, 1.0, 0.3, 0.0];
+
+/// Closest near plane and farthest far plane of the scene projection; within these the planes
+/// are fitted to the scene's bounds each frame, so the depth buffer's precision is spent on it
+pub const Z_NEAR: f32 = 0.1;
+pub const Z_FAR: f32 = 100.0;
+
+/// Bounding sphere radius scale for the fitted clip planes (room for the water surface's waves)
+const CLIP_MARGIN: f32 = 1.1;
+
+/// Per-instance star data for the starfield background
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct StarInstance {
+    seed: [f32; 3],
+    params: [f32; 2],
+}
+
+impl StarInstance {
+    const ATTRIBS: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2];
+
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<StarInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBS,
         }
     }
 }
 
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct StarfieldUniforms {
+    time: f32,
+    aspect: f32,
+    speed: f32,
+    _padding: f32,
+}
+
 // Internal vertex type matching external Vertex layout
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
@@ -118,16 +443,167 @@ impl InternalVertex {
     }
 }
 
+/// Per-instance model transform (columns of a 4x4 matrix)
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct InstanceRaw {
+    transform: [[f32; 4]; 4],
+}
+
+impl InstanceRaw {
+    const ATTRIBS: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+        3 => Float32x4,
+        4 => Float32x4,
+        5 => Float32x4,
+        6 => Float32x4
+    ];
+
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<InstanceRaw>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 struct Uniforms {
     mvp: [[f32; 4]; 4],
     model: [[f32; 4]; 4],
     light_dir: [f32; 4],
-    // Lighting mode (0=Flat, 1=Diffuse, 2=Specular, 3=Toon, 4=Gradient, 5=Normals)
+    // Lighting mode (0=Flat, 1=Diffuse, 2=Specular, 3=Toon, 4=Gradient, 5=Normals, 6=X-Ray)
     // Pack with padding to ensure 16-byte alignment
     lighting_mode: u32,
-    _padding: [u32; 3],
+    // Key light multiplier (1.0 = normal)
+    light_intensity: f32,
+    _padding: [u32; 2],
+    // Camera position in world space (w unused), for view-dependent shading
+    camera_pos: [f32; 4],
+    // Last frame's model-view-projection, for motion vectors
+    prev_mvp: [[f32; 4]; 4],
+}
+
+/// Camera and model placement of an encoded frame
+#[derive(Clone, Copy, PartialEq)]
+struct FramePose {
+    view_proj: Mat4,
+    view: Mat4,
+    model: Mat4,
+    /// Animation time (the marquee spins with it)
+    time: f32,
+}
+
+/// Everything a frame's picture depends on, so still-frame accumulation can tell when it changes
+#[derive(Clone, PartialEq)]
+struct SceneKey {
+    pose: FramePose,
+    lighting: LightingMode,
+    /// Count of geometry, texture and color uploads
+    uploads: u64,
+    explode: f32,
+    show_bounds: bool,
+    show_model: bool,
+    sdf_scene: Option<SdfScene>,
+    light_intensity: f32,
+    part_visible: Vec<bool>,
+    background: BackgroundStyle,
+    skybox_rotation: f32,
+    skybox_intensity: f32,
+    skybox_blur: f32,
+}
+
+/// Still-frame accumulation progress: the scene being converged and the renders averaged so far
+#[derive(Default)]
+struct Accumulation {
+    key: Option<SceneKey>,
+    samples: u32,
+}
+
+/// Per-draw material, one entry per sub-mesh at `material_stride` offsets
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct MaterialUniforms {
+    base_color: [f32; 4],
+    /// Draw list index plus one, written to the part ID target (0 = not a part)
+    part_id: u32,
+    /// Nonzero for the selected part, which is tinted
+    highlight: u32,
+    _padding: [u32; 2],
+}
+
+/// Marquee spin around the model (radians per second of animation time)
+const MARQUEE_SPIN: f32 = 0.5;
+
+/// Text marquee circling the model: a mesh of its own, drawn with the model pipeline under a
+/// transform that spins independently of the model's
+struct Marquee {
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    num_indices: u32,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    /// Untinted material, and a single identity instance
+    material_bind_group: wgpu::BindGroup,
+    instance_buffer: wgpu::Buffer,
+}
+
+impl Marquee {
+    fn new(
+        device: &wgpu::Device,
+        uniform_layout: &wgpu::BindGroupLayout,
+        material_layout: &wgpu::BindGroupLayout,
+        material_stride: u32,
+    ) -> Self {
+        let empty = |label, usage| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: 4,
+                usage: usage | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        };
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Marquee Uniform Buffer"),
+            size: std::mem::size_of::<Uniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Marquee Uniform Bind Group"),
+            layout: uniform_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+        let mut material = vec![0u8; material_stride as usize];
+        material[..std::mem::size_of::<MaterialUniforms>()]
+            .copy_from_slice(bytemuck::bytes_of(&MaterialUniforms { base_color: [1.0; 4], ..Zeroable::zeroed() }));
+        let material_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marquee Material Buffer"),
+            contents: &material,
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Marquee Instance Buffer"),
+            contents: bytemuck::cast_slice(&[InstanceRaw {
+                transform: Mat4::IDENTITY.to_cols_array_2d(),
+            }]),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        Self {
+            vertex_buffer: empty("Marquee Vertex Buffer", wgpu::BufferUsages::VERTEX),
+            index_buffer: empty("Marquee Index Buffer", wgpu::BufferUsages::INDEX),
+            num_indices: 0,
+            uniform_buffer,
+            uniform_bind_group,
+            material_bind_group: create_material_bind_group(device, material_layout, &material_buffer),
+            instance_buffer,
+        }
+    }
 }
 
 pub struct HeadlessGpu {
@@ -137,12 +613,33 @@ pub struct HeadlessGpu {
     render_view: wgpu::TextureView,
     depth_texture: wgpu::Texture,
     depth_view: wgpu::TextureView,
+    motion_texture: wgpu::Texture,
+    motion_view: wgpu::TextureView,
+    part_id_texture: wgpu::Texture,
+    part_id_view: wgpu::TextureView,
     pipeline: wgpu::RenderPipeline,
+    // X-ray lighting: back faces (opaque, depth-writing), then front faces blended over them
+    xray_back_pipeline: wgpu::RenderPipeline,
+    xray_front_pipeline: wgpu::RenderPipeline,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     uniform_buffer: wgpu::Buffer,
     uniform_bind_group: wgpu::BindGroup,
     num_indices: u32,
+    // Per-instance transforms (one identity instance unless instancing is enabled)
+    instance_buffer: wgpu::Buffer,
+    instance_transforms: Vec<Mat4>,
+    num_instances: u32,
+    // Sub-mesh draw ranges and exploded-view offset scale (0 = assembled)
+    mesh_ranges: Vec<MeshRange>,
+    /// Sub-mesh tinted as selected (by index into the draw list)
+    selected_part: Option<usize>,
+    explode: f32,
+    // Sub-mesh materials, bound with a dynamic offset per draw
+    material_buffer: wgpu::Buffer,
+    material_bind_group_layout: wgpu::BindGroupLayout,
+    material_bind_group: wgpu::BindGroup,
+    material_stride: u32,
     width: u32,
     height: u32,
     gpu_name: String,
@@ -150,46 +647,130 @@ pub struct HeadlessGpu {
     skybox_pipeline: wgpu::RenderPipeline,
     skybox_bind_group_layout: wgpu::BindGroupLayout,
     skybox_sampler: wgpu::Sampler,
-    skybox_texture: Option<wgpu::Texture>,
+    skybox_uniform_buffer: wgpu::Buffer,
+    skybox: Option<SkyboxImage>,
+    /// The image being faded out after a change
+    skybox_previous: Option<SkyboxImage>,
+    /// Texture of a cleared skybox, kept so a later image of the same size can reuse it
+    skybox_spare: Option<wgpu::Texture>,
     skybox_bind_group: Option<wgpu::BindGroup>,
+    /// Animation time the last skybox change started fading
+    skybox_fade_start: f32,
+    /// Background auto-rotation in turns per second
+    skybox_rotation: f32,
+    /// Axis rates the Tumble rotation mode composes
+    tumble: TumbleRates,
+    /// World rotation the camera sees the scene through (a tiled instance's fixed view)
+    view_turn: Mat4,
+    /// Skybox brightness multiplier and blur amount (0 to 1)
+    skybox_intensity: f32,
+    skybox_blur: f32,
+    /// Live video frame drawn in place of the skybox (texture and its skybox bind group)
+    video: Option<(wgpu::Texture, wgpu::BindGroup)>,
+    /// Draw the model (off shows only the background, e.g. video alone)
+    show_model: bool,
+    /// Raymarched scene drawn in place of the mesh
+    sdf_scene: Option<SdfScene>,
+    sdf_pass: SdfPass,
+    /// Water ripple simulation animating the mesh, when it is the water surface
+    wave_pass: WavePass,
+    wave_enabled: bool,
+    life_pass: LifePass,
+    life_enabled: bool,
+    marquee: Marquee,
+    /// Bounding sphere radii around the origin of the model mesh, the text marquee, and the
+    /// bounding box overlay, which the clip planes are fitted to
+    model_radius: f32,
+    marquee_radius: f32,
+    bounds_radius: f32,
+    /// Clip planes of the last frame encoded (the ASCII pipeline linearizes depth with them)
+    clip_planes: Cell<(f32, f32)>,
+    /// Averages jittered renders while the scene holds still (None = off)
+    accumulate: Option<AccumulatePass>,
+    accumulation: RefCell<Accumulation>,
+    /// Rendering a second view of the frame (the split pane), which accumulation leaves out
+    accumulation_paused: Cell<bool>,
+    /// Count of uploads that change the picture (see `SceneKey`)
+    uploads: u64,
+    /// Pose of the frame before the last one encoded and of the last one, which motion vectors
+    /// are measured from (None after a cut, so the next frame has no motion)
+    frame_poses: Cell<(Option<FramePose>, Option<FramePose>)>,
+    // Bounding box overlay (line list drawn over the model)
+    bounds_pipeline: wgpu::RenderPipeline,
+    bounds_vertex_buffer: wgpu::Buffer,
+    bounds_index_buffer: wgpu::Buffer,
+    num_bounds_indices: u32,
+    show_bounds: bool,
+    // Sub-meshes skipped by the draw loop (parallel to mesh_ranges; missing entries are drawn)
+    part_visible: Vec<bool>,
+    // Key light multiplier (audio-reactive mode)
+    light_intensity: f32,
+    // Procedural background
+    background: BackgroundStyle,
+    time: f32,
+    starfield_pipeline: wgpu::RenderPipeline,
+    starfield_instance_buffer: wgpu::Buffer,
+    starfield_uniform_buffer: wgpu::Buffer,
+    starfield_bind_group: wgpu::BindGroup,
+    // Vertical gradient background (fullscreen pass, drawn when no skybox image is set)
+    gradient_pipeline: wgpu::RenderPipeline,
+    gradient_uniform_buffer: wgpu::Buffer,
+    gradient_bind_group: wgpu::BindGroup,
+}
+
+/// Open the high-performance GPU with render targets allowed up to the adapter's size limit
+/// Returns the device, its queue, and the adapter name
+pub async fn request_device() -> Result<(wgpu::Device, wgpu::Queue, String)> {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::all(),
+        ..Default::default()
+    });
+
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        })
+        .await
+        .ok_or_else(|| anyhow::anyhow!("Failed to find an appropriate adapter"))?;
+
+    let gpu_name = adapter.get_info().name;
+
+    let (device, queue) = adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("Headless GPU Device"),
+                required_features: wgpu::Features::empty(),
+                // Allow render targets as large as the adapter supports
+                required_limits: wgpu::Limits {
+                    max_texture_dimension_2d: adapter.limits().max_texture_dimension_2d,
+                    ..wgpu::Limits::default()
+                },
+                memory_hints: Default::default(),
+            },
+            None,
+        )
+        .await?;
+    Ok((device, queue, gpu_name))
 }
 
 impl HeadlessGpu {
     pub async fn new(width: u32, height: u32) -> Result<Self> {
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            ..Default::default()
-        });
+        let (device, queue, gpu_name) = request_device().await?;
 
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: None,
-                force_fallback_adapter: false,
-            })
-            .await
-            .ok_or_else(|| anyhow::anyhow!("Failed to find an appropriate adapter"))?;
-
-        let adapter_info = adapter.get_info();
-        let gpu_name = adapter_info.name.clone();
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: Some("Headless GPU Device"),
-                    required_features: wgpu::Features::empty(),
-                    required_limits: wgpu::Limits::default(),
-                    memory_hints: Default::default(),
-                },
-                None,
-            )
-            .await?;
+        // Never create textures past the device limit (callers should clamp first)
+        let max_dim = device.limits().max_texture_dimension_2d;
+        let width = width.clamp(1, max_dim);
+        let height = height.clamp(1, max_dim);
 
         // Create render texture
-        let render_format = wgpu::TextureFormat::Rgba8Unorm;
+        let render_format = RENDER_FORMAT;
         let (render_texture, render_view) =
             create_render_texture(&device, width, height, render_format);
         let (depth_texture, depth_view) = create_depth_texture(&device, width, height);
+        let (motion_texture, motion_view) = create_render_texture(&device, width, height, MOTION_FORMAT);
+        let (part_id_texture, part_id_view) = create_render_texture(&device, width, height, PART_ID_FORMAT);
 
         // Create shader and pipeline
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -203,7 +784,8 @@ impl HeadlessGpu {
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Vertex Buffer"),
             contents: bytemuck::cast_slice(&vertices),
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            // Storage so compute simulations can animate the mesh in place
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
         });
 
         let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -212,12 +794,23 @@ impl HeadlessGpu {
             usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
         });
 
+        let instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Instance Buffer"),
+            contents: bytemuck::cast_slice(&[InstanceRaw {
+                transform: Mat4::IDENTITY.to_cols_array_2d(),
+            }]),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
         let uniforms = Uniforms {
             mvp: Mat4::IDENTITY.to_cols_array_2d(),
             model: Mat4::IDENTITY.to_cols_array_2d(),
-            light_dir: [0.5, 1.0, 0.3, 0.0],
+            light_dir: LIGHT_DIR,
             lighting_mode: LightingMode::default().to_u32(),
-            _padding: [0, 0, 0],
+            light_intensity: 1.0,
+            _padding: [0, 0],
+            camera_pos: [0.0, 0.0, CAMERA_DISTANCE, 0.0],
+            prev_mvp: Mat4::IDENTITY.to_cols_array_2d(),
         };
 
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -250,55 +843,106 @@ impl HeadlessGpu {
             }],
         });
 
+        let material_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Material Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: wgpu::BufferSize::new(std::mem::size_of::<MaterialUniforms>() as u64),
+                },
+                count: None,
+            }],
+        });
+
+        let material_stride = (std::mem::size_of::<MaterialUniforms>() as u32)
+            .next_multiple_of(device.limits().min_uniform_buffer_offset_alignment);
+        let material_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Material Buffer"),
+            size: material_stride as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let material_bind_group = create_material_bind_group(&device, &material_bind_group_layout, &material_buffer);
+        let marquee = Marquee::new(&device, &uniform_bind_group_layout, &material_bind_group_layout, material_stride);
+
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
-            bind_group_layouts: &[&uniform_bind_group_layout],
+            bind_group_layouts: &[&uniform_bind_group_layout, &material_bind_group_layout],
             push_constant_ranges: &[],
         });
 
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Headless Render Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[InternalVertex::desc()],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: render_format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                unclipped_depth: false,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                conservative: false,
-            },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
+        // Model pipelines differ only in fragment entry point, culled face, depth writes, and blending
+        let model_pipeline = |label: &str,
+                              entry_point: &str,
+                              cull_mode: wgpu::Face,
+                              depth_write_enabled: bool,
+                              blend: wgpu::BlendState| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[InternalVertex::desc(), InstanceRaw::desc()],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some(entry_point),
+                    targets: &[
+                        Some(wgpu::ColorTargetState {
+                            format: render_format,
+                            blend: Some(blend),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        }),
+                        Some(MOTION_TARGET),
+                        Some(PART_ID_TARGET),
+                    ],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: Some(cull_mode),
+                    unclipped_depth: false,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth32Float,
+                    depth_write_enabled,
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        };
+        let pipeline = model_pipeline("Headless Render Pipeline", "fs_main", wgpu::Face::Back, true, wgpu::BlendState::REPLACE);
+        let xray_back_pipeline =
+            model_pipeline("X-Ray Back Face Pipeline", "fs_xray_back", wgpu::Face::Front, true, wgpu::BlendState::REPLACE);
+        // Front faces leave the depth buffer to the back faces, so the edge detector sees inside too
+        // (and the render target's alpha stays 1)
+        let xray_front_pipeline = model_pipeline(
+            "X-Ray Front Face Pipeline",
+            "fs_xray_front",
+            wgpu::Face::Back,
+            false,
+            wgpu::BlendState {
+                color: wgpu::BlendState::ALPHA_BLENDING.color,
+                alpha: wgpu::BlendComponent::OVER,
             },
-            multiview: None,
-            cache: None,
-        });
+        );
 
         // Create skybox pipeline
         let skybox_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -326,9 +970,37 @@ impl HeadlessGpu {
                         ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                         count: None,
                     },
+                    // Previous image, faded out after a change
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
                 ],
             });
 
+        let skybox_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Skybox Uniform Buffer"),
+            size: std::mem::size_of::<SkyboxUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         let skybox_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Skybox Pipeline Layout"),
@@ -374,39 +1046,351 @@ impl HeadlessGpu {
             cache: None,
         });
 
-        let skybox_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            label: Some("Skybox Sampler"),
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: wgpu::FilterMode::Linear,
-            min_filter: wgpu::FilterMode::Linear,
-            mipmap_filter: wgpu::FilterMode::Linear,
-            ..Default::default()
+        // Create gradient pipeline (skybox fullscreen triangle with a uniform-driven fragment)
+        let gradient_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Gradient Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let gradient_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Gradient Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[GradientUniforms {
+                top: [0.02, 0.02, 0.05, 1.0],
+                bottom: [0.02, 0.02, 0.05, 1.0],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
-        Ok(Self {
-            device,
-            queue,
-            render_texture,
-            render_view,
+        let gradient_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Gradient Bind Group"),
+            layout: &gradient_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 2,
+                resource: gradient_uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let gradient_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Gradient Pipeline Layout"),
+                bind_group_layouts: &[&gradient_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let gradient_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Gradient Render Pipeline"),
+            layout: Some(&gradient_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &skybox_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &skybox_shader,
+                entry_point: Some("fs_gradient"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: render_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        // Create bounding box line pipeline (shares the scene uniforms)
+        let bounds_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Bounding Box Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/bbox.wgsl").into()),
+        });
+
+        let bounds_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Bounding Box Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &bounds_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[InternalVertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &bounds_shader,
+                entry_point: Some("fs_main"),
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format: render_format,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(MOTION_TARGET),
+                    Some(NO_PART_ID_TARGET),
+                ],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::LineList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            // Depth tested against the model but never written, so hidden edges stay hidden
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::LessEqual,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let (bounds_vertices, bounds_indices) = create_bounds_geometry(&vertices);
+        let num_bounds_indices = bounds_indices.len() as u32;
+
+        let bounds_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Bounds Vertex Buffer"),
+            contents: bytemuck::cast_slice(&bounds_vertices),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bounds_index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Bounds Index Buffer"),
+            contents: bytemuck::cast_slice(&bounds_indices),
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Create starfield particle pipeline (instanced quads, additive blending)
+        let starfield_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Starfield Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/starfield.wgsl").into()),
+        });
+
+        let starfield_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Starfield Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let starfield_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Starfield Uniform Buffer"),
+            size: std::mem::size_of::<StarfieldUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let starfield_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Starfield Bind Group"),
+            layout: &starfield_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: starfield_uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let starfield_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Starfield Pipeline Layout"),
+                bind_group_layouts: &[&starfield_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let starfield_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Starfield Render Pipeline"),
+            layout: Some(&starfield_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &starfield_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[StarInstance::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &starfield_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: render_format,
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::One,
+                            dst_factor: wgpu::BlendFactor::One,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                        alpha: wgpu::BlendComponent::OVER,
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None, // Background only, no depth testing
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let stars = create_star_instances(STAR_COUNT);
+        let starfield_instance_buffer =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Starfield Instance Buffer"),
+                contents: bytemuck::cast_slice(&stars),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+
+        let sdf_pass = SdfPass::new(&device, render_format);
+        let wave_pass = WavePass::new(&device);
+        let life_pass = LifePass::new(&device);
+
+        let skybox_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Skybox Sampler"),
+            // Wraps horizontally so the auto-rotating background scrolls seamlessly
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let mut gpu = Self {
+            device,
+            queue,
+            render_texture,
+            render_view,
             depth_texture,
             depth_view,
+            motion_texture,
+            motion_view,
+            part_id_texture,
+            part_id_view,
             pipeline,
+            xray_back_pipeline,
+            xray_front_pipeline,
             vertex_buffer,
             index_buffer,
             uniform_buffer,
             uniform_bind_group,
             num_indices,
+            instance_buffer,
+            instance_transforms: vec![Mat4::IDENTITY],
+            num_instances: 1,
+            mesh_ranges: Vec::new(),
+            selected_part: None,
+            explode: 0.0,
+            material_buffer,
+            material_bind_group_layout,
+            material_bind_group,
+            material_stride,
             width,
             height,
             gpu_name,
             skybox_pipeline,
             skybox_bind_group_layout,
             skybox_sampler,
-            skybox_texture: None,
+            skybox_uniform_buffer,
+            skybox: None,
+            skybox_previous: None,
+            skybox_spare: None,
             skybox_bind_group: None,
-        })
+            skybox_fade_start: 0.0,
+            skybox_rotation: 0.0,
+            tumble: TumbleRates::default(),
+            view_turn: Mat4::IDENTITY,
+            skybox_intensity: 1.0,
+            skybox_blur: 0.0,
+            video: None,
+            show_model: true,
+            sdf_scene: None,
+            sdf_pass,
+            wave_pass,
+            wave_enabled: false,
+            life_pass,
+            life_enabled: false,
+            marquee,
+            model_radius: 0.0,
+            marquee_radius: 0.0,
+            bounds_radius: 0.0,
+            clip_planes: Cell::new((Z_NEAR, Z_FAR)),
+            frame_poses: Cell::new((None, None)),
+            accumulate: None,
+            accumulation: RefCell::new(Accumulation::default()),
+            accumulation_paused: Cell::new(false),
+            uploads: 0,
+            bounds_pipeline,
+            bounds_vertex_buffer,
+            bounds_index_buffer,
+            num_bounds_indices,
+            show_bounds: false,
+            part_visible: Vec::new(),
+            light_intensity: 1.0,
+            background: BackgroundStyle::default(),
+            time: 0.0,
+            starfield_pipeline,
+            starfield_instance_buffer,
+            starfield_uniform_buffer,
+            starfield_bind_group,
+            gradient_pipeline,
+            gradient_uniform_buffer,
+            gradient_bind_group,
+        };
+        gpu.write_materials();
+        Ok(gpu)
     }
 
     pub fn gpu_name(&self) -> &str {
@@ -414,6 +1398,9 @@ impl HeadlessGpu {
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
+        let max_dim = self.max_texture_dimension();
+        let width = width.clamp(1, max_dim);
+        let height = height.clamp(1, max_dim);
         if width == self.width && height == self.height {
             return;
         }
@@ -421,7 +1408,7 @@ impl HeadlessGpu {
         self.width = width;
         self.height = height;
 
-        let render_format = wgpu::TextureFormat::Rgba8Unorm;
+        let render_format = RENDER_FORMAT;
         let (render_texture, render_view) =
             create_render_texture(&self.device, width, height, render_format);
         self.render_texture = render_texture;
@@ -429,10 +1416,64 @@ impl HeadlessGpu {
         let (depth_texture, depth_view) = create_depth_texture(&self.device, width, height);
         self.depth_texture = depth_texture;
         self.depth_view = depth_view;
+        let (motion_texture, motion_view) = create_render_texture(&self.device, width, height, MOTION_FORMAT);
+        self.motion_texture = motion_texture;
+        self.motion_view = motion_view;
+        let (part_id_texture, part_id_view) = create_render_texture(&self.device, width, height, PART_ID_FORMAT);
+        self.part_id_texture = part_id_texture;
+        self.part_id_view = part_id_view;
+        if let Some(ref mut accumulate) = self.accumulate {
+            accumulate.resize(&self.device, width, height);
+        }
+        self.uploads += 1;
+        self.cut();
+    }
+
+    /// Average jittered renders into a supersampled image while nothing in the scene changes,
+    /// restarting on any change (the ASCII pipeline reads the average via `render_texture_view`)
+    pub fn set_accumulate(&mut self, enabled: bool) {
+        if enabled != self.accumulate.is_some() {
+            self.accumulate = enabled.then(|| AccumulatePass::new(&self.device, self.width, self.height));
+            *self.accumulation.borrow_mut() = Accumulation::default();
+        }
+    }
+
+    /// Renders averaged into the current frame (0 with accumulation off)
+    pub fn accumulated_samples(&self) -> u32 {
+        self.accumulation.borrow().samples
+    }
+
+    /// Render unjittered and leave the accumulated frames alone until unpaused, for a second view
+    /// of the same frame with its own lighting (the split pane), which would otherwise restart
+    /// accumulation on every frame
+    pub fn pause_accumulation(&self, paused: bool) {
+        self.accumulation_paused.set(paused);
+    }
+
+    /// Sample index of a frame with key `key` (0 after a change, and at most `MAX_SAMPLES`, once
+    /// converged), or None with accumulation off or paused
+    fn next_sample(&self, key: SceneKey) -> Option<u32> {
+        self.accumulate.as_ref()?;
+        if self.accumulation_paused.get() {
+            return None;
+        }
+        let mut accumulation = self.accumulation.borrow_mut();
+        if accumulation.key.as_ref() != Some(&key) {
+            *accumulation = Accumulation { key: Some(key), samples: 0 };
+        }
+        let sample = accumulation.samples;
+        accumulation.samples = (sample + 1).min(MAX_SAMPLES);
+        Some(sample)
+    }
+
+    /// Start the next frame without motion (after a jump the previous frame has nothing to do with it)
+    pub fn cut(&self) {
+        self.frame_poses.set((None, None));
     }
 
     /// Set new geometry from external model data
     pub fn set_geometry(&mut self, vertices: &[Vertex], indices: &[u32]) {
+        self.uploads += 1;
         // Convert Vertex to InternalVertex (they have the same layout)
         let internal_vertices: Vec<InternalVertex> = vertices
             .iter()
@@ -443,27 +1484,370 @@ impl HeadlessGpu {
             })
             .collect();
 
-        self.vertex_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(&internal_vertices),
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-        });
-
-        self.index_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Index Buffer"),
-            contents: bytemuck::cast_slice(indices),
-            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
-        });
+        // Reuse the existing buffers when the new model fits
+        write_growable_buffer(
+            &self.device,
+            &self.queue,
+            &mut self.vertex_buffer,
+            bytemuck::cast_slice(&internal_vertices),
+            "Vertex Buffer",
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE,
+        );
+        write_growable_buffer(
+            &self.device,
+            &self.queue,
+            &mut self.index_buffer,
+            bytemuck::cast_slice(indices),
+            "Index Buffer",
+            wgpu::BufferUsages::INDEX,
+        );
 
         self.num_indices = indices.len() as u32;
+        self.model_radius = bounding_radius(vertices);
+
+        // Callers with sub-mesh information follow up with set_mesh_ranges
+        self.mesh_ranges.clear();
+        self.write_instances();
+        self.write_materials();
+
+        let (bounds_vertices, _) = create_bounds_geometry(&internal_vertices);
+        self.bounds_radius = bounding_radius(bytemuck::cast_slice(&bounds_vertices));
+        self.queue.write_buffer(
+            &self.bounds_vertex_buffer,
+            0,
+            bytemuck::cast_slice(&bounds_vertices),
+        );
+    }
+
+    /// Set the per-instance transforms used to draw multiple copies of the model
+    /// An empty slice is treated as a single identity instance
+    pub fn set_instances(&mut self, transforms: &[Mat4]) {
+        self.uploads += 1;
+        self.instance_transforms = if transforms.is_empty() {
+            vec![Mat4::IDENTITY]
+        } else {
+            transforms.to_vec()
+        };
+        self.write_instances();
+    }
+
+    /// Set the sub-mesh draw list: one draw call per range with its own material and,
+    /// in the exploded view, its own offset
+    pub fn set_mesh_ranges(&mut self, ranges: &[MeshRange]) {
+        self.uploads += 1;
+        self.mesh_ranges = ranges.to_vec();
+        self.write_instances();
+        self.write_materials();
+    }
+
+    /// Draw calls for the current geometry: the sub-meshes, or the whole index buffer when there are none
+    fn draw_list(&self) -> Vec<MeshRange> {
+        if self.mesh_ranges.is_empty() {
+            vec![MeshRange {
+                first_index: 0,
+                index_count: self.num_indices,
+                centroid: [0.0; 3],
+                extent: 0.0,
+                material: Material::default(),
+            }]
+        } else {
+            self.mesh_ranges.clone()
+        }
     }
 
-    /// Load a skybox image from file
-    pub fn set_skybox(&mut self, path: &std::path::Path) -> Result<()> {
+    /// Upload each draw's material at its dynamic offset
+    fn write_materials(&mut self) {
+        let stride = self.material_stride as usize;
+        let draws = self.draw_list();
+        let mut data = vec![0u8; draws.len() * stride];
+        for (i, draw) in draws.iter().enumerate() {
+            let [r, g, b] = draw.material.base_color;
+            let uniforms = MaterialUniforms {
+                base_color: [r, g, b, 1.0],
+                part_id: i as u32 + 1,
+                highlight: (self.selected_part == Some(i)) as u32,
+                _padding: [0; 2],
+            };
+            data[i * stride..i * stride + std::mem::size_of::<MaterialUniforms>()]
+                .copy_from_slice(bytemuck::bytes_of(&uniforms));
+        }
+
+        let size = self.material_buffer.size();
+        write_growable_buffer(
+            &self.device,
+            &self.queue,
+            &mut self.material_buffer,
+            &data,
+            "Material Buffer",
+            wgpu::BufferUsages::UNIFORM,
+        );
+        if self.material_buffer.size() != size {
+            self.material_bind_group =
+                create_material_bind_group(&self.device, &self.material_bind_group_layout, &self.material_buffer);
+        }
+    }
+
+    /// Set how far sub-meshes are pushed out along their centroid direction
+    /// 0.0 draws the model assembled, 1.0 doubles each sub-mesh's distance from the center
+    pub fn set_explode(&mut self, amount: f32) {
+        let amount = amount.max(0.0);
+        if amount != self.explode {
+            self.explode = amount;
+            self.write_instances();
+        }
+    }
+
+    /// Whether the model is drawn one sub-mesh at a time with exploded offsets
+    fn is_exploded(&self) -> bool {
+        self.explode > 0.0 && self.mesh_ranges.len() > 1
+    }
+
+    /// Upload instance transforms, expanded per sub-mesh while exploded
+    /// Sub-mesh i uses instances i*N..(i+1)*N where N is the copy count
+    fn write_instances(&mut self) {
+        let raw: Vec<InstanceRaw> = if self.is_exploded() {
+            self.mesh_ranges
+                .iter()
+                .flat_map(|range| {
+                    let offset = Mat4::from_translation(Vec3::from(range.centroid) * self.explode);
+                    self.instance_transforms.iter().map(move |m| InstanceRaw {
+                        transform: (*m * offset).to_cols_array_2d(),
+                    })
+                })
+                .collect()
+        } else {
+            self.instance_transforms
+                .iter()
+                .map(|m| InstanceRaw {
+                    transform: m.to_cols_array_2d(),
+                })
+                .collect()
+        };
+
+        write_growable_buffer(
+            &self.device,
+            &self.queue,
+            &mut self.instance_buffer,
+            bytemuck::cast_slice(&raw),
+            "Instance Buffer",
+            wgpu::BufferUsages::VERTEX,
+        );
+        self.num_instances = self.instance_transforms.len() as u32;
+    }
+
+    /// Radius of a sphere around the model's origin holding everything the model matrix poses:
+    /// the mesh (pushed apart while exploded, in every instanced copy) or the raymarched scene,
+    /// and the bounding box overlay
+    fn posed_radius(&self) -> f32 {
+        if self.sdf_scene.is_some() {
+            return SDF_BOUND_RADIUS;
+        }
+        // Sub-mesh centroids lie inside the mesh's sphere, so exploding moves nothing farther
+        // out than the radius times the explode amount
+        let part = self.model_radius * if self.is_exploded() { 1.0 + self.explode } else { 1.0 };
+        let copies = self
+            .instance_transforms
+            .iter()
+            .map(|m| {
+                let scale = m.x_axis.length().max(m.y_axis.length()).max(m.z_axis.length());
+                m.w_axis.truncate().length() + part * scale
+            })
+            .fold(0.0, f32::max);
+        let bounds = if self.show_bounds { self.bounds_radius } else { 0.0 };
+        copies.max(bounds)
+    }
+
+    /// Near and far planes holding everything the model pass draws with the model posed by
+    /// `model` and seen through `view` (the marquee circles the origin on its own)
+    fn fit_clip_planes(&self, model: Mat4, view: Mat4) -> (f32, f32) {
+        let spheres = [
+            (model.w_axis.truncate(), self.posed_radius()),
+            (Vec3::ZERO, self.marquee_radius),
+        ];
+        let (near, far) = spheres
+            .iter()
+            .filter(|&&(_, radius)| radius > 0.0)
+            .map(|&(center, radius)| {
+                let depth = -view.transform_point3(center).z;
+                (depth - radius * CLIP_MARGIN, depth + radius * CLIP_MARGIN)
+            })
+            .fold((f32::MAX, f32::MIN), |(near, far), (a, b)| (near.min(a), far.max(b)));
+        // Nothing drawn, or all of it behind the camera
+        if far <= Z_NEAR {
+            return (Z_NEAR, Z_FAR);
+        }
+        let near = near.max(Z_NEAR);
+        (near, far.clamp(near + Z_NEAR, Z_FAR))
+    }
+
+    /// Near and far clip planes of the last frame rendered
+    pub fn clip_planes(&self) -> (f32, f32) {
+        self.clip_planes.get()
+    }
+
+    /// Where a label anchor lands in the last frame rendered (without jitter): its position on
+    /// the render texture (0 to 1, y down) and the depth buffer value of its sphere's nearest
+    /// point; None when its part isn't drawn or the point is off screen
+    /// Anchors follow the first copy of the model, and their part's offset in the exploded view
+    pub fn project_anchor(&self, anchor: &LabelAnchor) -> Option<([f32; 2], f32)> {
+        let pose = self.frame_poses.get().1?;
+        if !self.show_model || anchor.part.is_some_and(|part| !self.part_visible.get(part).copied().unwrap_or(true)) {
+            return None;
+        }
+        let offset = match anchor.part.and_then(|part| self.mesh_ranges.get(part)) {
+            Some(range) if self.is_exploded() => Vec3::from(range.centroid) * self.explode,
+            _ => Vec3::ZERO,
+        };
+        let copy = self.instance_transforms.first().copied().unwrap_or(Mat4::IDENTITY);
+        let center = (pose.view * pose.model * copy).transform_point3(anchor.point + offset);
+        let (clip_near, _) = self.clip_planes.get();
+        if -center.z < clip_near {
+            return None;
+        }
+        let proj = pose.view_proj * pose.view.inverse();
+        let ndc = proj.project_point3(center);
+        if ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 {
+            return None;
+        }
+        // The camera may be inside the sphere, where nothing can be in front of it
+        let nearest = center - center.normalize() * anchor.radius;
+        let depth = if -nearest.z < clip_near { 0.0 } else { proj.project_point3(nearest).z.clamp(0.0, 1.0) };
+        Some(([(ndc.x + 1.0) / 2.0, (1.0 - ndc.y) / 2.0], depth))
+    }
+
+    /// Largest render texture width/height the device supports
+    pub fn max_texture_dimension(&self) -> u32 {
+        self.device.limits().max_texture_dimension_2d
+    }
+
+    /// Select the procedural background drawn behind the model
+    pub fn set_background(&mut self, style: BackgroundStyle) {
+        self.background = style;
+    }
+
+    /// Set the top and bottom colors of the gradient background
+    pub fn set_gradient(&mut self, top: [f32; 3], bottom: [f32; 3]) {
+        self.uploads += 1;
+        let uniforms = GradientUniforms {
+            top: [top[0], top[1], top[2], 1.0],
+            bottom: [bottom[0], bottom[1], bottom[2], 1.0],
+        };
+        self.queue.write_buffer(
+            &self.gradient_uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[uniforms]),
+        );
+    }
+
+    /// Set the animation clock used by animated backgrounds (seconds)
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    /// Scale the key light (1.0 = normal brightness)
+    pub fn set_light_intensity(&mut self, intensity: f32) {
+        self.light_intensity = intensity.max(0.0);
+    }
+
+    /// Toggle drawing the model's axis-aligned bounding box
+    /// Set which sub-meshes are drawn, by index into the mesh ranges
+    pub fn set_part_visibility(&mut self, visible: &[bool]) {
+        self.part_visible.clear();
+        self.part_visible.extend_from_slice(visible);
+    }
+
+    /// Tint a sub-mesh as selected (by index into the mesh ranges), or none
+    pub fn set_selected_part(&mut self, part: Option<usize>) {
+        if part != self.selected_part {
+            self.selected_part = part;
+            self.uploads += 1;
+            self.write_materials();
+        }
+    }
+
+    /// Draw the model, or only the background
+    pub fn set_show_model(&mut self, show: bool) {
+        self.show_model = show;
+    }
+
+    /// Raymarch a signed-distance-field scene instead of drawing the mesh (None = mesh)
+    pub fn set_sdf_scene(&mut self, scene: Option<SdfScene>) {
+        self.sdf_scene = scene;
+    }
+
+    /// Animate the mesh with the water ripple simulation, stepped to the animation clock
+    /// (the mesh must be the water surface); turning it on starts from calm water
+    pub fn set_wave(&mut self, enabled: bool) {
+        if enabled && !self.wave_enabled {
+            self.wave_pass.reset(&self.queue);
+        }
+        self.wave_enabled = enabled;
+        let needed = WavePass::vertex_count() * std::mem::size_of::<InternalVertex>() as u64;
+        if enabled && self.vertex_buffer.size() >= needed {
+            self.wave_pass.update(&self.device, &self.queue, &self.vertex_buffer, self.time);
+        }
+    }
+
+    /// Animate the mesh with the Game of Life, stepped to the animation clock (the mesh must be
+    /// the Life torus); turning it on starts from the first soup
+    pub fn set_life(&mut self, enabled: bool) -> Result<()> {
+        if enabled && !self.life_enabled {
+            self.life_pass.reset();
+        }
+        self.life_enabled = enabled;
+        let needed = LifePass::vertex_count() * std::mem::size_of::<InternalVertex>() as u64;
+        if enabled && self.vertex_buffer.size() >= needed {
+            self.life_pass.update(&self.device, &self.queue, &self.vertex_buffer, self.time)?;
+        }
+        Ok(())
+    }
+
+    /// Live cells on the Game of Life board (None unless it is running)
+    pub fn life_population(&self) -> Option<u32> {
+        self.life_enabled.then(|| self.life_pass.population())
+    }
+
+    /// Set the text marquee's mesh (empty hides the marquee)
+    pub fn set_marquee(&mut self, vertices: &[Vertex], indices: &[u32]) {
+        self.uploads += 1;
+        let marquee = &mut self.marquee;
+        write_growable_buffer(
+            &self.device,
+            &self.queue,
+            &mut marquee.vertex_buffer,
+            bytemuck::cast_slice(vertices),
+            "Marquee Vertex Buffer",
+            wgpu::BufferUsages::VERTEX,
+        );
+        write_growable_buffer(
+            &self.device,
+            &self.queue,
+            &mut marquee.index_buffer,
+            bytemuck::cast_slice(indices),
+            "Marquee Index Buffer",
+            wgpu::BufferUsages::INDEX,
+        );
+        marquee.num_indices = indices.len() as u32;
+        self.marquee_radius = bounding_radius(vertices);
+    }
+
+    pub fn set_show_bounds(&mut self, show: bool) {
+        self.show_bounds = show;
+    }
+
+    /// Load a skybox image from file, crossfading from the current one
+    /// `rotation_offset` turns the image horizontally, in turns
+    pub fn set_skybox(
+        &mut self,
+        path: &std::path::Path,
+        projection: SkyboxProjection,
+        rotation_offset: f32,
+    ) -> Result<()> {
+        self.uploads += 1;
         use image::GenericImageView;
 
         let img = image::open(path)?;
-        let rgba = img.to_rgba8();
+        let mut rgba = img.to_rgba8();
         let dimensions = img.dimensions();
 
         let texture_size = wgpu::Extent3d {
@@ -471,61 +1855,185 @@ impl HeadlessGpu {
             height: dimensions.1,
             depth_or_array_layers: 1,
         };
+        // Full mip chain, sampled with a bias to blur the background
+        let mip_level_count = texture_size.max_mips(wgpu::TextureDimension::D2);
+
+        // The image fading out now is replaced by the one being shown, so its texture
+        // (or a cleared skybox's) is free; images of the same size are uploaded into it
+        let free = self
+            .skybox_spare
+            .take()
+            .or_else(|| self.skybox_previous.take().map(|image| image.texture));
+        let texture = match free {
+            Some(texture) if texture.size() == texture_size && texture.mip_level_count() == mip_level_count => {
+                texture
+            }
+            _ => self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Skybox Texture"),
+                size: texture_size,
+                mip_level_count,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            }),
+        };
 
-        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Skybox Texture"),
-            size: texture_size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
-
-        self.queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &rgba,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * dimensions.0),
-                rows_per_image: Some(dimensions.1),
-            },
-            texture_size,
-        );
+        for mip_level in 0..mip_level_count {
+            if mip_level > 0 {
+                let (width, height) = ((rgba.width() / 2).max(1), (rgba.height() / 2).max(1));
+                rgba = image::imageops::resize(&rgba, width, height, image::imageops::FilterType::Triangle);
+            }
+            self.queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture,
+                    mip_level,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &rgba,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * rgba.width()),
+                    rows_per_image: Some(rgba.height()),
+                },
+                texture_size.mip_level_size(mip_level, wgpu::TextureDimension::D2),
+            );
+        }
 
-        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let image = SkyboxImage {
+            texture,
+            view,
+            projection,
+            rotation_offset,
+        };
+        self.skybox_previous = self.skybox.replace(image);
+        self.skybox_fade_start = self.time;
 
+        // Without a previous image the current one is bound in its place and faded in from black
+        let current = &self.skybox.as_ref().unwrap().view;
+        let previous = self.skybox_previous.as_ref().map_or(current, |image| &image.view);
         let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Skybox Bind Group"),
             layout: &self.skybox_bind_group_layout,
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&texture_view),
+                    resource: wgpu::BindingResource::TextureView(current),
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
                     resource: wgpu::BindingResource::Sampler(&self.skybox_sampler),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(previous),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: self.skybox_uniform_buffer.as_entire_binding(),
+                },
             ],
         });
-
-        self.skybox_texture = Some(texture);
         self.skybox_bind_group = Some(bind_group);
 
         Ok(())
     }
 
+    /// Show a video frame stretched over the background, in place of any skybox
+    /// Frames of the same size are uploaded into the same texture
+    pub fn set_video_frame(&mut self, frame: &image::RgbaImage) {
+        self.uploads += 1;
+        let size = wgpu::Extent3d {
+            width: frame.width(),
+            height: frame.height(),
+            depth_or_array_layers: 1,
+        };
+        if !self.video.as_ref().is_some_and(|(texture, _)| texture.size() == size) {
+            let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Video Texture"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Video Bind Group"),
+                layout: &self.skybox_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.skybox_sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 4,
+                        resource: self.skybox_uniform_buffer.as_entire_binding(),
+                    },
+                ],
+            });
+            self.video = Some((texture, bind_group));
+        }
+
+        let (texture, _) = self.video.as_ref().unwrap();
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            frame,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * frame.width()),
+                rows_per_image: Some(frame.height()),
+            },
+            size,
+        );
+    }
+
     /// Clear the skybox (use solid color background instead)
+    /// The texture is kept so a later skybox of the same size can reuse it
     pub fn clear_skybox(&mut self) {
-        self.skybox_texture = None;
+        self.uploads += 1;
         self.skybox_bind_group = None;
+        self.skybox_previous = None;
+        self.skybox_spare = self.skybox.take().map(|image| image.texture);
+    }
+
+    /// Slowly rotate the skybox image horizontally, in turns per second (0 = still)
+    pub fn set_skybox_rotation(&mut self, turns_per_second: f32) {
+        self.skybox_rotation = turns_per_second;
+    }
+
+    /// Set the axis rates the Tumble rotation mode composes
+    pub fn set_tumble(&mut self, tumble: TumbleRates) {
+        self.tumble = tumble;
+    }
+
+    /// Look at the scene from a fixed direction: `turn` rotates the world in front of the camera
+    pub fn set_view_turn(&mut self, turn: Mat4) {
+        self.view_turn = turn;
+    }
+
+    /// Dim (`intensity` below 1) and soften (`blur` from 0 to 1) the skybox image
+    pub fn set_skybox_look(&mut self, intensity: f32, blur: f32) {
+        self.skybox_intensity = intensity.max(0.0);
+        self.skybox_blur = blur.clamp(0.0, 1.0);
     }
 
     pub fn render_with_rotation(
@@ -533,67 +2041,145 @@ impl HeadlessGpu {
         time: f32,
         mode: RotationMode,
         speed: f32,
+        distance: f32,
+        orbit: OrbitCamera,
         lighting: LightingMode,
     ) -> wgpu::CommandBuffer {
-        let aspect = self.width as f32 / self.height as f32;
-
-        // Compute rotation and camera based on mode
+        // Compute rotation and camera based on mode (the fixed camera sits `distance` away)
+        let fixed_view = Mat4::look_at_rh(Vec3::new(0.0, 0.0, distance), Vec3::ZERO, Vec3::Y);
         let (model, view) = match mode {
-            RotationMode::Static => (
+            RotationMode::Static => (Mat4::IDENTITY, fixed_view),
+            RotationMode::AxisX => (Mat4::from_rotation_x(time * speed), fixed_view),
+            RotationMode::AxisY => (Mat4::from_rotation_y(time * speed), fixed_view),
+            RotationMode::AxisZ => (Mat4::from_rotation_z(time * speed), fixed_view),
+            RotationMode::Tumble => (self.tumble.rotation(time * speed), fixed_view),
+            RotationMode::Orbit => (
                 Mat4::IDENTITY,
-                Mat4::look_at_rh(Vec3::new(0.0, 0.0, 4.0), Vec3::ZERO, Vec3::Y),
-            ),
-            RotationMode::AxisX => (
-                Mat4::from_rotation_x(time * speed),
-                Mat4::look_at_rh(Vec3::new(0.0, 0.0, 4.0), Vec3::ZERO, Vec3::Y),
-            ),
-            RotationMode::AxisY => (
-                Mat4::from_rotation_y(time * speed),
-                Mat4::look_at_rh(Vec3::new(0.0, 0.0, 4.0), Vec3::ZERO, Vec3::Y),
+                Mat4::look_at_rh(orbit.eye(time * speed), orbit.target, Vec3::Y),
             ),
-            RotationMode::AxisZ => (
-                Mat4::from_rotation_z(time * speed),
-                Mat4::look_at_rh(Vec3::new(0.0, 0.0, 4.0), Vec3::ZERO, Vec3::Y),
-            ),
-            RotationMode::Tumble => (
-                Mat4::from_rotation_y(time * speed * 0.7)
-                    * Mat4::from_rotation_x(time * speed * 0.5)
-                    * Mat4::from_rotation_z(time * speed * 0.3),
-                Mat4::look_at_rh(Vec3::new(0.0, 0.0, 4.0), Vec3::ZERO, Vec3::Y),
-            ),
-            RotationMode::Orbit => {
-                let angle = time * speed * 0.5;
-                let cam_x = 4.0 * angle.cos();
-                let cam_z = 4.0 * angle.sin();
-                (
-                    Mat4::IDENTITY,
-                    Mat4::look_at_rh(Vec3::new(cam_x, 1.5, cam_z), Vec3::ZERO, Vec3::Y),
-                )
-            }
+            // Physics is stateful and rendered through render_with_model;
+            // fall back to the static pose if it ends up here
+            RotationMode::Physics => (Mat4::IDENTITY, fixed_view),
         };
 
-        let proj = Mat4::perspective_rh(45.0_f32.to_radians(), aspect, 0.1, 100.0);
+        self.encode_scene(model, view, lighting)
+    }
+
+    /// Render with an externally computed model matrix (e.g. from the physics simulation), seen
+    /// from `distance` away
+    pub fn render_with_model(&self, model: Mat4, distance: f32, lighting: LightingMode) -> wgpu::CommandBuffer {
+        let view = Mat4::look_at_rh(Vec3::new(0.0, 0.0, distance), Vec3::ZERO, Vec3::Y);
+        self.encode_scene(model, view, lighting)
+    }
+
+    /// Render the model at rest from an externally placed camera (e.g. the terrain flyover)
+    pub fn render_with_view(&self, view: Mat4, lighting: LightingMode) -> wgpu::CommandBuffer {
+        self.encode_scene(Mat4::IDENTITY, view, lighting)
+    }
+
+    /// Render with manual rotation angles and zoom (for manual control mode)
+    pub fn render_manual(
+        &self,
+        rotation_x: f32,
+        rotation_y: f32,
+        zoom: f32,
+        lighting: LightingMode,
+    ) -> wgpu::CommandBuffer {
+        // Apply rotation: Y rotation (yaw) first, then X rotation (pitch)
+        let model = Mat4::from_rotation_y(rotation_y) * Mat4::from_rotation_x(rotation_x);
+        let view = Mat4::look_at_rh(Vec3::new(0.0, 0.0, zoom), Vec3::ZERO, Vec3::Y);
+
+        self.encode_scene(model, view, lighting)
+    }
+
+    /// Upload uniforms for the given transforms and encode the skybox + model passes
+    fn encode_scene(&self, model: Mat4, view: Mat4, lighting: LightingMode) -> wgpu::CommandBuffer {
+        let view = view * self.view_turn;
+        let aspect = self.width as f32 / self.height as f32;
+        let (near, far) = self.fit_clip_planes(model, view);
+        self.clip_planes.set((near, far));
+        let proj = Mat4::perspective_rh(FIELD_OF_VIEW, aspect, near, far);
+
+        // Re-rendering the same frame (e.g. for the split pane) measures motion from the same
+        // previous frame as the first time
+        let pose = FramePose { view_proj: proj * view, view, model, time: self.time };
+        let previous = match self.frame_poses.get() {
+            (previous, Some(last)) if last == pose => previous,
+            (_, last) => last,
+        };
+        self.frame_poses.set((previous, Some(pose)));
+        let mut previous = previous.unwrap_or(pose);
+
+        // Accumulated renders are shifted a fraction of a pixel each, then averaged (converged
+        // frames are drawn unshifted); a still scene has no motion, jitter aside
+        let sample = self.next_sample(SceneKey {
+            pose,
+            lighting,
+            uploads: self.uploads,
+            explode: self.explode,
+            show_bounds: self.show_bounds,
+            show_model: self.show_model,
+            sdf_scene: self.sdf_scene,
+            light_intensity: self.light_intensity,
+            part_visible: self.part_visible.clone(),
+            background: self.background,
+            skybox_rotation: self.skybox_rotation,
+            skybox_intensity: self.skybox_intensity,
+            skybox_blur: self.skybox_blur,
+        });
+        let (jitter_x, jitter_y) = sample.filter(|&sample| sample < MAX_SAMPLES).map_or((0.0, 0.0), jitter);
+        let offset = Vec3::new(2.0 * jitter_x / self.width as f32, -2.0 * jitter_y / self.height as f32, 0.0);
+        let proj = Mat4::from_translation(offset) * proj;
+        if sample.is_some_and(|sample| sample > 0) {
+            previous = FramePose { view_proj: proj * view, ..pose };
+        }
         let mvp = proj * view * model;
 
         let uniforms = Uniforms {
             mvp: mvp.to_cols_array_2d(),
             model: model.to_cols_array_2d(),
-            light_dir: [0.5, 1.0, 0.3, 0.0],
+            light_dir: LIGHT_DIR,
             lighting_mode: lighting.to_u32(),
-            _padding: [0, 0, 0],
+            light_intensity: self.light_intensity,
+            _padding: [0, 0],
+            camera_pos: view.inverse().w_axis.to_array(),
+            prev_mvp: (previous.view_proj * previous.model).to_cols_array_2d(),
         };
 
         self.queue
             .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
 
+        if self.marquee.num_indices > 0 {
+            let spin = Mat4::from_rotation_y(-self.time * MARQUEE_SPIN);
+            let previous_spin = Mat4::from_rotation_y(-previous.time * MARQUEE_SPIN);
+            let uniforms = Uniforms {
+                mvp: (proj * view * spin).to_cols_array_2d(),
+                model: spin.to_cols_array_2d(),
+                prev_mvp: (previous.view_proj * previous_spin).to_cols_array_2d(),
+                ..uniforms
+            };
+            self.queue
+                .write_buffer(&self.marquee.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        }
+
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("Headless Render Encoder"),
             });
 
-        // Render skybox first if available
-        if let Some(ref skybox_bind_group) = self.skybox_bind_group {
+        let clear_color = wgpu::Color {
+            r: 0.02,
+            g: 0.02,
+            b: 0.05,
+            a: 1.0,
+        };
+        // Tracks whether a background pass already filled the render target
+        let mut background_drawn = false;
+
+        // Render skybox (or video) first if available
+        let video_bind_group = self.video.as_ref().map(|(_, bind_group)| bind_group);
+        if let Some(skybox_bind_group) = video_bind_group.or(self.skybox_bind_group.as_ref()) {
             let mut skybox_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Skybox Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -614,105 +2200,97 @@ impl HeadlessGpu {
                 occlusion_query_set: None,
             });
 
-            skybox_pass.set_pipeline(&self.skybox_pipeline);
-            skybox_pass.set_bind_group(0, skybox_bind_group, &[]);
-            skybox_pass.draw(0..3, 0..1); // Fullscreen triangle
-        }
-
-        // Render 3D model
-        {
-            // Use LoadOp::Load if skybox was rendered, Clear otherwise
-            let color_load_op = if self.skybox_bind_group.is_some() {
-                wgpu::LoadOp::Load
-            } else {
-                wgpu::LoadOp::Clear(wgpu::Color {
-                    r: 0.02,
-                    g: 0.02,
-                    b: 0.05,
-                    a: 1.0,
+            let scroll = self.time * self.skybox_rotation;
+            let placement = |image: Option<&SkyboxImage>| {
+                image.map_or((0.0, 0), |image| {
+                    ((scroll + image.rotation_offset).fract(), image.projection.to_u32())
                 })
             };
+            let (offset, projection) = placement(self.skybox.as_ref());
+            let (previous_offset, previous_projection) = placement(self.skybox_previous.as_ref());
+            let skybox_uniforms = if video_bind_group.is_some() {
+                // Video is stretched over the screen, fully shown and never scrolled
+                SkyboxUniforms {
+                    fade: 1.0,
+                    has_previous: 0,
+                    offset: 0.0,
+                    projection: SkyboxProjection::Stretch.to_u32(),
+                    previous_offset: 0.0,
+                    previous_projection: 0,
+                    intensity: self.skybox_intensity,
+                    blur: 0.0,
+                }
+            } else {
+                SkyboxUniforms {
+                    fade: ((self.time - self.skybox_fade_start) / SKYBOX_FADE_TIME).clamp(0.0, 1.0),
+                    has_previous: self.skybox_previous.is_some() as u32,
+                    offset,
+                    projection,
+                    previous_offset,
+                    previous_projection,
+                    intensity: self.skybox_intensity,
+                    blur: self.skybox_blur * SKYBOX_MAX_BLUR,
+                }
+            };
+            self.queue.write_buffer(
+                &self.skybox_uniform_buffer,
+                0,
+                bytemuck::cast_slice(&[skybox_uniforms]),
+            );
 
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Headless Render Pass"),
+            skybox_pass.set_pipeline(&self.skybox_pipeline);
+            skybox_pass.set_bind_group(0, skybox_bind_group, &[]);
+            skybox_pass.draw(0..3, 0..1); // Fullscreen triangle
+            background_drawn = true;
+        } else if self.background == BackgroundStyle::Gradient {
+            // Gradient replaces the solid clear color when no skybox image is loaded
+            let mut gradient_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Gradient Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &self.render_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: color_load_op,
+                        load: wgpu::LoadOp::Clear(clear_color),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
-                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                    view: &self.depth_view,
-                    depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
-                        store: wgpu::StoreOp::Store,
-                    }),
-                    stencil_ops: None,
-                }),
+                depth_stencil_attachment: None,
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
 
-            render_pass.set_pipeline(&self.pipeline);
-            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-            render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+            gradient_pass.set_pipeline(&self.gradient_pipeline);
+            gradient_pass.set_bind_group(0, &self.gradient_bind_group, &[]);
+            gradient_pass.draw(0..3, 0..1);
+            background_drawn = true;
         }
 
-        encoder.finish()
-    }
-
-    /// Render with manual rotation angles and zoom (for manual control mode)
-    pub fn render_manual(
-        &self,
-        rotation_x: f32,
-        rotation_y: f32,
-        zoom: f32,
-        lighting: LightingMode,
-    ) -> wgpu::CommandBuffer {
-        let aspect = self.width as f32 / self.height as f32;
-
-        // Apply rotation: Y rotation (yaw) first, then X rotation (pitch)
-        let model = Mat4::from_rotation_y(rotation_y) * Mat4::from_rotation_x(rotation_x);
-        let view = Mat4::look_at_rh(Vec3::new(0.0, 0.0, zoom), Vec3::ZERO, Vec3::Y);
-
-        let proj = Mat4::perspective_rh(45.0_f32.to_radians(), aspect, 0.1, 100.0);
-        let mvp = proj * view * model;
-
-        let uniforms = Uniforms {
-            mvp: mvp.to_cols_array_2d(),
-            model: model.to_cols_array_2d(),
-            light_dir: [0.5, 1.0, 0.3, 0.0],
-            lighting_mode: lighting.to_u32(),
-            _padding: [0, 0, 0],
-        };
-
-        self.queue
-            .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
-
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Headless Render Encoder"),
-            });
+        // Starfield particles over the skybox / solid color
+        if self.background == BackgroundStyle::Starfield {
+            let starfield_uniforms = StarfieldUniforms {
+                time: self.time,
+                aspect,
+                speed: 0.08,
+                _padding: 0.0,
+            };
+            self.queue.write_buffer(
+                &self.starfield_uniform_buffer,
+                0,
+                bytemuck::cast_slice(&[starfield_uniforms]),
+            );
 
-        // Render skybox first if available
-        if let Some(ref skybox_bind_group) = self.skybox_bind_group {
-            let mut skybox_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Skybox Render Pass"),
+            let load = if background_drawn {
+                wgpu::LoadOp::Load
+            } else {
+                wgpu::LoadOp::Clear(clear_color)
+            };
+            let mut star_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Starfield Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &self.render_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.0,
-                            g: 0.0,
-                            b: 0.0,
-                            a: 1.0,
-                        }),
+                        load,
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -721,34 +2299,52 @@ impl HeadlessGpu {
                 occlusion_query_set: None,
             });
 
-            skybox_pass.set_pipeline(&self.skybox_pipeline);
-            skybox_pass.set_bind_group(0, skybox_bind_group, &[]);
-            skybox_pass.draw(0..3, 0..1);
+            star_pass.set_pipeline(&self.starfield_pipeline);
+            star_pass.set_bind_group(0, &self.starfield_bind_group, &[]);
+            star_pass.set_vertex_buffer(0, self.starfield_instance_buffer.slice(..));
+            star_pass.draw(0..6, 0..STAR_COUNT as u32);
+            background_drawn = true;
         }
 
         // Render 3D model
         {
-            let color_load_op = if self.skybox_bind_group.is_some() {
+            // Use LoadOp::Load if a background was rendered, Clear otherwise
+            let color_load_op = if background_drawn {
                 wgpu::LoadOp::Load
             } else {
-                wgpu::LoadOp::Clear(wgpu::Color {
-                    r: 0.02,
-                    g: 0.02,
-                    b: 0.05,
-                    a: 1.0,
-                })
+                wgpu::LoadOp::Clear(clear_color)
             };
 
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Headless Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &self.render_view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: color_load_op,
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
+                color_attachments: &[
+                    Some(wgpu::RenderPassColorAttachment {
+                        view: &self.render_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: color_load_op,
+                            store: wgpu::StoreOp::Store,
+                        },
+                    }),
+                    // The background never moves
+                    Some(wgpu::RenderPassColorAttachment {
+                        view: &self.motion_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    }),
+                    // The background is part 0
+                    Some(wgpu::RenderPassColorAttachment {
+                        view: &self.part_id_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    }),
+                ],
                 depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
                     view: &self.depth_view,
                     depth_ops: Some(wgpu::Operations {
@@ -761,24 +2357,130 @@ impl HeadlessGpu {
                 occlusion_query_set: None,
             });
 
-            render_pass.set_pipeline(&self.pipeline);
+            if let Some(scene) = self.sdf_scene.filter(|_| self.show_model) {
+                let previous_mvp = previous.view_proj * previous.model;
+                self.sdf_pass.write_uniforms(&self.queue, scene, model, proj * view, previous_mvp, lighting, self.light_intensity, self.time);
+                self.sdf_pass.draw(&mut render_pass);
+            }
+
+            // X-ray draws the mesh twice: back faces first, then the front faces over them
+            let passes = if lighting == LightingMode::XRay {
+                vec![&self.xray_back_pipeline, &self.xray_front_pipeline]
+            } else {
+                vec![&self.pipeline]
+            };
             render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
             render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
             render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-            render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+            let exploded = self.is_exploded();
+            for pipeline in passes {
+                render_pass.set_pipeline(pipeline);
+                for (i, range) in self.draw_list().iter().enumerate() {
+                    if !self.show_model || self.sdf_scene.is_some() || !self.part_visible.get(i).copied().unwrap_or(true) {
+                        continue;
+                    }
+                    // Exploded sub-meshes each have their own block of instance transforms
+                    let first_instance = if exploded { i as u32 * self.num_instances } else { 0 };
+                    render_pass.set_bind_group(1, &self.material_bind_group, &[i as u32 * self.material_stride]);
+                    render_pass.draw_indexed(
+                        range.first_index..range.first_index + range.index_count,
+                        0,
+                        first_instance..first_instance + self.num_instances,
+                    );
+                }
+            }
+
+            if self.show_model && self.marquee.num_indices > 0 {
+                render_pass.set_pipeline(&self.pipeline);
+                render_pass.set_bind_group(0, &self.marquee.uniform_bind_group, &[]);
+                render_pass.set_bind_group(1, &self.marquee.material_bind_group, &[0]);
+                render_pass.set_vertex_buffer(0, self.marquee.vertex_buffer.slice(..));
+                render_pass.set_vertex_buffer(1, self.marquee.instance_buffer.slice(..));
+                render_pass.set_index_buffer(self.marquee.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                render_pass.draw_indexed(0..self.marquee.num_indices, 0, 0..1);
+            }
+
+            if self.show_bounds && self.sdf_scene.is_none() {
+                render_pass.set_pipeline(&self.bounds_pipeline);
+                render_pass.set_vertex_buffer(0, self.bounds_vertex_buffer.slice(..));
+                render_pass
+                    .set_index_buffer(self.bounds_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                render_pass.draw_indexed(0..self.num_bounds_indices, 0, 0..1);
+            }
+        }
+
+        // Sample n goes into history texture n % 2, averaged over the n before it
+        if let (Some(accumulate), Some(sample)) = (&self.accumulate, sample.filter(|&sample| sample < MAX_SAMPLES)) {
+            let weight = 1.0 / (sample + 1) as f32;
+            accumulate.encode(&self.device, &self.queue, &mut encoder, &self.render_view, sample as usize % 2, weight);
         }
 
         encoder.finish()
     }
 
+    /// Scene color of the last frame (the accumulated average while accumulating, unless paused)
     pub fn render_texture_view(&self) -> &wgpu::TextureView {
-        &self.render_view
+        match (&self.accumulate, self.accumulated_samples()) {
+            (Some(accumulate), samples) if samples > 0 && !self.accumulation_paused.get() => accumulate.view((samples as usize - 1) % 2),
+            _ => &self.render_view,
+        }
     }
 
     pub fn depth_texture_view(&self) -> &wgpu::TextureView {
         &self.depth_view
     }
 
+    /// Per-pixel motion since the previous frame (see `MOTION_FORMAT`)
+    pub fn motion_texture_view(&self) -> &wgpu::TextureView {
+        &self.motion_view
+    }
+
+    /// Per-pixel sub-mesh of the last frame: the part index plus one, 0 where no model part was drawn
+    pub fn part_id_texture_view(&self) -> &wgpu::TextureView {
+        &self.part_id_view
+    }
+
+    /// Sub-mesh under a point of the last frame (0 to 1 across the render texture, y down), by
+    /// index into the mesh ranges; None over the background or anything else that isn't a part
+    pub fn pick_part(&self, position: [f32; 2]) -> Result<Option<usize>> {
+        let x = ((position[0] * self.width as f32) as u32).min(self.width - 1);
+        let y = ((position[1] * self.height as f32) as u32).min(self.height - 1);
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Part Pick Buffer"),
+            size: 4,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Part Pick Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.part_id_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout { offset: 0, bytes_per_row: None, rows_per_image: None },
+            },
+            wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let buffer_slice = buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            tx.send(result).unwrap();
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv()??;
+        let id = u32::from_le_bytes(buffer_slice.get_mapped_range()[..4].try_into()?);
+        Ok(id.checked_sub(1).map(|part| part as usize))
+    }
+
     pub fn render_size(&self) -> (u32, u32) {
         (self.width, self.height)
     }
@@ -801,7 +2503,7 @@ fn create_render_texture(
         sample_count: 1,
         dimension: wgpu::TextureDimension::D2,
         format,
-        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC,
         view_formats: &[],
     });
     let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
@@ -827,6 +2529,105 @@ fn create_depth_texture(device: &wgpu::Device, width: u32, height: u32) -> (wgpu
     (texture, view)
 }
 
+/// Write `data` to the start of `buffer`, reallocating only when it doesn't fit
+/// Capacity grows to the next power of two so repeated swaps settle on one allocation
+fn write_growable_buffer(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    buffer: &mut wgpu::Buffer,
+    data: &[u8],
+    label: &str,
+    usage: wgpu::BufferUsages,
+) {
+    let size = data.len() as u64;
+    if size > buffer.size() {
+        *buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size: size.next_power_of_two(),
+            usage: usage | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+    }
+    queue.write_buffer(buffer, 0, data);
+}
+
+/// Bind group exposing one material-sized window of the material buffer (moved by dynamic offsets)
+fn create_material_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Material Bind Group"),
+        layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer,
+                offset: 0,
+                size: wgpu::BufferSize::new(std::mem::size_of::<MaterialUniforms>() as u64),
+            }),
+        }],
+    })
+}
+
+/// Generate star instances with a small deterministic PRNG (xorshift)
+fn create_star_instances(count: usize) -> Vec<StarInstance> {
+    let mut state: u32 = 0x9E37_79B9;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state as f32 / u32::MAX as f32
+    };
+
+    (0..count)
+        .map(|_| StarInstance {
+            seed: [next() * 2.0 - 1.0, next() * 2.0 - 1.0, next()],
+            params: [0.5 + next(), 0.4 + next() * 0.6],
+        })
+        .collect()
+}
+
+/// Build an axis-aligned bounding box as line list geometry (8 corners, 12 edges)
+fn create_bounds_geometry(vertices: &[InternalVertex]) -> (Vec<InternalVertex>, Vec<u32>) {
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for v in vertices {
+        for i in 0..3 {
+            min[i] = min[i].min(v.position[i]);
+            max[i] = max[i].max(v.position[i]);
+        }
+    }
+    if vertices.is_empty() {
+        min = [0.0; 3];
+        max = [0.0; 3];
+    }
+
+    // Bright yellow so the box stands out from typical model colors
+    let color = [1.0, 0.9, 0.2];
+    let corners: Vec<InternalVertex> = (0..8)
+        .map(|i| InternalVertex {
+            position: [
+                if i & 1 == 0 { min[0] } else { max[0] },
+                if i & 2 == 0 { min[1] } else { max[1] },
+                if i & 4 == 0 { min[2] } else { max[2] },
+            ],
+            normal: [0.0, 1.0, 0.0],
+            color,
+        })
+        .collect();
+
+    // Each edge connects two corners differing in exactly one bit
+    let indices: Vec<u32> = vec![
+        0, 1, 2, 3, 4, 5, 6, 7, // X edges
+        0, 2, 1, 3, 4, 6, 5, 7, // Y edges
+        0, 4, 1, 5, 2, 6, 3, 7, // Z edges
+    ];
+
+    (corners, indices)
+}
+
 fn create_cube_geometry() -> (Vec<InternalVertex>, Vec<u32>) {
     let s = 0.8;
 
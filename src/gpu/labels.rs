@@ -0,0 +1,212 @@
+use anyhow::Result;
+use bytemuck::{Pod, Zeroable};
+use glam::Vec3;
+
+/// Uniforms for the label occlusion pass
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct LabelUniforms {
+    tex_width: u32,
+    tex_height: u32,
+    count: u32,
+    spread: f32,
+}
+
+/// Threads per workgroup in the label occlusion shader
+const LABEL_WORKGROUP_SIZE: u32 = 64;
+
+/// A point on the model that a label is attached to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LabelAnchor {
+    /// Position in normalized model space
+    pub point: Vec3,
+    /// Radius of the geometry around the point that belongs to it, so its own surface doesn't hide it
+    pub radius: f32,
+    /// Sub-mesh the point belongs to: it moves with the part in the exploded view and is hidden with it
+    pub part: Option<usize>,
+}
+
+/// Tests projected label anchors against the depth buffer: how much of the neighborhood of each
+/// anchor has nothing in front of it
+pub struct LabelPass {
+    /// Anchors the buffers hold room for
+    capacity: u32,
+    pipeline: wgpu::ComputePipeline,
+    layout: wgpu::BindGroupLayout,
+    uniform_buf: wgpu::Buffer,
+    anchor_buffer: wgpu::Buffer,
+    output_buffer: wgpu::Buffer,
+    staging_buffer: wgpu::Buffer,
+}
+
+impl LabelPass {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Label Layout"),
+            entries: &[
+                // Depth input
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Uniforms
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Projected anchors
+                storage(2, true),
+                // Output visibility
+                storage(3, false),
+            ],
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Label Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/labels.wgsl").into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Label Pipeline Layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Label Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let uniform_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Label Uniforms"),
+            size: std::mem::size_of::<LabelUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let capacity = LABEL_WORKGROUP_SIZE;
+        let (anchor_buffer, output_buffer, staging_buffer) = Self::create_buffers(device, capacity);
+
+        Self {
+            capacity,
+            pipeline,
+            layout,
+            uniform_buf,
+            anchor_buffer,
+            output_buffer,
+            staging_buffer,
+        }
+    }
+
+    fn create_buffers(device: &wgpu::Device, capacity: u32) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer) {
+        let anchors = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Label Anchor Buffer"),
+            size: capacity as u64 * 16,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let output = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Label Output Buffer"),
+            size: capacity as u64 * 4,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Label Staging Buffer"),
+            size: capacity as u64 * 4,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        (anchors, output, staging)
+    }
+
+    /// Visibility (0 to 1) of each anchor projected onto `depth_view` (of the given size) as
+    /// (position on the texture, depth of its nearest point), sampling taps `spread` pixels apart
+    pub fn run(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        depth_view: &wgpu::TextureView,
+        tex_size: (u32, u32),
+        anchors: &[([f32; 2], f32)],
+        spread: f32,
+    ) -> Result<Vec<f32>> {
+        if anchors.is_empty() {
+            return Ok(Vec::new());
+        }
+        let count = anchors.len() as u32;
+        if count > self.capacity {
+            self.capacity = count.next_power_of_two();
+            (self.anchor_buffer, self.output_buffer, self.staging_buffer) = Self::create_buffers(device, self.capacity);
+        }
+
+        let uniforms = LabelUniforms { tex_width: tex_size.0, tex_height: tex_size.1, count, spread };
+        queue.write_buffer(&self.uniform_buf, 0, bytemuck::cast_slice(&[uniforms]));
+        let packed: Vec<[f32; 4]> = anchors.iter().map(|&([u, v], depth)| [u, v, depth, 0.0]).collect();
+        queue.write_buffer(&self.anchor_buffer, 0, bytemuck::cast_slice(&packed));
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Label Bind Group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(depth_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: self.uniform_buf.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: self.anchor_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: self.output_buffer.as_entire_binding() },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Label Encoder"),
+        });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Label Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(count.div_ceil(LABEL_WORKGROUP_SIZE), 1, 1);
+        }
+        let size = count as u64 * 4;
+        encoder.copy_buffer_to_buffer(&self.output_buffer, 0, &self.staging_buffer, 0, size);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let buffer_slice = self.staging_buffer.slice(..size);
+        let (tx, rx) = std::sync::mpsc::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            tx.send(result).unwrap();
+        });
+        device.poll(wgpu::Maintain::Wait);
+        rx.recv()??;
+
+        let data = buffer_slice.get_mapped_range();
+        let visibility: Vec<f32> = bytemuck::cast_slice(&data).to_vec();
+        drop(data);
+        self.staging_buffer.unmap();
+        Ok(visibility)
+    }
+}
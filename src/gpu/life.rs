@@ -0,0 +1,313 @@
+use anyhow::Result;
+use bytemuck::{Pod, Zeroable};
+
+/// Cells around the torus (major circle) and around its tube
+pub const LIFE_COLS: u32 = 48;
+pub const LIFE_ROWS: u32 = 20;
+
+/// Torus radii: ring center to tube center, and tube (together they span the 1.6 unit view)
+pub const LIFE_RING_RADIUS: f32 = 0.56;
+pub const LIFE_TUBE_RADIUS: f32 = 0.24;
+
+/// How far live cells stand out from the surface
+pub const LIFE_CELL_HEIGHT: f32 = 0.05;
+
+/// Generations per second of animation time, and the most run for one frame (after a stall)
+const STEP_RATE: f32 = 6.0;
+const MAX_STEPS_PER_FRAME: u32 = 4;
+
+/// Generations spent stuck in a still life or blinker before the board is reseeded
+const STALE_LIMIT: u32 = 15;
+
+const CELL_COUNT: u64 = (LIFE_COLS * LIFE_ROWS) as u64;
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct LifeUniforms {
+    cols: u32,
+    rows: u32,
+    seed: u32,
+    _pad0: u32,
+    ring_radius: f32,
+    tube_radius: f32,
+    cell_height: f32,
+    _pad1: f32,
+}
+
+/// Conway's Game of Life on a torus, stepped by compute shaders which write the cell heights and
+/// colors straight into the model's vertex buffer; only the population and whether the board
+/// changed are read back, to decide when to reseed
+pub(super) struct LifePass {
+    seed_pipeline: wgpu::ComputePipeline,
+    step_pipeline: wgpu::ComputePipeline,
+    mesh_pipeline: wgpu::ComputePipeline,
+    layout: wgpu::BindGroupLayout,
+    uniform_buffer: wgpu::Buffer,
+    /// Boards two generations back, one back, and next (rotated after each step)
+    cells: [wgpu::Buffer; 3],
+    since_death_buffer: wgpu::Buffer,
+    /// Population and changed-cell counters, and where they are copied to be read
+    stats_buffer: wgpu::Buffer,
+    staging_buffer: wgpu::Buffer,
+    /// Generations run since the last reset
+    generation: u32,
+    /// Soups seeded so far (each reseed starts a different one)
+    seeds: u32,
+    stale: u32,
+    population: u32,
+    /// Whether the board has been written into the vertex buffer since the last reset
+    drawn: bool,
+}
+
+impl LifePass {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Life Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/life.wgsl").into()),
+        });
+
+        let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Life Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage(1, true),
+                storage(2, true),
+                storage(3, false),
+                storage(4, false),
+                storage(5, false),
+                storage(6, false),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Life Pipeline Layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry_point, label| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: Some(entry_point),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        };
+        let seed_pipeline = pipeline("seed_main", "Life Seed Pipeline");
+        let step_pipeline = pipeline("step_main", "Life Step Pipeline");
+        let mesh_pipeline = pipeline("mesh_main", "Life Mesh Pipeline");
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Life Uniform Buffer"),
+            size: std::mem::size_of::<LifeUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let cell_buffer = |label| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: CELL_COUNT * 4,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        };
+        let cells = std::array::from_fn(|_| cell_buffer("Life Cell Buffer"));
+        let since_death_buffer = cell_buffer("Life Trail Buffer");
+        let stats_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Life Stats Buffer"),
+            size: 8,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Life Staging Buffer"),
+            size: 8,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            seed_pipeline,
+            step_pipeline,
+            mesh_pipeline,
+            layout,
+            uniform_buffer,
+            cells,
+            since_death_buffer,
+            stats_buffer,
+            staging_buffer,
+            generation: 0,
+            seeds: 0,
+            stale: 0,
+            population: 0,
+            drawn: false,
+        }
+    }
+
+    /// Vertices the torus mesh uses (a surface quad and a box per cell), and so the vertex
+    /// buffer needs
+    pub fn vertex_count() -> u64 {
+        CELL_COUNT * (4 + 24)
+    }
+
+    /// Live cells in the current generation
+    pub fn population(&self) -> u32 {
+        self.population
+    }
+
+    /// Restart from the first soup
+    pub fn reset(&mut self) {
+        self.generation = 0;
+        self.seeds = 0;
+        self.drawn = false;
+    }
+
+    /// Run the generations due by animation time `time` (a few at most, after a stall), then
+    /// rewrite the torus vertices in `vertex_buffer`
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, vertex_buffer: &wgpu::Buffer, time: f32) -> Result<()> {
+        let due = (time.max(0.0) * STEP_RATE) as u32;
+        if due < self.generation {
+            // The clock went back (a loop capture restarting): start over
+            self.reset();
+        }
+        if !self.drawn {
+            self.seed(device, queue, vertex_buffer)?;
+        }
+        let count = due.saturating_sub(self.generation).min(MAX_STEPS_PER_FRAME);
+        if count == 0 && self.drawn {
+            return Ok(());
+        }
+        // Skip ahead rather than simulating a long gap generation by generation
+        self.generation = self.generation.max(due.saturating_sub(MAX_STEPS_PER_FRAME));
+
+        for _ in 0..count {
+            let encoder = self.dispatch(device, queue, vertex_buffer, &self.step_pipeline, "Life Step Pass");
+            let (population, changed) = self.submit_step(device, queue, encoder)?;
+            self.population = population;
+            self.stale = if changed == 0 { self.stale + 1 } else { 0 };
+            self.generation += 1;
+            if self.stale > STALE_LIMIT || population == 0 {
+                self.seed(device, queue, vertex_buffer)?;
+            }
+        }
+        // The newest board is now the "current" one the mesh pass reads
+        let encoder = self.dispatch(device, queue, vertex_buffer, &self.mesh_pipeline, "Life Mesh Pass");
+        queue.submit(std::iter::once(encoder.finish()));
+        self.drawn = true;
+        Ok(())
+    }
+
+    /// Fill the board with the next random soup, with no trails and nothing to compare it to
+    fn seed(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, vertex_buffer: &wgpu::Buffer) -> Result<()> {
+        // Becomes the board before the soup once it is written
+        queue.write_buffer(&self.cells[1], 0, bytemuck::cast_slice(&vec![0u32; CELL_COUNT as usize]));
+        let encoder = self.dispatch(device, queue, vertex_buffer, &self.seed_pipeline, "Life Seed Pass");
+        (self.population, _) = self.submit_step(device, queue, encoder)?;
+        self.seeds += 1;
+        self.stale = 0;
+        Ok(())
+    }
+
+    /// Record `pipeline` over the board with fresh uniforms and cleared counters (each pass has
+    /// its own uniforms, so each goes in its own submission)
+    fn dispatch(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        vertex_buffer: &wgpu::Buffer,
+        pipeline: &wgpu::ComputePipeline,
+        label: &str,
+    ) -> wgpu::CommandEncoder {
+        let uniforms = LifeUniforms {
+            cols: LIFE_COLS,
+            rows: LIFE_ROWS,
+            seed: self.seeds,
+            _pad0: 0,
+            ring_radius: LIFE_RING_RADIUS,
+            tube_radius: LIFE_TUBE_RADIUS,
+            cell_height: LIFE_CELL_HEIGHT,
+            _pad1: 0.0,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        queue.write_buffer(&self.stats_buffer, 0, bytemuck::cast_slice(&[0u32; 2]));
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some(label) });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some(label),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &self.bind_group(device, vertex_buffer), &[]);
+            pass.dispatch_workgroups(LIFE_COLS.div_ceil(8), LIFE_ROWS.div_ceil(8), 1);
+        }
+        encoder
+    }
+
+    /// Submit a seed or step pass, make the board it wrote the current one, and read back the
+    /// population and changed-cell counts it added up
+    fn submit_step(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, mut encoder: wgpu::CommandEncoder) -> Result<(u32, u32)> {
+        encoder.copy_buffer_to_buffer(&self.stats_buffer, 0, &self.staging_buffer, 0, 8);
+        queue.submit(std::iter::once(encoder.finish()));
+        self.cells.rotate_left(1);
+
+        let buffer_slice = self.staging_buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            tx.send(result).unwrap();
+        });
+        device.poll(wgpu::Maintain::Wait);
+        rx.recv()??;
+        let data = buffer_slice.get_mapped_range();
+        let stats: [u32; 2] = bytemuck::pod_read_unaligned(&data);
+        drop(data);
+        self.staging_buffer.unmap();
+        Ok((stats[0], stats[1]))
+    }
+
+    /// The boards in their current roles, and the vertex buffer (which may have been
+    /// reallocated since the last frame)
+    fn bind_group(&self, device: &wgpu::Device, vertex_buffer: &wgpu::Buffer) -> wgpu::BindGroup {
+        let buffers = [
+            &self.uniform_buffer,
+            &self.cells[0],
+            &self.cells[1],
+            &self.cells[2],
+            &self.since_death_buffer,
+            &self.stats_buffer,
+            vertex_buffer,
+        ];
+        let entries: Vec<_> = buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect();
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Life Bind Group"),
+            layout: &self.layout,
+            entries: &entries,
+        })
+    }
+}
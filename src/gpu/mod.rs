@@ -1,5 +1,27 @@
+mod accumulate;
 mod headless;
+mod labels;
+mod life;
+mod outline;
 mod pipeline;
+mod preview;
+mod ramp;
+mod sdf;
+mod shaders;
+mod stereogram;
+mod wave;
 
-pub use headless::{HeadlessGpu, LightingMode, RotationMode, Vertex};
-pub use pipeline::AsciiPipeline;
+pub use accumulate::MAX_SAMPLES as ACCUMULATE_SAMPLES;
+pub use headless::{
+    framing_distance, request_device, BackgroundStyle, HeadlessGpu, LightingMode, Material, MeshRange, OrbitCamera, RotationMode, SkyboxProjection, TumbleRates, Vertex, CAMERA_DISTANCE,
+};
+pub use labels::{LabelAnchor, LabelPass};
+pub use life::{LIFE_CELL_HEIGHT, LIFE_COLS, LIFE_RING_RADIUS, LIFE_ROWS, LIFE_TUBE_RADIUS};
+pub use outline::MAX_OUTLINE_WIDTH;
+pub use pipeline::{AsciiPipeline, EdgeAlgorithm, Tonemap, DEFAULT_EXPOSURE, DEFAULT_GAMMA};
+pub use preview::PreviewPass;
+pub use ramp::{CharRamp, RAMP_CHARS};
+pub use sdf::SdfScene;
+pub use shaders::{AsciiShaders, ShaderWatcher};
+pub use stereogram::StereogramPass;
+pub use wave::{WAVE_GRID, WAVE_HALF_SIZE, WAVE_TILT};
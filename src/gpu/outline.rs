@@ -0,0 +1,193 @@
+use bytemuck::{Pod, Zeroable};
+
+use super::pipeline::PIXEL_WORKGROUP_SIZE;
+
+/// Widest outline, in character cells
+pub const MAX_OUTLINE_WIDTH: u32 = 4;
+
+/// Format of the mask, spread and outlined direction textures (the direction texture's format)
+const OUTLINE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba32Float;
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct OutlineUniforms {
+    width: u32,
+    height: u32,
+    radius_x: f32,
+    radius_y: f32,
+}
+
+/// Thickens silhouettes in the Sobel pass's direction texture: marks where the model meets the
+/// background or another part, spreads those pixels across rows then columns, and writes the
+/// result over a copy of the direction texture for the ASCII pass to read
+pub(super) struct OutlinePass {
+    pipelines: [wgpu::ComputePipeline; 3],
+    layout: wgpu::BindGroupLayout,
+    uniform_buffer: wgpu::Buffer,
+    /// Silhouette mask, spread along rows, and the outlined direction texture
+    textures: [(wgpu::Texture, wgpu::TextureView); 3],
+    bind_groups: Option<[wgpu::BindGroup; 3]>,
+    width: u32,
+    height: u32,
+}
+
+impl OutlinePass {
+    pub fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Outline Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/outline.wgsl").into()),
+        });
+
+        let texture = |binding, sample_type| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                sample_type,
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let float = wgpu::TextureSampleType::Float { filterable: false };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Outline Bind Group Layout"),
+            entries: &[
+                texture(0, float),
+                texture(1, wgpu::TextureSampleType::Uint),
+                texture(2, float),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: OUTLINE_FORMAT,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Outline Pipeline Layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipelines = ["mark", "spread_x", "spread_y"].map(|entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("Outline Pipeline"),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: Some(entry_point),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Outline Uniform Buffer"),
+            size: std::mem::size_of::<OutlineUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipelines,
+            layout,
+            uniform_buffer,
+            textures: std::array::from_fn(|_| create_outline_texture(device, width, height)),
+            bind_groups: None,
+            width,
+            height,
+        }
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        if (width, height) != (self.width, self.height) {
+            self.textures = std::array::from_fn(|_| create_outline_texture(device, width, height));
+            self.bind_groups = None;
+            self.width = width;
+            self.height = height;
+        }
+    }
+
+    /// The direction texture with outlines drawn in, valid after `dispatch`
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.textures[2].1
+    }
+
+    /// Read `direction_view` and `part_id_view` (both the pass's size) and spread silhouettes
+    /// `radius` pixels (horizontal, vertical) to each side
+    pub fn update_bind_groups(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        direction_view: &wgpu::TextureView,
+        part_id_view: &wgpu::TextureView,
+        radius: (f32, f32),
+    ) {
+        let uniforms = OutlineUniforms {
+            width: self.width,
+            height: self.height,
+            radius_x: radius.0,
+            radius_y: radius.1,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+
+        let [mask, spread, outlined] = &self.textures;
+        // Each step reads the one before; marking reads nothing but still needs a source bound
+        let steps = [(spread, mask), (mask, spread), (spread, outlined)];
+        self.bind_groups = Some(steps.map(|(source, output)| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Outline Bind Group"),
+                layout: &self.layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(direction_view) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(part_id_view) },
+                    wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(&source.1) },
+                    wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(&output.1) },
+                    wgpu::BindGroupEntry { binding: 4, resource: self.uniform_buffer.as_entire_binding() },
+                ],
+            })
+        }));
+    }
+
+    pub fn dispatch(&self, encoder: &mut wgpu::CommandEncoder) {
+        let Some(bind_groups) = &self.bind_groups else {
+            return;
+        };
+        for (pipeline, bind_group) in self.pipelines.iter().zip(bind_groups) {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Outline Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.dispatch_workgroups(self.width.div_ceil(PIXEL_WORKGROUP_SIZE), self.height.div_ceil(PIXEL_WORKGROUP_SIZE), 1);
+        }
+    }
+}
+
+fn create_outline_texture(device: &wgpu::Device, width: u32, height: u32) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Outline Texture"),
+        size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: OUTLINE_FORMAT,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&Default::default());
+    (texture, view)
+}
@@ -1,6 +1,78 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use bytemuck::{Pod, Zeroable};
 
+use super::headless::{Z_FAR, Z_NEAR};
+use super::outline::{OutlinePass, MAX_OUTLINE_WIDTH};
+use super::ramp::CharRamp;
+use super::shaders::AsciiShaders;
+
+/// Threads per side of the pixel-pass workgroups (display transform, edge detection and Sobel)
+/// Must match `@workgroup_size` in display_transform.wgsl, edge_detect.wgsl, sobel_edges.wgsl, outline.wgsl and accumulate.wgsl
+pub const PIXEL_WORKGROUP_SIZE: u32 = 16;
+
+/// Cells per side of the ASCII-pass workgroups, one thread per cell
+/// Must match `@workgroup_size` in ascii_edges.wgsl and in style shaders
+pub const CELL_WORKGROUP_SIZE: u32 = 8;
+
+/// Luminance boost applied before the character ramp
+pub const DEFAULT_EXPOSURE: f32 = 1.5;
+
+/// Contrast curve applied after exposure (< 1 brightens midtones)
+pub const DEFAULT_GAMMA: f32 = 0.8;
+
+/// Curve applied to linear scene color before sRGB encoding
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tonemap {
+    /// Clamp to [0, 1]
+    #[default]
+    None,
+    /// ACES filmic curve, rolls highlights off instead of clipping them
+    Aces,
+}
+
+impl Tonemap {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "none" => Ok(Self::None),
+            "aces" => Ok(Self::Aces),
+            other => Err(anyhow!("Unknown tonemap {:?} (expected \"none\" or \"aces\")", other)),
+        }
+    }
+}
+
+/// Which edges the edge-detection pass looks for
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EdgeAlgorithm {
+    /// Depth, normal, and DoG luminance edges combined (good all-rounder)
+    #[default]
+    Hybrid,
+    /// Canny on luminance, thin lines that follow shading and texture detail (organic scans)
+    Canny,
+    /// Depth discontinuities only, clean silhouettes and outlines (hard-surface models)
+    Depth,
+}
+
+impl EdgeAlgorithm {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "hybrid" => Ok(Self::Hybrid),
+            "canny" => Ok(Self::Canny),
+            "depth" => Ok(Self::Depth),
+            other => Err(anyhow!("Unknown edge algorithm {:?} (expected \"hybrid\", \"canny\", or \"depth\")", other)),
+        }
+    }
+}
+
+/// Uniforms for the display transform pass
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct DisplayUniforms {
+    width: u32,
+    height: u32,
+    tonemap: u32,
+    motion_blur: f32,
+}
+
 /// Uniforms for edge detection pass
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
@@ -13,6 +85,14 @@ struct EdgeDetectUniforms {
     use_depth: u32,
     use_normals: u32,
     use_dog: u32,
+    use_canny: u32,
+    canny_low: f32,
+    canny_high: f32,
+    clip_near: f32,
+    clip_far: f32,
+    depth_world_units: u32,
+    use_ids: u32,
+    interior_edges: u32,
 }
 
 /// Uniforms for Sobel pass
@@ -21,7 +101,8 @@ struct EdgeDetectUniforms {
 struct SobelUniforms {
     width: u32,
     height: u32,
-    _padding: [u32; 2],
+    use_depth_gradient: u32,
+    _padding: u32,
 }
 
 /// Uniforms for final ASCII pass
@@ -35,12 +116,21 @@ struct AsciiUniforms {
     edge_threshold: u32,
     exposure: f32,
     gamma: f32,
-    _padding: f32,
+    depth_weight: f32,
+    depth_near: f32,
+    depth_far: f32,
+    clip_near: f32,
+    clip_far: f32,
+    suppress_background: u32,
+    fill_levels: u32,
+    ink_outlines: u32,
+    _padding: u32,
 }
 
-/// 3-Pass ASCII Pipeline with edge detection
+/// ASCII Pipeline with edge detection
+/// Pass 0: Display transform (linear scene color to sRGB, optionally ACES tonemapped)
 /// Pass 1: Edge detection (depth + normals + DoG)
-/// Pass 2: Sobel direction
+/// Pass 2: Sobel direction, then thicker silhouettes (with an outline width above 1)
 /// Pass 3: ASCII character selection with tile voting
 pub struct AsciiPipeline {
     // Dimensions
@@ -50,43 +140,74 @@ pub struct AsciiPipeline {
     tex_height: u32,
 
     // Compute pipelines
+    display_pipeline: wgpu::ComputePipeline,
     edge_pipeline: wgpu::ComputePipeline,
     sobel_pipeline: wgpu::ComputePipeline,
     ascii_pipeline: wgpu::ComputePipeline,
 
     // Bind group layouts
+    display_layout: wgpu::BindGroupLayout,
     edge_layout: wgpu::BindGroupLayout,
     sobel_layout: wgpu::BindGroupLayout,
     ascii_layout: wgpu::BindGroupLayout,
 
     // Intermediate textures
+    display_tex: wgpu::Texture,   // sRGB-encoded scene color
     edge_tex: wgpu::Texture,      // R=edge, G=lum, B=depth
     direction_tex: wgpu::Texture, // R=dir, G=edge_flag, B=lum, A=depth
 
     // Uniform buffers
+    display_uniform_buf: wgpu::Buffer,
     edge_uniform_buf: wgpu::Buffer,
     sobel_uniform_buf: wgpu::Buffer,
     ascii_uniform_buf: wgpu::Buffer,
+    ramp_buf: wgpu::Buffer,
 
     // Output buffers
     output_buffer: wgpu::Buffer,
     staging_buffer: wgpu::Buffer,
 
     // Bind groups (created when input textures are provided)
+    display_bind_group: Option<wgpu::BindGroup>,
     edge_bind_group: Option<wgpu::BindGroup>,
     sobel_bind_group: Option<wgpu::BindGroup>,
     ascii_bind_group: Option<wgpu::BindGroup>,
 
     // Tunable parameters
     depth_threshold: f32,
+    /// Depth edge threshold in world units, replacing the fraction of the clip range
+    world_depth_threshold: Option<f32>,
     normal_threshold: f32,
     dog_threshold: f32,
     use_depth: bool,
     use_normals: bool,
     use_dog: bool,
+    use_canny: bool,
+    canny_thresholds: (f32, f32),
+    /// Edges where the part ID changes
+    object_edges: bool,
+    /// Normal and luminance edges inside a single part
+    interior_edges: bool,
+    /// Silhouette thickness in character cells (1 = as detected, no outline pass)
+    outline_width: u32,
+    outline: Option<OutlinePass>,
+    /// Color outline edge characters black instead of the surface under them
+    ink_outlines: bool,
+    /// Luminance bands fills are snapped to (0 = continuous)
+    fill_levels: u32,
     edge_vote_threshold: u32,
+    edge_chars: bool,
     exposure: f32,
     gamma: f32,
+    depth_weight: f32,
+    depth_window: (f32, f32),
+    /// Near and far planes the scene was projected with, to linearize its depth
+    clip_planes: (f32, f32),
+    suppress_background: bool,
+    tonemap: Tonemap,
+    /// Fraction of each pixel's motion since the previous frame blurred over (0 = off)
+    motion_blur: f32,
+    ramp: CharRamp,
 }
 
 impl AsciiPipeline {
@@ -96,6 +217,18 @@ impl AsciiPipeline {
         rows: u32,
         tex_width: u32,
         tex_height: u32,
+    ) -> Result<Self> {
+        Self::with_shaders(device, cols, rows, tex_width, tex_height, &AsciiShaders::builtin())
+    }
+
+    /// Create the pipeline from the given WGSL sources
+    pub fn with_shaders(
+        device: &wgpu::Device,
+        cols: u32,
+        rows: u32,
+        tex_width: u32,
+        tex_height: u32,
+        shaders: &AsciiShaders,
     ) -> Result<Self> {
         // Tunable parameters - adjusted for cleaner output with loaded models
         let depth_threshold = 0.08;   // Depth discontinuity threshold (higher = less sensitive)
@@ -104,41 +237,41 @@ impl AsciiPipeline {
         let use_depth = true;         // Enable depth-based edges
         let use_normals = true;       // Enable normal-based edges
         let use_dog = true;           // Enable DoG edges - all three are critical
+        let use_canny = false;        // Canny replaces the other three (see set_edge_algorithm)
+        let canny_thresholds = (0.1, 0.3); // Canny hysteresis (low, high) on gradient magnitude
         let edge_vote_threshold = 3;  // Min edge pixels in tile to use edge char
-        let exposure = 1.5;           // Luminance boost
-        let gamma = 0.8;              // Contrast curve (attenuation)
-
-        // Create shader modules
-        let edge_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Edge Detection Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/edge_detect.wgsl").into()),
-        });
-
-        let sobel_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Sobel Direction Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/sobel_edges.wgsl").into()),
-        });
-
-        let ascii_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("ASCII Edges Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/ascii_edges.wgsl").into()),
-        });
+        let exposure = DEFAULT_EXPOSURE; // Luminance boost
+        let gamma = DEFAULT_GAMMA;    // Contrast curve (attenuation)
+        let depth_weight = 0.0;       // Depth mixed into luminance (0 = off)
 
         // Create bind group layouts
+        let display_layout = Self::create_display_layout(device);
         let edge_layout = Self::create_edge_layout(device);
         let sobel_layout = Self::create_sobel_layout(device);
         let ascii_layout = Self::create_ascii_layout(device);
 
         // Create pipelines
-        let edge_pipeline = Self::create_pipeline(device, &edge_shader, &edge_layout, "Edge Pipeline");
-        let sobel_pipeline = Self::create_pipeline(device, &sobel_shader, &sobel_layout, "Sobel Pipeline");
-        let ascii_pipeline = Self::create_pipeline(device, &ascii_shader, &ascii_layout, "ASCII Pipeline");
+        let (edge_pipeline, sobel_pipeline, ascii_pipeline) =
+            Self::create_pipelines(device, shaders, &edge_layout, &sobel_layout, &ascii_layout);
+        let display_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Display Transform Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/display_transform.wgsl").into()),
+        });
+        let display_pipeline = Self::create_pipeline(device, &display_shader, &display_layout, "Display Pipeline");
 
         // Create intermediate textures (RGBA32Float for flexibility)
+        let display_tex = Self::create_display_texture(device, tex_width, tex_height);
         let edge_tex = Self::create_rgba32f_texture(device, tex_width, tex_height, "Edge Texture");
         let direction_tex = Self::create_rgba32f_texture(device, tex_width, tex_height, "Direction Texture");
 
         // Create uniform buffers
+        let display_uniform_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Display Uniforms"),
+            size: std::mem::size_of::<DisplayUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         let edge_uniform_buf = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Edge Uniforms"),
             size: std::mem::size_of::<EdgeDetectUniforms>() as u64,
@@ -160,6 +293,13 @@ impl AsciiPipeline {
             mapped_at_creation: false,
         });
 
+        let ramp_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("ASCII Ramp Buffer"),
+            size: std::mem::size_of::<CharRamp>() as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         // Create output buffers
         let buffer_size = (cols * rows * 4) as u64;
         let output_buffer = device.create_buffer(&wgpu::BufferDescriptor {
@@ -181,31 +321,156 @@ impl AsciiPipeline {
             rows,
             tex_width,
             tex_height,
+            display_pipeline,
             edge_pipeline,
             sobel_pipeline,
             ascii_pipeline,
+            display_layout,
             edge_layout,
             sobel_layout,
             ascii_layout,
+            display_tex,
             edge_tex,
             direction_tex,
+            display_uniform_buf,
             edge_uniform_buf,
             sobel_uniform_buf,
             ascii_uniform_buf,
+            ramp_buf,
             output_buffer,
             staging_buffer,
+            display_bind_group: None,
             edge_bind_group: None,
             sobel_bind_group: None,
             ascii_bind_group: None,
             depth_threshold,
+            world_depth_threshold: None,
             normal_threshold,
             dog_threshold,
             use_depth,
             use_normals,
             use_dog,
+            use_canny,
+            canny_thresholds,
+            object_edges: false,
+            interior_edges: true,
+            outline_width: 1,
+            outline: None,
+            ink_outlines: false,
+            fill_levels: 0,
             edge_vote_threshold,
+            edge_chars: true,
             exposure,
             gamma,
+            depth_weight,
+            depth_window: (Z_NEAR, Z_FAR),
+            clip_planes: (Z_NEAR, Z_FAR),
+            suppress_background: false,
+            tonemap: Tonemap::None,
+            motion_blur: 0.0,
+            ramp: CharRamp::default(),
+        })
+    }
+
+    /// Compile the three passes from WGSL sources
+    fn create_pipelines(
+        device: &wgpu::Device,
+        shaders: &AsciiShaders,
+        edge_layout: &wgpu::BindGroupLayout,
+        sobel_layout: &wgpu::BindGroupLayout,
+        ascii_layout: &wgpu::BindGroupLayout,
+    ) -> (wgpu::ComputePipeline, wgpu::ComputePipeline, wgpu::ComputePipeline) {
+        let edge_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Edge Detection Shader"),
+            source: wgpu::ShaderSource::Wgsl(shaders.edge_detect.as_str().into()),
+        });
+
+        let sobel_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Sobel Direction Shader"),
+            source: wgpu::ShaderSource::Wgsl(shaders.sobel_edges.as_str().into()),
+        });
+
+        let ascii_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("ASCII Edges Shader"),
+            source: wgpu::ShaderSource::Wgsl(shaders.ascii_edges.as_str().into()),
+        });
+
+        (
+            Self::create_pipeline(device, &edge_shader, edge_layout, "Edge Pipeline"),
+            Self::create_pipeline(device, &sobel_shader, sobel_layout, "Sobel Pipeline"),
+            Self::create_pipeline(device, &ascii_shader, ascii_layout, "ASCII Pipeline"),
+        )
+    }
+
+    /// Recompile the passes from new sources (shader hot-reload)
+    /// On a compile or validation error the current pipelines are kept and the error is returned
+    pub async fn reload_shaders(&mut self, device: &wgpu::Device, shaders: &AsciiShaders) -> Result<()> {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let pipelines = Self::create_pipelines(
+            device,
+            shaders,
+            &self.edge_layout,
+            &self.sobel_layout,
+            &self.ascii_layout,
+        );
+        if let Some(error) = device.pop_error_scope().await {
+            return Err(anyhow!("{}", error));
+        }
+
+        // Bind groups only depend on the layouts, so they stay valid
+        (self.edge_pipeline, self.sobel_pipeline, self.ascii_pipeline) = pipelines;
+        Ok(())
+    }
+
+    fn create_display_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Display Transform Layout"),
+            entries: &[
+                // Linear scene color input
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // sRGB color output
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: wgpu::TextureFormat::Rgba8Unorm,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                // Uniforms
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Motion vectors, for motion blur
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
         })
     }
 
@@ -257,6 +522,17 @@ impl AsciiPipeline {
                     },
                     count: None,
                 },
+                // Part ID texture
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Uint,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
             ],
         })
     }
@@ -350,6 +626,17 @@ impl AsciiPipeline {
                     },
                     count: None,
                 },
+                // Character ramp (fill order and coverage)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         })
     }
@@ -389,6 +676,21 @@ impl AsciiPipeline {
         })
     }
 
+    /// Display-referred color read by the edge and ASCII passes (8 bits per channel, like the
+    /// terminal colors it feeds)
+    fn create_display_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Display Texture"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING,
+            view_formats: &[],
+        })
+    }
+
     pub fn resize(&mut self, device: &wgpu::Device, cols: u32, rows: u32, tex_width: u32, tex_height: u32) {
         let size_changed = tex_width != self.tex_width || tex_height != self.tex_height;
         let cols_changed = cols != self.cols || rows != self.rows;
@@ -397,6 +699,7 @@ impl AsciiPipeline {
             self.tex_width = tex_width;
             self.tex_height = tex_height;
 
+            self.display_tex = Self::create_display_texture(device, tex_width, tex_height);
             self.edge_tex = Self::create_rgba32f_texture(device, tex_width, tex_height, "Edge Texture");
             self.direction_tex = Self::create_rgba32f_texture(device, tex_width, tex_height, "Direction Texture");
         }
@@ -422,36 +725,160 @@ impl AsciiPipeline {
         }
 
         if size_changed || cols_changed {
+            self.display_bind_group = None;
             self.edge_bind_group = None;
             self.sobel_bind_group = None;
             self.ascii_bind_group = None;
         }
     }
 
+    /// Set the luminance boost (applied on the next `update_bind_groups`)
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.exposure = exposure.max(0.0);
+    }
+
+    /// Set the contrast curve (applied on the next `update_bind_groups`)
+    pub fn set_gamma(&mut self, gamma: f32) {
+        self.gamma = gamma.max(0.01);
+    }
+
+    /// Set how much depth is mixed into luminance, 0 (off) to 1 (depth only)
+    /// Closer surfaces get brighter, which separates silhouettes of flat-shaded models
+    pub fn set_depth_weight(&mut self, weight: f32) {
+        self.depth_weight = weight.clamp(0.0, 1.0);
+    }
+
+    /// Set the view-space distances mapped to full (near) and zero (far) depth brightness
+    pub fn set_depth_window(&mut self, near: f32, far: f32) {
+        self.depth_window = (near, far.max(near + 1e-3));
+    }
+
+    /// Set the near and far planes the scene was last rendered with (see `HeadlessGpu::clip_planes`)
+    pub fn set_clip_planes(&mut self, near: f32, far: f32) {
+        self.clip_planes = (near, far.max(near + 1e-3));
+    }
+
+    /// Treat pixels at far depth (the background) as black with no edges, so only the model
+    /// is drawn as characters
+    pub fn set_suppress_background(&mut self, suppress: bool) {
+        self.suppress_background = suppress;
+    }
+
+    /// Set the depth edge threshold as the total depth jump around a pixel in world units, or None
+    /// for the default fraction of the clip range (applied on the next `update_bind_groups`)
+    pub fn set_world_depth_threshold(&mut self, threshold: Option<f32>) {
+        self.world_depth_threshold = threshold;
+    }
+
+    /// Draw edge characters where edges are found, or fill characters everywhere (edge
+    /// characters flicker as edges move; applied on the next `update_bind_groups`)
+    pub fn set_edge_chars(&mut self, enabled: bool) {
+        self.edge_chars = enabled;
+    }
+
+    /// Choose the edge detector (applied on the next `update_bind_groups`)
+    pub fn set_edge_algorithm(&mut self, algorithm: EdgeAlgorithm) {
+        (self.use_depth, self.use_normals, self.use_dog, self.use_canny) = match algorithm {
+            EdgeAlgorithm::Hybrid => (true, true, true, false),
+            EdgeAlgorithm::Canny => (false, false, false, true),
+            EdgeAlgorithm::Depth => (true, false, false, false),
+        };
+    }
+
+    /// Outline each model part where it meets another part or the background, from the part ID
+    /// target (applied on the next `update_bind_groups`)
+    pub fn set_object_edges(&mut self, enabled: bool) {
+        self.object_edges = enabled;
+    }
+
+    /// Keep normal and luminance edges inside a model part; off leaves a part's interior to depth
+    /// edges alone, so smooth shading inside one object draws no lines (applied on the next
+    /// `update_bind_groups`)
+    pub fn set_interior_edges(&mut self, enabled: bool) {
+        self.interior_edges = enabled;
+    }
+
+    /// Set how many character cells thick silhouettes are drawn, 1 (as detected) to
+    /// `MAX_OUTLINE_WIDTH`; interior edges keep their width (applied on the next `update_bind_groups`)
+    pub fn set_outline_width(&mut self, width: u32) {
+        self.outline_width = width.clamp(1, MAX_OUTLINE_WIDTH);
+    }
+
+    /// Color the edge characters of thick outlines black, like inked lines (applied on the next
+    /// `update_bind_groups`)
+    pub fn set_ink_outlines(&mut self, enabled: bool) {
+        self.ink_outlines = enabled;
+    }
+
+    /// Snap fill brightness and color to this many evenly spaced bands, for toon fills; 0 keeps
+    /// them continuous (applied on the next `update_bind_groups`)
+    pub fn set_fill_levels(&mut self, levels: u32) {
+        self.fill_levels = levels;
+    }
+
+    /// Set the curve applied to linear scene color before sRGB encoding
+    pub fn set_tonemap(&mut self, tonemap: Tonemap) {
+        self.tonemap = tonemap;
+    }
+
+    /// Set the motion blur shutter: the fraction of each pixel's movement since the previous frame
+    /// its color is spread along, 0 (off) to 1
+    pub fn set_motion_blur(&mut self, shutter: f32) {
+        self.motion_blur = shutter.clamp(0.0, 1.0);
+    }
+
+    /// Set the fill character order and coverage (applied on the next `update_bind_groups`)
+    pub fn set_ramp(&mut self, ramp: CharRamp) {
+        self.ramp = ramp;
+    }
+
     pub fn update_bind_groups(
         &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         color_view: &wgpu::TextureView,
         depth_view: &wgpu::TextureView,
+        motion_view: &wgpu::TextureView,
+        part_id_view: &wgpu::TextureView,
     ) {
         // Update uniform buffers
+        let display_uniforms = DisplayUniforms {
+            width: self.tex_width,
+            height: self.tex_height,
+            tonemap: match self.tonemap {
+                Tonemap::None => 0,
+                Tonemap::Aces => 1,
+            },
+            motion_blur: self.motion_blur,
+        };
+        queue.write_buffer(&self.display_uniform_buf, 0, bytemuck::cast_slice(&[display_uniforms]));
+
         let edge_uniforms = EdgeDetectUniforms {
             width: self.tex_width,
             height: self.tex_height,
-            depth_threshold: self.depth_threshold,
+            depth_threshold: self.world_depth_threshold.unwrap_or(self.depth_threshold),
             normal_threshold: self.normal_threshold,
             dog_threshold: self.dog_threshold,
             use_depth: if self.use_depth { 1 } else { 0 },
             use_normals: if self.use_normals { 1 } else { 0 },
             use_dog: if self.use_dog { 1 } else { 0 },
+            use_canny: self.use_canny as u32,
+            canny_low: self.canny_thresholds.0,
+            canny_high: self.canny_thresholds.1,
+            clip_near: self.clip_planes.0,
+            clip_far: self.clip_planes.1,
+            depth_world_units: self.world_depth_threshold.is_some() as u32,
+            use_ids: self.object_edges as u32,
+            interior_edges: self.interior_edges as u32,
         };
         queue.write_buffer(&self.edge_uniform_buf, 0, bytemuck::cast_slice(&[edge_uniforms]));
 
         let sobel_uniforms = SobelUniforms {
             width: self.tex_width,
             height: self.tex_height,
-            _padding: [0; 2],
+            // Depth-only edges are oriented along the silhouette, not the shading
+            use_depth_gradient: (self.use_depth && !self.use_normals && !self.use_dog && !self.use_canny) as u32,
+            _padding: 0,
         };
         queue.write_buffer(&self.sobel_uniform_buf, 0, bytemuck::cast_slice(&[sobel_uniforms]));
 
@@ -460,26 +887,50 @@ impl AsciiPipeline {
             tex_height: self.tex_height,
             cols: self.cols,
             rows: self.rows,
-            edge_threshold: self.edge_vote_threshold,
+            // A threshold no tile reaches leaves every cell its fill character
+            edge_threshold: if self.edge_chars { self.edge_vote_threshold } else { u32::MAX },
             exposure: self.exposure,
             gamma: self.gamma,
-            _padding: 0.0,
+            depth_weight: self.depth_weight,
+            depth_near: self.depth_window.0,
+            depth_far: self.depth_window.1,
+            clip_near: self.clip_planes.0,
+            clip_far: self.clip_planes.1,
+            suppress_background: self.suppress_background as u32,
+            fill_levels: self.fill_levels,
+            ink_outlines: self.ink_outlines as u32,
+            _padding: 0,
         };
         queue.write_buffer(&self.ascii_uniform_buf, 0, bytemuck::cast_slice(&[ascii_uniforms]));
+        queue.write_buffer(&self.ramp_buf, 0, bytemuck::cast_slice(&[self.ramp]));
 
         // Create texture views for intermediate textures
+        let display_view = self.display_tex.create_view(&Default::default());
         let edge_view = self.edge_tex.create_view(&Default::default());
         let direction_view = self.direction_tex.create_view(&Default::default());
 
+        // Display transform bind group
+        self.display_bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Display Bind Group"),
+            layout: &self.display_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(color_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&display_view) },
+                wgpu::BindGroupEntry { binding: 2, resource: self.display_uniform_buf.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(motion_view) },
+            ],
+        }));
+
         // Edge detection bind group
         self.edge_bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Edge Bind Group"),
             layout: &self.edge_layout,
             entries: &[
-                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(color_view) },
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&display_view) },
                 wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(depth_view) },
                 wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(&edge_view) },
                 wgpu::BindGroupEntry { binding: 3, resource: self.edge_uniform_buf.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 4, resource: wgpu::BindingResource::TextureView(part_id_view) },
             ],
         }));
 
@@ -494,27 +945,56 @@ impl AsciiPipeline {
             ],
         }));
 
+        // Outline pass, between Sobel and ASCII: each side of a silhouette grows by half the
+        // extra width, so the line is about `outline_width` cells across
+        let ascii_input = if self.outline_width > 1 {
+            let outline = self.outline.get_or_insert_with(|| OutlinePass::new(device, self.tex_width, self.tex_height));
+            outline.resize(device, self.tex_width, self.tex_height);
+            let extra = (self.outline_width - 1) as f32 / 2.0;
+            let radius = (
+                extra * self.tex_width as f32 / self.cols as f32,
+                extra * self.tex_height as f32 / self.rows as f32,
+            );
+            outline.update_bind_groups(device, queue, &direction_view, part_id_view, radius);
+            outline.view()
+        } else {
+            self.outline = None;
+            &direction_view
+        };
+
         // ASCII bind group
         self.ascii_bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("ASCII Bind Group"),
             layout: &self.ascii_layout,
             entries: &[
-                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&direction_view) },
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(ascii_input) },
                 wgpu::BindGroupEntry { binding: 1, resource: self.ascii_uniform_buf.as_entire_binding() },
                 wgpu::BindGroupEntry { binding: 2, resource: self.output_buffer.as_entire_binding() },
-                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(color_view) },
+                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(&display_view) },
+                wgpu::BindGroupEntry { binding: 4, resource: self.ramp_buf.as_entire_binding() },
             ],
         }));
     }
 
     pub fn dispatch(&self, encoder: &mut wgpu::CommandEncoder) {
-        // Workgroup counts for pixel-level passes (16x16 workgroups)
-        let pixel_wg_x = (self.tex_width + 15) / 16;
-        let pixel_wg_y = (self.tex_height + 15) / 16;
+        // Workgroup counts for pixel-level passes
+        let pixel_wg_x = self.tex_width.div_ceil(PIXEL_WORKGROUP_SIZE);
+        let pixel_wg_y = self.tex_height.div_ceil(PIXEL_WORKGROUP_SIZE);
 
-        // Workgroup counts for ASCII pass (1 thread per cell)
-        let ascii_wg_x = self.cols;
-        let ascii_wg_y = self.rows;
+        // Workgroup counts for ASCII pass (8x8 cells per workgroup, 1 thread per cell)
+        let ascii_wg_x = self.cols.div_ceil(CELL_WORKGROUP_SIZE);
+        let ascii_wg_y = self.rows.div_ceil(CELL_WORKGROUP_SIZE);
+
+        // Pass 0: Display transform
+        if let Some(bg) = &self.display_bind_group {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Display Transform Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.display_pipeline);
+            pass.set_bind_group(0, bg, &[]);
+            pass.dispatch_workgroups(pixel_wg_x, pixel_wg_y, 1);
+        }
 
         // Pass 1: Edge detection
         if let Some(bg) = &self.edge_bind_group {
@@ -538,6 +1018,11 @@ impl AsciiPipeline {
             pass.dispatch_workgroups(pixel_wg_x, pixel_wg_y, 1);
         }
 
+        // Thicker silhouettes
+        if let Some(outline) = &self.outline {
+            outline.dispatch(encoder);
+        }
+
         // Pass 3: ASCII character selection
         if let Some(bg) = &self.ascii_bind_group {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
@@ -575,6 +1060,16 @@ impl AsciiPipeline {
         Ok(result)
     }
 
+    /// The sRGB scene color of the last dispatch, as the edge and ASCII passes saw it
+    pub fn display_view(&self) -> wgpu::TextureView {
+        self.display_tex.create_view(&Default::default())
+    }
+
+    /// Size of the textures the pipeline reads, in pixels
+    pub fn tex_size(&self) -> (u32, u32) {
+        (self.tex_width, self.tex_height)
+    }
+
     pub fn cols(&self) -> u32 {
         self.cols
     }
@@ -0,0 +1,187 @@
+use anyhow::Result;
+use bytemuck::{Pod, Zeroable};
+
+use super::pipeline::CELL_WORKGROUP_SIZE;
+
+/// Uniforms for the preview pass
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct PreviewUniforms {
+    tex_width: u32,
+    tex_height: u32,
+    cols: u32,
+    rows: u32,
+}
+
+/// Downsamples the display-transformed scene (before ASCII conversion) to a small color grid,
+/// for the picture-in-picture preview
+pub struct PreviewPass {
+    cols: u32,
+    rows: u32,
+    pipeline: wgpu::ComputePipeline,
+    layout: wgpu::BindGroupLayout,
+    uniform_buf: wgpu::Buffer,
+    output_buffer: wgpu::Buffer,
+    staging_buffer: wgpu::Buffer,
+}
+
+impl PreviewPass {
+    pub fn new(device: &wgpu::Device, cols: u32, rows: u32) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Preview Layout"),
+            entries: &[
+                // Display color input
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Uniforms
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Output buffer
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Preview Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/preview.wgsl").into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Preview Pipeline Layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Preview Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let uniform_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Preview Uniforms"),
+            size: std::mem::size_of::<PreviewUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let (output_buffer, staging_buffer) = Self::create_buffers(device, cols, rows);
+
+        Self {
+            cols,
+            rows,
+            pipeline,
+            layout,
+            uniform_buf,
+            output_buffer,
+            staging_buffer,
+        }
+    }
+
+    fn create_buffers(device: &wgpu::Device, cols: u32, rows: u32) -> (wgpu::Buffer, wgpu::Buffer) {
+        let size = (cols * rows * 4) as u64;
+        let output = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Preview Output Buffer"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Preview Staging Buffer"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        (output, staging)
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, cols: u32, rows: u32) {
+        if (cols, rows) != (self.cols, self.rows) {
+            (self.output_buffer, self.staging_buffer) = Self::create_buffers(device, cols, rows);
+            self.cols = cols;
+            self.rows = rows;
+        }
+    }
+
+    /// Downsample `color_view` (of the given size) and read the packed cells back
+    pub fn run(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        color_view: &wgpu::TextureView,
+        tex_size: (u32, u32),
+    ) -> Result<Vec<u32>> {
+        let uniforms = PreviewUniforms {
+            tex_width: tex_size.0,
+            tex_height: tex_size.1,
+            cols: self.cols,
+            rows: self.rows,
+        };
+        queue.write_buffer(&self.uniform_buf, 0, bytemuck::cast_slice(&[uniforms]));
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Preview Bind Group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(color_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: self.uniform_buf.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: self.output_buffer.as_entire_binding() },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Preview Encoder"),
+        });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Preview Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(self.cols.div_ceil(CELL_WORKGROUP_SIZE), self.rows.div_ceil(CELL_WORKGROUP_SIZE), 1);
+        }
+        let size = (self.cols * self.rows * 4) as u64;
+        encoder.copy_buffer_to_buffer(&self.output_buffer, 0, &self.staging_buffer, 0, size);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let buffer_slice = self.staging_buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            tx.send(result).unwrap();
+        });
+        device.poll(wgpu::Maintain::Wait);
+        rx.recv()??;
+
+        let data = buffer_slice.get_mapped_range();
+        let result: Vec<u32> = bytemuck::cast_slice(&data).to_vec();
+        drop(data);
+        self.staging_buffer.unmap();
+        Ok(result)
+    }
+}
@@ -0,0 +1,94 @@
+use bytemuck::{Pod, Zeroable};
+
+/// Fill characters of the ramp (cell indices 0-9); the four edge characters follow them
+pub const FILL_CHARS: usize = 10;
+
+/// Every character the ASCII pass outputs, fill then edge
+pub const RAMP_CHARS: usize = FILL_CHARS + 4;
+
+/// Ink coverage of ` .;coPO?@#` and `| - \ /` measured from DejaVu Sans Mono by
+/// `ascii-3d calibrate`, for terminals whose font hasn't been calibrated
+const EMBEDDED_COVERAGE: [f32; RAMP_CHARS] =
+    [0.0, 0.0282, 0.0686, 0.1278, 0.1786, 0.2112, 0.2359, 0.1259, 0.2789, 0.2415, 0.1068, 0.0260, 0.1096, 0.1094];
+
+/// Fill of the densest character once measured coverage is scaled
+/// (fonts leave a lot of the cell empty, so raw coverage would over-brighten every color)
+const DENSEST_FILL: f32 = 0.85;
+
+/// Lowest fill, so the color boost of a blank cell stays finite
+const MIN_FILL: f32 = 0.01;
+
+/// Smallest coverage gap between the fill characters a high-contrast ramp keeps
+const HIGH_CONTRAST_GAP: f32 = 0.2;
+
+/// Level of a fill character left out of the ramp: further from any luminance than the
+/// shader's starting distance, so it is never the nearest
+const UNUSED_LEVEL: f32 = 4.0;
+
+/// Character ramp the ASCII pass picks fill characters from, laid out as the shader reads it
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct CharRamp {
+    /// Coverage of each fill character scaled so the densest is 1: the luminance (after
+    /// exposure and gamma) it stands for, the nearest of which is picked for a cell
+    levels: [f32; FILL_CHARS],
+    /// How much of the cell each character covers (by cell index), which sets how much its
+    /// color is boosted to make up for the ink it lacks
+    fill: [f32; RAMP_CHARS],
+}
+
+impl Default for CharRamp {
+    /// The ramp as measured in a common terminal font
+    fn default() -> Self {
+        Self::from_coverage(&EMBEDDED_COVERAGE)
+    }
+}
+
+impl CharRamp {
+    /// Ramp for measured ink coverage of each character (by cell index, as `ascii-3d calibrate`
+    /// prints it): fill characters spaced by their coverage relative to the densest one, whose
+    /// fill is scaled to `DENSEST_FILL`
+    pub fn from_coverage(coverage: &[f32; RAMP_CHARS]) -> Self {
+        let densest = coverage[..FILL_CHARS].iter().copied().fold(0.0, f32::max);
+        let scale = if densest > 0.0 { 1.0 / densest } else { 1.0 };
+        Self {
+            levels: std::array::from_fn(|i| (coverage[i] * scale).min(1.0)),
+            fill: coverage.map(|c| (c * scale * DENSEST_FILL).clamp(MIN_FILL, 1.0)),
+        }
+    }
+
+    /// Fill character indices from dark to bright
+    pub fn order(&self) -> [u32; FILL_CHARS] {
+        let mut order: [u32; FILL_CHARS] = std::array::from_fn(|i| i as u32);
+        // Stable, so characters with equal coverage keep the default order
+        order.sort_by(|&a, &b| self.levels[a as usize].total_cmp(&self.levels[b as usize]));
+        order
+    }
+
+    /// Ramp for the high-contrast mode: only fill characters whose coverage differs by at least
+    /// `HIGH_CONTRAST_GAP` (the blank and densest always among them), spread evenly over the
+    /// luminance range so neighbouring shades are as far apart as the font allows
+    pub fn high_contrast(&self) -> Self {
+        let order = self.order();
+        let mut kept: Vec<u32> = Vec::new();
+        for &i in &order {
+            match kept.last() {
+                Some(&last) if self.levels[i as usize] - self.levels[last as usize] < HIGH_CONTRAST_GAP => {}
+                _ => kept.push(i),
+            }
+        }
+        // The densest character stands in for the last one kept if it was too close to it
+        let densest = order[FILL_CHARS - 1];
+        if kept.last() != Some(&densest) {
+            kept.pop();
+            kept.push(densest);
+        }
+
+        let mut levels = [UNUSED_LEVEL; FILL_CHARS];
+        let steps = (kept.len() - 1).max(1) as f32;
+        for (rank, &i) in kept.iter().enumerate() {
+            levels[i as usize] = rank as f32 / steps;
+        }
+        Self { levels, fill: self.fill }
+    }
+}
@@ -0,0 +1,168 @@
+use bytemuck::{Pod, Zeroable};
+use glam::Mat4;
+
+use super::headless::{LightingMode, LIGHT_DIR, MOTION_TARGET, NO_PART_ID_TARGET};
+
+/// Radius of the sphere every raymarched scene fits in (`BOUND_RADIUS` in sdf.wgsl)
+pub(super) const SDF_BOUND_RADIUS: f32 = 1.2;
+
+/// Signed-distance-field scene raymarched in place of the model mesh
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SdfScene {
+    Mandelbulb,
+    Gyroid,
+    RoundedBoxes,
+}
+
+impl SdfScene {
+    fn to_u32(self) -> u32 {
+        match self {
+            SdfScene::Mandelbulb => 0,
+            SdfScene::Gyroid => 1,
+            SdfScene::RoundedBoxes => 2,
+        }
+    }
+}
+
+/// Uniforms for the raymarch pass
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct SdfUniforms {
+    inv_view_proj: [[f32; 4]; 4],
+    view_proj: [[f32; 4]; 4],
+    model: [[f32; 4]; 4],
+    inv_model: [[f32; 4]; 4],
+    prev_mvp: [[f32; 4]; 4],
+    light_dir: [f32; 4],
+    scene: u32,
+    lighting_mode: u32,
+    light_intensity: f32,
+    time: f32,
+}
+
+/// Fullscreen raymarching pass: writes color, motion and depth into the model's render pass
+pub(super) struct SdfPass {
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl SdfPass {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("SDF Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/sdf.wgsl").into()),
+        });
+
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("SDF Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("SDF Uniform Buffer"),
+            size: std::mem::size_of::<SdfUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("SDF Bind Group"),
+            layout: &layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("SDF Pipeline Layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("SDF Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(MOTION_TARGET),
+                    Some(NO_PART_ID_TARGET),
+                ],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            // Same depth target as the mesh pipeline, so edge detection sees the raymarched surface
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            uniform_buffer,
+            bind_group,
+        }
+    }
+
+    /// Upload the scene, transforms and lighting for the next draw
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_uniforms(
+        &self,
+        queue: &wgpu::Queue,
+        scene: SdfScene,
+        model: Mat4,
+        view_proj: Mat4,
+        previous_mvp: Mat4,
+        lighting: LightingMode,
+        light_intensity: f32,
+        time: f32,
+    ) {
+        let uniforms = SdfUniforms {
+            inv_view_proj: view_proj.inverse().to_cols_array_2d(),
+            view_proj: view_proj.to_cols_array_2d(),
+            model: model.to_cols_array_2d(),
+            inv_model: model.inverse().to_cols_array_2d(),
+            prev_mvp: previous_mvp.to_cols_array_2d(),
+            light_dir: LIGHT_DIR,
+            scene: scene.to_u32(),
+            lighting_mode: lighting.to_u32(),
+            light_intensity,
+            time,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+    }
+
+    /// Raymarch the scene over the whole render target
+    pub fn draw(&self, pass: &mut wgpu::RenderPass) {
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
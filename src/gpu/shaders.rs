@@ -0,0 +1,99 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// File names of the ASCII pipeline shaders, as found in the shader directory
+const EDGE_DETECT_FILE: &str = "edge_detect.wgsl";
+const SOBEL_EDGES_FILE: &str = "sobel_edges.wgsl";
+const ASCII_EDGES_FILE: &str = "ascii_edges.wgsl";
+
+/// How often the shader directory is checked for edits
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// WGSL sources for the three ASCII pipeline passes
+#[derive(Clone)]
+pub struct AsciiShaders {
+    pub edge_detect: String,
+    pub sobel_edges: String,
+    pub ascii_edges: String,
+}
+
+impl AsciiShaders {
+    /// Sources compiled into the binary
+    pub fn builtin() -> Self {
+        Self {
+            edge_detect: include_str!("../../shaders/edge_detect.wgsl").to_string(),
+            sobel_edges: include_str!("../../shaders/sobel_edges.wgsl").to_string(),
+            ascii_edges: include_str!("../../shaders/ascii_edges.wgsl").to_string(),
+        }
+    }
+
+    /// Read sources from a directory, using the built-in copy for any missing file
+    pub fn load(dir: &Path) -> Result<Self> {
+        let builtin = Self::builtin();
+        Ok(Self {
+            edge_detect: read_or(dir, EDGE_DETECT_FILE, builtin.edge_detect)?,
+            sobel_edges: read_or(dir, SOBEL_EDGES_FILE, builtin.sobel_edges)?,
+            ascii_edges: read_or(dir, ASCII_EDGES_FILE, builtin.ascii_edges)?,
+        })
+    }
+
+    /// Replace the final ASCII selection pass with a style shader, if one is given
+    pub fn with_style(mut self, style: Option<&str>) -> Self {
+        if let Some(source) = style {
+            self.ascii_edges = source.to_string();
+        }
+        self
+    }
+}
+
+fn read_or(dir: &Path, file: &str, fallback: String) -> Result<String> {
+    let path = dir.join(file);
+    if !path.exists() {
+        return Ok(fallback);
+    }
+    std::fs::read_to_string(&path).with_context(|| format!("Failed to read shader {:?}", path))
+}
+
+/// Polls a shader directory for edits to the ASCII pipeline shaders
+pub struct ShaderWatcher {
+    dir: PathBuf,
+    stamps: Vec<Option<SystemTime>>,
+    last_poll: Option<Instant>,
+}
+
+impl ShaderWatcher {
+    pub fn new(dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            return Err(anyhow!("Shader directory {:?} does not exist", dir));
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            stamps: Vec::new(),
+            last_poll: None,
+        })
+    }
+
+    /// Reload the sources if any file changed since the last call
+    /// The first call always reports a change so the on-disk shaders replace the built-in ones
+    pub fn poll(&mut self) -> Option<Result<AsciiShaders>> {
+        if self.last_poll.is_some_and(|t| t.elapsed() < POLL_INTERVAL) {
+            return None;
+        }
+        self.last_poll = Some(Instant::now());
+
+        let stamps: Vec<Option<SystemTime>> = [EDGE_DETECT_FILE, SOBEL_EDGES_FILE, ASCII_EDGES_FILE]
+            .iter()
+            .map(|file| {
+                std::fs::metadata(self.dir.join(file))
+                    .and_then(|m| m.modified())
+                    .ok()
+            })
+            .collect();
+        if stamps == self.stamps {
+            return None;
+        }
+        self.stamps = stamps;
+        Some(AsciiShaders::load(&self.dir))
+    }
+}
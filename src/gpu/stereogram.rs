@@ -0,0 +1,254 @@
+use anyhow::Result;
+use bytemuck::{Pod, Zeroable};
+
+use super::pipeline::CELL_WORKGROUP_SIZE;
+
+/// Uniforms for the stereogram depth pass
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct StereogramUniforms {
+    tex_width: u32,
+    tex_height: u32,
+    cols: u32,
+    rows: u32,
+    depth_near: f32,
+    depth_far: f32,
+    clip_near: f32,
+    clip_far: f32,
+}
+
+/// Character indices the random pattern is drawn from (the fill ramp past space, and the edges)
+const PATTERN_CHARS: u32 = 13;
+
+/// Pattern color (neutral grey, so color gives nothing away)
+const PATTERN_RGB: u32 = 0xC0C0C0;
+
+/// Repeat width of the pattern in cells, as a fraction of the grid and within limits
+const PERIOD_FRACTION: u32 = 8;
+const MIN_PERIOD: u32 = 8;
+const MAX_PERIOD: u32 = 24;
+
+/// Fraction of the period the nearest surface shortens it by (more depth, harder to fuse)
+const MAX_SHIFT: f32 = 0.3;
+
+/// Converts the depth buffer into a single-image random-character stereogram: the GPU reduces
+/// depth to per-cell nearness, then each row links the cells a viewer's eyes converge on and
+/// repeats random characters along those links
+pub struct StereogramPass {
+    cols: u32,
+    rows: u32,
+    pipeline: wgpu::ComputePipeline,
+    layout: wgpu::BindGroupLayout,
+    uniform_buf: wgpu::Buffer,
+    output_buffer: wgpu::Buffer,
+    staging_buffer: wgpu::Buffer,
+}
+
+impl StereogramPass {
+    pub fn new(device: &wgpu::Device, cols: u32, rows: u32) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Stereogram Layout"),
+            entries: &[
+                // Depth input
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Uniforms
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Output nearness
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Stereogram Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/stereogram.wgsl").into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Stereogram Pipeline Layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Stereogram Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let uniform_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Stereogram Uniforms"),
+            size: std::mem::size_of::<StereogramUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let (output_buffer, staging_buffer) = Self::create_buffers(device, cols, rows);
+
+        Self {
+            cols,
+            rows,
+            pipeline,
+            layout,
+            uniform_buf,
+            output_buffer,
+            staging_buffer,
+        }
+    }
+
+    fn create_buffers(device: &wgpu::Device, cols: u32, rows: u32) -> (wgpu::Buffer, wgpu::Buffer) {
+        let size = (cols * rows * 4) as u64;
+        let output = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Stereogram Output Buffer"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Stereogram Staging Buffer"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        (output, staging)
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, cols: u32, rows: u32) {
+        if (cols, rows) != (self.cols, self.rows) {
+            (self.output_buffer, self.staging_buffer) = Self::create_buffers(device, cols, rows);
+            self.cols = cols;
+            self.rows = rows;
+        }
+    }
+
+    /// Read per-cell nearness from `depth_view` (of the given size, rendered with the near and far
+    /// planes `clip_planes`, and with `depth_window` the view distances mapped to near and far) and
+    /// lay out the stereogram as packed cells
+    pub fn run(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        depth_view: &wgpu::TextureView,
+        tex_size: (u32, u32),
+        clip_planes: (f32, f32),
+        depth_window: (f32, f32),
+    ) -> Result<Vec<u32>> {
+        let uniforms = StereogramUniforms {
+            tex_width: tex_size.0,
+            tex_height: tex_size.1,
+            cols: self.cols,
+            rows: self.rows,
+            depth_near: depth_window.0,
+            depth_far: depth_window.1.max(depth_window.0 + 1e-3),
+            clip_near: clip_planes.0,
+            clip_far: clip_planes.1,
+        };
+        queue.write_buffer(&self.uniform_buf, 0, bytemuck::cast_slice(&[uniforms]));
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Stereogram Bind Group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(depth_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: self.uniform_buf.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: self.output_buffer.as_entire_binding() },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Stereogram Encoder"),
+        });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Stereogram Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(self.cols.div_ceil(CELL_WORKGROUP_SIZE), self.rows.div_ceil(CELL_WORKGROUP_SIZE), 1);
+        }
+        let size = (self.cols * self.rows * 4) as u64;
+        encoder.copy_buffer_to_buffer(&self.output_buffer, 0, &self.staging_buffer, 0, size);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let buffer_slice = self.staging_buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            tx.send(result).unwrap();
+        });
+        device.poll(wgpu::Maintain::Wait);
+        rx.recv()??;
+
+        let data = buffer_slice.get_mapped_range();
+        let nearness: Vec<u32> = bytemuck::cast_slice(&data).to_vec();
+        drop(data);
+        self.staging_buffer.unmap();
+        Ok(layout_stereogram(&nearness, self.cols as usize, self.rows as usize))
+    }
+}
+
+/// Random-dot stereogram layout (Thimbleby, Inglis and Witten) with characters for dots: each
+/// cell is tied to the one a surface at its nearness puts under the other eye, and tied cells
+/// repeat the same character
+fn layout_stereogram(nearness: &[u32], cols: usize, rows: usize) -> Vec<u32> {
+    let period = (cols as u32 / PERIOD_FRACTION).clamp(MIN_PERIOD, MAX_PERIOD) as f32;
+    let mut cells = vec![0; cols * rows];
+    let mut same = vec![0; cols];
+    for row in 0..rows {
+        let depths = &nearness[row * cols..(row + 1) * cols];
+        for (x, link) in same.iter_mut().enumerate() {
+            *link = x;
+        }
+        for (x, &depth) in depths.iter().enumerate() {
+            // Nearer surfaces converge closer together
+            let separation = (period * (1.0 - MAX_SHIFT * depth as f32 / 255.0)).round() as usize;
+            let left = x as isize - (separation / 2) as isize;
+            let right = left + separation as isize;
+            if left >= 0 && (right as usize) < cols {
+                same[right as usize] = left as usize;
+            }
+        }
+
+        // Left to right, so each linked cell copies one already filled in
+        let out = &mut cells[row * cols..(row + 1) * cols];
+        for x in 0..cols {
+            out[x] = if same[x] == x { (PATTERN_RGB << 8) | (1 + pattern_hash(row, x) % PATTERN_CHARS) } else { out[same[x]] };
+        }
+    }
+    cells
+}
+
+/// Stable pseudo-random value for a cell, so the pattern holds still between frames
+fn pattern_hash(row: usize, x: usize) -> u32 {
+    let mut h = (row as u32).wrapping_mul(0x9e37_79b9) ^ (x as u32).wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h
+}
@@ -0,0 +1,218 @@
+use bytemuck::{Pod, Zeroable};
+
+/// Grid points along each side of the water surface
+pub const WAVE_GRID: u32 = 96;
+
+/// Half the width of the surface (matches the 1.6 unit size models are normalized to)
+pub const WAVE_HALF_SIZE: f32 = 0.8;
+
+/// Tilt about X (radians) tipping the far edge of the surface up towards the camera
+pub const WAVE_TILT: f32 = 0.7;
+
+/// Simulation steps per second of animation time, and the most run for one frame (after a stall)
+const STEP_RATE: f32 = 60.0;
+const MAX_STEPS_PER_FRAME: u32 = 8;
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct WaveUniforms {
+    grid: u32,
+    step: u32,
+    half_size: f32,
+    tilt: f32,
+}
+
+/// Water ripple simulation stepped by compute shaders, which write the heights and normals
+/// straight into the model's vertex buffer
+pub(super) struct WavePass {
+    step_pipeline: wgpu::ComputePipeline,
+    mesh_pipeline: wgpu::ComputePipeline,
+    layout: wgpu::BindGroupLayout,
+    uniform_buffer: wgpu::Buffer,
+    /// Heights two steps back, one step back, and next (rotated after each step)
+    heights: [wgpu::Buffer; 3],
+    /// Steps run since the last reset
+    steps: u32,
+    /// Whether the surface has been written into the vertex buffer since the last reset
+    drawn: bool,
+}
+
+impl WavePass {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Wave Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/wave.wgsl").into()),
+        });
+
+        let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Wave Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage(1, true),
+                storage(2, true),
+                storage(3, false),
+                storage(4, false),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Wave Pipeline Layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry_point, label| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: Some(entry_point),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        };
+        let step_pipeline = pipeline("step_main", "Wave Step Pipeline");
+        let mesh_pipeline = pipeline("mesh_main", "Wave Mesh Pipeline");
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Wave Uniform Buffer"),
+            size: std::mem::size_of::<WaveUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let heights = std::array::from_fn(|_| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Wave Height Buffer"),
+                size: (WAVE_GRID * WAVE_GRID) as u64 * 4,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+
+        Self {
+            step_pipeline,
+            mesh_pipeline,
+            layout,
+            uniform_buffer,
+            heights,
+            steps: 0,
+            drawn: false,
+        }
+    }
+
+    /// Vertices the surface mesh uses (front and back faces), and so the vertex buffer needs
+    pub fn vertex_count() -> u64 {
+        2 * (WAVE_GRID * WAVE_GRID) as u64
+    }
+
+    /// Calm the water and restart the raindrops from the first one
+    pub fn reset(&mut self, queue: &wgpu::Queue) {
+        let still = vec![0.0f32; (WAVE_GRID * WAVE_GRID) as usize];
+        for buffer in &self.heights {
+            queue.write_buffer(buffer, 0, bytemuck::cast_slice(&still));
+        }
+        self.steps = 0;
+        self.drawn = false;
+    }
+
+    /// Run the steps due by animation time `time` (a few at most, after a stall), then rewrite
+    /// the surface vertices in `vertex_buffer`
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, vertex_buffer: &wgpu::Buffer, time: f32) {
+        let due = (time.max(0.0) * STEP_RATE) as u32;
+        if due < self.steps {
+            // The clock went back (a loop capture restarting): start over
+            self.reset(queue);
+        }
+        let count = due.saturating_sub(self.steps).min(MAX_STEPS_PER_FRAME);
+        if count == 0 && self.drawn {
+            return;
+        }
+        // Skip ahead rather than simulating a long gap step by step
+        self.steps = self.steps.max(due.saturating_sub(MAX_STEPS_PER_FRAME));
+
+        let groups = WAVE_GRID.div_ceil(8);
+        let dispatch = |pipeline: &wgpu::ComputePipeline, bind_group: &wgpu::BindGroup, label| {
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some(label) });
+            {
+                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: Some(label),
+                    timestamp_writes: None,
+                });
+                pass.set_pipeline(pipeline);
+                pass.set_bind_group(0, bind_group, &[]);
+                pass.dispatch_workgroups(groups, groups, 1);
+            }
+            queue.submit(std::iter::once(encoder.finish()));
+        };
+
+        let write_uniforms = |step| {
+            let uniforms = WaveUniforms {
+                grid: WAVE_GRID,
+                step,
+                half_size: WAVE_HALF_SIZE,
+                tilt: WAVE_TILT,
+            };
+            queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        };
+
+        // Each step has its own uniforms, so each goes in its own submission
+        for _ in 0..count {
+            write_uniforms(self.steps);
+            dispatch(&self.step_pipeline, &self.bind_group(device, vertex_buffer), "Wave Step Pass");
+            self.heights.rotate_left(1);
+            self.steps += 1;
+        }
+        // The newest heights are now the "current" ones the mesh pass reads
+        write_uniforms(self.steps);
+        dispatch(&self.mesh_pipeline, &self.bind_group(device, vertex_buffer), "Wave Mesh Pass");
+        self.drawn = true;
+    }
+
+    /// The height buffers in their current roles, and the vertex buffer (which may have been
+    /// reallocated since the last frame)
+    fn bind_group(&self, device: &wgpu::Device, vertex_buffer: &wgpu::Buffer) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Wave Bind Group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.heights[0].as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.heights[1].as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.heights[2].as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: vertex_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+}
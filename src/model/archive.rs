@@ -0,0 +1,139 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read};
+use std::path::Path;
+use zip::ZipArchive;
+
+use super::loader::{gltf_model_data, obj_load_options, obj_model_data, LoadOptions, ModelData, Progress, SUPPORTED_EXTENSIONS};
+
+/// Path inside a zip archive of the model to load
+/// glTF/GLB is preferred over OBJ, then the shallowest entry (packages like Sketchfab
+/// downloads keep `scene.gltf` at the top and textures in subfolders)
+pub fn find_model(path: &Path) -> Result<String> {
+    let archive = open(path)?;
+    let rank = |name: &str| {
+        let ext = extension(name);
+        let format = if ext == "obj" { 1 } else { 0 };
+        (format, name.matches('/').count(), name.to_string())
+    };
+    archive
+        .file_names()
+        .filter(|name| SUPPORTED_EXTENSIONS.contains(&extension(name).as_str()))
+        .min_by_key(|name| rank(name))
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("No OBJ or glTF model in {}", path.display()))
+}
+
+/// Load the model inside a zip archive without extracting it
+pub fn load_zip(path: &Path, options: &LoadOptions, progress: &Progress) -> Result<ModelData> {
+    let entry = find_model(path)?;
+    let mut archive = open(path)?;
+    let bytes = read_entry(&mut archive, &entry)?;
+    match extension(&entry).as_str() {
+        "obj" => load_obj(&mut archive, &entry, &bytes, options, progress),
+        _ => load_gltf(&mut archive, &entry, &bytes, options, progress),
+    }
+    .with_context(|| format!("Failed to load {} from the archive", entry))
+}
+
+fn open(path: &Path) -> Result<ZipArchive<BufReader<File>>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    ZipArchive::new(BufReader::new(file)).with_context(|| format!("Invalid zip archive {}", path.display()))
+}
+
+fn extension(name: &str) -> String {
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+fn read_entry(archive: &mut ZipArchive<BufReader<File>>, name: &str) -> Result<Vec<u8>> {
+    let mut file = archive
+        .by_name(name)
+        .with_context(|| format!("{} is missing from the archive", name))?;
+    let mut bytes = Vec::with_capacity(file.size() as usize);
+    file.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Archive path of a file referenced relative to `entry` (e.g. "scene.bin" next to "model/scene.gltf")
+fn sibling(entry: &str, relative: &str) -> String {
+    let relative = relative.replace("%20", " ");
+    let mut parts: Vec<&str> = entry.split('/').collect();
+    parts.pop();
+    for part in relative.split('/') {
+        match part {
+            "." | "" => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+fn load_obj(
+    archive: &mut ZipArchive<BufReader<File>>,
+    entry: &str,
+    bytes: &[u8],
+    options: &LoadOptions,
+    progress: &Progress,
+) -> Result<ModelData> {
+    // The material loader can't borrow the archive, so material libraries are read up front
+    let mut libraries = HashMap::new();
+    let names: Vec<String> = archive
+        .file_names()
+        .filter(|name| extension(name) == "mtl")
+        .map(str::to_string)
+        .collect();
+    for name in names {
+        let data = read_entry(archive, &name)?;
+        libraries.insert(name, data);
+    }
+
+    let (models, materials) = tobj::load_obj_buf(&mut Cursor::new(bytes), &obj_load_options(), |mtl| {
+        let name = sibling(entry, &mtl.to_string_lossy());
+        match libraries.get(&name) {
+            Some(data) => tobj::load_mtl_buf(&mut Cursor::new(data)),
+            None => Err(tobj::LoadError::OpenFileFailed),
+        }
+    })?;
+    obj_model_data(models, materials.ok().unwrap_or_default(), options, progress)
+}
+
+fn load_gltf(
+    archive: &mut ZipArchive<BufReader<File>>,
+    entry: &str,
+    bytes: &[u8],
+    options: &LoadOptions,
+    progress: &Progress,
+) -> Result<ModelData> {
+    let gltf::Gltf { document, mut blob } = gltf::Gltf::from_slice(bytes)?;
+
+    let external = |uri: &str| !uri.starts_with("data:");
+    let has_external = document
+        .buffers()
+        .any(|b| matches!(b.source(), gltf::buffer::Source::Uri(uri) if external(uri)));
+    let buffers = if has_external {
+        // Buffer files (e.g. scene.bin) are read from next to the glTF in the archive
+        let mut buffers = Vec::new();
+        for buffer in document.buffers() {
+            let data = match buffer.source() {
+                gltf::buffer::Source::Bin => blob.take().ok_or_else(|| anyhow!("Missing binary glTF chunk"))?,
+                gltf::buffer::Source::Uri(uri) if external(uri) => read_entry(archive, &sibling(entry, uri))?,
+                gltf::buffer::Source::Uri(_) => {
+                    return Err(anyhow!("glTF mixing data URIs with buffer files is not supported in archives"))
+                }
+            };
+            buffers.push(gltf::buffer::Data(data));
+        }
+        buffers
+    } else {
+        gltf::import_buffers(&document, None, blob)?
+    };
+    gltf_model_data(&document, &buffers, options, progress)
+}
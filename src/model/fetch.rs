@@ -0,0 +1,109 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::loader::SUPPORTED_EXTENSIONS;
+
+/// Directory downloaded models are cached in (`~/.cache/ascii-3d/models` on Linux)
+fn cache_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library").join("Caches"))
+    } else {
+        std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    };
+    base.map(|dir| dir.join("ascii-3d").join("models"))
+}
+
+/// FNV-1a, so a URL maps to the same cache file in every build
+fn url_hash(url: &str) -> u64 {
+    url.bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// GitHub file pages (`github.com/USER/REPO/blob/BRANCH/PATH`) serve HTML; fetch the raw file instead
+fn raw_url(url: &str) -> String {
+    if let Some(rest) = url.strip_prefix("https://github.com/") {
+        let parts: Vec<&str> = rest.splitn(4, '/').collect();
+        if let [user, repo, "blob", path] = parts[..] {
+            return format!("https://raw.githubusercontent.com/{}/{}/{}", user, repo, path);
+        }
+    }
+    url.to_string()
+}
+
+/// Last path segment of a URL, without the query or fragment
+pub fn url_file_name(url: &str) -> Option<&str> {
+    url.split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .filter(|name| !name.is_empty())
+}
+
+/// Download a glTF/GLB/OBJ file or a zip containing one with `curl`, returning the cached file
+/// A URL already in the cache is not downloaded again
+pub fn fetch_model(url: &str) -> Result<PathBuf> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(anyhow!("Model URL must start with http:// or https://, got {:?}", url));
+    }
+    let url = raw_url(url);
+
+    // Name the cache file after the URL's last path segment, which must say what format it is
+    let name = url_file_name(&url).unwrap_or("model");
+    let ext = name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+    if !(SUPPORTED_EXTENSIONS.contains(&ext.as_str()) || ext == "zip") {
+        return Err(anyhow!("Model URL must name a .glb, .gltf, .obj, or .zip file, got {:?}", name));
+    }
+
+    let dir = cache_dir().ok_or_else(|| anyhow!("No cache directory (HOME is not set)"))?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("{:016x}-{}", url_hash(&url), name));
+    if path.is_file() {
+        return Ok(path);
+    }
+
+    download(&url, &path)?;
+    Ok(path)
+}
+
+/// Download `url` to `path` with `curl`, showing its progress bar
+/// The transfer goes to a `.part` file next to `path`, so an interrupted one is never taken for the
+/// finished file
+pub fn download(url: &str, path: &Path) -> Result<()> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+    eprintln!("Downloading {}", url);
+    let status = Command::new("curl")
+        .args(["--fail", "--location", "--progress-bar", "--output"])
+        .arg(&partial)
+        .arg(url)
+        .status()
+        .context("Failed to start curl (downloads need curl on the PATH)")?;
+    if !status.success() {
+        let _ = std::fs::remove_file(&partial);
+        return Err(anyhow!("Download of {} failed ({})", url, status));
+    }
+    std::fs::rename(&partial, path).with_context(|| format!("Failed to move the download to {}", path.display()))
+}
+
+/// Fetch a small text file (a manifest) with `curl`
+pub fn fetch_text(url: &str) -> Result<String> {
+    let url = raw_url(url);
+    let output = Command::new("curl")
+        .args(["--fail", "--location", "--silent", "--show-error"])
+        .arg(&url)
+        .output()
+        .context("Failed to start curl (downloads need curl on the PATH)")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Download of {} failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout).with_context(|| format!("{} is not UTF-8 text", url))
+}
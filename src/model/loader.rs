@@ -1,25 +1,141 @@
 use anyhow::{anyhow, Result};
+use rayon::prelude::*;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
+use super::{archive, occlusion};
 // Use Vertex from the gpu module
-use crate::gpu::Vertex;
+use crate::gpu::{LabelAnchor, Material, MeshRange, Vertex};
 
-const SUPPORTED_EXTENSIONS: &[&str] = &["obj", "gltf", "glb"];
+/// Model formats that can be loaded directly or from inside a zip archive
+pub(super) const SUPPORTED_EXTENSIONS: &[&str] = &["obj", "gltf", "glb"];
+
+/// Vertices per parallel work item in model post-processing (and per progress update)
+const CHUNK_SIZE: usize = 1 << 16;
+
+/// Receives the current loading stage and its completion (0 to 1); called from worker threads
+pub type Progress = dyn Fn(&'static str, f32) + Sync;
+
+/// Progress callback that ignores updates
+pub fn no_progress(_stage: &'static str, _done: f32) {}
+
+/// Welding tolerance on positions, relative to the model's largest dimension
+const WELD_POSITION_EPSILON: f32 = 1e-5;
+
+/// Welding tolerance on each normal component
+const WELD_NORMAL_EPSILON: f32 = 1e-3;
+
+/// How a model file is post-processed after parsing
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LoadOptions {
+    /// Merge vertices with matching position, normal and color
+    pub weld: bool,
+    /// Darken vertex colors by baked ambient occlusion (model files only, cached beside the file)
+    pub ambient_occlusion: bool,
+    /// Generated normals stay sharp across edges steeper than this many degrees
+    /// (None = smooth across every shared vertex)
+    pub crease_angle: Option<f32>,
+}
 
 pub struct ModelData {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
+    /// Index range of each sub-mesh, in file order
+    pub ranges: Vec<MeshRange>,
+    /// Display name of each sub-mesh, parallel to `ranges`
+    pub part_names: Vec<String>,
+    /// Bounding box extents in the file's own units (before normalization)
+    pub source_size: [f32; 3],
+    /// Bounding sphere radius around the origin in normalized units (what the camera is fitted to)
+    pub radius: f32,
+    /// Vertex count before welding (None if the model wasn't welded)
+    pub welded_from: Option<usize>,
+}
+
+/// Summary statistics for a loaded model (shown in the info panel)
+#[derive(Clone, Copy, Debug)]
+pub struct ModelStats {
+    pub vertices: usize,
+    pub triangles: usize,
+    pub size: [f32; 3],
+    pub welded_from: Option<usize>,
+}
+
+impl ModelData {
+    /// Normalize raw geometry to fit the view and compute sub-mesh centroids
+    pub fn from_parts(vertices: Vec<Vertex>, indices: Vec<u32>, ranges: Vec<MeshRange>) -> Self {
+        Self::from_parts_with_progress(vertices, indices, ranges, &no_progress)
+    }
+
+    /// `from_parts`, reporting normalization progress
+    pub fn from_parts_with_progress(
+        mut vertices: Vec<Vertex>,
+        indices: Vec<u32>,
+        mut ranges: Vec<MeshRange>,
+        progress: &Progress,
+    ) -> Self {
+        let source_size = normalize_model(&mut vertices, progress);
+        compute_range_centroids(&vertices, &indices, &mut ranges);
+        let part_names = (1..=ranges.len()).map(|i| format!("Part {}", i)).collect();
+        let radius = bounding_radius(&vertices);
+        Self {
+            vertices,
+            indices,
+            ranges,
+            part_names,
+            source_size,
+            radius,
+            welded_from: None,
+        }
+    }
+
+    /// Geometry already centered and sized for the view (scenes whose scale must not follow their
+    /// changing bounds); only sub-mesh centroids are computed
+    pub fn from_normalized_parts(vertices: Vec<Vertex>, indices: Vec<u32>, mut ranges: Vec<MeshRange>, source_size: [f32; 3]) -> Self {
+        compute_range_centroids(&vertices, &indices, &mut ranges);
+        let part_names = (1..=ranges.len()).map(|i| format!("Part {}", i)).collect();
+        let radius = bounding_radius(&vertices);
+        Self {
+            vertices,
+            indices,
+            ranges,
+            part_names,
+            source_size,
+            radius,
+            welded_from: None,
+        }
+    }
+
+    pub fn stats(&self) -> ModelStats {
+        ModelStats {
+            vertices: self.vertices.len(),
+            triangles: self.indices.len() / 3,
+            size: self.source_size,
+            welded_from: self.welded_from,
+        }
+    }
+
+    /// A label for each part, named as in the parts list and attached to the middle of its bounds
+    pub fn part_labels(&self) -> Vec<(String, LabelAnchor)> {
+        self.part_names
+            .iter()
+            .zip(&self.ranges)
+            .enumerate()
+            .map(|(i, (name, range))| (name.clone(), LabelAnchor { point: range.centroid.into(), radius: range.extent, part: Some(i) }))
+            .collect()
+    }
 }
 
 /// Discover all supported model files in a directory (including subdirectories)
 pub fn discover_models(dir: &Path) -> Vec<PathBuf> {
     let mut models = Vec::new();
-    discover_models_recursive(dir, dir, &mut models);
-    models.sort_by(|a, b| get_model_display_name(a).cmp(&get_model_display_name(b)));
+    discover_models_recursive(dir, &mut models);
+    models.sort_by_key(|p| get_model_display_name(p));
     models
 }
 
-fn discover_models_recursive(base_dir: &Path, dir: &Path, models: &mut Vec<PathBuf>) {
+fn discover_models_recursive(dir: &Path, models: &mut Vec<PathBuf>) {
     let Ok(entries) = std::fs::read_dir(dir) else {
         return;
     };
@@ -28,10 +144,14 @@ fn discover_models_recursive(base_dir: &Path, dir: &Path, models: &mut Vec<PathB
         let path = entry.path();
         if path.is_dir() {
             // Recurse into subdirectories
-            discover_models_recursive(base_dir, &path, models);
+            discover_models_recursive(&path, models);
         } else if path.is_file() {
             if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                if SUPPORTED_EXTENSIONS.contains(&ext.to_lowercase().as_str()) {
+                let ext = ext.to_lowercase();
+                // Archives are listed only if there is a model inside
+                if SUPPORTED_EXTENSIONS.contains(&ext.as_str())
+                    || (ext == "zip" && archive::find_model(&path).is_ok())
+                {
                     models.push(path);
                 }
             }
@@ -73,33 +193,51 @@ pub fn get_model_display_name(path: &Path) -> String {
 }
 
 /// Load a model from file, dispatching based on extension
-pub fn load_model(path: &Path) -> Result<ModelData> {
-    match path.extension().and_then(|e| e.to_str()).map(|s| s.to_lowercase()) {
-        Some(ext) if ext == "obj" => load_obj(path),
-        Some(ext) if ext == "gltf" || ext == "glb" => load_gltf(path),
-        _ => Err(anyhow!("Unsupported model format: {:?}", path)),
+/// `progress` receives the stage and completion of post-processing large meshes
+pub fn load_model(path: &Path, options: &LoadOptions, progress: &Progress) -> Result<ModelData> {
+    progress("Reading", 0.0);
+    let mut model = match path.extension().and_then(|e| e.to_str()).map(|s| s.to_lowercase()) {
+        Some(ext) if ext == "obj" => load_obj(path, options, progress)?,
+        Some(ext) if ext == "gltf" || ext == "glb" => load_gltf(path, options, progress)?,
+        Some(ext) if ext == "zip" => archive::load_zip(path, options, progress)?,
+        _ => return Err(anyhow!("Unsupported model format: {:?}", path)),
+    };
+    if options.ambient_occlusion {
+        occlusion::bake_ambient_occlusion(&mut model, path, progress);
     }
+    Ok(model)
 }
 
 /// Load an OBJ file using tobj
-fn load_obj(path: &Path) -> Result<ModelData> {
-    let load_options = tobj::LoadOptions {
+fn load_obj(path: &Path, options: &LoadOptions, progress: &Progress) -> Result<ModelData> {
+    let (models, materials_result) = tobj::load_obj(path, &obj_load_options())?;
+    obj_model_data(models, materials_result.ok().unwrap_or_default(), options, progress)
+}
+
+/// Options for loading OBJ files (from disk or an archive)
+pub(super) fn obj_load_options() -> tobj::LoadOptions {
+    tobj::LoadOptions {
         triangulate: true,
         single_index: true,
         ..Default::default()
-    };
-
-    let (models, materials_result) = tobj::load_obj(path, &load_options)?;
+    }
+}
 
+/// Convert parsed OBJ meshes and their materials to model data
+pub(super) fn obj_model_data(
+    models: Vec<tobj::Model>,
+    materials: Vec<tobj::Material>,
+    options: &LoadOptions,
+    progress: &Progress,
+) -> Result<ModelData> {
     if models.is_empty() {
         return Err(anyhow!("No meshes found in OBJ file"));
     }
 
-    // Get materials if available
-    let materials = materials_result.ok().unwrap_or_default();
-
     let mut all_vertices = Vec::new();
     let mut all_indices = Vec::new();
+    let mut ranges = Vec::new();
+    let mut part_names = Vec::new();
 
     for model in &models {
         let mesh = &model.mesh;
@@ -115,6 +253,8 @@ fn load_obj(path: &Path) -> Result<ModelData> {
         // Process vertices
         let num_vertices = mesh.positions.len() / 3;
         let has_normals = !mesh.normals.is_empty();
+        // Vertex colors replace the material color rather than tinting it
+        let has_colors = mesh.vertex_color.len() >= num_vertices * 3 && num_vertices > 0;
 
         for i in 0..num_vertices {
             let px = mesh.positions[i * 3];
@@ -131,15 +271,15 @@ fn load_obj(path: &Path) -> Result<ModelData> {
                 (0.0, 1.0, 0.0) // Default up normal, will compute later if needed
             };
 
-            // Use vertex colors if available, otherwise material color
-            let color = if !mesh.vertex_color.is_empty() && mesh.vertex_color.len() > i * 3 + 2 {
+            // Use vertex colors if available, otherwise the sub-mesh material colors it
+            let color = if has_colors {
                 [
                     mesh.vertex_color[i * 3],
                     mesh.vertex_color[i * 3 + 1],
                     mesh.vertex_color[i * 3 + 2],
                 ]
             } else {
-                material_color
+                [1.0; 3]
             };
 
             all_vertices.push(Vertex {
@@ -150,33 +290,52 @@ fn load_obj(path: &Path) -> Result<ModelData> {
         }
 
         // Process indices
+        let first_index = all_indices.len() as u32;
         for &idx in &mesh.indices {
             all_indices.push(base_index + idx);
         }
+        ranges.push(MeshRange {
+            first_index,
+            index_count: all_indices.len() as u32 - first_index,
+            centroid: [0.0; 3],
+            extent: 0.0,
+            material: Material {
+                base_color: if has_colors { [1.0; 3] } else { material_color },
+            },
+        });
+        part_names.push(if model.name.is_empty() {
+            format!("Part {}", ranges.len())
+        } else {
+            model.name.clone()
+        });
     }
 
     // Compute normals if not provided
-    if models.iter().all(|m| m.mesh.normals.is_empty()) {
-        compute_normals(&mut all_vertices, &all_indices);
-    }
-
-    // Normalize model to fit in view
-    normalize_model(&mut all_vertices);
-
-    Ok(ModelData {
-        vertices: all_vertices,
-        indices: all_indices,
-    })
+    let needs_normals = models.iter().all(|m| m.mesh.normals.is_empty());
+    Ok(finish_model(all_vertices, all_indices, ranges, part_names, needs_normals, options, progress))
 }
 
 /// Load a glTF/GLB file
-fn load_gltf(path: &Path) -> Result<ModelData> {
+fn load_gltf(path: &Path, options: &LoadOptions, progress: &Progress) -> Result<ModelData> {
     let (document, buffers, _images) = gltf::import(path)?;
+    gltf_model_data(&document, &buffers, options, progress)
+}
 
+/// Convert a glTF document and its buffer data to model data
+pub(super) fn gltf_model_data(
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    options: &LoadOptions,
+    progress: &Progress,
+) -> Result<ModelData> {
     let mut all_vertices = Vec::new();
     let mut all_indices = Vec::new();
+    let mut ranges = Vec::new();
+    let mut part_names = Vec::new();
 
     for mesh in document.meshes() {
+        let mesh_name = mesh.name().map_or_else(|| format!("Mesh {}", mesh.index() + 1), str::to_string);
+        let primitive_count = mesh.primitives().len();
         for primitive in mesh.primitives() {
             let base_index = all_vertices.len() as u32;
 
@@ -206,21 +365,22 @@ fn load_gltf(path: &Path) -> Result<ModelData> {
                 iter.into_rgb_f32().collect()
             });
 
-            // Build vertices
-            for i in 0..positions.len() {
+            // Build vertices (vertex colors are tinted by the material's base color)
+            for (i, &position) in positions.iter().enumerate() {
                 let vertex_color = colors
                     .as_ref()
                     .and_then(|c| c.get(i).copied())
-                    .unwrap_or(color);
+                    .unwrap_or([1.0; 3]);
 
                 all_vertices.push(Vertex {
-                    position: positions[i],
+                    position,
                     normal: normals.get(i).copied().unwrap_or([0.0, 1.0, 0.0]),
                     color: vertex_color,
                 });
             }
 
             // Read indices
+            let first_index = all_indices.len() as u32;
             if let Some(indices) = reader.read_indices() {
                 for idx in indices.into_u32() {
                     all_indices.push(base_index + idx);
@@ -231,6 +391,18 @@ fn load_gltf(path: &Path) -> Result<ModelData> {
                     all_indices.push(base_index + i);
                 }
             }
+            ranges.push(MeshRange {
+                first_index,
+                index_count: all_indices.len() as u32 - first_index,
+                centroid: [0.0; 3],
+                extent: 0.0,
+                material: Material { base_color: color },
+            });
+            part_names.push(if primitive_count > 1 {
+                format!("{} #{}", mesh_name, primitive.index() + 1)
+            } else {
+                mesh_name.clone()
+            });
         }
     }
 
@@ -240,89 +412,340 @@ fn load_gltf(path: &Path) -> Result<ModelData> {
 
     // Compute normals if they were all default
     let needs_normals = all_vertices.iter().all(|v| v.normal == [0.0, 1.0, 0.0]);
+    Ok(finish_model(all_vertices, all_indices, ranges, part_names, needs_normals, options, progress))
+}
+
+/// Shared post-processing for parsed geometry: optional welding, missing normals, normalization
+/// Welding runs first so generated normals are smoothed across the merged vertices
+fn finish_model(
+    mut vertices: Vec<Vertex>,
+    mut indices: Vec<u32>,
+    ranges: Vec<MeshRange>,
+    part_names: Vec<String>,
+    needs_normals: bool,
+    options: &LoadOptions,
+    progress: &Progress,
+) -> ModelData {
+    let welded_from = options.weld.then(|| {
+        let before = vertices.len();
+        vertices = weld_vertices(&vertices, &mut indices, progress);
+        before
+    });
     if needs_normals {
-        compute_normals(&mut all_vertices, &all_indices);
+        match options.crease_angle {
+            Some(angle) => compute_creased_normals(&mut vertices, &mut indices, angle, progress),
+            None => compute_normals(&mut vertices, &indices, progress),
+        }
+    }
+    ModelData {
+        welded_from,
+        part_names,
+        ..ModelData::from_parts_with_progress(vertices, indices, ranges, progress)
     }
+}
 
-    // Normalize model to fit in view
-    normalize_model(&mut all_vertices);
+/// Merge vertices whose positions and normals agree within the weld tolerances and whose
+/// colors match exactly, rewriting `indices` to the merged vertices
+fn weld_vertices(vertices: &[Vertex], indices: &mut [u32], progress: &Progress) -> Vec<Vertex> {
+    const STAGE: &str = "Welding";
+
+    // Position tolerance scales with the model so it works in any units
+    let max_dim = (0..3)
+        .map(|i| {
+            let (min, max) = vertices.iter().fold((f32::MAX, f32::MIN), |(min, max), v| {
+                (min.min(v.position[i]), max.max(v.position[i]))
+            });
+            max - min
+        })
+        .fold(0.0f32, f32::max);
+    let position_step = (max_dim * WELD_POSITION_EPSILON).max(f32::MIN_POSITIVE);
+    let quantize = |value: f32, step: f32| (value / step).round() as i64;
+
+    let mut welded = Vec::new();
+    let mut lookup = HashMap::with_capacity(vertices.len());
+    let mut remap = Vec::with_capacity(vertices.len());
+    for (i, v) in vertices.iter().enumerate() {
+        let key = (
+            v.position.map(|p| quantize(p, position_step)),
+            v.normal.map(|n| quantize(n, WELD_NORMAL_EPSILON)),
+            v.color.map(f32::to_bits),
+        );
+        let index = *lookup.entry(key).or_insert_with(|| {
+            welded.push(*v);
+            welded.len() as u32 - 1
+        });
+        remap.push(index);
+        if (i + 1) % CHUNK_SIZE == 0 {
+            progress(STAGE, (i + 1) as f32 / vertices.len() as f32);
+        }
+    }
 
-    Ok(ModelData {
-        vertices: all_vertices,
-        indices: all_indices,
-    })
+    indices.par_iter_mut().for_each(|index| {
+        if let Some(&merged) = remap.get(*index as usize) {
+            *index = merged;
+        }
+    });
+    welded
 }
 
 /// Compute face normals and assign to vertices
-fn compute_normals(vertices: &mut [Vertex], indices: &[u32]) {
-    // Reset all normals
-    for v in vertices.iter_mut() {
-        v.normal = [0.0, 0.0, 0.0];
-    }
-
-    // Accumulate face normals
-    for tri in indices.chunks(3) {
-        if tri.len() < 3 {
-            continue;
+/// Each vertex sums the normals of its faces in face order, so the result doesn't depend on
+/// how the work is split across threads
+fn compute_normals(vertices: &mut [Vertex], indices: &[u32], progress: &Progress) {
+    const STAGE: &str = "Computing normals";
+    let vertex_count = vertices.len();
+    let face_normals = face_normals(vertices, indices);
+    let (start, faces) = face_adjacency(indices, &face_normals, vertex_count, |i| i as usize);
+
+    // Accumulate and normalize
+    let done = AtomicUsize::new(0);
+    vertices.par_chunks_mut(CHUNK_SIZE).enumerate().for_each(|(chunk, chunk_vertices)| {
+        for (offset, v) in chunk_vertices.iter_mut().enumerate() {
+            let index = chunk * CHUNK_SIZE + offset;
+            v.normal = normalize_or_up(sum_normals(&faces[start[index]..start[index + 1]], &face_normals, |_| true));
         }
-        let i0 = tri[0] as usize;
-        let i1 = tri[1] as usize;
-        let i2 = tri[2] as usize;
+        let finished = done.fetch_add(chunk_vertices.len(), Ordering::Relaxed) + chunk_vertices.len();
+        progress(STAGE, finished as f32 / vertex_count as f32);
+    });
+}
 
-        if i0 >= vertices.len() || i1 >= vertices.len() || i2 >= vertices.len() {
+/// Compute normals that are smooth across faces meeting at less than `crease_angle` degrees and
+/// sharp across steeper edges
+/// Corners at the same position are smoothed together even when they are separate vertices; a
+/// vertex whose corners end up with different normals is split into one vertex per normal
+fn compute_creased_normals(vertices: &mut Vec<Vertex>, indices: &mut [u32], crease_angle: f32, progress: &Progress) {
+    const STAGE: &str = "Computing normals";
+    let min_cos = crease_angle.to_radians().cos();
+    let face_normals = face_normals(vertices, indices);
+
+    // Vertices sharing an exact position form one group
+    let mut groups = HashMap::new();
+    let group_of: Vec<usize> = vertices
+        .iter()
+        .map(|v| {
+            let next = groups.len();
+            *groups.entry(v.position.map(f32::to_bits)).or_insert(next)
+        })
+        .collect();
+    let (start, faces) = face_adjacency(indices, &face_normals, groups.len(), |i| group_of[i as usize]);
+
+    // Each corner sums the faces around its position that are within the crease angle of its own face
+    let unit_normals: Vec<Option<[f32; 3]>> = face_normals
+        .par_iter()
+        .map(|n| {
+            n.and_then(|n| {
+                let len = (n[0].powi(2) + n[1].powi(2) + n[2].powi(2)).sqrt();
+                (len > 0.0).then(|| [n[0] / len, n[1] / len, n[2] / len])
+            })
+        })
+        .collect();
+    let total = face_normals.len();
+    let done = AtomicUsize::new(0);
+    let corner_normals: Vec<[f32; 3]> = indices
+        .par_chunks(3 * CHUNK_SIZE)
+        .enumerate()
+        .flat_map_iter(|(chunk, chunk_indices)| {
+            let corners: Vec<[f32; 3]> = chunk_indices
+                .iter()
+                .enumerate()
+                .map(|(offset, &i)| {
+                    let face = (chunk * 3 * CHUNK_SIZE + offset) / 3;
+                    let Some(&group) = group_of.get(i as usize) else {
+                        return [0.0, 1.0, 0.0];
+                    };
+                    let adjacent = &faces[start[group]..start[group + 1]];
+                    let normal = match unit_normals[face] {
+                        Some(own) => sum_normals(adjacent, &face_normals, |other| {
+                            unit_normals[other].is_some_and(|n| n[0] * own[0] + n[1] * own[1] + n[2] * own[2] >= min_cos)
+                        }),
+                        // Degenerate faces take the fully smoothed normal
+                        None => sum_normals(adjacent, &face_normals, |_| true),
+                    };
+                    normalize_or_up(normal)
+                })
+                .collect();
+            let finished = done.fetch_add(chunk_indices.len() / 3, Ordering::Relaxed) + chunk_indices.len() / 3;
+            progress(STAGE, finished as f32 / total as f32);
+            corners
+        })
+        .collect();
+
+    // First corner to use a vertex sets its normal; corners needing another normal get a copy
+    let mut assigned: Vec<Option<[f32; 3]>> = vec![None; vertices.len()];
+    let mut copies = HashMap::new();
+    for (index, normal) in indices.iter_mut().zip(corner_normals) {
+        let Some(slot) = assigned.get_mut(*index as usize) else {
             continue;
+        };
+        match slot {
+            None => {
+                *slot = Some(normal);
+                vertices[*index as usize].normal = normal;
+            }
+            Some(existing) if *existing == normal => {}
+            Some(_) => {
+                *index = *copies.entry((*index, normal.map(f32::to_bits))).or_insert_with(|| {
+                    vertices.push(Vertex { normal, ..vertices[*index as usize] });
+                    vertices.len() as u32 - 1
+                });
+            }
         }
+    }
+}
 
-        let v0 = vertices[i0].position;
-        let v1 = vertices[i1].position;
-        let v2 = vertices[i2].position;
-
-        // Edge vectors
-        let e1 = [v1[0] - v0[0], v1[1] - v0[1], v1[2] - v0[2]];
-        let e2 = [v2[0] - v0[0], v2[1] - v0[1], v2[2] - v0[2]];
+/// Area-weighted normal of each triangle (cross product of two edges), None for bad triangles
+fn face_normals(vertices: &[Vertex], indices: &[u32]) -> Vec<Option<[f32; 3]>> {
+    let vertex_count = vertices.len();
+    indices
+        .par_chunks(3)
+        .map(|tri| {
+            if tri.len() < 3 || tri.iter().any(|&i| i as usize >= vertex_count) {
+                return None;
+            }
+            let v0 = vertices[tri[0] as usize].position;
+            let v1 = vertices[tri[1] as usize].position;
+            let v2 = vertices[tri[2] as usize].position;
+
+            // Edge vectors
+            let e1 = [v1[0] - v0[0], v1[1] - v0[1], v1[2] - v0[2]];
+            let e2 = [v2[0] - v0[0], v2[1] - v0[1], v2[2] - v0[2]];
+
+            // Cross product
+            Some([
+                e1[1] * e2[2] - e1[2] * e2[1],
+                e1[2] * e2[0] - e1[0] * e2[2],
+                e1[0] * e2[1] - e1[1] * e2[0],
+            ])
+        })
+        .collect()
+}
 
-        // Cross product
-        let nx = e1[1] * e2[2] - e1[2] * e2[1];
-        let ny = e1[2] * e2[0] - e1[0] * e2[2];
-        let nz = e1[0] * e2[1] - e1[1] * e2[0];
+/// Faces touching each group of vertices, in face order
+/// Compressed: group g uses `faces[start[g]..start[g + 1]]`
+fn face_adjacency(
+    indices: &[u32],
+    face_normals: &[Option<[f32; 3]>],
+    group_count: usize,
+    group_of: impl Fn(u32) -> usize,
+) -> (Vec<usize>, Vec<u32>) {
+    let mut start = vec![0usize; group_count + 1];
+    for (face, tri) in indices.chunks(3).enumerate() {
+        if face_normals[face].is_some() {
+            for &i in tri {
+                start[group_of(i) + 1] += 1;
+            }
+        }
+    }
+    for g in 0..group_count {
+        start[g + 1] += start[g];
+    }
+    let mut faces = vec![0u32; start[group_count]];
+    let mut next = start.clone();
+    for (face, tri) in indices.chunks(3).enumerate() {
+        if face_normals[face].is_some() {
+            for &i in tri {
+                let g = group_of(i);
+                faces[next[g]] = face as u32;
+                next[g] += 1;
+            }
+        }
+    }
+    (start, faces)
+}
 
-        // Accumulate (area-weighted)
-        for &i in &[i0, i1, i2] {
-            vertices[i].normal[0] += nx;
-            vertices[i].normal[1] += ny;
-            vertices[i].normal[2] += nz;
+/// Sum of the normals of the listed faces that pass `include`
+fn sum_normals(faces: &[u32], face_normals: &[Option<[f32; 3]>], include: impl Fn(usize) -> bool) -> [f32; 3] {
+    let mut normal = [0.0f32; 3];
+    for &face in faces {
+        if include(face as usize) {
+            let n = face_normals[face as usize].unwrap_or_default();
+            normal[0] += n[0];
+            normal[1] += n[1];
+            normal[2] += n[2];
         }
     }
+    normal
+}
 
-    // Normalize
-    for v in vertices.iter_mut() {
-        let len = (v.normal[0].powi(2) + v.normal[1].powi(2) + v.normal[2].powi(2)).sqrt();
-        if len > 1e-6 {
-            v.normal[0] /= len;
-            v.normal[1] /= len;
-            v.normal[2] /= len;
-        } else {
-            v.normal = [0.0, 1.0, 0.0];
+/// Unit-length copy of a normal, or straight up if it is (nearly) zero
+fn normalize_or_up(normal: [f32; 3]) -> [f32; 3] {
+    let len = (normal[0].powi(2) + normal[1].powi(2) + normal[2].powi(2)).sqrt();
+    if len > 1e-6 {
+        [normal[0] / len, normal[1] / len, normal[2] / len]
+    } else {
+        [0.0, 1.0, 0.0]
+    }
+}
+
+/// Fill in each range's centroid and extent from the bounding box of the vertices it references
+fn compute_range_centroids(vertices: &[Vertex], indices: &[u32], ranges: &mut [MeshRange]) {
+    for range in ranges.iter_mut() {
+        let start = range.first_index as usize;
+        let end = start + range.index_count as usize;
+
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for &idx in &indices[start..end] {
+            let Some(v) = vertices.get(idx as usize) else {
+                continue;
+            };
+            for i in 0..3 {
+                min[i] = min[i].min(v.position[i]);
+                max[i] = max[i].max(v.position[i]);
+            }
+        }
+
+        if min[0] <= max[0] {
+            range.centroid = [
+                (min[0] + max[0]) / 2.0,
+                (min[1] + max[1]) / 2.0,
+                (min[2] + max[2]) / 2.0,
+            ];
+            range.extent = (0..3).map(|i| (max[i] - min[i]).powi(2)).sum::<f32>().sqrt() / 2.0;
         }
     }
 }
 
+/// Distance of the farthest vertex from the origin
+pub fn bounding_radius(vertices: &[Vertex]) -> f32 {
+    vertices
+        .par_chunks(CHUNK_SIZE)
+        .map(|chunk| chunk.iter().map(|v| v.position.iter().map(|c| c * c).sum::<f32>()).fold(0.0, f32::max))
+        .reduce(|| 0.0, f32::max)
+        .sqrt()
+}
+
 /// Normalize model to fit in a unit cube centered at origin
-fn normalize_model(vertices: &mut [Vertex]) {
+/// Returns the original bounding box size
+fn normalize_model(vertices: &mut [Vertex], progress: &Progress) -> [f32; 3] {
+    const STAGE: &str = "Normalizing";
     if vertices.is_empty() {
-        return;
+        return [0.0; 3];
     }
 
     // Find bounding box
-    let mut min = [f32::MAX; 3];
-    let mut max = [f32::MIN; 3];
-
-    for v in vertices.iter() {
-        for i in 0..3 {
-            min[i] = min[i].min(v.position[i]);
-            max[i] = max[i].max(v.position[i]);
-        }
-    }
+    let (min, max) = vertices
+        .par_chunks(CHUNK_SIZE)
+        .map(|chunk| {
+            let mut min = [f32::MAX; 3];
+            let mut max = [f32::MIN; 3];
+            for v in chunk {
+                for i in 0..3 {
+                    min[i] = min[i].min(v.position[i]);
+                    max[i] = max[i].max(v.position[i]);
+                }
+            }
+            (min, max)
+        })
+        .reduce(
+            || ([f32::MAX; 3], [f32::MIN; 3]),
+            |(a_min, a_max), (b_min, b_max)| {
+                (
+                    [0, 1, 2].map(|i| a_min[i].min(b_min[i])),
+                    [0, 1, 2].map(|i| a_max[i].max(b_max[i])),
+                )
+            },
+        );
 
     // Center
     let center = [
@@ -337,9 +760,17 @@ fn normalize_model(vertices: &mut [Vertex]) {
     let scale = if max_dim > 1e-6 { 1.6 / max_dim } else { 1.0 };
 
     // Apply transform
-    for v in vertices.iter_mut() {
-        v.position[0] = (v.position[0] - center[0]) * scale;
-        v.position[1] = (v.position[1] - center[1]) * scale;
-        v.position[2] = (v.position[2] - center[2]) * scale;
-    }
+    let total = vertices.len();
+    let done = AtomicUsize::new(0);
+    vertices.par_chunks_mut(CHUNK_SIZE).for_each(|chunk| {
+        for v in chunk.iter_mut() {
+            v.position[0] = (v.position[0] - center[0]) * scale;
+            v.position[1] = (v.position[1] - center[1]) * scale;
+            v.position[2] = (v.position[2] - center[2]) * scale;
+        }
+        let finished = done.fetch_add(chunk.len(), Ordering::Relaxed) + chunk.len();
+        progress(STAGE, finished as f32 / total as f32);
+    });
+
+    size
 }
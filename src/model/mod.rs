@@ -1,4 +1,8 @@
+mod archive;
+mod fetch;
 mod loader;
+mod occlusion;
 
+pub use fetch::{download, fetch_model, fetch_text, url_file_name};
 #[allow(unused_imports)]
-pub use loader::{discover_models, get_model_display_name, load_model, ModelData};
+pub use loader::{bounding_radius, discover_models, get_model_display_name, load_model, LoadOptions, ModelData, ModelStats, Progress};
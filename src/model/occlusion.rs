@@ -0,0 +1,264 @@
+use glam::Vec3;
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::loader::{ModelData, Progress};
+
+/// Rays cast per vertex
+const SAMPLES: u32 = 32;
+
+/// Occluders farther than this (in normalized model units, where the model spans 1.6) don't count
+const RADIUS: f32 = 0.4;
+
+/// Ray start offset along the normal, so a vertex doesn't hit its own faces
+const BIAS: f32 = 1e-4;
+
+/// Triangles per BVH leaf
+const LEAF_SIZE: usize = 4;
+
+/// Vertices per parallel work item (and per progress update)
+const CHUNK_SIZE: usize = 4096;
+
+/// Cache file header
+const CACHE_MAGIC: &[u8; 4] = b"A3AO";
+const CACHE_VERSION: u32 = 1;
+
+/// Darken vertex colors by ambient occlusion ray-cast on the CPU
+/// Results are cached beside the model file (`<model>.ao`) and reused while the geometry and bake
+/// settings are unchanged
+pub fn bake_ambient_occlusion(model: &mut ModelData, path: &Path, progress: &Progress) {
+    let key = geometry_key(model);
+    let cache = cache_path(path);
+    let occlusion = match read_cache(&cache, key, model.vertices.len()) {
+        Some(occlusion) => occlusion,
+        None => {
+            let occlusion = compute_occlusion(model, progress);
+            // Read-only model folders just skip the cache
+            let _ = write_cache(&cache, key, &occlusion);
+            occlusion
+        }
+    };
+    for (v, ao) in model.vertices.iter_mut().zip(occlusion) {
+        v.color = v.color.map(|c| c * ao);
+    }
+}
+
+/// Fraction of unoccluded hemisphere at each vertex (1 = fully open)
+fn compute_occlusion(model: &ModelData, progress: &Progress) -> Vec<f32> {
+    const STAGE: &str = "Baking occlusion";
+    let triangles: Vec<[Vec3; 3]> = model
+        .indices
+        .chunks_exact(3)
+        .filter(|tri| tri.iter().all(|&i| (i as usize) < model.vertices.len()))
+        .map(|tri| [0, 1, 2].map(|k| Vec3::from(model.vertices[tri[k] as usize].position)))
+        .collect();
+    let bvh = Bvh::build(&triangles);
+    let directions = hemisphere_directions();
+
+    let total = model.vertices.len();
+    let done = AtomicUsize::new(0);
+    model
+        .vertices
+        .par_chunks(CHUNK_SIZE)
+        .flat_map_iter(|chunk| {
+            let occlusion: Vec<f32> = chunk
+                .iter()
+                .map(|v| {
+                    let normal = Vec3::from(v.normal);
+                    let (tangent, bitangent) = normal.any_orthonormal_pair();
+                    let origin = Vec3::from(v.position) + normal * BIAS;
+                    let open = directions
+                        .iter()
+                        .filter(|d| {
+                            let dir = tangent * d.x + bitangent * d.y + normal * d.z;
+                            !bvh.occluded(&triangles, origin, dir, RADIUS)
+                        })
+                        .count();
+                    open as f32 / directions.len() as f32
+                })
+                .collect();
+            let finished = done.fetch_add(chunk.len(), Ordering::Relaxed) + chunk.len();
+            progress(STAGE, finished as f32 / total as f32);
+            occlusion
+        })
+        .collect()
+}
+
+/// Cosine-weighted directions over the +Z hemisphere (Fibonacci spiral, so bakes are repeatable)
+fn hemisphere_directions() -> Vec<Vec3> {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+    (0..SAMPLES)
+        .map(|k| {
+            let u = (k as f32 + 0.5) / SAMPLES as f32;
+            let r = u.sqrt();
+            let phi = k as f32 * golden_angle;
+            Vec3::new(r * phi.cos(), r * phi.sin(), (1.0 - u).sqrt())
+        })
+        .collect()
+}
+
+/// Bounding volume hierarchy over triangles, for occlusion queries
+struct Bvh {
+    nodes: Vec<BvhNode>,
+    /// Triangle indices, grouped so each leaf covers a contiguous range
+    order: Vec<u32>,
+}
+
+struct BvhNode {
+    min: Vec3,
+    max: Vec3,
+    /// Leaf: first entry in `order`; inner node: index of the second child (the first follows this node)
+    start: u32,
+    /// Triangles in a leaf (0 for inner nodes)
+    count: u32,
+}
+
+impl Bvh {
+    fn build(triangles: &[[Vec3; 3]]) -> Self {
+        let centroids: Vec<Vec3> = triangles.iter().map(|t| (t[0] + t[1] + t[2]) / 3.0).collect();
+        let mut bvh = Self {
+            nodes: Vec::with_capacity(2 * triangles.len() / LEAF_SIZE + 1),
+            order: (0..triangles.len() as u32).collect(),
+        };
+        if !triangles.is_empty() {
+            bvh.build_node(triangles, &centroids, 0, triangles.len());
+        }
+        bvh
+    }
+
+    /// Add the node covering `order[start..end]` and its subtree
+    fn build_node(&mut self, triangles: &[[Vec3; 3]], centroids: &[Vec3], start: usize, end: usize) {
+        let mut min = Vec3::MAX;
+        let mut max = Vec3::MIN;
+        for &t in &self.order[start..end] {
+            for p in triangles[t as usize] {
+                min = min.min(p);
+                max = max.max(p);
+            }
+        }
+        let node = self.nodes.len();
+        self.nodes.push(BvhNode { min, max, start: start as u32, count: (end - start) as u32 });
+        if end - start <= LEAF_SIZE {
+            return;
+        }
+
+        // Split at the median centroid along the longest axis
+        let extent = max - min;
+        let axis = (0..3).max_by(|&a, &b| extent[a].total_cmp(&extent[b])).unwrap_or(0);
+        let mid = (start + end) / 2;
+        self.order[start..end].select_nth_unstable_by(mid - start, |&a, &b| {
+            centroids[a as usize][axis].total_cmp(&centroids[b as usize][axis])
+        });
+        self.build_node(triangles, centroids, start, mid);
+        self.nodes[node].start = self.nodes.len() as u32;
+        self.nodes[node].count = 0;
+        self.build_node(triangles, centroids, mid, end);
+    }
+
+    /// Whether any triangle blocks the ray within `max_distance`
+    fn occluded(&self, triangles: &[[Vec3; 3]], origin: Vec3, dir: Vec3, max_distance: f32) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let inv_dir = dir.recip();
+        let mut stack = vec![0u32];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index as usize];
+            if !ray_hits_box(origin, inv_dir, node.min, node.max, max_distance) {
+                continue;
+            }
+            if node.count > 0 {
+                let leaf = &self.order[node.start as usize..(node.start + node.count) as usize];
+                if leaf
+                    .iter()
+                    .any(|&t| ray_triangle(origin, dir, &triangles[t as usize]).is_some_and(|d| d < max_distance))
+                {
+                    return true;
+                }
+            } else {
+                stack.push(index + 1);
+                stack.push(node.start);
+            }
+        }
+        false
+    }
+}
+
+/// Slab test: whether the ray enters the box before `max_distance`
+fn ray_hits_box(origin: Vec3, inv_dir: Vec3, min: Vec3, max: Vec3, max_distance: f32) -> bool {
+    let t1 = (min - origin) * inv_dir;
+    let t2 = (max - origin) * inv_dir;
+    let near = t1.min(t2).max_element().max(0.0);
+    let far = t1.max(t2).min_element().min(max_distance);
+    near <= far
+}
+
+/// Distance along the ray to the triangle (Möller-Trumbore), if it is hit in front of the origin
+fn ray_triangle(origin: Vec3, dir: Vec3, tri: &[Vec3; 3]) -> Option<f32> {
+    let e1 = tri[1] - tri[0];
+    let e2 = tri[2] - tri[0];
+    let p = dir.cross(e2);
+    let det = e1.dot(p);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let s = origin - tri[0];
+    let u = s.dot(p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(e1);
+    let v = dir.dot(q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = e2.dot(q) * inv_det;
+    (t > 0.0).then_some(t)
+}
+
+/// Cache file for a model: the model path with `.ao` appended
+fn cache_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".ao");
+    PathBuf::from(name)
+}
+
+/// Hash of everything the bake depends on, so a stale cache is never applied
+fn geometry_key(model: &ModelData) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (SAMPLES, RADIUS.to_bits(), BIAS.to_bits()).hash(&mut hasher);
+    for v in &model.vertices {
+        v.position.map(f32::to_bits).hash(&mut hasher);
+        v.normal.map(f32::to_bits).hash(&mut hasher);
+    }
+    model.indices.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Cached occlusion, if the file exists and matches the geometry
+fn read_cache(path: &Path, key: u64, vertex_count: usize) -> Option<Vec<f32>> {
+    let bytes = std::fs::read(path).ok()?;
+    let (header, values) = bytes.split_at_checked(16)?;
+    if &header[0..4] != CACHE_MAGIC
+        || u32::from_le_bytes(header[4..8].try_into().ok()?) != CACHE_VERSION
+        || u64::from_le_bytes(header[8..16].try_into().ok()?) != key
+        || values.len() != vertex_count
+    {
+        return None;
+    }
+    Some(values.iter().map(|&b| b as f32 / 255.0).collect())
+}
+
+/// Store occlusion as one byte per vertex after a magic/version/key header
+fn write_cache(path: &Path, key: u64, occlusion: &[f32]) -> std::io::Result<()> {
+    let mut bytes = Vec::with_capacity(16 + occlusion.len());
+    bytes.extend_from_slice(CACHE_MAGIC);
+    bytes.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&key.to_le_bytes());
+    bytes.extend(occlusion.iter().map(|&ao| (ao.clamp(0.0, 1.0) * 255.0).round() as u8));
+    std::fs::write(path, bytes)
+}
@@ -0,0 +1,114 @@
+use std::time::{Duration, Instant};
+
+/// Smoothing factor for the per-stage moving averages (higher reacts faster)
+const SMOOTHING: f32 = 0.1;
+
+/// Stages of a frame, in execution order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    Input,
+    Upload,
+    Scene,
+    Compute,
+    Readback,
+    Emit,
+}
+
+impl Stage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Input => "Input",
+            Stage::Upload => "Upload",
+            Stage::Scene => "Scene pass",
+            Stage::Compute => "Compute",
+            Stage::Readback => "Readback",
+            Stage::Emit => "Terminal",
+        }
+    }
+
+    pub fn all() -> &'static [Stage] {
+        &[
+            Stage::Input,
+            Stage::Upload,
+            Stage::Scene,
+            Stage::Compute,
+            Stage::Readback,
+            Stage::Emit,
+        ]
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Per-stage frame timings for the profiler overlay
+/// While profiling, the GPU is waited on after each pass so its time lands in the right stage
+pub struct FrameProfiler {
+    /// Time accumulated for each stage during the current frame
+    current: [Duration; 6],
+    /// Smoothed milliseconds per stage
+    average_ms: [f32; 6],
+    /// Start of the stage being timed
+    mark: Instant,
+    has_samples: bool,
+}
+
+impl FrameProfiler {
+    pub fn new() -> Self {
+        Self {
+            current: [Duration::ZERO; 6],
+            average_ms: [0.0; 6],
+            mark: Instant::now(),
+            has_samples: false,
+        }
+    }
+
+    /// Start timing from now (call at the beginning of a stage)
+    pub fn mark(&mut self) {
+        self.mark = Instant::now();
+    }
+
+    /// Attribute the time since the last mark to `stage` and restart the mark
+    pub fn record(&mut self, stage: Stage) {
+        let now = Instant::now();
+        self.current[stage.index()] += now - self.mark;
+        self.mark = now;
+    }
+
+    /// Add time measured elsewhere (e.g. on the render thread) to the current frame
+    pub fn add(&mut self, stage: Stage, time: Duration) {
+        self.current[stage.index()] += time;
+    }
+
+    /// Stages with time recorded in the current frame
+    pub fn frame_times(&self) -> Vec<(Stage, Duration)> {
+        Stage::all()
+            .iter()
+            .map(|&stage| (stage, self.current[stage.index()]))
+            .filter(|&(_, time)| time > Duration::ZERO)
+            .collect()
+    }
+
+    /// Fold the current frame's timings into the averages
+    pub fn end_frame(&mut self) {
+        for (average, current) in self.average_ms.iter_mut().zip(self.current.iter_mut()) {
+            let ms = current.as_secs_f32() * 1000.0;
+            *average = if self.has_samples {
+                *average + (ms - *average) * SMOOTHING
+            } else {
+                ms
+            };
+            *current = Duration::ZERO;
+        }
+        self.has_samples = true;
+    }
+
+    /// Smoothed (stage, milliseconds) pairs in execution order
+    pub fn stages(&self) -> Vec<(Stage, f32)> {
+        Stage::all()
+            .iter()
+            .map(|&stage| (stage, self.average_ms[stage.index()]))
+            .collect()
+    }
+}
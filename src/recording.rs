@@ -0,0 +1,278 @@
+//! `.a3d` recordings: the packed cell buffers of each shown frame, replayed exactly by `play`
+//!
+//! Layout: the magic bytes `A3D1`, then a zstd stream of frames. Each frame is a header
+//! (time in ms: u32, cols: u16, rows: u16, render mode: u8, keyframe: u8, little-endian) followed
+//! by cols x rows packed cells. A keyframe stores the cells as-is; other frames store them XORed
+//! with the previous frame, so unchanged cells are zeros that compress to almost nothing
+
+use anyhow::{anyhow, Context, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use zstd::stream::read::Decoder;
+use zstd::stream::write::Encoder;
+
+use crate::config::ConfigFile;
+use crate::terminal::{ColorDepth, HalfBlock, Palette, RenderMode, TerminalRenderer};
+
+const MAGIC: &[u8; 4] = b"A3D1";
+
+/// zstd compression level (fast enough to keep up with every shown frame)
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// How often playback checks for keys while waiting for the next frame
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+fn mode_to_byte(mode: RenderMode) -> u8 {
+    match mode {
+        RenderMode::PlainAscii => 0,
+        RenderMode::ColoredAscii => 1,
+        RenderMode::HalfBlock => 2,
+        RenderMode::HalfBlockEdges => 3,
+    }
+}
+
+fn mode_from_byte(byte: u8) -> Result<RenderMode> {
+    match byte {
+        0 => Ok(RenderMode::PlainAscii),
+        1 => Ok(RenderMode::ColoredAscii),
+        2 => Ok(RenderMode::HalfBlock),
+        3 => Ok(RenderMode::HalfBlockEdges),
+        _ => Err(anyhow!("Unknown render mode {} in recording", byte)),
+    }
+}
+
+/// One recorded frame
+pub struct RecordedFrame {
+    /// Seconds since the recording started
+    pub time: f32,
+    pub cols: u32,
+    pub rows: u32,
+    pub mode: RenderMode,
+    pub cells: Vec<u32>,
+}
+
+/// Writes shown frames to an `.a3d` file (`--record`)
+pub struct Recorder {
+    encoder: Encoder<'static, BufWriter<File>>,
+    /// Cells and grid of the last written frame, the base of the next delta
+    previous: Vec<u32>,
+    previous_dims: (u32, u32),
+    start: Option<f32>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("Failed to create recording {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(MAGIC)?;
+        Ok(Self {
+            encoder: Encoder::new(writer, COMPRESSION_LEVEL)?,
+            previous: Vec::new(),
+            previous_dims: (0, 0),
+            start: None,
+        })
+    }
+
+    /// Append a frame shown at animation time `time` (seconds)
+    pub fn write_frame(&mut self, time: f32, cells: &[u32], cols: u32, rows: u32, mode: RenderMode) -> Result<()> {
+        if cols > u16::MAX as u32 || rows > u16::MAX as u32 {
+            return Err(anyhow!("Can't record a {}x{} frame: at most {} cells per side", cols, rows, u16::MAX));
+        }
+        let count = cols as usize * rows as usize;
+        if cells.len() < count {
+            return Err(anyhow!("Can't record a {}x{} frame from {} cells", cols, rows, cells.len()));
+        }
+        let start = *self.start.get_or_insert(time);
+        let time_ms = ((time - start).max(0.0) * 1000.0).round() as u32;
+        let cells = &cells[..count];
+        let keyframe = (cols, rows) != self.previous_dims;
+
+        let mut header = [0u8; 10];
+        header[0..4].copy_from_slice(&time_ms.to_le_bytes());
+        header[4..6].copy_from_slice(&(cols as u16).to_le_bytes());
+        header[6..8].copy_from_slice(&(rows as u16).to_le_bytes());
+        header[8] = mode_to_byte(mode);
+        header[9] = keyframe as u8;
+        self.encoder.write_all(&header)?;
+
+        let mut body = Vec::with_capacity(cells.len() * 4);
+        for (i, &cell) in cells.iter().enumerate() {
+            let value = if keyframe { cell } else { cell ^ self.previous[i] };
+            body.extend_from_slice(&value.to_le_bytes());
+        }
+        self.encoder.write_all(&body)?;
+
+        self.previous.clear();
+        self.previous.extend_from_slice(cells);
+        self.previous_dims = (cols, rows);
+        Ok(())
+    }
+
+    /// Flush the compressed stream and close the file
+    pub fn finish(self) -> Result<()> {
+        self.encoder.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+/// Reads frames back from an `.a3d` file, one at a time
+pub struct RecordingReader {
+    decoder: Decoder<'static, BufReader<File>>,
+    previous: Vec<u32>,
+}
+
+impl RecordingReader {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open recording {}", path.display()))?;
+        let mut reader = BufReader::new(file);
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic).context("Recording is empty")?;
+        if &magic != MAGIC {
+            return Err(anyhow!("{} is not an .a3d recording", path.display()));
+        }
+        Ok(Self {
+            decoder: Decoder::with_buffer(reader)?,
+            previous: Vec::new(),
+        })
+    }
+
+    /// The next frame, or None at the end of the recording
+    pub fn next_frame(&mut self) -> Result<Option<RecordedFrame>> {
+        let mut header = [0u8; 10];
+        match self.decoder.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e).context("Recording is corrupt"),
+        }
+        let time_ms = u32::from_le_bytes(header[0..4].try_into()?);
+        let cols = u16::from_le_bytes(header[4..6].try_into()?) as u32;
+        let rows = u16::from_le_bytes(header[6..8].try_into()?) as u32;
+        let mode = mode_from_byte(header[8])?;
+        let keyframe = header[9] != 0;
+
+        let count = (cols * rows) as usize;
+        if !keyframe && self.previous.len() != count {
+            return Err(anyhow!("Recording is corrupt: delta frame without a matching keyframe"));
+        }
+        let mut body = vec![0u8; count * 4];
+        self.decoder.read_exact(&mut body).context("Recording ends mid-frame")?;
+        let cells: Vec<u32> = body
+            .chunks_exact(4)
+            .enumerate()
+            .map(|(i, bytes)| {
+                let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                if keyframe {
+                    value
+                } else {
+                    value ^ self.previous[i]
+                }
+            })
+            .collect();
+        self.previous.clone_from(&cells);
+
+        Ok(Some(RecordedFrame {
+            time: time_ms as f32 / 1000.0,
+            cols,
+            rows,
+            mode,
+            cells,
+        }))
+    }
+}
+
+/// Run the `play` subcommand: replay a recording at its original timing
+/// Space pauses, Esc or Q quits
+pub fn play(path: &Path, config: &ConfigFile) -> Result<()> {
+    let mut reader = RecordingReader::open(path)?;
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+
+    let mut term = TerminalRenderer::new()?;
+    term.set_theme(config.theme.clone());
+    term.set_charset(config.charset);
+    term.set_half_block(config.half_block.unwrap_or_else(HalfBlock::detect));
+    term.set_show_status(config.status_bar);
+    term.set_palette(Palette::new(config.color_depth.unwrap_or_else(ColorDepth::detect), config.chroma_boost));
+
+    // Playback clock: recording time at `resumed`, paused time excluded
+    let mut resumed = Instant::now();
+    let mut offset = 0.0f32;
+    let mut paused = false;
+    let mut last_time = 0.0f32;
+
+    while let Some(frame) = reader.next_frame()? {
+        // Wait for the frame's time, handling keys meanwhile
+        loop {
+            while event::poll(Duration::ZERO)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => return Ok(()),
+                        KeyCode::Char(' ') => {
+                            if paused {
+                                resumed = Instant::now();
+                            } else {
+                                offset += resumed.elapsed().as_secs_f32();
+                            }
+                            paused = !paused;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            let now = if paused { offset } else { offset + resumed.elapsed().as_secs_f32() };
+            if !paused && now >= frame.time {
+                break;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        term.check_resize()?;
+
+        let mut screen = term.screen();
+        term.draw_content(&mut screen, &term.to_frame(&frame.cells, frame.cols, frame.rows, frame.mode));
+        let fps = 1.0 / (frame.time - last_time).max(0.001);
+        last_time = frame.time;
+        term.draw_status(&mut screen, &name, fps, &format!("{} [Replay {:.1}s]", frame.mode.name(), frame.time));
+        term.present(&screen)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recording_round_trip() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("ascii-3d-round-trip-{}.a3d", std::process::id()));
+        // A keyframe, a delta against it with one cell changed, then a new size (a keyframe again)
+        let frames = [
+            (0.5, 2, 2, RenderMode::ColoredAscii, vec![1, 2, 3, 0xffff_ffff]),
+            (0.75, 2, 2, RenderMode::ColoredAscii, vec![1, 2, 7, 0xffff_ffff]),
+            (1.0, 3, 1, RenderMode::HalfBlock, vec![9, 8, 7]),
+        ];
+
+        let mut recorder = Recorder::create(&path)?;
+        for (time, cols, rows, mode, cells) in &frames {
+            recorder.write_frame(*time, cells, *cols, *rows, *mode)?;
+        }
+        assert!(recorder.write_frame(1.5, &[1, 2], 2, 2, RenderMode::PlainAscii).is_err());
+        assert!(recorder.write_frame(1.5, &[], 0x1_0000, 0, RenderMode::PlainAscii).is_err());
+        recorder.finish()?;
+
+        let mut reader = RecordingReader::open(&path)?;
+        for (time, cols, rows, mode, cells) in &frames {
+            let frame = reader.next_frame()?.ok_or_else(|| anyhow!("Recording ended early"))?;
+            assert_eq!((frame.time, frame.cols, frame.rows), (time - 0.5, *cols, *rows));
+            assert_eq!(mode_to_byte(frame.mode), mode_to_byte(*mode));
+            assert_eq!(&frame.cells, cells);
+        }
+        assert!(reader.next_frame()?.is_none());
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
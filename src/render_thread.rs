@@ -0,0 +1,607 @@
+use anyhow::{anyhow, Result};
+use glam::Mat4;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::time::{Duration, Instant};
+
+use crate::gpu::{AsciiPipeline, AsciiShaders, CharRamp, EdgeAlgorithm, HeadlessGpu, LabelAnchor, LabelPass, LightingMode, OrbitCamera, PreviewPass, RotationMode, SdfScene, StereogramPass, Tonemap};
+use crate::model::ModelData;
+use crate::profiler::{FrameProfiler, Stage};
+
+/// How the model is posed for a frame
+#[derive(Clone, Copy, PartialEq)]
+pub enum CameraPose {
+    /// Manual rotation angles and zoom
+    Manual { pitch: f32, yaw: f32, zoom: f32 },
+    /// Externally computed model matrix (physics simulation) seen from `distance` away
+    Model { model: Mat4, distance: f32 },
+    /// Externally placed camera (view matrix) looking at the model at rest (terrain flyover)
+    View(Mat4),
+    /// Automatic rotation at a point in time
+    Rotation {
+        time: f32,
+        mode: RotationMode,
+        speed: f32,
+        /// Camera distance in the fixed-camera modes
+        distance: f32,
+        /// Camera path in Orbit mode
+        orbit: OrbitCamera,
+    },
+}
+
+/// Half the depth range mapped to nearness; normalized models fit in a 1.6 unit cube
+const DEPTH_WINDOW_RADIUS: f32 = 1.4;
+
+impl CameraPose {
+    /// Distance from the camera to the model's center
+    fn camera_distance(&self) -> f32 {
+        match *self {
+            CameraPose::Manual { zoom, .. } => zoom,
+            CameraPose::View(view) => view.inverse().w_axis.truncate().length(),
+            CameraPose::Rotation { time, mode: RotationMode::Orbit, speed, orbit, .. } => orbit.eye(time * speed).length(),
+            CameraPose::Rotation { distance, .. } | CameraPose::Model { distance, .. } => distance,
+        }
+    }
+}
+
+/// Cell grid and pixels per cell of the main pane: (cols, rows, px_x, px_y)
+pub type PaneDims = (u32, u32, u32, u32);
+
+/// Everything the render thread needs to produce one frame
+pub struct FrameRequest {
+    pub pose: CameraPose,
+    pub lighting: LightingMode,
+    /// Animation clock for animated backgrounds (seconds)
+    pub time: f32,
+    pub explode: f32,
+    pub show_bounds: bool,
+    /// Whether each sub-mesh is drawn (missing entries are drawn)
+    pub part_visibility: Vec<bool>,
+    /// Key light multiplier (1.0 = normal)
+    pub light_intensity: f32,
+    /// ASCII luminance boost
+    pub exposure: f32,
+    /// ASCII contrast curve
+    pub gamma: f32,
+    /// Curve applied to linear scene color before sRGB encoding
+    pub tonemap: Tonemap,
+    /// Motion blur shutter, the fraction of each pixel's motion since the last frame (0 = off)
+    pub motion_blur: f32,
+    /// Average jittered renders while the scene holds still
+    pub accumulate: bool,
+    /// Edge detector for the ASCII passes
+    pub edge_algorithm: EdgeAlgorithm,
+    /// Depth edge threshold in world units (None = the default fraction of the clip range)
+    pub depth_edge_threshold: Option<f32>,
+    /// Outline each model part from the part ID target
+    pub object_edges: bool,
+    /// Keep normal and luminance edges inside a model part
+    pub interior_edges: bool,
+    /// Silhouette thickness in character cells (1 = as detected)
+    pub outline_width: u32,
+    /// Draw thick outlines in black ink
+    pub ink_outlines: bool,
+    /// Luminance bands fills are snapped to (0 = continuous)
+    pub fill_levels: u32,
+    /// Draw edge characters (off draws fill characters everywhere)
+    pub edge_chars: bool,
+    /// Fill character order and coverage for the ASCII passes
+    pub ramp: CharRamp,
+    /// Depth mixed into ASCII luminance (0 = off, 1 = depth only)
+    pub depth_weight: f32,
+    /// Draw background pixels as blank cells in the main pane
+    pub suppress_background: bool,
+    pub dims: PaneDims,
+    /// Split view right pane: cell grid (sharing the main render size), lighting,
+    /// and background suppression
+    pub split: Option<((u32, u32), LightingMode, bool)>,
+    /// New geometry to upload before rendering
+    pub geometry: Option<ModelData>,
+    /// New video frame for the background (--video)
+    pub video_frame: Option<image::RgbaImage>,
+    /// Draw the model (off with --video-only)
+    pub show_model: bool,
+    /// Raymarched scene drawn in place of the model mesh
+    pub sdf: Option<SdfScene>,
+    /// Animate the mesh as the water ripple surface
+    pub wave: bool,
+    /// Animate the mesh as the Game of Life torus
+    pub life: bool,
+    /// Raw render preview grid (cols, rows of half-block pixels), None when hidden
+    pub preview: Option<(u32, u32)>,
+    /// Replace the main pane with a random-character stereogram of the depth buffer
+    pub stereogram: bool,
+    /// Repeat every main pane cell across two terminal columns (double-wide ASCII)
+    pub double_wide: bool,
+    /// Text attached to points on the model, located in the main pane each frame
+    pub labels: Vec<(String, LabelAnchor)>,
+    /// Sub-mesh tinted as selected
+    pub selected_part: Option<usize>,
+    /// Find the part under this point of the main pane (0 to 1, y down)
+    pub pick: Option<[f32; 2]>,
+    /// Wait on the GPU after each pass and report per-stage timings
+    pub profile: bool,
+}
+
+/// A finished frame read back from the GPU
+pub struct FrameResult {
+    pub cells: Vec<u32>,
+    pub cols: u32,
+    pub rows: u32,
+    /// Split view right pane: (cells, cols, rows)
+    pub split: Option<(Vec<u32>, u32, u32)>,
+    /// Raw render preview of the main pane: (packed colors, cols, rows)
+    pub preview: Option<(Vec<u32>, u32, u32)>,
+    pub gpu_time_ms: f32,
+    pub render_size: (u32, u32),
+    /// Whether the render size was reduced to fit the device texture limit
+    pub clamped: bool,
+    /// Renders averaged into the frame (0 unless accumulating)
+    pub samples: u32,
+    /// GPU stage timings (only when profiling)
+    pub stage_times: Vec<(Stage, Duration)>,
+    /// Labels in view: text, position in the main pane (0 to 1, y down), and how much of the
+    /// area around their point is unoccluded (0 to 1)
+    pub labels: Vec<(String, [f32; 2], f32)>,
+    /// Answer to the request's `pick`: the part under the point, if any
+    pub picked: Option<Option<usize>>,
+    /// Live cells on the Game of Life board, while it runs
+    pub life_population: Option<u32>,
+}
+
+/// GPU state owned by the render thread
+pub struct RenderState {
+    pub gpu: HeadlessGpu,
+    pipeline: AsciiPipeline,
+    split_pipeline: Option<AsciiPipeline>,
+    /// Picture-in-picture downsampler, created when the preview is first shown
+    preview: Option<PreviewPass>,
+    /// Depth-to-stereogram pass, created when the stereogram mode is first used
+    stereogram: Option<StereogramPass>,
+    /// Label occlusion pass, created when labels are first shown
+    labels: Option<LabelPass>,
+    /// Active shader sources, used when the split pipeline is created
+    shaders: AsciiShaders,
+    dims: PaneDims,
+    split_dims: Option<(u32, u32)>,
+    clamped: bool,
+}
+
+impl RenderState {
+    fn new(dims: PaneDims) -> Result<Self> {
+        let (cols, rows, px_x, px_y) = dims;
+
+        // Initialize headless GPU
+        eprintln!("Creating HeadlessGpu...");
+        let mut gpu = pollster::block_on(HeadlessGpu::new(cols * px_x, rows * px_y))?;
+        eprintln!("HeadlessGpu created");
+
+        // Huge terminals can exceed the texture limit; supersampling is reduced to fit
+        let (width, height, clamped) =
+            fit_render_size(cols, rows, px_x, px_y, gpu.max_texture_dimension());
+        gpu.resize(width, height);
+
+        // Initialize edge-aware ASCII pipeline
+        eprintln!("Creating AsciiPipeline...");
+        let pipeline = AsciiPipeline::new(&gpu.device, cols, rows, width, height)?;
+        eprintln!("AsciiPipeline created");
+
+        Ok(Self {
+            gpu,
+            pipeline,
+            split_pipeline: None,
+            preview: None,
+            stereogram: None,
+            labels: None,
+            shaders: AsciiShaders::builtin(),
+            dims,
+            split_dims: None,
+            clamped,
+        })
+    }
+
+    /// Recompile the ASCII passes of the main and split-view pipelines
+    pub fn reload_shaders(&mut self, shaders: AsciiShaders) -> Result<()> {
+        pollster::block_on(self.pipeline.reload_shaders(&self.gpu.device, &shaders))?;
+        if let Some(ref mut split_pipe) = self.split_pipeline {
+            pollster::block_on(split_pipe.reload_shaders(&self.gpu.device, &shaders))?;
+        }
+        self.shaders = shaders;
+        Ok(())
+    }
+
+    /// Match the render targets and pipelines to the requested cell grids
+    fn apply_dims(&mut self, dims: PaneDims, split: Option<(u32, u32)>) -> Result<()> {
+        if dims != self.dims {
+            let (cols, rows, px_x, px_y) = dims;
+            let (width, height, clamped) =
+                fit_render_size(cols, rows, px_x, px_y, self.gpu.max_texture_dimension());
+            self.clamped = clamped;
+            self.gpu.resize(width, height);
+            self.pipeline.resize(&self.gpu.device, cols, rows, width, height);
+            self.dims = dims;
+            self.split_dims = None;
+        }
+
+        // The right pane shares the render size; its cell grid depends on its own mode
+        match split {
+            None => {
+                self.split_pipeline = None;
+                self.split_dims = None;
+            }
+            Some(grid) if self.split_dims != Some(grid) || self.split_pipeline.is_none() => {
+                let (cols, rows) = grid;
+                let (width, height) = self.gpu.render_size();
+                match self.split_pipeline {
+                    Some(ref mut p) => p.resize(&self.gpu.device, cols, rows, width, height),
+                    None => {
+                        self.split_pipeline = Some(AsciiPipeline::with_shaders(
+                            &self.gpu.device,
+                            cols,
+                            rows,
+                            width,
+                            height,
+                            &self.shaders,
+                        )?);
+                    }
+                }
+                self.split_dims = Some(grid);
+            }
+            Some(_) => {}
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, request: FrameRequest) -> Result<FrameResult> {
+        let mut profiler = request.profile.then(FrameProfiler::new);
+
+        self.apply_dims(request.dims, request.split.map(|(grid, _, _)| grid))?;
+
+        if let Some(model_data) = request.geometry {
+            self.gpu.set_geometry(&model_data.vertices, &model_data.indices);
+            self.gpu.set_mesh_ranges(&model_data.ranges);
+        }
+        self.gpu.set_time(request.time);
+        self.gpu.set_explode(request.explode);
+        self.gpu.set_show_bounds(request.show_bounds);
+        self.gpu.set_show_model(request.show_model);
+        self.gpu.set_sdf_scene(request.sdf);
+        self.gpu.set_wave(request.wave);
+        self.gpu.set_life(request.life)?;
+        if let Some(ref frame) = request.video_frame {
+            self.gpu.set_video_frame(frame);
+        }
+        self.gpu.set_part_visibility(&request.part_visibility);
+        self.gpu.set_selected_part(request.selected_part);
+        self.gpu.set_light_intensity(request.light_intensity);
+        self.gpu.set_accumulate(request.accumulate);
+        let distance = request.pose.camera_distance();
+        let (near, far) = (distance - DEPTH_WINDOW_RADIUS, distance + DEPTH_WINDOW_RADIUS);
+        for pipeline in std::iter::once(&mut self.pipeline).chain(self.split_pipeline.as_mut()) {
+            pipeline.set_exposure(request.exposure);
+            pipeline.set_gamma(request.gamma);
+            pipeline.set_tonemap(request.tonemap);
+            pipeline.set_motion_blur(request.motion_blur);
+            pipeline.set_edge_algorithm(request.edge_algorithm);
+            pipeline.set_world_depth_threshold(request.depth_edge_threshold);
+            pipeline.set_object_edges(request.object_edges);
+            pipeline.set_interior_edges(request.interior_edges);
+            pipeline.set_outline_width(request.outline_width);
+            pipeline.set_ink_outlines(request.ink_outlines);
+            pipeline.set_fill_levels(request.fill_levels);
+            pipeline.set_edge_chars(request.edge_chars);
+            pipeline.set_ramp(request.ramp);
+            pipeline.set_depth_weight(request.depth_weight);
+            pipeline.set_depth_window(near, far);
+        }
+        self.pipeline.set_suppress_background(request.suppress_background);
+
+        // Time GPU operations
+        let gpu_start = Instant::now();
+
+        let mut cells = render_to_cells(
+            &self.gpu,
+            &mut self.pipeline,
+            request.pose,
+            request.lighting,
+            profiler.as_mut(),
+        )?;
+
+        // The preview reads the main pipeline's display texture before the split pane overwrites it
+        let preview = match request.preview {
+            Some((cols, rows)) => {
+                let device = &self.gpu.device;
+                let pass = match self.preview {
+                    Some(ref mut pass) => {
+                        pass.resize(device, cols, rows);
+                        pass
+                    }
+                    None => self.preview.insert(PreviewPass::new(device, cols, rows)),
+                };
+                let colors = pass.run(device, &self.gpu.queue, &self.pipeline.display_view(), self.pipeline.tex_size())?;
+                Some((colors, cols, rows))
+            }
+            None => None,
+        };
+
+        let picked = request.pick.map(|position| self.gpu.pick_part(position)).transpose()?;
+
+        // Labels are tested against the depth buffer before the split pane redraws the scene
+        let projected: Vec<_> = request
+            .labels
+            .into_iter()
+            .filter_map(|(text, anchor)| self.gpu.project_anchor(&anchor).map(|projection| (text, projection)))
+            .collect();
+        let labels = if projected.is_empty() {
+            Vec::new()
+        } else {
+            let pass = self.labels.get_or_insert_with(|| LabelPass::new(&self.gpu.device));
+            let size = self.gpu.render_size();
+            let spread = size.0 as f32 / self.pipeline.cols() as f32;
+            let anchors: Vec<_> = projected.iter().map(|(_, projection)| *projection).collect();
+            let visibility = pass.run(&self.gpu.device, &self.gpu.queue, self.gpu.depth_texture_view(), size, &anchors, spread)?;
+            projected
+                .into_iter()
+                .zip(visibility)
+                .filter(|&(_, visible)| visible > 0.0)
+                .map(|((text, (position, _)), visible)| (text, position, visible))
+                .collect()
+        };
+
+        // The stereogram reads the depth buffer before the split pane redraws the scene
+        if request.stereogram {
+            let device = &self.gpu.device;
+            let (cols, rows) = (self.pipeline.cols(), self.pipeline.rows());
+            let pass = match self.stereogram {
+                Some(ref mut pass) => {
+                    pass.resize(device, cols, rows);
+                    pass
+                }
+                None => self.stereogram.insert(StereogramPass::new(device, cols, rows)),
+            };
+            cells = pass.run(device, &self.gpu.queue, self.gpu.depth_texture_view(), self.gpu.render_size(), self.gpu.clip_planes(), (near, far))?;
+        }
+
+        // Double-wide cells are rendered square at half the columns, then each fills two columns
+        let mut cols = self.pipeline.cols();
+        if request.double_wide {
+            cells = cells.iter().flat_map(|&cell| [cell, cell]).collect();
+            cols *= 2;
+        }
+
+        // Right pane of the split view reuses the same render target; accumulation carries on
+        // converging the main pane only
+        let split = match (request.split, self.split_pipeline.as_mut()) {
+            (Some((_, lighting, suppress_background)), Some(split_pipe)) => {
+                split_pipe.set_suppress_background(suppress_background);
+                self.gpu.pause_accumulation(true);
+                let data = render_to_cells(
+                    &self.gpu,
+                    split_pipe,
+                    request.pose,
+                    lighting,
+                    profiler.as_mut(),
+                );
+                self.gpu.pause_accumulation(false);
+                let data = data?;
+                Some((data, split_pipe.cols(), split_pipe.rows()))
+            }
+            _ => None,
+        };
+
+        Ok(FrameResult {
+            cells,
+            cols,
+            rows: self.pipeline.rows(),
+            split,
+            preview,
+            gpu_time_ms: gpu_start.elapsed().as_secs_f32() * 1000.0,
+            render_size: self.gpu.render_size(),
+            clamped: self.clamped,
+            samples: self.gpu.accumulated_samples(),
+            stage_times: profiler.map(|p| p.frame_times()).unwrap_or_default(),
+            labels,
+            picked,
+            life_population: self.gpu.life_population(),
+        })
+    }
+}
+
+/// Render texture size for a cell grid, reducing pixels per cell to stay within `max_dim`
+/// Returns (width, height, clamped)
+fn fit_render_size(cols: u32, rows: u32, px_x: u32, px_y: u32, max_dim: u32) -> (u32, u32, bool) {
+    let (width, height) = (cols * px_x, rows * px_y);
+    if width <= max_dim && height <= max_dim {
+        return (width, height, false);
+    }
+
+    // Scale both axes by the same factor to keep the cell aspect ratio
+    let scale = (max_dim as f32 / width as f32).min(max_dim as f32 / height as f32);
+    let px_x = ((px_x as f32 * scale) as u32).max(1);
+    let px_y = ((px_y as f32 * scale) as u32).max(1);
+    ((cols * px_x).min(max_dim), (rows * px_y).min(max_dim), true)
+}
+
+/// Render the scene with the given lighting and convert it through an ASCII pipeline
+/// With a profiler, the GPU is waited on after each pass so the stage timings are attributable
+fn render_to_cells(
+    gpu: &HeadlessGpu,
+    pipeline: &mut AsciiPipeline,
+    pose: CameraPose,
+    lighting: LightingMode,
+    mut profiler: Option<&mut FrameProfiler>,
+) -> Result<Vec<u32>> {
+    let render_cmd = match pose {
+        CameraPose::Manual { pitch, yaw, zoom } => gpu.render_manual(pitch, yaw, zoom, lighting),
+        CameraPose::Model { model, distance } => gpu.render_with_model(model, distance, lighting),
+        CameraPose::View(view) => gpu.render_with_view(view, lighting),
+        CameraPose::Rotation { time, mode, speed, distance, orbit } => {
+            gpu.render_with_rotation(time, mode, speed, distance, orbit, lighting)
+        }
+    };
+    if let Some(ref mut profiler) = profiler {
+        profiler.record(Stage::Upload);
+    }
+    gpu.queue.submit(std::iter::once(render_cmd));
+    if let Some(ref mut profiler) = profiler {
+        gpu.device.poll(wgpu::Maintain::Wait);
+        profiler.record(Stage::Scene);
+    }
+
+    // Update pipeline bind groups with color and depth textures
+    let (near, far) = gpu.clip_planes();
+    pipeline.set_clip_planes(near, far);
+    pipeline.update_bind_groups(
+        &gpu.device,
+        &gpu.queue,
+        gpu.render_texture_view(),
+        gpu.depth_texture_view(),
+        gpu.motion_texture_view(),
+        gpu.part_id_texture_view(),
+    );
+
+    // Run edge-aware compute pipeline
+    let mut encoder = gpu
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Pipeline Encoder"),
+        });
+
+    pipeline.dispatch(&mut encoder);
+    pipeline.copy_to_staging(&mut encoder);
+
+    gpu.queue.submit(std::iter::once(encoder.finish()));
+    if let Some(ref mut profiler) = profiler {
+        gpu.device.poll(wgpu::Maintain::Wait);
+        profiler.record(Stage::Compute);
+    }
+
+    // Read results (includes GPU sync)
+    let cells = pollster::block_on(pipeline.read_results(&gpu.device));
+    if let Some(profiler) = profiler {
+        profiler.record(Stage::Readback);
+    }
+    cells
+}
+
+type RenderCall = Box<dyn FnOnce(&mut RenderState) -> Result<()> + Send>;
+
+enum Command {
+    Frame(Box<FrameRequest>),
+    Call(RenderCall, Sender<Result<()>>),
+}
+
+/// Handle to the thread that owns the GPU
+/// Frames are requested one at a time so input and the status bar never wait on the GPU
+pub struct RenderThread {
+    commands: Sender<Command>,
+    frames: Receiver<Result<FrameResult>>,
+    in_flight: bool,
+    gpu_name: String,
+    max_texture_dimension: u32,
+}
+
+impl RenderThread {
+    /// Start the render thread and wait for the GPU to initialize
+    pub fn spawn(dims: PaneDims) -> Result<Self> {
+        let (command_tx, command_rx) = mpsc::channel::<Command>();
+        let (frame_tx, frame_rx) = mpsc::channel();
+        let (init_tx, init_rx) = mpsc::channel();
+
+        std::thread::Builder::new()
+            .name("render".to_string())
+            .spawn(move || {
+                let mut state = match RenderState::new(dims) {
+                    Ok(state) => {
+                        let _ = init_tx.send(Ok((
+                            state.gpu.gpu_name().to_string(),
+                            state.gpu.max_texture_dimension(),
+                        )));
+                        state
+                    }
+                    Err(e) => {
+                        let _ = init_tx.send(Err(e));
+                        return;
+                    }
+                };
+
+                // Runs until the handle is dropped
+                while let Ok(command) = command_rx.recv() {
+                    match command {
+                        Command::Frame(request) => {
+                            if frame_tx.send(state.render(*request)).is_err() {
+                                break;
+                            }
+                        }
+                        Command::Call(f, reply) => {
+                            let _ = reply.send(f(&mut state));
+                        }
+                    }
+                }
+            })?;
+
+        let (gpu_name, max_texture_dimension) = init_rx
+            .recv()
+            .map_err(|_| anyhow!("Render thread stopped during GPU initialization"))??;
+
+        Ok(Self {
+            commands: command_tx,
+            frames: frame_rx,
+            in_flight: false,
+            gpu_name,
+            max_texture_dimension,
+        })
+    }
+
+    pub fn gpu_name(&self) -> &str {
+        &self.gpu_name
+    }
+
+    pub fn max_texture_dimension(&self) -> u32 {
+        self.max_texture_dimension
+    }
+
+    /// Whether the render thread is ready for another frame request
+    pub fn is_idle(&self) -> bool {
+        !self.in_flight
+    }
+
+    /// Queue a frame (callers check `is_idle` first so requests don't pile up)
+    pub fn submit(&mut self, request: FrameRequest) -> Result<()> {
+        self.commands
+            .send(Command::Frame(Box::new(request)))
+            .map_err(|_| anyhow!("Render thread stopped unexpectedly"))?;
+        self.in_flight = true;
+        Ok(())
+    }
+
+    /// The finished frame, if any (waits for the in-flight frame when `block` is set)
+    pub fn poll(&mut self, block: bool) -> Result<Option<FrameResult>> {
+        if !self.in_flight {
+            return Ok(None);
+        }
+        let result = if block {
+            self.frames.recv().map_err(|_| TryRecvError::Disconnected)
+        } else {
+            self.frames.try_recv()
+        };
+        match result {
+            Ok(frame) => {
+                self.in_flight = false;
+                frame.map(Some)
+            }
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(anyhow!("Render thread stopped unexpectedly")),
+        }
+    }
+
+    /// Run `f` on the render thread and wait for it (waits behind an in-flight frame)
+    pub fn call(&self, f: impl FnOnce(&mut RenderState) -> Result<()> + Send + 'static) -> Result<()> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        self.commands
+            .send(Command::Call(Box::new(f), reply_tx))
+            .map_err(|_| anyhow!("Render thread stopped unexpectedly"))?;
+        reply_rx
+            .recv()
+            .map_err(|_| anyhow!("Render thread stopped unexpectedly"))?
+    }
+}
@@ -0,0 +1,481 @@
+//! Constructive solid geometry on polygon soups, using BSP trees (after Evan Wallace's csg.js)
+
+use glam::{Quat, Vec3};
+
+use super::shapes::MeshBuilder;
+use crate::gpu::Vertex;
+use crate::model::ModelData;
+
+/// Distance within which a point counts as lying on a plane
+const EPSILON: f32 = 1e-5;
+
+/// Sides of a plane a vertex or polygon can be on (bit flags, spanning = front | back)
+const COPLANAR: u8 = 0;
+const FRONT: u8 = 1;
+const BACK: u8 = 2;
+const SPANNING: u8 = 3;
+
+/// Colors of the surfaces contributed by the base and tool solids
+const BASE_COLOR: [f32; 3] = [0.85, 0.55, 0.25];
+const TOOL_COLOR: [f32; 3] = [0.3, 0.65, 0.95];
+const STAGE_COLOR: [f32; 3] = [0.25, 0.25, 0.3];
+
+/// How far the tool slides either side of the base, and how fast (radians per second)
+const TOOL_TRAVEL: f32 = 0.45;
+const TOOL_SPEED: f32 = 0.8;
+
+#[derive(Clone, Copy)]
+struct CsgVertex {
+    pos: Vec3,
+    normal: Vec3,
+}
+
+impl CsgVertex {
+    fn lerp(&self, other: &CsgVertex, t: f32) -> CsgVertex {
+        CsgVertex {
+            pos: self.pos.lerp(other.pos, t),
+            normal: self.normal.lerp(other.normal, t),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Plane {
+    normal: Vec3,
+    w: f32,
+}
+
+impl Plane {
+    /// Plane through three points, facing the side they wind counter-clockwise around
+    fn from_points(a: Vec3, b: Vec3, c: Vec3) -> Option<Plane> {
+        let normal = (b - a).cross(c - a).try_normalize()?;
+        Some(Plane { normal, w: normal.dot(a) })
+    }
+
+    fn flip(&mut self) {
+        self.normal = -self.normal;
+        self.w = -self.w;
+    }
+
+    /// Sort a polygon into the lists for its side of this plane, splitting it if it spans both
+    fn split_polygon(
+        &self,
+        polygon: &Polygon,
+        coplanar_front: &mut Vec<Polygon>,
+        coplanar_back: &mut Vec<Polygon>,
+        front: &mut Vec<Polygon>,
+        back: &mut Vec<Polygon>,
+    ) {
+        let mut polygon_type = COPLANAR;
+        let types: Vec<u8> = polygon
+            .vertices
+            .iter()
+            .map(|v| {
+                let t = self.normal.dot(v.pos) - self.w;
+                let side = if t < -EPSILON {
+                    BACK
+                } else if t > EPSILON {
+                    FRONT
+                } else {
+                    COPLANAR
+                };
+                polygon_type |= side;
+                side
+            })
+            .collect();
+
+        match polygon_type {
+            COPLANAR if self.normal.dot(polygon.plane.normal) > 0.0 => coplanar_front.push(polygon.clone()),
+            COPLANAR => coplanar_back.push(polygon.clone()),
+            FRONT => front.push(polygon.clone()),
+            BACK => back.push(polygon.clone()),
+            _ => {
+                let (mut f, mut b) = (Vec::new(), Vec::new());
+                let count = polygon.vertices.len();
+                for i in 0..count {
+                    let j = (i + 1) % count;
+                    let (ti, tj) = (types[i], types[j]);
+                    let (vi, vj) = (&polygon.vertices[i], &polygon.vertices[j]);
+                    if ti != BACK {
+                        f.push(*vi);
+                    }
+                    if ti != FRONT {
+                        b.push(*vi);
+                    }
+                    if ti | tj == SPANNING {
+                        let t = (self.w - self.normal.dot(vi.pos)) / self.normal.dot(vj.pos - vi.pos);
+                        let v = vi.lerp(vj, t);
+                        f.push(v);
+                        b.push(v);
+                    }
+                }
+                // Pieces keep the parent's plane: recomputing it from sliver triangles is unstable
+                if f.len() >= 3 {
+                    front.push(Polygon { vertices: f, ..polygon.clone_empty() });
+                }
+                if b.len() >= 3 {
+                    back.push(Polygon { vertices: b, ..polygon.clone_empty() });
+                }
+            }
+        }
+    }
+}
+
+/// A convex planar polygon, tagged with the part of the scene it came from
+#[derive(Clone)]
+struct Polygon {
+    vertices: Vec<CsgVertex>,
+    plane: Plane,
+    part: usize,
+}
+
+impl Polygon {
+    /// None for degenerate polygons
+    fn new(vertices: Vec<CsgVertex>, part: usize) -> Option<Polygon> {
+        let plane = Plane::from_points(vertices[0].pos, vertices[1].pos, vertices[2].pos)?;
+        Some(Polygon { vertices, plane, part })
+    }
+
+    fn clone_empty(&self) -> Polygon {
+        Polygon {
+            vertices: Vec::new(),
+            plane: self.plane,
+            part: self.part,
+        }
+    }
+
+    fn flip(&mut self) {
+        self.vertices.reverse();
+        for v in &mut self.vertices {
+            v.normal = -v.normal;
+        }
+        self.plane.flip();
+    }
+}
+
+/// BSP tree node: polygons on its splitting plane, with subtrees in front of and behind it
+#[derive(Default)]
+struct Node {
+    plane: Option<Plane>,
+    front: Option<Box<Node>>,
+    back: Option<Box<Node>>,
+    polygons: Vec<Polygon>,
+}
+
+impl Node {
+    fn new(polygons: Vec<Polygon>) -> Node {
+        let mut node = Node::default();
+        node.build(polygons);
+        node
+    }
+
+    /// Swap solid and empty space
+    fn invert(&mut self) {
+        for polygon in &mut self.polygons {
+            polygon.flip();
+        }
+        if let Some(plane) = &mut self.plane {
+            plane.flip();
+        }
+        if let Some(front) = &mut self.front {
+            front.invert();
+        }
+        if let Some(back) = &mut self.back {
+            back.invert();
+        }
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    /// Remove the parts of `polygons` inside this tree's solid
+    fn clip_polygons(&self, polygons: Vec<Polygon>) -> Vec<Polygon> {
+        let Some(plane) = self.plane else {
+            return polygons;
+        };
+        let (mut front, mut back) = (Vec::new(), Vec::new());
+        for polygon in &polygons {
+            let (mut coplanar_front, mut coplanar_back) = (Vec::new(), Vec::new());
+            plane.split_polygon(polygon, &mut coplanar_front, &mut coplanar_back, &mut front, &mut back);
+            front.append(&mut coplanar_front);
+            back.append(&mut coplanar_back);
+        }
+        let mut front = match &self.front {
+            Some(node) => node.clip_polygons(front),
+            None => front,
+        };
+        if let Some(node) = &self.back {
+            front.extend(node.clip_polygons(back));
+        }
+        front
+    }
+
+    /// Remove the parts of this tree's polygons inside `bsp`
+    fn clip_to(&mut self, bsp: &Node) {
+        self.polygons = bsp.clip_polygons(std::mem::take(&mut self.polygons));
+        if let Some(front) = &mut self.front {
+            front.clip_to(bsp);
+        }
+        if let Some(back) = &mut self.back {
+            back.clip_to(bsp);
+        }
+    }
+
+    fn all_polygons(&self) -> Vec<Polygon> {
+        let mut polygons = self.polygons.clone();
+        if let Some(front) = &self.front {
+            polygons.extend(front.all_polygons());
+        }
+        if let Some(back) = &self.back {
+            polygons.extend(back.all_polygons());
+        }
+        polygons
+    }
+
+    /// Add polygons to the tree, splitting them by the planes already in it
+    fn build(&mut self, polygons: Vec<Polygon>) {
+        let Some(first) = polygons.first() else {
+            return;
+        };
+        let plane = *self.plane.get_or_insert(first.plane);
+        let (mut front, mut back) = (Vec::new(), Vec::new());
+        let (mut coplanar_front, mut coplanar_back) = (Vec::new(), Vec::new());
+        for polygon in &polygons {
+            plane.split_polygon(polygon, &mut coplanar_front, &mut coplanar_back, &mut front, &mut back);
+        }
+        self.polygons.append(&mut coplanar_front);
+        self.polygons.append(&mut coplanar_back);
+        if !front.is_empty() {
+            self.front.get_or_insert_with(Default::default).build(front);
+        }
+        if !back.is_empty() {
+            self.back.get_or_insert_with(Default::default).build(back);
+        }
+    }
+}
+
+/// Boolean operation combining the base and tool solids
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsgOp {
+    Union,
+    Subtract,
+    Intersect,
+}
+
+impl CsgOp {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CsgOp::Union => "Union",
+            CsgOp::Subtract => "Subtract",
+            CsgOp::Intersect => "Intersect",
+        }
+    }
+
+    pub fn next(&self) -> CsgOp {
+        match self {
+            CsgOp::Union => CsgOp::Subtract,
+            CsgOp::Subtract => CsgOp::Intersect,
+            CsgOp::Intersect => CsgOp::Union,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            CsgOp::Union => "+",
+            CsgOp::Subtract => "-",
+            CsgOp::Intersect => "&",
+        }
+    }
+
+    fn apply(&self, a: Vec<Polygon>, b: Vec<Polygon>) -> Vec<Polygon> {
+        let (mut a, mut b) = (Node::new(a), Node::new(b));
+        match self {
+            CsgOp::Union => {
+                a.clip_to(&b);
+                b.clip_to(&a);
+                b.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.build(b.all_polygons());
+            }
+            CsgOp::Subtract => {
+                a.invert();
+                a.clip_to(&b);
+                b.clip_to(&a);
+                b.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.build(b.all_polygons());
+                a.invert();
+            }
+            CsgOp::Intersect => {
+                a.invert();
+                b.clip_to(&a);
+                b.invert();
+                a.clip_to(&b);
+                b.clip_to(&a);
+                a.build(b.all_polygons());
+                a.invert();
+            }
+        }
+        a.all_polygons()
+    }
+}
+
+/// Procedural primitive solid
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsgShape {
+    Cube,
+    Sphere,
+    Cylinder,
+}
+
+impl CsgShape {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CsgShape::Cube => "Cube",
+            CsgShape::Sphere => "Sphere",
+            CsgShape::Cylinder => "Cylinder",
+        }
+    }
+
+    pub fn next(&self) -> CsgShape {
+        match self {
+            CsgShape::Cube => CsgShape::Sphere,
+            CsgShape::Sphere => CsgShape::Cylinder,
+            CsgShape::Cylinder => CsgShape::Cube,
+        }
+    }
+
+    /// Closed polygon surface of the shape, sized to fit a box of half-extent `size`
+    fn polygons(&self, center: Vec3, size: f32, part: usize) -> Vec<Polygon> {
+        let vertex = |pos: Vec3, normal: Vec3| CsgVertex { pos, normal };
+        let mut polygons = Vec::new();
+        match self {
+            CsgShape::Cube => {
+                // Corner i has x, y, z on the positive side when bits 0, 1, 2 are set
+                let corner = |i: usize| {
+                    center + Vec3::new(
+                        if i & 1 != 0 { size } else { -size },
+                        if i & 2 != 0 { size } else { -size },
+                        if i & 4 != 0 { size } else { -size },
+                    )
+                };
+                let faces = [
+                    ([0, 4, 6, 2], Vec3::NEG_X),
+                    ([1, 3, 7, 5], Vec3::X),
+                    ([0, 1, 5, 4], Vec3::NEG_Y),
+                    ([2, 6, 7, 3], Vec3::Y),
+                    ([0, 2, 3, 1], Vec3::NEG_Z),
+                    ([4, 5, 7, 6], Vec3::Z),
+                ];
+                for (corners, normal) in faces {
+                    polygons.extend(Polygon::new(corners.iter().map(|&i| vertex(corner(i), normal)).collect(), part));
+                }
+            }
+            CsgShape::Sphere => {
+                let (slices, stacks) = (16, 8);
+                let point = |i: usize, j: usize| {
+                    let theta = i as f32 / slices as f32 * std::f32::consts::TAU;
+                    let phi = j as f32 / stacks as f32 * std::f32::consts::PI;
+                    let dir = Vec3::new(theta.cos() * phi.sin(), phi.cos(), theta.sin() * phi.sin());
+                    vertex(center + dir * size, dir)
+                };
+                for i in 0..slices {
+                    for j in 0..stacks {
+                        // Triangles at the poles, quads elsewhere
+                        let mut vertices = vec![point(i, j)];
+                        if j > 0 {
+                            vertices.push(point(i + 1, j));
+                        }
+                        if j < stacks - 1 {
+                            vertices.push(point(i + 1, j + 1));
+                        }
+                        vertices.push(point(i, j + 1));
+                        polygons.extend(Polygon::new(vertices, part));
+                    }
+                }
+            }
+            CsgShape::Cylinder => {
+                let slices = 24;
+                let (top, bottom) = (center + Vec3::Y * size, center - Vec3::Y * size);
+                let radius = size * 0.8;
+                for i in 0..slices {
+                    let a0 = i as f32 / slices as f32 * std::f32::consts::TAU;
+                    let a1 = (i + 1) as f32 / slices as f32 * std::f32::consts::TAU;
+                    let (d0, d1) = (Vec3::new(a0.cos(), 0.0, a0.sin()), Vec3::new(a1.cos(), 0.0, a1.sin()));
+                    let side = [bottom + d0 * radius, top + d0 * radius, top + d1 * radius, bottom + d1 * radius];
+                    let normals = [d0, d0, d1, d1];
+                    polygons.extend(Polygon::new(side.into_iter().zip(normals).map(|(p, n)| vertex(p, n)).collect(), part));
+                    polygons.extend(Polygon::new(
+                        vec![vertex(top, Vec3::Y), vertex(top + d1 * radius, Vec3::Y), vertex(top + d0 * radius, Vec3::Y)],
+                        part,
+                    ));
+                    polygons.extend(Polygon::new(
+                        vec![
+                            vertex(bottom, Vec3::NEG_Y),
+                            vertex(bottom + d0 * radius, Vec3::NEG_Y),
+                            vertex(bottom + d1 * radius, Vec3::NEG_Y),
+                        ],
+                        part,
+                    ));
+                }
+            }
+        }
+        polygons
+    }
+}
+
+/// Interactive CSG scene: a base solid combined with a tool solid that slides through it
+pub struct CsgPlayground {
+    pub operation: CsgOp,
+    pub base: CsgShape,
+    pub tool: CsgShape,
+}
+
+impl CsgPlayground {
+    pub fn new() -> Self {
+        Self {
+            operation: CsgOp::Subtract,
+            base: CsgShape::Cube,
+            tool: CsgShape::Sphere,
+        }
+    }
+
+    /// e.g. "Cube - Sphere"
+    pub fn label(&self) -> String {
+        format!("{} {} {}", self.base.name(), self.operation.symbol(), self.tool.name())
+    }
+
+    /// Build the combined solid with the tool at its position for animation time `time`,
+    /// standing on a fixed stage so the view's scale stays put as the result changes shape
+    pub fn build_model(&self, time: f32) -> ModelData {
+        let offset = Vec3::new((time * TOOL_SPEED).sin() * TOOL_TRAVEL, 0.0, 0.0);
+        let base = self.base.polygons(Vec3::ZERO, 0.6, 0);
+        let tool = self.tool.polygons(offset, 0.5, 1);
+        let result = self.operation.apply(base, tool);
+
+        let mut mesh = MeshBuilder::new();
+        mesh.begin_part();
+        mesh.push_box(Vec3::new(0.0, -0.72, 0.0), Vec3::new(1.1, 0.04, 1.1), Quat::IDENTITY, STAGE_COLOR);
+        mesh.end_part();
+
+        // Base and tool surfaces form separate parts, so explode pulls the cut apart
+        for part in 0..2 {
+            let color = if part == 0 { BASE_COLOR } else { TOOL_COLOR };
+            mesh.begin_part();
+            for polygon in result.iter().filter(|p| p.part == part) {
+                let first = mesh.vertices.len() as u32;
+                mesh.vertices.extend(polygon.vertices.iter().map(|v| Vertex {
+                    position: v.pos.to_array(),
+                    normal: v.normal.to_array(),
+                    color,
+                }));
+                for i in 1..polygon.vertices.len() as u32 - 1 {
+                    mesh.indices.extend_from_slice(&[first, first + i, first + i + 1]);
+                }
+            }
+            mesh.end_part();
+        }
+
+        ModelData::from_parts(mesh.vertices, mesh.indices, mesh.ranges)
+    }
+}
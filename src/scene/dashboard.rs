@@ -0,0 +1,234 @@
+use chrono::Timelike;
+use glam::{Quat, Vec3};
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+use super::shapes::MeshBuilder;
+use crate::model::ModelData;
+
+/// How often the system gauges re-sample CPU and memory usage
+const GAUGE_REFRESH: Duration = Duration::from_millis(500);
+
+/// Maximum number of CPU bars (extra cores are averaged into groups)
+const MAX_CPU_BARS: usize = 8;
+
+/// Height of a full gauge bar
+const BAR_HEIGHT: f32 = 1.6;
+
+/// Usage levels shown by frozen gauges (CPU bars, then memory)
+const FROZEN_CPU_LEVELS: &[f32] = &[0.2, 0.45, 0.7, 0.95];
+const FROZEN_MEMORY_LEVEL: f32 = 0.6;
+
+/// Seconds since local midnight
+pub fn local_time_of_day() -> f32 {
+    let now = chrono::Local::now();
+    now.num_seconds_from_midnight() as f32 + now.nanosecond().min(999_999_999) as f32 / 1e9
+}
+
+/// Build an analog clock showing a time of day (seconds since midnight)
+pub fn build_clock_model(time_of_day: f32) -> ModelData {
+    let seconds = time_of_day.rem_euclid(60.0);
+    let minutes = (time_of_day / 60.0).rem_euclid(60.0);
+    let hours = (time_of_day / 3600.0).rem_euclid(12.0);
+
+    let mut mesh = MeshBuilder::new();
+
+    // Face and rim
+    mesh.begin_part();
+    mesh.push_cylinder(Vec3::new(0.0, 0.0, -0.06), 1.0, 0.04, 64, [0.15, 0.17, 0.22]);
+    mesh.end_part();
+
+    // Hour and minute ticks
+    mesh.begin_part();
+    for i in 0..60 {
+        let angle = i as f32 / 60.0 * std::f32::consts::TAU;
+        let (length, width, color) = if i % 5 == 0 {
+            (0.16, 0.045, [0.95, 0.95, 0.9])
+        } else {
+            (0.06, 0.015, [0.6, 0.6, 0.65])
+        };
+        let rotation = Quat::from_rotation_z(-angle);
+        let center = rotation * Vec3::new(0.0, 0.92 - length * 0.5, 0.0);
+        mesh.push_box(center, Vec3::new(width, length * 0.5, 0.02), rotation, color);
+    }
+    mesh.end_part();
+
+    // Hands: fraction of a turn, length, half-width, depth offset, color
+    let hands = [
+        (hours / 12.0, 0.5, 0.045, 0.0, [0.9, 0.85, 0.7]),
+        (minutes / 60.0, 0.78, 0.03, 0.04, [0.85, 0.9, 1.0]),
+        (seconds / 60.0, 0.86, 0.012, 0.08, [1.0, 0.25, 0.2]),
+    ];
+    for (turn, length, half_width, z, color) in hands {
+        // Clockwise from 12 o'clock, with a short tail past the pivot
+        let rotation = Quat::from_rotation_z(-turn * std::f32::consts::TAU);
+        let tail = 0.12;
+        let center = rotation * Vec3::new(0.0, (length - tail) * 0.5, z);
+        mesh.begin_part();
+        mesh.push_box(
+            center,
+            Vec3::new(half_width, (length + tail) * 0.5, 0.015),
+            rotation,
+            color,
+        );
+        mesh.end_part();
+    }
+
+    // Center cap
+    mesh.begin_part();
+    mesh.push_cylinder(Vec3::new(0.0, 0.0, 0.06), 0.06, 0.05, 24, [0.8, 0.8, 0.8]);
+    mesh.end_part();
+
+    ModelData::from_parts(mesh.vertices, mesh.indices, mesh.ranges)
+}
+
+/// Live CPU and memory usage rendered as 3D bar gauges
+pub struct SystemGauges {
+    system: System,
+    last_refresh: Option<Instant>,
+    /// Show a fixed sample instead of live usage (deterministic mode)
+    frozen: bool,
+}
+
+impl SystemGauges {
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+            last_refresh: None,
+            frozen: false,
+        }
+    }
+
+    /// Gauges that always show the same sample, for reproducible output
+    pub fn frozen() -> Self {
+        Self {
+            frozen: true,
+            ..Self::new()
+        }
+    }
+
+    /// Re-sample usage if the refresh interval has passed
+    /// Returns true when new values are available
+    pub fn update(&mut self) -> bool {
+        if self.frozen {
+            return self.last_refresh.replace(Instant::now()).is_none();
+        }
+        if self
+            .last_refresh
+            .is_some_and(|t| t.elapsed() < GAUGE_REFRESH)
+        {
+            return false;
+        }
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+        self.last_refresh = Some(Instant::now());
+        true
+    }
+
+    /// CPU usage per bar (0-1), grouping cores when there are more than MAX_CPU_BARS
+    fn cpu_levels(&self) -> Vec<f32> {
+        if self.frozen {
+            return FROZEN_CPU_LEVELS.to_vec();
+        }
+        let usage: Vec<f32> = self
+            .system
+            .cpus()
+            .iter()
+            .map(|c| c.cpu_usage() / 100.0)
+            .collect();
+        if usage.is_empty() {
+            return vec![self.system.global_cpu_usage() / 100.0];
+        }
+        let group = usage.len().div_ceil(MAX_CPU_BARS);
+        usage
+            .chunks(group)
+            .map(|c| c.iter().sum::<f32>() / c.len() as f32)
+            .collect()
+    }
+
+    /// Fraction of memory in use (0-1)
+    fn memory_level(&self) -> f32 {
+        if self.frozen {
+            return FROZEN_MEMORY_LEVEL;
+        }
+        let total = self.system.total_memory();
+        if total == 0 {
+            0.0
+        } else {
+            self.system.used_memory() as f32 / total as f32
+        }
+    }
+
+    /// Build the gauge geometry from the latest sample
+    /// Every bar has a full-height track so the model bounds stay constant
+    pub fn build_model(&mut self) -> ModelData {
+        if self.last_refresh.is_none() {
+            self.update();
+        }
+
+        let cpu = self.cpu_levels();
+        let memory = self.memory_level();
+
+        // CPU bars, a gap, then the memory bar
+        let spacing = 0.3;
+        let half_width = 0.11;
+        let total_width = (cpu.len() + 1) as f32 * spacing + spacing;
+        let start_x = -total_width * 0.5 + spacing * 0.5;
+        let base_y = -BAR_HEIGHT * 0.5;
+
+        let mut mesh = MeshBuilder::new();
+
+        // Base plate
+        mesh.begin_part();
+        mesh.push_box(
+            Vec3::new(0.0, base_y - 0.05, 0.0),
+            Vec3::new(total_width * 0.5, 0.05, 0.25),
+            Quat::IDENTITY,
+            [0.25, 0.25, 0.3],
+        );
+        mesh.end_part();
+
+        let bars = cpu
+            .iter()
+            .enumerate()
+            .map(|(i, &level)| (start_x + i as f32 * spacing, level, usage_color(level)))
+            .chain(std::iter::once((
+                start_x + (cpu.len() as f32 + 1.0) * spacing,
+                memory,
+                [0.35, 0.55, 1.0],
+            )));
+
+        for (x, level, color) in bars {
+            let level = level.clamp(0.0, 1.0);
+            let fill = (level * BAR_HEIGHT).max(0.02);
+
+            mesh.begin_part();
+            // Dim track behind the fill
+            mesh.push_box(
+                Vec3::new(x, 0.0, -0.08),
+                Vec3::new(half_width, BAR_HEIGHT * 0.5, 0.03),
+                Quat::IDENTITY,
+                [0.12, 0.12, 0.15],
+            );
+            mesh.push_box(
+                Vec3::new(x, base_y + fill * 0.5, 0.0),
+                Vec3::new(half_width, fill * 0.5, half_width),
+                Quat::IDENTITY,
+                color,
+            );
+            mesh.end_part();
+        }
+
+        ModelData::from_parts(mesh.vertices, mesh.indices, mesh.ranges)
+    }
+}
+
+/// Green at idle through yellow to red at full load
+fn usage_color(level: f32) -> [f32; 3] {
+    let t = level.clamp(0.0, 1.0);
+    if t < 0.5 {
+        [t * 2.0, 0.85, 0.2]
+    } else {
+        [1.0, 0.85 * (1.0 - (t - 0.5) * 2.0), 0.2]
+    }
+}
@@ -0,0 +1,90 @@
+use glam::{Mat4, Quat, Vec3};
+
+/// Arrangement used when drawing several copies of the model
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InstancePattern {
+    #[default]
+    Single,
+    Ring,
+    Grid,
+    Spiral,
+}
+
+impl InstancePattern {
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstancePattern::Single => "Single",
+            InstancePattern::Ring => "Ring",
+            InstancePattern::Grid => "Grid",
+            InstancePattern::Spiral => "Spiral",
+        }
+    }
+
+    pub fn all() -> &'static [InstancePattern] {
+        &[
+            InstancePattern::Single,
+            InstancePattern::Ring,
+            InstancePattern::Grid,
+            InstancePattern::Spiral,
+        ]
+    }
+}
+
+/// Per-instance transforms for a pattern, sized to stay inside the default view
+/// `Single` always yields one identity transform regardless of `count`
+pub fn instance_transforms(pattern: InstancePattern, count: u32) -> Vec<Mat4> {
+    let n = count.max(1);
+    match pattern {
+        InstancePattern::Single => vec![Mat4::IDENTITY],
+        InstancePattern::Ring => {
+            let radius = 1.6;
+            let scale = (2.4 / n as f32).clamp(0.15, 0.6);
+            (0..n)
+                .map(|i| {
+                    let angle = i as f32 / n as f32 * std::f32::consts::TAU;
+                    let position = Vec3::new(radius * angle.cos(), 0.0, radius * angle.sin());
+                    // Rotate each copy to face outward from the ring center
+                    Mat4::from_scale_rotation_translation(
+                        Vec3::splat(scale),
+                        Quat::from_rotation_y(-angle),
+                        position,
+                    )
+                })
+                .collect()
+        }
+        InstancePattern::Grid => {
+            let side = (n as f32).sqrt().ceil() as u32;
+            let spacing = 3.2 / side as f32;
+            let scale = spacing * 0.55;
+            let offset = (side - 1) as f32 * spacing * 0.5;
+            (0..n)
+                .map(|i| {
+                    let x = (i % side) as f32 * spacing - offset;
+                    let y = offset - (i / side) as f32 * spacing;
+                    Mat4::from_scale_rotation_translation(
+                        Vec3::splat(scale),
+                        Quat::IDENTITY,
+                        Vec3::new(x, y, 0.0),
+                    )
+                })
+                .collect()
+        }
+        InstancePattern::Spiral => {
+            let scale = (3.0 / n as f32).clamp(0.12, 0.4);
+            (0..n)
+                .map(|i| {
+                    let t = if n > 1 { i as f32 / (n - 1) as f32 } else { 0.5 };
+                    let angle = t * std::f32::consts::TAU * 2.0;
+                    let radius = 0.4 + t * 1.2;
+                    let position =
+                        Vec3::new(radius * angle.cos(), t * 2.4 - 1.2, radius * angle.sin());
+                    Mat4::from_scale_rotation_translation(
+                        Vec3::splat(scale),
+                        Quat::from_rotation_y(-angle),
+                        position,
+                    )
+                })
+                .collect()
+        }
+    }
+}
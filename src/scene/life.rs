@@ -0,0 +1,52 @@
+use glam::{Mat3, Quat, Vec3};
+
+use super::shapes::MeshBuilder;
+use crate::gpu::{LIFE_CELL_HEIGHT, LIFE_COLS, LIFE_RING_RADIUS, LIFE_ROWS, LIFE_TUBE_RADIUS};
+use crate::model::ModelData;
+
+const SURFACE_COLOR: [f32; 3] = [0.12, 0.14, 0.2];
+const LIVE_COLOR: [f32; 3] = [0.55, 1.0, 0.45];
+
+/// Empty torus for the GPU Game of Life: a surface quad per cell, then a box per cell collapsed
+/// to its center, laid out the way the life compute shader rewrites them (raising the boxes of
+/// live cells and tinting the surface where cells just died)
+pub fn build_life_model() -> ModelData {
+    let (cols, rows) = (LIFE_COLS as usize, LIFE_ROWS as usize);
+    let (du, dv) = (std::f32::consts::TAU / cols as f32, std::f32::consts::TAU / rows as f32);
+    let point = |u: f32, v: f32, radius: f32| {
+        Vec3::new((LIFE_RING_RADIUS + radius * v.cos()) * u.cos(), radius * v.sin(), (LIFE_RING_RADIUS + radius * v.cos()) * u.sin())
+    };
+    let normal = |u: f32, v: f32| Vec3::new(v.cos() * u.cos(), v.sin(), v.cos() * u.sin());
+
+    let mut mesh = MeshBuilder::new();
+    mesh.begin_part();
+    for row in 0..rows {
+        for col in 0..cols {
+            let (u0, v0) = (col as f32 * du, row as f32 * dv);
+            let (u1, v1) = (u0 + du, v0 + dv);
+            let (uc, vc) = (u0 + du / 2.0, v0 + dv / 2.0);
+            let radius = LIFE_TUBE_RADIUS;
+            mesh.push_quad(
+                [point(u0, v0, radius), point(u0, v1, radius), point(u1, v1, radius), point(u1, v0, radius)],
+                normal(uc, vc),
+                SURFACE_COLOR,
+            );
+        }
+    }
+    mesh.end_part();
+
+    mesh.begin_part();
+    for row in 0..rows {
+        for col in 0..cols {
+            let (uc, vc) = ((col as f32 + 0.5) * du, (row as f32 + 0.5) * dv);
+            let along_ring = Vec3::new(-uc.sin(), 0.0, uc.cos());
+            let rotation = Quat::from_mat3(&Mat3::from_cols(along_ring, normal(uc, vc), along_ring.cross(normal(uc, vc))));
+            mesh.push_box(point(uc, vc, LIFE_TUBE_RADIUS + LIFE_CELL_HEIGHT / 2.0), Vec3::ZERO, rotation, LIVE_COLOR);
+        }
+    }
+    mesh.end_part();
+
+    // Already sized to the view, and kept that way as live cells come and go at the edges
+    let (outer, tube) = (LIFE_RING_RADIUS + LIFE_TUBE_RADIUS + LIFE_CELL_HEIGHT, LIFE_TUBE_RADIUS + LIFE_CELL_HEIGHT);
+    ModelData::from_normalized_parts(mesh.vertices, mesh.indices, mesh.ranges, [outer * 2.0, tube * 2.0, outer * 2.0])
+}
@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Result};
+use glam::Vec3;
+use std::f32::consts::TAU;
+
+use super::text::{flat_glyphs, hue_to_rgb, push_triangle};
+use crate::gpu::{Material, MeshRange};
+use crate::model::ModelData;
+
+/// Distance of the letters from the model's center (just outside its 1.6 unit box)
+const RADIUS: f32 = 1.2;
+
+/// Letter size (em height in world units)
+const LETTER_SIZE: f32 = 0.2;
+
+/// Drop of the helix per turn, and the most turns it makes before the letters shrink to fit
+const HELIX_RISE: f32 = 0.3;
+const MAX_HELIX_TURNS: f32 = 3.0;
+
+/// Placed between repeats of the text around the ring
+const SEPARATOR: &str = "   *   ";
+
+/// Path the marquee text follows around the model
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MarqueePath {
+    #[default]
+    Off,
+    /// A flat ring, the text repeated to close the loop
+    Ring,
+    /// A helix spiralling down around the model
+    Helix,
+}
+
+impl MarqueePath {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarqueePath::Off => "Off",
+            MarqueePath::Ring => "Ring",
+            MarqueePath::Helix => "Helix",
+        }
+    }
+
+    pub fn all() -> &'static [MarqueePath] {
+        &[MarqueePath::Off, MarqueePath::Ring, MarqueePath::Helix]
+    }
+}
+
+/// Flat letters wrapped around the model along a path, facing outwards and colored in a rainbow
+/// (an empty mesh when the path is `Off` or the text is blank)
+pub fn build_marquee_model(text: &str, path: MarqueePath) -> Result<ModelData> {
+    let text = text.trim();
+    if path == MarqueePath::Off || text.is_empty() {
+        return Ok(ModelData::from_normalized_parts(Vec::new(), Vec::new(), Vec::new(), [0.0; 3]));
+    }
+
+    let circumference = TAU * RADIUS;
+    let (glyphs, width, size, drop_per_turn) = match path {
+        MarqueePath::Ring => {
+            // Repeat the text as often as fits, then size the letters so the loop closes exactly
+            let (_, unit) = flat_glyphs(&format!("{}{}", text, SEPARATOR))?;
+            let repeats = (circumference / (unit * LETTER_SIZE)).round().max(1.0) as usize;
+            let (glyphs, width) = flat_glyphs(&format!("{}{}", text, SEPARATOR).repeat(repeats))?;
+            (glyphs, width, circumference / width, 0.0)
+        }
+        _ => {
+            let (glyphs, width) = flat_glyphs(text)?;
+            (glyphs, width, LETTER_SIZE.min(circumference * MAX_HELIX_TURNS / width), HELIX_RISE)
+        }
+    };
+    if glyphs.is_empty() {
+        return Err(anyhow!("Marquee text {:?} has no visible glyphs", text));
+    }
+
+    // Arc length along the path becomes the angle around the model; the helix also drops as it
+    // goes, starting high enough that the whole spiral is centered
+    let drop = drop_per_turn * width * size / circumference;
+    let place = |[x, y]: [f32; 2]| {
+        let angle = x * size / RADIUS;
+        let height = (y - 0.35) * size + drop / 2.0 - drop_per_turn * angle / TAU;
+        Vec3::new(RADIUS * angle.sin(), height, RADIUS * angle.cos())
+    };
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let count = glyphs.len();
+    for (i, triangles) in glyphs.into_iter().enumerate() {
+        let color = hue_to_rgb(i as f32 / count as f32);
+        for corners in triangles {
+            let corners = corners.map(place);
+            let center = (corners[0] + corners[1] + corners[2]) / 3.0;
+            let outward = Vec3::new(center.x, 0.0, center.z).normalize_or_zero();
+            // Both sides, so the far side of the loop shows (mirrored) through the middle
+            push_triangle(&mut vertices, &mut indices, corners, outward, color);
+            push_triangle(&mut vertices, &mut indices, corners, -outward, color);
+        }
+    }
+
+    let ranges = vec![MeshRange {
+        first_index: 0,
+        index_count: indices.len() as u32,
+        centroid: [0.0; 3],
+        extent: 0.0,
+        material: Material::default(),
+    }];
+    let diameter = RADIUS * 2.0;
+    Ok(ModelData::from_normalized_parts(vertices, indices, ranges, [diameter, drop + size, diameter]))
+}
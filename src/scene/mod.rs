@@ -0,0 +1,98 @@
+mod csg;
+mod dashboard;
+mod instancing;
+mod life;
+mod marquee;
+mod physics;
+mod plant;
+mod shapes;
+mod solar;
+mod terrain;
+mod text;
+mod water;
+
+pub use csg::CsgPlayground;
+pub use dashboard::{build_clock_model, local_time_of_day, SystemGauges};
+pub use instancing::{instance_transforms, InstancePattern};
+pub use life::build_life_model;
+pub use marquee::{build_marquee_model, MarqueePath};
+pub use physics::PhysicsSim;
+pub use plant::PlantScene;
+pub use shapes::write_primitive_models;
+pub use solar::{load_solar_bodies, SolarSystem};
+pub use terrain::Terrain;
+pub use text::build_text_model;
+pub use water::build_water_model;
+
+use crate::gpu::SdfScene;
+
+/// Procedural scenes listed alongside model files in the config UI
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuiltinScene {
+    Text,
+    Clock,
+    SystemInfo,
+    Csg,
+    Mandelbulb,
+    Gyroid,
+    RoundedBoxes,
+    Terrain,
+    Plant,
+    Life,
+    Water,
+    SolarSystem,
+}
+
+impl BuiltinScene {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuiltinScene::Text => "3D Text",
+            BuiltinScene::Clock => "Analog Clock",
+            BuiltinScene::SystemInfo => "CPU/RAM Gauges",
+            BuiltinScene::Csg => "CSG Playground",
+            BuiltinScene::Mandelbulb => "Mandelbulb (SDF)",
+            BuiltinScene::Gyroid => "Gyroid (SDF)",
+            BuiltinScene::RoundedBoxes => "Rounded Boxes (SDF)",
+            BuiltinScene::Terrain => "Terrain Flyover",
+            BuiltinScene::Plant => "L-System Plant",
+            BuiltinScene::Life => "Game of Life Torus",
+            BuiltinScene::Water => "Water Ripples",
+            BuiltinScene::SolarSystem => "Solar System",
+        }
+    }
+
+    pub fn all() -> &'static [BuiltinScene] {
+        &[
+            BuiltinScene::Text,
+            BuiltinScene::Clock,
+            BuiltinScene::SystemInfo,
+            BuiltinScene::Csg,
+            BuiltinScene::Mandelbulb,
+            BuiltinScene::Gyroid,
+            BuiltinScene::RoundedBoxes,
+            BuiltinScene::Terrain,
+            BuiltinScene::Plant,
+            BuiltinScene::Life,
+            BuiltinScene::Water,
+            BuiltinScene::SolarSystem,
+        ]
+    }
+
+    /// Whether the scene's geometry is rebuilt while it is displayed
+    pub fn is_animated(&self) -> bool {
+        matches!(
+            self,
+            BuiltinScene::Clock | BuiltinScene::SystemInfo | BuiltinScene::Csg | BuiltinScene::Plant | BuiltinScene::SolarSystem
+        )
+    }
+
+    /// Signed-distance-field scene the GPU raymarches for this scene (these have no mesh)
+    pub fn sdf(&self) -> Option<SdfScene> {
+        match self {
+            BuiltinScene::Mandelbulb => Some(SdfScene::Mandelbulb),
+            BuiltinScene::Gyroid => Some(SdfScene::Gyroid),
+            BuiltinScene::RoundedBoxes => Some(SdfScene::RoundedBoxes),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use glam::{Mat4, Quat, Vec3};
+
+/// Half extents of the invisible box the model bounces around in
+const BOX_HALF_EXTENTS: Vec3 = Vec3::new(1.8, 1.2, 0.8);
+/// Model scale while in physics mode (leaves room to move around)
+const MODEL_SCALE: f32 = 0.5;
+/// Collision radius of the scaled model (normalized models fit a 1.6 unit cube)
+const RADIUS: f32 = 0.8 * MODEL_SCALE;
+
+const GRAVITY: f32 = 9.0;
+const RESTITUTION: f32 = 0.8;
+const FRICTION: f32 = 0.9;
+const SPIN_TRANSFER: f32 = 1.5;
+const ANGULAR_DAMPING: f32 = 0.995;
+/// Below this total speed at the floor, the model gets kicked back up
+const REST_SPEED: f32 = 0.6;
+
+/// Simple rigid-body simulation for the "Physics" rotation mode
+/// The model is treated as a sphere for collisions, while its orientation
+/// picks up spin from glancing impacts with the box walls
+#[derive(Clone)]
+pub struct PhysicsSim {
+    position: Vec3,
+    velocity: Vec3,
+    orientation: Quat,
+    angular_velocity: Vec3,
+    /// PRNG state for relaunch impulses (fixed seed keeps runs reproducible)
+    rng: u32,
+}
+
+impl PhysicsSim {
+    pub fn new() -> Self {
+        Self {
+            position: Vec3::new(-1.0, 0.8, 0.0),
+            velocity: Vec3::new(1.6, 0.0, 0.6),
+            orientation: Quat::IDENTITY,
+            angular_velocity: Vec3::new(0.5, 1.2, 0.3),
+            rng: 0x2545_F491,
+        }
+    }
+
+    /// Restart the simulation from the initial drop
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Advance the simulation by `dt` seconds (sub-stepped for stable collisions)
+    pub fn step(&mut self, dt: f32) {
+        const MAX_STEP: f32 = 1.0 / 120.0;
+        let mut remaining = dt.min(0.25);
+        while remaining > 0.0 {
+            let h = remaining.min(MAX_STEP);
+            self.substep(h);
+            remaining -= h;
+        }
+    }
+
+    fn substep(&mut self, h: f32) {
+        self.velocity.y -= GRAVITY * h;
+        self.position += self.velocity * h;
+
+        let limit = BOX_HALF_EXTENTS - Vec3::splat(RADIUS);
+        for axis in 0..3 {
+            if self.position[axis].abs() > limit[axis] {
+                let normal_sign = -self.position[axis].signum();
+                self.position[axis] = -normal_sign * limit[axis];
+
+                // Only bounce if moving into the wall
+                if self.velocity[axis] * normal_sign < 0.0 {
+                    self.velocity[axis] = -self.velocity[axis] * RESTITUTION;
+
+                    let mut normal = Vec3::ZERO;
+                    normal[axis] = normal_sign;
+                    let tangential = self.velocity - normal * self.velocity.dot(normal);
+                    self.velocity = normal * self.velocity.dot(normal) + tangential * FRICTION;
+
+                    // Glancing impacts spin the model
+                    self.angular_velocity += normal.cross(tangential) * SPIN_TRANSFER;
+                }
+            }
+        }
+
+        // Integrate orientation
+        let rotation = Quat::from_scaled_axis(self.angular_velocity * h);
+        self.orientation = (rotation * self.orientation).normalize();
+        self.angular_velocity *= ANGULAR_DAMPING;
+
+        // Keep the demo alive: relaunch once the model settles on the floor
+        let on_floor = self.position.y <= -limit.y + 1e-3;
+        if on_floor && self.velocity.length() < REST_SPEED {
+            self.velocity = Vec3::new(
+                self.next_random() * 4.0 - 2.0,
+                6.0 + self.next_random() * 2.0,
+                self.next_random() * 2.0 - 1.0,
+            );
+            let spin = Vec3::new(
+                self.next_random() - 0.5,
+                self.next_random() - 0.5,
+                self.next_random() - 0.5,
+            );
+            self.angular_velocity += spin * 6.0;
+        }
+    }
+
+    /// Model matrix for the current simulation state
+    pub fn model_matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(
+            Vec3::splat(MODEL_SCALE),
+            self.orientation,
+            self.position,
+        )
+    }
+
+    /// Model matrix `dt` seconds ahead, leaving the simulation where it is
+    pub fn predicted_model_matrix(&self, dt: f32) -> Mat4 {
+        let mut ahead = self.clone();
+        ahead.step(dt);
+        ahead.model_matrix()
+    }
+
+    /// xorshift PRNG returning a value in [0, 1]
+    fn next_random(&mut self) -> f32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        self.rng as f32 / u32::MAX as f32
+    }
+}
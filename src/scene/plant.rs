@@ -0,0 +1,187 @@
+use glam::{Quat, Vec3};
+
+use super::shapes::MeshBuilder;
+use crate::model::ModelData;
+
+/// Branch levels grown per second, and how long (in levels) the grown plant is shown before regrowing
+const GROWTH_RATE: f32 = 1.2;
+const HOLD_LEVELS: f32 = 3.0;
+
+/// Sides of each branch tube
+const TUBE_SIDES: u32 = 5;
+
+/// Bark at the trunk, fading to leaf green at the tips
+const BARK_COLOR: Vec3 = Vec3::new(0.45, 0.3, 0.18);
+const LEAF_COLOR: Vec3 = Vec3::new(0.35, 0.8, 0.3);
+
+/// An L-system: rewriting rules applied to the axiom, then drawn by a 3D turtle
+/// `F` draws a branch, `+ -` turn, `& ^` pitch, `/ \` roll, `[ ]` start and end a side branch;
+/// other symbols only drive the rewriting
+struct Preset {
+    name: &'static str,
+    axiom: &'static str,
+    rules: &'static [(char, &'static str)],
+    iterations: u32,
+    /// Turn and pitch angle, and roll angle (degrees)
+    angle: f32,
+    roll: f32,
+    /// Branch length and radius multipliers per level of nesting
+    length_decay: f32,
+    radius: f32,
+    radius_decay: f32,
+}
+
+const PRESETS: &[Preset] = &[
+    Preset {
+        name: "Tree",
+        axiom: "FFA",
+        rules: &[('A', "F[&A]/[&A]/[&A]")],
+        iterations: 6,
+        angle: 32.0,
+        roll: 137.5,
+        length_decay: 0.78,
+        radius: 0.07,
+        radius_decay: 0.74,
+    },
+    Preset {
+        name: "Bush",
+        axiom: "A",
+        rules: &[('A', "F[+A][-A][&A][^A]")],
+        iterations: 5,
+        angle: 24.0,
+        roll: 0.0,
+        length_decay: 0.8,
+        radius: 0.06,
+        radius_decay: 0.7,
+    },
+    Preset {
+        name: "Fern",
+        axiom: "X",
+        rules: &[('X', "F+[[X]-X]-F[-FX]+X"), ('F', "FF")],
+        iterations: 5,
+        angle: 25.0,
+        roll: 0.0,
+        length_decay: 1.0,
+        radius: 1.2,
+        radius_decay: 0.82,
+    },
+];
+
+/// Apply the rules to the axiom `iterations` times
+fn expand(preset: &Preset) -> Vec<char> {
+    let mut symbols: Vec<char> = preset.axiom.chars().collect();
+    for _ in 0..preset.iterations {
+        symbols = symbols
+            .iter()
+            .flat_map(|&c| match preset.rules.iter().find(|(from, _)| *from == c) {
+                Some((_, to)) => to.chars().collect(),
+                None => vec![c],
+            })
+            .collect();
+    }
+    symbols
+}
+
+/// Walk the turtle over the symbols, calling `branch(start, end, radius, level)` for each drawn
+/// branch; `growth(level)` (0 to 1) scales the length of branches at each nesting level
+fn walk(preset: &Preset, symbols: &[char], growth: impl Fn(usize) -> f32, mut branch: impl FnMut(Vec3, Vec3, f32, usize)) {
+    let (angle, roll) = (preset.angle.to_radians(), preset.roll.to_radians());
+    let (mut position, mut rotation, mut level) = (Vec3::ZERO, Quat::IDENTITY, 0usize);
+    let mut stack = Vec::new();
+    for &symbol in symbols {
+        match symbol {
+            'F' => {
+                let length = preset.length_decay.powi(level as i32) * growth(level);
+                if length > 0.0 {
+                    let end = position + rotation * Vec3::Y * length;
+                    branch(position, end, preset.radius * preset.radius_decay.powi(level as i32), level);
+                    position = end;
+                }
+            }
+            '+' => rotation *= Quat::from_rotation_z(angle),
+            '-' => rotation *= Quat::from_rotation_z(-angle),
+            '&' => rotation *= Quat::from_rotation_x(angle),
+            '^' => rotation *= Quat::from_rotation_x(-angle),
+            '/' => rotation *= Quat::from_rotation_y(roll),
+            '\\' => rotation *= Quat::from_rotation_y(-roll),
+            '[' => {
+                stack.push((position, rotation));
+                level += 1;
+            }
+            ']' => {
+                if let Some((p, r)) = stack.pop() {
+                    (position, rotation) = (p, r);
+                    level -= 1;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// L-system plant that grows branch level by branch level, then regrows
+pub struct PlantScene {
+    preset: usize,
+    symbols: Vec<char>,
+    /// Deepest branch nesting level
+    max_level: usize,
+    /// Center and scale that fit the fully grown plant to the view (the growing plant keeps them)
+    center: Vec3,
+    scale: f32,
+    size: [f32; 3],
+}
+
+impl PlantScene {
+    pub fn new() -> Self {
+        Self::with_preset(0)
+    }
+
+    fn with_preset(preset: usize) -> Self {
+        let symbols = expand(&PRESETS[preset]);
+        let (mut min, mut max, mut max_level) = (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN), 0);
+        walk(&PRESETS[preset], &symbols, |_| 1.0, |start, end, radius, level| {
+            min = min.min(start.min(end) - Vec3::splat(radius));
+            max = max.max(start.max(end) + Vec3::splat(radius));
+            max_level = max_level.max(level);
+        });
+        let size = max - min;
+        Self {
+            preset,
+            symbols,
+            max_level,
+            center: (min + max) / 2.0,
+            scale: 1.6 / size.max_element().max(1e-6),
+            size: size.to_array(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        PRESETS[self.preset].name
+    }
+
+    /// Switch to the next preset
+    pub fn next_preset(&mut self) {
+        *self = Self::with_preset((self.preset + 1) % PRESETS.len());
+    }
+
+    /// Build the plant as grown at animation time `time`
+    pub fn build_model(&self, time: f32) -> ModelData {
+        let preset = &PRESETS[self.preset];
+        let period = self.max_level as f32 + 1.0 + HOLD_LEVELS;
+        let grown = (time * GROWTH_RATE).rem_euclid(period).max(0.05);
+
+        let mut mesh = MeshBuilder::new();
+        mesh.begin_part();
+        walk(preset, &self.symbols, |level| (grown - level as f32).clamp(0.0, 1.0), |start, end, radius, level| {
+            let t = level as f32 / self.max_level.max(1) as f32;
+            let color = BARK_COLOR.lerp(LEAF_COLOR, t).to_array();
+            mesh.push_tube(start, end, radius, TUBE_SIDES, color);
+        });
+        mesh.end_part();
+
+        for vertex in &mut mesh.vertices {
+            vertex.position = ((Vec3::from(vertex.position) - self.center) * self.scale).to_array();
+        }
+        ModelData::from_normalized_parts(mesh.vertices, mesh.indices, mesh.ranges, self.size)
+    }
+}
@@ -0,0 +1,203 @@
+use anyhow::{Context, Result};
+use glam::{Quat, Vec3};
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::gpu::{Material, MeshRange, Vertex};
+
+/// Accumulates flat-shaded primitive shapes into one indexed mesh
+/// Each `begin_part`/`end_part` pair becomes a sub-mesh range
+#[derive(Default)]
+pub struct MeshBuilder {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub ranges: Vec<MeshRange>,
+    part_start: u32,
+}
+
+impl MeshBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new sub-mesh
+    pub fn begin_part(&mut self) {
+        self.part_start = self.indices.len() as u32;
+    }
+
+    /// Close the current sub-mesh (centroids are filled in by ModelData::from_parts)
+    pub fn end_part(&mut self) {
+        let count = self.indices.len() as u32 - self.part_start;
+        if count > 0 {
+            self.ranges.push(MeshRange {
+                first_index: self.part_start,
+                index_count: count,
+                centroid: [0.0; 3],
+                extent: 0.0,
+                material: Material::default(),
+            });
+        }
+        self.part_start = self.indices.len() as u32;
+    }
+
+    /// Add a quad with corners in counter-clockwise order when viewed along -normal
+    pub fn push_quad(&mut self, corners: [Vec3; 4], normal: Vec3, color: [f32; 3]) {
+        let base = self.vertices.len() as u32;
+        for p in corners {
+            self.vertices.push(Vertex {
+                position: p.to_array(),
+                normal: normal.to_array(),
+                color,
+            });
+        }
+        self.indices
+            .extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    /// Add an oriented box
+    pub fn push_box(&mut self, center: Vec3, half: Vec3, rotation: Quat, color: [f32; 3]) {
+        // Each face: normal axis and two tangent axes chosen so (u x v) = normal
+        let faces = [
+            (Vec3::X, Vec3::Y, Vec3::Z),
+            (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+            (Vec3::Y, Vec3::Z, Vec3::X),
+            (Vec3::NEG_Y, Vec3::X, Vec3::Z),
+            (Vec3::Z, Vec3::X, Vec3::Y),
+            (Vec3::NEG_Z, Vec3::Y, Vec3::X),
+        ];
+        for (n, u, v) in faces {
+            let c = n * half;
+            let du = u * half;
+            let dv = v * half;
+            let corners = [c - du - dv, c + du - dv, c + du + dv, c - du + dv]
+                .map(|p| center + rotation * p);
+            self.push_quad(corners, rotation * n, color);
+        }
+    }
+
+    /// Add a closed cylinder around the Z axis, centered at `center`
+    pub fn push_cylinder(
+        &mut self,
+        center: Vec3,
+        radius: f32,
+        half_depth: f32,
+        segments: u32,
+        color: [f32; 3],
+    ) {
+        let front = center + Vec3::Z * half_depth;
+        let back = center - Vec3::Z * half_depth;
+        for i in 0..segments {
+            let a0 = i as f32 / segments as f32 * std::f32::consts::TAU;
+            let a1 = (i + 1) as f32 / segments as f32 * std::f32::consts::TAU;
+            let d0 = Vec3::new(a0.cos(), a0.sin(), 0.0);
+            let d1 = Vec3::new(a1.cos(), a1.sin(), 0.0);
+
+            // Side wall
+            let normal = (d0 + d1).normalize();
+            self.push_quad(
+                [
+                    back + d0 * radius,
+                    back + d1 * radius,
+                    front + d1 * radius,
+                    front + d0 * radius,
+                ],
+                normal,
+                color,
+            );
+
+            // Caps (degenerate quads collapse to triangles at the center)
+            self.push_quad(
+                [front, front + d0 * radius, front + d1 * radius, front],
+                Vec3::Z,
+                color,
+            );
+            self.push_quad(
+                [back, back + d1 * radius, back + d0 * radius, back],
+                Vec3::NEG_Z,
+                color,
+            );
+        }
+    }
+
+    /// Add a UV sphere with smooth normals
+    pub fn push_sphere(&mut self, center: Vec3, radius: f32, segments: u32, rings: u32, color: [f32; 3]) {
+        let base = self.vertices.len() as u32;
+        for ring in 0..=rings {
+            let theta = ring as f32 / rings as f32 * std::f32::consts::PI;
+            for segment in 0..=segments {
+                let phi = segment as f32 / segments as f32 * std::f32::consts::TAU;
+                let normal = Vec3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+                self.vertices.push(Vertex {
+                    position: (center + normal * radius).to_array(),
+                    normal: normal.to_array(),
+                    color,
+                });
+            }
+        }
+        let stride = segments + 1;
+        for ring in 0..rings {
+            for segment in 0..segments {
+                let a = base + ring * stride + segment;
+                let b = a + stride;
+                self.indices.extend_from_slice(&[a, a + 1, b, a + 1, b + 1, b]);
+            }
+        }
+    }
+
+    /// Add an open tube (no end caps) of constant radius from `start` to `end`
+    pub fn push_tube(&mut self, start: Vec3, end: Vec3, radius: f32, segments: u32, color: [f32; 3]) {
+        let Some(axis) = (end - start).try_normalize() else {
+            return;
+        };
+        let u = axis.any_orthonormal_vector();
+        let v = axis.cross(u);
+        for i in 0..segments {
+            let a0 = i as f32 / segments as f32 * std::f32::consts::TAU;
+            let a1 = (i + 1) as f32 / segments as f32 * std::f32::consts::TAU;
+            let d0 = u * a0.cos() + v * a0.sin();
+            let d1 = u * a1.cos() + v * a1.sin();
+            self.push_quad(
+                [start + d0 * radius, start + d1 * radius, end + d1 * radius, end + d0 * radius],
+                (d0 + d1).normalize(),
+                color,
+            );
+        }
+    }
+
+    /// The mesh as Wavefront OBJ text, with vertex colors (`v x y z r g b`) and normals
+    pub fn to_obj(&self) -> String {
+        let mut obj = String::new();
+        for v in &self.vertices {
+            let ([x, y, z], [r, g, b]) = (v.position, v.color);
+            let _ = writeln!(obj, "v {} {} {} {} {} {}", x, y, z, r, g, b);
+        }
+        for v in &self.vertices {
+            let [x, y, z] = v.normal;
+            let _ = writeln!(obj, "vn {} {} {}", x, y, z);
+        }
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0] + 1, triangle[1] + 1, triangle[2] + 1];
+            let _ = writeln!(obj, "f {a}//{a} {b}//{b} {c}//{c}");
+        }
+        obj
+    }
+}
+
+/// Write a cube, cylinder, and sphere to `dir` as OBJ files (the first-run wizard's offline
+/// alternative to the asset gallery), returning their names
+pub fn write_primitive_models(dir: &Path) -> Result<Vec<&'static str>> {
+    let mut cube = MeshBuilder::new();
+    cube.push_box(Vec3::ZERO, Vec3::splat(0.5), Quat::IDENTITY, [0.9, 0.45, 0.2]);
+    let mut cylinder = MeshBuilder::new();
+    cylinder.push_cylinder(Vec3::ZERO, 0.5, 0.6, 32, [0.3, 0.6, 0.9]);
+    let mut sphere = MeshBuilder::new();
+    sphere.push_sphere(Vec3::ZERO, 0.5, 32, 16, [0.4, 0.8, 0.4]);
+
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let shapes = [("cube.obj", cube), ("cylinder.obj", cylinder), ("sphere.obj", sphere)];
+    for (name, mesh) in &shapes {
+        let path = dir.join(name);
+        std::fs::write(&path, mesh.to_obj()).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(shapes.map(|(name, _)| name).to_vec())
+}
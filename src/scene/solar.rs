@@ -0,0 +1,241 @@
+use anyhow::{Context, Result};
+use glam::{Quat, Vec3};
+use std::path::{Path, PathBuf};
+
+use super::shapes::MeshBuilder;
+use crate::gpu::{Material, MeshRange, Vertex};
+use crate::model::{get_model_display_name, load_model, LoadOptions, ModelData, Progress};
+
+/// Models placed in orbit (the rest of the model list is left out)
+const MAX_PLANETS: usize = 6;
+
+/// Primitive stand-ins fill the orbits when there are fewer other models than this
+const MIN_PLANETS: usize = 3;
+
+/// Half extent of loaded models (they are normalized to the 1.6 unit view)
+const MODEL_HALF_SIZE: f32 = 0.8;
+
+/// Half extent of the central body and of each planet
+const SUN_SIZE: f32 = 0.3;
+const PLANET_SIZE: f32 = 0.12;
+
+/// Radius of the innermost and outermost orbits (the others are spaced evenly between)
+const INNER_ORBIT: f32 = 0.55;
+const OUTER_ORBIT: f32 = 1.15;
+
+/// Angular speed of the innermost orbit in radians per second; outer orbits are slower,
+/// following Kepler's third law
+const INNER_SPEED: f32 = 1.1;
+
+/// Inclination of each orbit plane (alternating up and down) so the planets pass in front of and
+/// behind one another
+const ORBIT_TILT: f32 = 0.12;
+
+/// Spin of the central body and of the planets about their own axes (radians per second)
+const SUN_SPIN: f32 = 0.2;
+const PLANET_SPIN: f32 = 0.9;
+
+/// Arc of orbit each trail covers behind its planet (radians), the tubes it is built from,
+/// and its radius at the planet (tapering to nothing at the tail)
+const TRAIL_ARC: f32 = 1.8;
+const TRAIL_SEGMENTS: usize = 24;
+const TRAIL_RADIUS: f32 = 0.014;
+const TRAIL_SIDES: u32 = 4;
+
+/// Color a trail fades to at its tail
+const TRAIL_TAIL_COLOR: Vec3 = Vec3::new(0.05, 0.05, 0.08);
+
+/// Sample resolution of the primitive stand-ins
+const SPHERE_SEGMENTS: u32 = 24;
+const SPHERE_RINGS: u32 = 12;
+const CYLINDER_SEGMENTS: u32 = 24;
+
+/// One body's mesh, centered on the origin with a half extent of `MODEL_HALF_SIZE`
+struct Body {
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    /// Average vertex color, which its trail is drawn in
+    color: Vec3,
+}
+
+/// Several models orbiting a central one at different radii and speeds, with optional trails
+pub struct SolarSystem {
+    /// The central body first, then the planets from the innermost orbit out
+    bodies: Vec<Body>,
+    /// Draw a fading trail behind each planet
+    pub trails: bool,
+}
+
+impl SolarSystem {
+    pub fn new() -> Self {
+        Self {
+            bodies: Vec::new(),
+            trails: true,
+        }
+    }
+
+    /// Take the bodies from a library built by `load_solar_bodies` (one sub-mesh per body)
+    pub fn set_bodies(&mut self, library: &ModelData) {
+        self.bodies = library
+            .ranges
+            .iter()
+            .map(|range| {
+                let start = range.first_index as usize;
+                let used = &library.indices[start..start + range.index_count as usize];
+                // Library vertices are indexed from zero again for each body
+                let mut remap = vec![u32::MAX; library.vertices.len()];
+                let mut vertices = Vec::new();
+                let indices = used
+                    .iter()
+                    .map(|&index| {
+                        if remap[index as usize] == u32::MAX {
+                            remap[index as usize] = vertices.len() as u32;
+                            vertices.push(library.vertices[index as usize]);
+                        }
+                        remap[index as usize]
+                    })
+                    .collect();
+                let color = vertices.iter().map(|v: &Vertex| Vec3::from(v.color)).sum::<Vec3>() / vertices.len().max(1) as f32;
+                Body { vertices, indices, color }
+            })
+            .collect();
+    }
+
+    /// Orbit radius and angular speed of planet `planet` (0 = innermost)
+    fn orbit(&self, planet: usize) -> (f32, f32) {
+        let planets = self.bodies.len().saturating_sub(1);
+        let radius = INNER_ORBIT + (OUTER_ORBIT - INNER_ORBIT) * planet as f32 / planets.saturating_sub(1).max(1) as f32;
+        (radius, INNER_SPEED * (INNER_ORBIT / radius).powf(1.5))
+    }
+
+    /// Position of planet `planet` at animation time `time`
+    fn orbit_position(&self, planet: usize, time: f32) -> Vec3 {
+        let (radius, speed) = self.orbit(planet);
+        // Golden-angle starting phases keep the planets from lining up
+        let phase = planet as f32 * 2.4;
+        let tilt = if planet.is_multiple_of(2) { ORBIT_TILT } else { -ORBIT_TILT };
+        let angle = phase + speed * time;
+        Quat::from_rotation_x(tilt) * Vec3::new(radius * angle.cos(), 0.0, radius * angle.sin())
+    }
+
+    /// Build the system as placed at animation time `time`: one sub-mesh per body (its trail included)
+    pub fn build_model(&self, time: f32) -> ModelData {
+        let mut mesh = MeshBuilder::new();
+        for (i, body) in self.bodies.iter().enumerate() {
+            mesh.begin_part();
+            let (center, size, spin) = match i {
+                0 => (Vec3::ZERO, SUN_SIZE, SUN_SPIN),
+                _ => (self.orbit_position(i - 1, time), PLANET_SIZE, PLANET_SPIN),
+            };
+            let rotation = Quat::from_rotation_y(spin * time);
+            let scale = size / MODEL_HALF_SIZE;
+            let base = mesh.vertices.len() as u32;
+            mesh.vertices.extend(body.vertices.iter().map(|v| Vertex {
+                position: (center + rotation * Vec3::from(v.position) * scale).to_array(),
+                normal: (rotation * Vec3::from(v.normal)).to_array(),
+                color: v.color,
+            }));
+            mesh.indices.extend(body.indices.iter().map(|&index| base + index));
+
+            if self.trails && i > 0 {
+                // Trail points are where the planet was, so the trail follows its orbit exactly
+                let step = TRAIL_ARC / self.orbit(i - 1).1 / TRAIL_SEGMENTS as f32;
+                for segment in 0..TRAIL_SEGMENTS {
+                    let start = self.orbit_position(i - 1, time - segment as f32 * step);
+                    let end = self.orbit_position(i - 1, time - (segment + 1) as f32 * step);
+                    let fade = 1.0 - (segment as f32 + 0.5) / TRAIL_SEGMENTS as f32;
+                    let color = TRAIL_TAIL_COLOR.lerp(body.color, fade).to_array();
+                    mesh.push_tube(start, end, TRAIL_RADIUS * fade, TRAIL_SIDES, color);
+                }
+            }
+            mesh.end_part();
+        }
+        let extent = 2.0 * (OUTER_ORBIT + PLANET_SIZE);
+        ModelData::from_normalized_parts(mesh.vertices, mesh.indices, mesh.ranges, [extent, 2.0 * SUN_SIZE, extent])
+    }
+}
+
+/// A primitive shape standing in for a missing model: the sun, or one of the planets
+fn stand_in(index: usize) -> (String, Vec<Vertex>, Vec<u32>) {
+    let mut mesh = MeshBuilder::new();
+    let name = match index {
+        0 => {
+            mesh.push_sphere(Vec3::ZERO, MODEL_HALF_SIZE, SPHERE_SEGMENTS, SPHERE_RINGS, [1.0, 0.8, 0.3]);
+            "Sun"
+        }
+        1 => {
+            mesh.push_sphere(Vec3::ZERO, MODEL_HALF_SIZE, SPHERE_SEGMENTS, SPHERE_RINGS, [0.3, 0.5, 1.0]);
+            "Blue Planet"
+        }
+        2 => {
+            let half = Vec3::splat(MODEL_HALF_SIZE * 0.6);
+            mesh.push_box(Vec3::ZERO, half, Quat::from_rotation_x(0.6), [1.0, 0.4, 0.3]);
+            "Red Cube"
+        }
+        _ => {
+            mesh.push_cylinder(Vec3::ZERO, MODEL_HALF_SIZE, MODEL_HALF_SIZE * 0.3, CYLINDER_SEGMENTS, [0.4, 0.9, 0.4]);
+            "Green Disc"
+        }
+    };
+    (name.to_string(), mesh.vertices, mesh.indices)
+}
+
+/// Load a model as one body, with each sub-mesh's material baked into its vertex colors
+fn load_body(path: &Path, options: &LoadOptions, progress: &Progress) -> Result<(String, Vec<Vertex>, Vec<u32>)> {
+    let mut model = load_model(path, options, progress)
+        .with_context(|| format!("Failed to load model {}", get_model_display_name(path)))?;
+    let mut tint = vec![Material::default().base_color; model.vertices.len()];
+    for range in &model.ranges {
+        let start = range.first_index as usize;
+        for &index in &model.indices[start..start + range.index_count as usize] {
+            tint[index as usize] = range.material.base_color;
+        }
+    }
+    for (vertex, tint) in model.vertices.iter_mut().zip(&tint) {
+        vertex.color = [0, 1, 2].map(|c| vertex.color[c] * tint[c]);
+    }
+    Ok((get_model_display_name(path), model.vertices, model.indices))
+}
+
+/// Load the bodies of the solar system scene (runs on the loader thread): `central` (None = the
+/// first model) in the middle and up to `MAX_PLANETS` of the other models in orbit, with
+/// primitive stand-ins when there are fewer than `MIN_PLANETS`
+/// Returns a library for `SolarSystem::set_bodies`: one sub-mesh and part name per body
+pub fn load_solar_bodies(central: Option<&Path>, models: &[PathBuf], options: &LoadOptions, progress: &Progress) -> Result<ModelData> {
+    let central = central.or(models.first().map(PathBuf::as_path));
+    let planets: Vec<&Path> = models.iter().map(PathBuf::as_path).filter(|&path| Some(path) != central).take(MAX_PLANETS).collect();
+
+    let mut bodies = Vec::new();
+    if central.is_none() {
+        bodies.push(stand_in(0));
+    }
+    // Each model reports its own progress from the start
+    for path in central.into_iter().chain(planets.iter().copied()) {
+        bodies.push(load_body(path, options, progress)?);
+    }
+    for index in planets.len()..MIN_PLANETS {
+        bodies.push(stand_in(index + 1));
+    }
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let mut ranges = Vec::new();
+    let mut names = Vec::new();
+    for (name, body_vertices, body_indices) in bodies {
+        let base = vertices.len() as u32;
+        ranges.push(MeshRange {
+            first_index: indices.len() as u32,
+            index_count: body_indices.len() as u32,
+            centroid: [0.0; 3],
+            extent: 0.0,
+            material: Material::default(),
+        });
+        vertices.extend(body_vertices);
+        indices.extend(body_indices.iter().map(|&index| base + index));
+        names.push(name);
+    }
+    let extent = 2.0 * (OUTER_ORBIT + PLANET_SIZE);
+    let mut library = ModelData::from_normalized_parts(vertices, indices, ranges, [extent, 2.0 * SUN_SIZE, extent]);
+    library.part_names = names;
+    Ok(library)
+}
@@ -0,0 +1,199 @@
+use glam::{Mat4, Vec3};
+
+use crate::gpu::{Material, MeshRange, Vertex};
+use crate::model::ModelData;
+
+/// Grid cells along each side of the heightfield
+const GRID: usize = 128;
+
+/// Half the width of the terrain (matches the 1.6 unit size models are normalized to)
+const HALF_SIZE: f32 = 0.8;
+
+/// Height of the tallest possible peak, and of the sea that floods everything below it
+const MAX_HEIGHT: f32 = 0.34;
+const SEA_LEVEL: f32 = 0.05;
+
+/// Noise octaves summed into the heightfield, and the base feature frequency (per unit)
+const OCTAVES: u32 = 6;
+const BASE_FREQUENCY: f32 = 2.2;
+
+/// How high the camera flies above the highest ground near its path
+const FLIGHT_CLEARANCE: f32 = 0.1;
+
+/// Hash of a lattice point to [0, 1)
+fn lattice(x: i32, z: i32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x27d4_eb2d) ^ (z as u32).wrapping_mul(0x1656_67b1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    (h & 0x00ff_ffff) as f32 / 0x0100_0000 as f32
+}
+
+/// Smoothly interpolated value noise in [0, 1)
+fn value_noise(x: f32, z: f32) -> f32 {
+    let (x0, z0) = (x.floor(), z.floor());
+    let (fx, fz) = (x - x0, z - z0);
+    let (sx, sz) = (fx * fx * (3.0 - 2.0 * fx), fz * fz * (3.0 - 2.0 * fz));
+    let (ix, iz) = (x0 as i32, z0 as i32);
+    let top = lattice(ix, iz) + (lattice(ix + 1, iz) - lattice(ix, iz)) * sx;
+    let bottom = lattice(ix, iz + 1) + (lattice(ix + 1, iz + 1) - lattice(ix, iz + 1)) * sx;
+    top + (bottom - top) * sz
+}
+
+/// Fractal Brownian motion: octaves of noise, each twice the frequency and half the amplitude
+fn fbm(x: f32, z: f32) -> f32 {
+    let (mut sum, mut amplitude, mut frequency, mut total) = (0.0, 1.0, BASE_FREQUENCY, 0.0);
+    for octave in 0..OCTAVES {
+        // Offset each octave so their lattices don't line up at the origin
+        let shift = octave as f32 * 17.3;
+        sum += value_noise(x * frequency + shift, z * frequency - shift) * amplitude;
+        total += amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    sum / total
+}
+
+/// Land height above the sea floor at a point (before flooding), shaped into an island
+fn land_height(x: f32, z: f32) -> f32 {
+    let ridges = fbm(x, z);
+    // Sharpen peaks and flatten lowlands
+    let shaped = ridges * ridges * 1.3;
+    let edge = (x.abs().max(z.abs()) / HALF_SIZE).min(1.0);
+    let falloff = 1.0 - smoothstep(0.55, 0.95, edge);
+    (shaped * falloff).min(1.0) * MAX_HEIGHT
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Biome color for a height (above the sea floor) and slope (0 = flat, 1 = vertical)
+fn biome_color(height: f32, slope: f32) -> [f32; 3] {
+    let above_sea = height - SEA_LEVEL;
+    if above_sea <= 0.0 {
+        [0.12, 0.32, 0.62]
+    } else if above_sea < 0.012 {
+        [0.86, 0.8, 0.55]
+    } else if slope > 0.55 {
+        [0.45, 0.42, 0.4]
+    } else if above_sea < 0.08 {
+        [0.3, 0.62, 0.25]
+    } else if above_sea < 0.16 {
+        [0.18, 0.42, 0.2]
+    } else if above_sea < 0.21 {
+        [0.5, 0.46, 0.42]
+    } else {
+        [0.95, 0.96, 1.0]
+    }
+}
+
+/// A procedural island heightfield with biome colors, flown over by a slow automated camera
+pub struct Terrain {
+    /// Heights at the (GRID + 1)² grid points, row by row along +z
+    heights: Vec<f32>,
+    /// Subtracted from every height so the mesh is centered like a normalized model
+    offset: f32,
+}
+
+impl Terrain {
+    pub fn generate() -> Self {
+        let mut heights = Vec::with_capacity((GRID + 1) * (GRID + 1));
+        for row in 0..=GRID {
+            for col in 0..=GRID {
+                let (x, z) = (Self::coord(col), Self::coord(row));
+                heights.push(land_height(x, z).max(SEA_LEVEL));
+            }
+        }
+        let peak = heights.iter().copied().fold(SEA_LEVEL, f32::max);
+        Self {
+            heights,
+            offset: (SEA_LEVEL + peak) / 2.0,
+        }
+    }
+
+    /// World coordinate of a grid line
+    fn coord(index: usize) -> f32 {
+        index as f32 / GRID as f32 * 2.0 * HALF_SIZE - HALF_SIZE
+    }
+
+    fn height(&self, col: usize, row: usize) -> f32 {
+        self.heights[row.min(GRID) * (GRID + 1) + col.min(GRID)]
+    }
+
+    /// Bilinearly interpolated height (above the sea floor) at a world position
+    fn height_at(&self, x: f32, z: f32) -> f32 {
+        let to_grid = |v: f32| ((v + HALF_SIZE) / (2.0 * HALF_SIZE) * GRID as f32).clamp(0.0, GRID as f32);
+        let (gx, gz) = (to_grid(x), to_grid(z));
+        let (col, row) = (gx.floor() as usize, gz.floor() as usize);
+        let (fx, fz) = (gx - col as f32, gz - row as f32);
+        let top = self.height(col, row) + (self.height(col + 1, row) - self.height(col, row)) * fx;
+        let bottom = self.height(col, row + 1) + (self.height(col + 1, row + 1) - self.height(col, row + 1)) * fx;
+        top + (bottom - top) * fz
+    }
+
+    /// The heightfield as one colored mesh, already centered and sized like a normalized model
+    pub fn build_model(&self) -> ModelData {
+        let step = 2.0 * HALF_SIZE / GRID as f32;
+        let mut vertices = Vec::with_capacity((GRID + 1) * (GRID + 1));
+        for row in 0..=GRID {
+            for col in 0..=GRID {
+                let height = self.height(col, row);
+                // Central differences (one-sided at the border) for a smooth normal
+                let dx = (self.height(col + 1, row) - self.height(col.saturating_sub(1), row)) / (2.0 * step);
+                let dz = (self.height(col, row + 1) - self.height(col, row.saturating_sub(1))) / (2.0 * step);
+                let normal = Vec3::new(-dx, 1.0, -dz).normalize();
+                vertices.push(Vertex {
+                    position: [Self::coord(col), height - self.offset, Self::coord(row)],
+                    normal: normal.to_array(),
+                    color: biome_color(height, 1.0 - normal.y),
+                });
+            }
+        }
+
+        let mut indices = Vec::with_capacity(GRID * GRID * 6);
+        for row in 0..GRID as u32 {
+            for col in 0..GRID as u32 {
+                let i = row * (GRID as u32 + 1) + col;
+                let below = i + GRID as u32 + 1;
+                // Counter-clockwise seen from above
+                indices.extend_from_slice(&[i, below, i + 1, i + 1, below, below + 1]);
+            }
+        }
+
+        let ranges = vec![MeshRange {
+            first_index: 0,
+            index_count: indices.len() as u32,
+            centroid: [0.0; 3],
+            extent: 0.0,
+            material: Material::default(),
+        }];
+        ModelData::from_parts(vertices, indices, ranges)
+    }
+
+    /// Camera for the flyover at animation time `time`: a wandering loop around the island at
+    /// low altitude, rising over ridges ahead and looking slightly down along its path
+    pub fn flyover_view(&self, time: f32, speed: f32) -> Mat4 {
+        let angle = time * speed * 0.08;
+        let path = |a: f32| {
+            let radius = 0.42 + 0.12 * (a * 3.0).sin();
+            Vec3::new(radius * a.cos(), 0.0, radius * a.sin())
+        };
+        // Clear the highest ground over the next stretch so ridges never clip the camera
+        let altitude = |a: f32| {
+            let ground = (0..6)
+                .map(|i| {
+                    let p = path(a + i as f32 * 0.04);
+                    self.height_at(p.x, p.z)
+                })
+                .fold(SEA_LEVEL, f32::max);
+            ground + FLIGHT_CLEARANCE - self.offset
+        };
+
+        let eye = path(angle) + Vec3::Y * altitude(angle);
+        let ahead = angle + 0.3;
+        let target = path(ahead) + Vec3::Y * (altitude(ahead) - 0.1);
+        Mat4::look_at_rh(eye, target, Vec3::Y)
+    }
+}
@@ -0,0 +1,319 @@
+use anyhow::{anyhow, Context, Result};
+use glam::Vec3;
+use lyon_tessellation::geom::point;
+use lyon_tessellation::path::iterator::PathIterator;
+use lyon_tessellation::path::{Path as LyonPath, PathEvent};
+use lyon_tessellation::{BuffersBuilder, FillOptions, FillRule, FillTessellator, FillVertex, VertexBuffers};
+use std::path::{Path, PathBuf};
+
+use crate::gpu::{Material, MeshRange, Vertex};
+use crate::model::ModelData;
+
+/// Directory searched first for a user-supplied font
+const FONTS_DIR: &str = "assets/fonts";
+
+/// Supported font file extensions
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf"];
+
+/// Common system fonts used when assets/fonts is empty
+const SYSTEM_FONTS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Bold.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans-Bold.ttf",
+    "/System/Library/Fonts/Supplemental/Arial Bold.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "C:\\Windows\\Fonts\\arialbd.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+
+/// Extrusion depth as a fraction of the font's em size
+const EXTRUDE_DEPTH: f32 = 0.25;
+
+/// Curve flattening tolerance as a fraction of the em size
+const TOLERANCE: f32 = 0.004;
+
+/// Find a font file, preferring assets/fonts over system locations
+fn find_font() -> Option<PathBuf> {
+    let mut fonts: Vec<PathBuf> = std::fs::read_dir(FONTS_DIR)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|e| FONT_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    fonts.sort();
+
+    fonts
+        .into_iter()
+        .next()
+        .or_else(|| SYSTEM_FONTS.iter().map(PathBuf::from).find(|p| p.is_file()))
+}
+
+/// Converts ttf-parser outline callbacks into a lyon path
+struct OutlineToPath {
+    builder: lyon_tessellation::path::path::Builder,
+    scale: f32,
+    offset_x: f32,
+    open: bool,
+}
+
+impl OutlineToPath {
+    fn pt(&self, x: f32, y: f32) -> lyon_tessellation::math::Point {
+        point(self.offset_x + x * self.scale, y * self.scale)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineToPath {
+    fn move_to(&mut self, x: f32, y: f32) {
+        if self.open {
+            self.builder.end(true);
+        }
+        let p = self.pt(x, y);
+        self.builder.begin(p);
+        self.open = true;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.pt(x, y);
+        self.builder.line_to(p);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (c, p) = (self.pt(x1, y1), self.pt(x, y));
+        self.builder.quadratic_bezier_to(c, p);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (c1, c2, p) = (self.pt(x1, y1), self.pt(x2, y2), self.pt(x, y));
+        self.builder.cubic_bezier_to(c1, c2, p);
+    }
+
+    fn close(&mut self) {
+        if self.open {
+            self.builder.end(true);
+            self.open = false;
+        }
+    }
+}
+
+/// Build extruded 3D text geometry for a string using the first available font
+/// Each glyph becomes its own mesh range so the exploded view separates letters
+pub fn build_text_model(text: &str) -> Result<ModelData> {
+    let (font_data, font_path) = read_font()?;
+    build_text_model_with_font(text, &font_data, &font_path)
+}
+
+/// Read the first available font file (its bytes and path)
+fn read_font() -> Result<(Vec<u8>, PathBuf)> {
+    let font_path = find_font().ok_or_else(|| {
+        anyhow!("No font found: place a .ttf or .otf file in {}", FONTS_DIR)
+    })?;
+    let font_data = std::fs::read(&font_path)
+        .with_context(|| format!("Failed to read font {:?}", font_path))?;
+    Ok((font_data, font_path))
+}
+
+/// Outline of each glyph with a shape in `text`, laid out left to right in em units,
+/// and the width of the whole line
+fn glyph_paths(font_data: &[u8], font_path: &Path, text: &str) -> Result<(Vec<(char, LyonPath)>, f32)> {
+    let face = ttf_parser::Face::parse(font_data, 0)
+        .map_err(|e| anyhow!("Failed to parse font {:?}: {}", font_path, e))?;
+
+    // Work in em units so depth and tolerance are font-independent
+    let scale = 1.0 / face.units_per_em() as f32;
+    let mut paths = Vec::new();
+    let mut pen_x = 0.0f32;
+
+    for c in text.chars() {
+        let Some(glyph) = face.glyph_index(c) else {
+            // Unknown glyphs still take up space
+            pen_x += 0.5;
+            continue;
+        };
+
+        let mut outline = OutlineToPath {
+            builder: LyonPath::builder(),
+            scale,
+            offset_x: pen_x,
+            open: false,
+        };
+        let has_outline = face.outline_glyph(glyph, &mut outline).is_some();
+        if outline.open {
+            outline.builder.end(true);
+        }
+        pen_x += face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale;
+
+        if has_outline {
+            paths.push((c, outline.builder.build()));
+        }
+    }
+    Ok((paths, pen_x))
+}
+
+/// A triangle of a flat glyph shape (em units)
+type GlyphTriangle = [[f32; 2]; 3];
+
+/// Tessellate a glyph outline into triangles
+fn tessellate_glyph(tessellator: &mut FillTessellator, c: char, path: &LyonPath) -> Result<Vec<GlyphTriangle>> {
+    let mut caps: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
+    tessellator
+        .tessellate_path(
+            path,
+            &FillOptions::tolerance(TOLERANCE).with_fill_rule(FillRule::NonZero),
+            &mut BuffersBuilder::new(&mut caps, |v: FillVertex| v.position().to_array()),
+        )
+        .map_err(|e| anyhow!("Failed to tessellate glyph {:?}: {:?}", c, e))?;
+    Ok(caps.indices.chunks(3).map(|tri| [tri[0], tri[1], tri[2]].map(|i| caps.vertices[i as usize])).collect())
+}
+
+/// Flat (unextruded) glyph shapes for a string: triangles of each glyph in em units, with the
+/// baseline at y = 0, and the width of the whole line
+pub(super) fn flat_glyphs(text: &str) -> Result<(Vec<Vec<GlyphTriangle>>, f32)> {
+    let (font_data, font_path) = read_font()?;
+    let (paths, width) = glyph_paths(&font_data, &font_path, text)?;
+    let mut tessellator = FillTessellator::new();
+    let glyphs = paths
+        .iter()
+        .map(|(c, path)| tessellate_glyph(&mut tessellator, *c, path))
+        .collect::<Result<_>>()?;
+    Ok((glyphs, width))
+}
+
+fn build_text_model_with_font(text: &str, font_data: &[u8], font_path: &Path) -> Result<ModelData> {
+    let half_depth = EXTRUDE_DEPTH * 0.5;
+    let visible: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let mut ranges = Vec::new();
+    let mut tessellator = FillTessellator::new();
+
+    let (paths, _) = glyph_paths(font_data, font_path, text)?;
+    for (glyph_number, (c, path)) in paths.into_iter().enumerate() {
+        // Hue sweeps across the visible glyphs
+        let t = if visible.len() > 1 {
+            glyph_number as f32 / (visible.len() - 1) as f32
+        } else {
+            0.0
+        };
+        let color = hue_to_rgb(0.55 + t * 0.35);
+
+        let first_index = indices.len() as u32;
+
+        // Front and back caps from the tessellated glyph shape
+        for [a, b, c2] in tessellate_glyph(&mut tessellator, c, &path)? {
+            for (z, normal) in [(half_depth, Vec3::Z), (-half_depth, Vec3::NEG_Z)] {
+                push_triangle(
+                    &mut vertices,
+                    &mut indices,
+                    [
+                        Vec3::new(a[0], a[1], z),
+                        Vec3::new(b[0], b[1], z),
+                        Vec3::new(c2[0], c2[1], z),
+                    ],
+                    normal,
+                    color,
+                );
+            }
+        }
+
+        // Side walls from the flattened contours
+        let segments = flatten_segments(&path);
+        let area: f32 = segments
+            .iter()
+            .map(|(from, to)| from[0] * to[1] - to[0] * from[1])
+            .sum();
+        // Outer contours dominate the signed area, so it tells us which side is outside
+        let outward = if area >= 0.0 { 1.0 } else { -1.0 };
+
+        for (from, to) in segments {
+            let d = [to[0] - from[0], to[1] - from[1]];
+            let normal = Vec3::new(d[1], -d[0], 0.0).normalize_or_zero() * outward;
+            if normal == Vec3::ZERO {
+                continue;
+            }
+            let f0 = Vec3::new(from[0], from[1], half_depth);
+            let t0 = Vec3::new(to[0], to[1], half_depth);
+            let f1 = Vec3::new(from[0], from[1], -half_depth);
+            let t1 = Vec3::new(to[0], to[1], -half_depth);
+            push_triangle(&mut vertices, &mut indices, [f0, t0, t1], normal, color);
+            push_triangle(&mut vertices, &mut indices, [f0, t1, f1], normal, color);
+        }
+
+        ranges.push(MeshRange {
+            first_index,
+            index_count: indices.len() as u32 - first_index,
+            centroid: [0.0; 3],
+            extent: 0.0,
+            material: Material::default(),
+        });
+    }
+
+    if vertices.is_empty() {
+        return Err(anyhow!("Text {:?} produced no visible glyphs", text));
+    }
+
+    Ok(ModelData::from_parts(vertices, indices, ranges))
+}
+
+/// Flatten a path into straight (from, to) segments, including closing edges
+fn flatten_segments(path: &LyonPath) -> Vec<([f32; 2], [f32; 2])> {
+    let mut segments = Vec::new();
+    for event in path.iter().flattened(TOLERANCE) {
+        match event {
+            PathEvent::Line { from, to } => segments.push((from.to_array(), to.to_array())),
+            PathEvent::End {
+                last,
+                first,
+                close: true,
+            } if last != first => segments.push((last.to_array(), first.to_array())),
+            _ => {}
+        }
+    }
+    segments
+}
+
+/// Append a flat-shaded triangle, flipping its winding to face along `normal`
+pub(super) fn push_triangle(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+    corners: [Vec3; 3],
+    normal: Vec3,
+    color: [f32; 3],
+) {
+    let [a, mut b, mut c] = corners;
+    if (b - a).cross(c - a).dot(normal) < 0.0 {
+        std::mem::swap(&mut b, &mut c);
+    }
+
+    let base = vertices.len() as u32;
+    for p in [a, b, c] {
+        vertices.push(Vertex {
+            position: p.to_array(),
+            normal: normal.to_array(),
+            color,
+        });
+    }
+    indices.extend_from_slice(&[base, base + 1, base + 2]);
+}
+
+/// Fully saturated color for a hue in [0, 1)
+pub(super) fn hue_to_rgb(hue: f32) -> [f32; 3] {
+    let h = hue.rem_euclid(1.0) * 6.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    match h as u32 {
+        0 => [1.0, x, 0.0],
+        1 => [x, 1.0, 0.0],
+        2 => [0.0, 1.0, x],
+        3 => [0.0, x, 1.0],
+        4 => [x, 0.0, 1.0],
+        _ => [1.0, 0.0, x],
+    }
+}
@@ -0,0 +1,51 @@
+use glam::{Quat, Vec3};
+
+use crate::gpu::{Material, MeshRange, Vertex, WAVE_GRID, WAVE_HALF_SIZE, WAVE_TILT};
+use crate::model::ModelData;
+
+const CALM_COLOR: [f32; 3] = [0.08, 0.3, 0.6];
+
+/// Calm water surface for the GPU ripple simulation: a grid with a front face and a back face
+/// (so it stays visible from below), laid out the way the wave compute shader rewrites it
+pub fn build_water_model() -> ModelData {
+    let n = WAVE_GRID as usize;
+    let cell = 2.0 * WAVE_HALF_SIZE / (n - 1) as f32;
+    let tilt = Quat::from_rotation_x(WAVE_TILT);
+
+    let mut vertices = Vec::with_capacity(2 * n * n);
+    for side in [1.0, -1.0] {
+        for row in 0..n {
+            for col in 0..n {
+                let position = Vec3::new(col as f32 * cell - WAVE_HALF_SIZE, 0.0, row as f32 * cell - WAVE_HALF_SIZE);
+                vertices.push(Vertex {
+                    position: (tilt * position).to_array(),
+                    normal: (tilt * Vec3::Y * side).to_array(),
+                    color: CALM_COLOR,
+                });
+            }
+        }
+    }
+
+    let mut indices = Vec::with_capacity(12 * (n - 1) * (n - 1));
+    let back = (n * n) as u32;
+    for row in 0..n as u32 - 1 {
+        for col in 0..n as u32 - 1 {
+            let i = row * n as u32 + col;
+            let below = i + n as u32;
+            // Counter-clockwise seen from above, and the reverse for the back face
+            indices.extend_from_slice(&[i, below, i + 1, i + 1, below, below + 1]);
+            indices.extend_from_slice(&[back + i, back + i + 1, back + below, back + i + 1, back + below + 1, back + below]);
+        }
+    }
+
+    let ranges = vec![MeshRange {
+        first_index: 0,
+        index_count: indices.len() as u32,
+        centroid: [0.0; 3],
+        extent: 0.0,
+        material: Material::default(),
+    }];
+    // Already centered and sized to the view (the ripples only add a little height)
+    let size = 2.0 * WAVE_HALF_SIZE;
+    ModelData::from_normalized_parts(vertices, indices, ranges, [size, size * WAVE_TILT.sin(), size * WAVE_TILT.cos()])
+}
@@ -0,0 +1,225 @@
+use anyhow::{anyhow, Context, Result};
+use rhai::{Array, Engine, EvalAltResult};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+
+use crate::terminal::HudItem;
+
+/// Playlist run by `--screensaver` (built in so the screensaver works from any directory)
+pub const ATTRACT_PLAYLIST: &str = include_str!("../assets/scripts/attract.rhai");
+
+/// A scene change requested by a demo script
+pub enum ScriptCommand {
+    /// Show a built-in scene or model file, by name
+    SetModel(String),
+    /// Show the 3D text scene with this string
+    SetText(String),
+    SetLighting(String),
+    /// Switch to the orbit camera at this speed
+    Orbit(f32),
+    /// Show a caption, optionally for a number of seconds (an empty string hides it)
+    Caption(String, Option<f32>),
+    /// Circle the model with marquee text, along a named path (ring by default; an empty string hides it)
+    Marquee(String, Option<String>),
+    /// Pause the script for this many seconds of animation time
+    Wait(f32),
+    /// Set the named HUD item, or remove it (None)
+    Hud(String, Option<HudItem>),
+    /// Attach the named HUD label's text to a point in model space
+    HudPin(String, String, [f32; 3]),
+    /// Remove every HUD item
+    ClearHud,
+}
+
+/// A command from the script, which stays blocked until `reply` is finished
+pub struct ScriptRequest {
+    pub command: ScriptCommand,
+    pub reply: ScriptReply,
+}
+
+/// Resumes the script; an error is raised inside the script at the calling line
+pub struct ScriptReply(Sender<Result<(), String>>);
+
+impl ScriptReply {
+    pub fn finish(self, result: Result<(), String>) {
+        // The script thread may already have stopped
+        let _ = self.0.send(result);
+    }
+}
+
+enum ScriptEvent {
+    Request(ScriptRequest),
+    Finished(Result<()>),
+}
+
+/// A Rhai script running on its own thread, driving the demo through `ScriptCommand`s
+pub struct ScriptRunner {
+    events: Receiver<ScriptEvent>,
+}
+
+impl ScriptRunner {
+    /// Read a script file, then compile and start it as `from_source` does
+    pub fn spawn(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read script {}", path.display()))?;
+        Self::from_source(source, &path.display().to_string())
+    }
+
+    /// Compile the script (so syntax errors are reported before the terminal takes over) and start it
+    pub fn from_source(source: String, name: &str) -> Result<Self> {
+        Engine::new()
+            .compile(&source)
+            .map_err(|e| anyhow!("{}", e))
+            .with_context(|| format!("Failed to compile script {}", name))?;
+
+        let (event_tx, event_rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("script".to_string())
+            .spawn(move || {
+                let mut engine = Engine::new();
+                register_api(&mut engine, event_tx.clone());
+                // Compiled again here: the AST can't cross threads
+                let result = engine.run(&source).map_err(|e| anyhow!("{}", e));
+                let _ = event_tx.send(ScriptEvent::Finished(result));
+            })?;
+
+        Ok(Self { events: event_rx })
+    }
+
+    /// The next pending request, `Ok(None)` if there is none yet,
+    /// or `Err` once the script has finished (with its error, if it failed)
+    pub fn poll(&self) -> Result<Option<ScriptRequest>, Option<anyhow::Error>> {
+        match self.events.try_recv() {
+            Ok(ScriptEvent::Request(request)) => Ok(Some(request)),
+            Ok(ScriptEvent::Finished(result)) => Err(result.err()),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(Some(anyhow!("Script thread stopped unexpectedly"))),
+        }
+    }
+}
+
+/// Send a command to the main loop and wait until it has been applied
+fn call(events: &Sender<ScriptEvent>, command: ScriptCommand) -> Result<(), Box<EvalAltResult>> {
+    let (reply_tx, reply_rx) = mpsc::channel();
+    events
+        .send(ScriptEvent::Request(ScriptRequest {
+            command,
+            reply: ScriptReply(reply_tx),
+        }))
+        .map_err(|_| "Demo has exited")?;
+    reply_rx.recv().map_err(|_| "Demo has exited")?.map_err(|e| e.into())
+}
+
+/// HUD cell coordinate from a script integer (negative ones count from the right or bottom)
+fn hud_position(value: i64) -> i32 {
+    value.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+/// HUD size in cells from a script integer
+fn hud_size(value: i64) -> u16 {
+    value.clamp(0, u16::MAX as i64) as u16
+}
+
+/// Model-space point from a script array of three numbers
+fn script_point(point: Array) -> Result<[f32; 3], Box<EvalAltResult>> {
+    let coords: Vec<f32> = point
+        .into_iter()
+        .map(|value| value.as_float().or_else(|_| value.as_int().map(|v| v as f64)).map(|v| v as f32))
+        .collect::<Result<_, _>>()
+        .map_err(|_| "Point coordinates must be numbers")?;
+    coords.try_into().map_err(|_| "A point is [x, y, z]".into())
+}
+
+/// Register `set_model`, `set_text`, `set_lighting`, `orbit`, `wait`, `caption`, `marquee`, and
+/// the `hud_*` functions
+fn register_api(engine: &mut Engine, events: Sender<ScriptEvent>) {
+    let tx = events.clone();
+    engine.register_fn("set_model", move |name: &str| {
+        call(&tx, ScriptCommand::SetModel(name.to_string()))
+    });
+    let tx = events.clone();
+    engine.register_fn("set_text", move |text: &str| {
+        call(&tx, ScriptCommand::SetText(text.to_string()))
+    });
+    let tx = events.clone();
+    engine.register_fn("set_lighting", move |name: &str| {
+        call(&tx, ScriptCommand::SetLighting(name.to_string()))
+    });
+    let tx = events.clone();
+    engine.register_fn("marquee", move |text: &str| {
+        call(&tx, ScriptCommand::Marquee(text.to_string(), None))
+    });
+    let tx = events.clone();
+    engine.register_fn("marquee", move |text: &str, path: &str| {
+        call(&tx, ScriptCommand::Marquee(text.to_string(), Some(path.to_string())))
+    });
+    let tx = events.clone();
+    engine.register_fn("caption", move |text: &str| {
+        call(&tx, ScriptCommand::Caption(text.to_string(), None))
+    });
+
+    // Numeric arguments accept both integer and float literals
+    let tx = events.clone();
+    engine.register_fn("caption", move |text: &str, seconds: f64| {
+        call(&tx, ScriptCommand::Caption(text.to_string(), Some(seconds as f32)))
+    });
+    let tx = events.clone();
+    engine.register_fn("caption", move |text: &str, seconds: i64| {
+        call(&tx, ScriptCommand::Caption(text.to_string(), Some(seconds as f32)))
+    });
+    let tx = events.clone();
+    engine.register_fn("orbit", move |speed: f64| call(&tx, ScriptCommand::Orbit(speed as f32)));
+    let tx = events.clone();
+    engine.register_fn("orbit", move |speed: i64| call(&tx, ScriptCommand::Orbit(speed as f32)));
+    let tx = events.clone();
+    engine.register_fn("wait", move |seconds: f64| call(&tx, ScriptCommand::Wait(seconds as f32)));
+    let tx = events.clone();
+    engine.register_fn("wait", move |seconds: i64| call(&tx, ScriptCommand::Wait(seconds as f32)));
+
+    let tx = events.clone();
+    engine.register_fn("hud_label", move |name: &str, col: i64, row: i64, text: &str| {
+        let item = HudItem::Label { col: hud_position(col), row: hud_position(row), text: text.to_string() };
+        call(&tx, ScriptCommand::Hud(name.to_string(), Some(item)))
+    });
+    let tx = events.clone();
+    engine.register_fn("hud_box", move |name: &str, col: i64, row: i64, width: i64, height: i64| {
+        let item = HudItem::Box {
+            col: hud_position(col),
+            row: hud_position(row),
+            width: hud_size(width),
+            height: hud_size(height),
+            title: String::new(),
+        };
+        call(&tx, ScriptCommand::Hud(name.to_string(), Some(item)))
+    });
+    let tx = events.clone();
+    engine.register_fn("hud_box", move |name: &str, col: i64, row: i64, width: i64, height: i64, title: &str| {
+        let item = HudItem::Box {
+            col: hud_position(col),
+            row: hud_position(row),
+            width: hud_size(width),
+            height: hud_size(height),
+            title: title.to_string(),
+        };
+        call(&tx, ScriptCommand::Hud(name.to_string(), Some(item)))
+    });
+    let tx = events.clone();
+    engine.register_fn("hud_bar", move |name: &str, col: i64, row: i64, width: i64, progress: f64| {
+        let item = HudItem::Bar { col: hud_position(col), row: hud_position(row), width: hud_size(width), progress: progress as f32 };
+        call(&tx, ScriptCommand::Hud(name.to_string(), Some(item)))
+    });
+    let tx = events.clone();
+    engine.register_fn("hud_bar", move |name: &str, col: i64, row: i64, width: i64, progress: i64| {
+        let item = HudItem::Bar { col: hud_position(col), row: hud_position(row), width: hud_size(width), progress: progress as f32 };
+        call(&tx, ScriptCommand::Hud(name.to_string(), Some(item)))
+    });
+    let tx = events.clone();
+    engine.register_fn("hud_pin", move |name: &str, point: Array, text: &str| {
+        call(&tx, ScriptCommand::HudPin(name.to_string(), text.to_string(), script_point(point)?))
+    });
+    let tx = events.clone();
+    engine.register_fn("hud_remove", move |name: &str| call(&tx, ScriptCommand::Hud(name.to_string(), None)));
+    let tx = events;
+    engine.register_fn("hud_clear", move || call(&tx, ScriptCommand::ClearHud));
+}
+
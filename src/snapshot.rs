@@ -0,0 +1,146 @@
+//! Snapshot-test harness for the ASCII output
+//!
+//! Renders the built-in cube at a fixed pose and size and compares the cell grid against
+//! golden files in `tests/snapshots/`. Set `UPDATE_SNAPSHOTS=1` to record them, or to re-record
+//! after an intentional output change. A snapshot without a golden file is skipped with a note,
+//! or fails with `REQUIRE_SNAPSHOTS=1` (for CI on a machine with a GPU). Tests are skipped when
+//! no GPU adapter can run the pipelines.
+
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+
+use crate::gpu::{AsciiPipeline, HeadlessGpu, LightingMode};
+use crate::terminal::{frame_to_text, Charset, HalfBlock, Palette, RenderMode, TextStyle};
+
+/// Snapshot size in terminal cells
+const SNAPSHOT_COLS: u16 = 48;
+const SNAPSHOT_ROWS: u16 = 20;
+
+/// Fixed manual pose (pitch, yaw, zoom) so every edge direction shows up
+const SNAPSHOT_POSE: (f32, f32, f32) = (0.45, 0.6, 1.0);
+
+/// Render the built-in cube and return the frame as text
+/// Returns Ok(None) when there is no GPU that can run the render and compute pipelines
+pub fn render_snapshot(mode: RenderMode, lighting: LightingMode) -> Result<Option<String>> {
+    let (cols, rows, px_x, px_y) = crate::get_pipeline_dims(SNAPSHOT_COLS, SNAPSHOT_ROWS, mode, 1);
+    let (width, height) = (cols * px_x, rows * px_y);
+
+    let Ok(gpu) = pollster::block_on(HeadlessGpu::new(width, height)) else {
+        return Ok(None);
+    };
+
+    // Some backends (e.g. GL) cannot compile the compute shaders; treat that as "no GPU"
+    gpu.device.push_error_scope(wgpu::ErrorFilter::Internal);
+    gpu.device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let pipeline = AsciiPipeline::new(&gpu.device, cols, rows, width, height);
+    let validation = pollster::block_on(gpu.device.pop_error_scope());
+    let internal = pollster::block_on(gpu.device.pop_error_scope());
+    let mut pipeline = match (pipeline, validation, internal) {
+        (Ok(pipeline), None, None) => pipeline,
+        _ => return Ok(None),
+    };
+
+    let (pitch, yaw, zoom) = SNAPSHOT_POSE;
+    gpu.queue
+        .submit(std::iter::once(gpu.render_manual(pitch, yaw, zoom, lighting)));
+
+    let (near, far) = gpu.clip_planes();
+    pipeline.set_clip_planes(near, far);
+    pipeline.update_bind_groups(
+        &gpu.device,
+        &gpu.queue,
+        gpu.render_texture_view(),
+        gpu.depth_texture_view(),
+        gpu.motion_texture_view(),
+        gpu.part_id_texture_view(),
+    );
+    let mut encoder = gpu
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Snapshot Encoder"),
+        });
+    pipeline.dispatch(&mut encoder);
+    pipeline.copy_to_staging(&mut encoder);
+    gpu.queue.submit(std::iter::once(encoder.finish()));
+
+    let data = pollster::block_on(pipeline.read_results(&gpu.device))?;
+    let style = TextStyle {
+        palette: &Palette::default(),
+        charset: Charset::default(),
+        half_block: HalfBlock::default(),
+    };
+    Ok(Some(frame_to_text(&data, cols, rows, mode, (u16::MAX, u16::MAX), &style)))
+}
+
+/// Path of the golden file for a snapshot name
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.txt", name))
+}
+
+/// Compare a render against its golden file, or record it when updating (one with no golden file
+/// is skipped unless REQUIRE_SNAPSHOTS is set)
+/// Returns Ok(false) when the test was skipped for lack of a usable GPU
+fn check_snapshot(name: &str, mode: RenderMode, lighting: LightingMode) -> Result<bool> {
+    let Some(actual) = render_snapshot(mode, lighting)? else {
+        eprintln!("skipping snapshot {}: no usable GPU", name);
+        return Ok(false);
+    };
+
+    let path = golden_path(name);
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    if update {
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(&path, &actual)?;
+        eprintln!("recorded snapshot {}", path.display());
+        return Ok(true);
+    }
+    if !path.exists() {
+        let message = format!("snapshot {} has no golden file {} (run with UPDATE_SNAPSHOTS=1 to record it)", name, path.display());
+        if std::env::var_os("REQUIRE_SNAPSHOTS").is_some() {
+            return Err(anyhow!(message));
+        }
+        eprintln!("skipping {}", message);
+        return Ok(true);
+    }
+
+    let expected = std::fs::read_to_string(&path)?;
+    if actual != expected {
+        return Err(anyhow!(
+            "snapshot {} differs from {}\n--- expected\n{}\n--- actual\n{}",
+            name,
+            path.display(),
+            expected,
+            actual
+        ));
+    }
+    Ok(true)
+}
+
+#[test]
+fn snapshot_render_modes() -> Result<()> {
+    for (name, mode) in [
+        ("mode_plain", RenderMode::PlainAscii),
+        ("mode_colored", RenderMode::ColoredAscii),
+        ("mode_half_block", RenderMode::HalfBlock),
+        ("mode_half_block_edges", RenderMode::HalfBlockEdges),
+    ] {
+        if !check_snapshot(name, mode, LightingMode::default())? {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn snapshot_lighting_modes() -> Result<()> {
+    for &lighting in LightingMode::all() {
+        let name = format!("lighting_{}", lighting.name().to_lowercase());
+        if !check_snapshot(&name, RenderMode::PlainAscii, lighting)? {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
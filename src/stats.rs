@@ -0,0 +1,263 @@
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::terminal::RenderMode;
+
+/// One frame received from the render thread
+struct FrameRecord {
+    /// Seconds since the session started
+    time: f64,
+    /// Milliseconds since the previous frame (or the session start)
+    interval_ms: f32,
+    gpu_ms: f32,
+    cols: u32,
+    rows: u32,
+    render_size: (u32, u32),
+    mode: &'static str,
+}
+
+/// Terminal content size at a point in the session
+struct ResizeRecord {
+    time: f64,
+    cols: u16,
+    rows: u16,
+}
+
+/// Aggregates over all recorded frames
+struct Summary {
+    frames: usize,
+    duration: f64,
+    avg_fps: f64,
+    avg_frame_ms: f64,
+    p50_frame_ms: f32,
+    p95_frame_ms: f32,
+    p99_frame_ms: f32,
+    avg_gpu_ms: f64,
+}
+
+/// Per-frame timings, resolution changes, and GPU info for a run, exported as CSV or JSON
+/// so terminals and GPUs can be compared across runs
+pub struct SessionStats {
+    started: Instant,
+    /// Wall-clock start, for the export header and default file name
+    started_unix: u64,
+    gpu_name: String,
+    max_texture_dimension: u32,
+    last_frame: Instant,
+    frames: Vec<FrameRecord>,
+    resizes: Vec<ResizeRecord>,
+}
+
+impl SessionStats {
+    /// Start a session; `size` is the initial terminal content size
+    pub fn new(gpu_name: &str, max_texture_dimension: u32, size: (u16, u16)) -> Self {
+        let now = Instant::now();
+        let mut stats = Self {
+            started: now,
+            started_unix: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            gpu_name: gpu_name.to_string(),
+            max_texture_dimension,
+            last_frame: now,
+            frames: Vec::new(),
+            resizes: Vec::new(),
+        };
+        stats.record_resize(size);
+        stats
+    }
+
+    pub fn record_frame(&mut self, gpu_ms: f32, cols: u32, rows: u32, render_size: (u32, u32), mode: RenderMode) {
+        let now = Instant::now();
+        self.frames.push(FrameRecord {
+            time: (now - self.started).as_secs_f64(),
+            interval_ms: (now - self.last_frame).as_secs_f32() * 1000.0,
+            gpu_ms,
+            cols,
+            rows,
+            render_size,
+            mode: mode.name(),
+        });
+        self.last_frame = now;
+    }
+
+    pub fn record_resize(&mut self, size: (u16, u16)) {
+        self.resizes.push(ResizeRecord {
+            time: self.started.elapsed().as_secs_f64(),
+            cols: size.0,
+            rows: size.1,
+        });
+    }
+
+    /// `ascii-3d-stats-<unix time>.csv` in the working directory
+    pub fn default_path(&self) -> PathBuf {
+        PathBuf::from(format!("ascii-3d-stats-{}.csv", self.started_unix))
+    }
+
+    /// Write the session so far: JSON if the path ends in `.json`, CSV otherwise
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let text = if json { self.to_json() } else { self.to_csv() };
+        std::fs::write(path, text).with_context(|| format!("Failed to write stats to {}", path.display()))
+    }
+
+    fn summary(&self) -> Summary {
+        let frames = self.frames.len();
+        let duration = self.frames.last().map_or(0.0, |f| f.time);
+        let total_ms: f64 = self.frames.iter().map(|f| f.interval_ms as f64).sum();
+        let total_gpu_ms: f64 = self.frames.iter().map(|f| f.gpu_ms as f64).sum();
+
+        let mut intervals: Vec<f32> = self.frames.iter().map(|f| f.interval_ms).collect();
+        intervals.sort_by(f32::total_cmp);
+        let percentile = |p: f32| {
+            if intervals.is_empty() {
+                0.0
+            } else {
+                intervals[((intervals.len() - 1) as f32 * p).round() as usize]
+            }
+        };
+
+        let per_frame = |total: f64| if frames > 0 { total / frames as f64 } else { 0.0 };
+        Summary {
+            frames,
+            duration,
+            avg_fps: if duration > 0.0 { frames as f64 / duration } else { 0.0 },
+            avg_frame_ms: per_frame(total_ms),
+            p50_frame_ms: percentile(0.5),
+            p95_frame_ms: percentile(0.95),
+            p99_frame_ms: percentile(0.99),
+            avg_gpu_ms: per_frame(total_gpu_ms),
+        }
+    }
+
+    /// One row per frame; session info, summary, and resizes as leading `#` comments
+    fn to_csv(&self) -> String {
+        let summary = self.summary();
+        let mut out = String::new();
+        let _ = writeln!(out, "# ascii-3d session stats, started {} (unix)", self.started_unix);
+        let _ = writeln!(out, "# gpu: {}", self.gpu_name);
+        let _ = writeln!(out, "# max_texture_dimension: {}", self.max_texture_dimension);
+        let _ = writeln!(
+            out,
+            "# frames: {}, duration: {:.3} s, avg fps: {:.2}, frame ms avg/p50/p95/p99: {:.2}/{:.2}/{:.2}/{:.2}, avg gpu ms: {:.2}",
+            summary.frames,
+            summary.duration,
+            summary.avg_fps,
+            summary.avg_frame_ms,
+            summary.p50_frame_ms,
+            summary.p95_frame_ms,
+            summary.p99_frame_ms,
+            summary.avg_gpu_ms
+        );
+        for resize in &self.resizes {
+            let _ = writeln!(out, "# resize at {:.3} s: {} x {} cells", resize.time, resize.cols, resize.rows);
+        }
+
+        out.push_str("frame,time_s,frame_ms,fps,gpu_ms,cols,rows,render_width,render_height,mode\n");
+        for (i, frame) in self.frames.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "{},{:.4},{:.3},{:.2},{:.3},{},{},{},{},{}",
+                i,
+                frame.time,
+                frame.interval_ms,
+                fps(frame.interval_ms),
+                frame.gpu_ms,
+                frame.cols,
+                frame.rows,
+                frame.render_size.0,
+                frame.render_size.1,
+                frame.mode
+            );
+        }
+        out
+    }
+
+    fn to_json(&self) -> String {
+        let summary = self.summary();
+        let mut out = String::from("{\n");
+        let _ = writeln!(out, "  \"started_unix\": {},", self.started_unix);
+        let _ = writeln!(
+            out,
+            "  \"gpu\": {{\"name\": {}, \"max_texture_dimension\": {}}},",
+            json_string(&self.gpu_name),
+            self.max_texture_dimension
+        );
+        let _ = writeln!(
+            out,
+            "  \"summary\": {{\"frames\": {}, \"duration_s\": {:.3}, \"avg_fps\": {:.2}, \"avg_frame_ms\": {:.3}, \
+             \"p50_frame_ms\": {:.3}, \"p95_frame_ms\": {:.3}, \"p99_frame_ms\": {:.3}, \"avg_gpu_ms\": {:.3}}},",
+            summary.frames,
+            summary.duration,
+            summary.avg_fps,
+            summary.avg_frame_ms,
+            summary.p50_frame_ms,
+            summary.p95_frame_ms,
+            summary.p99_frame_ms,
+            summary.avg_gpu_ms
+        );
+
+        out.push_str("  \"resizes\": [");
+        for (i, resize) in self.resizes.iter().enumerate() {
+            let _ = write!(
+                out,
+                "{}\n    {{\"time_s\": {:.3}, \"cols\": {}, \"rows\": {}}}",
+                if i > 0 { "," } else { "" },
+                resize.time,
+                resize.cols,
+                resize.rows
+            );
+        }
+        out.push_str("\n  ],\n  \"frames\": [");
+        for (i, frame) in self.frames.iter().enumerate() {
+            let _ = write!(
+                out,
+                "{}\n    {{\"time_s\": {:.4}, \"frame_ms\": {:.3}, \"fps\": {:.2}, \"gpu_ms\": {:.3}, \
+                 \"cols\": {}, \"rows\": {}, \"render_width\": {}, \"render_height\": {}, \"mode\": {}}}",
+                if i > 0 { "," } else { "" },
+                frame.time,
+                frame.interval_ms,
+                fps(frame.interval_ms),
+                frame.gpu_ms,
+                frame.cols,
+                frame.rows,
+                frame.render_size.0,
+                frame.render_size.1,
+                json_string(frame.mode)
+            );
+        }
+        out.push_str("\n  ]\n}\n");
+        out
+    }
+}
+
+fn fps(interval_ms: f32) -> f32 {
+    if interval_ms > 0.0 {
+        1000.0 / interval_ms
+    } else {
+        0.0
+    }
+}
+
+/// Quote and escape a string for JSON
+fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,147 @@
+use anyhow::{anyhow, Result};
+use ratatui::symbols::border;
+
+/// Code page 437 glyphs for bytes 0x80-0xFF, in byte order
+const CP437_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒáíóúñÑªº¿⌐¬½¼¡«»\
+░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀\
+αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{a0}";
+
+/// Box-drawing border for the config menu in ASCII-only mode
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+/// Glyph half-block mode uses to show two pixels per cell
+/// Some fonts draw `▀` with a gap at the cell edge, leaving lines between rows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HalfBlock {
+    /// `▀` with the top pixel as foreground
+    #[default]
+    Upper,
+    /// `▄` with the bottom pixel as foreground, for fonts that only pad the top of the cell
+    Lower,
+    /// `█` over a matching background: one blended color per cell, gap-free on any font
+    Full,
+}
+
+impl HalfBlock {
+    /// Guess from the environment: the classic Windows console host's raster and
+    /// Consolas fonts leave gaps around block elements, so it gets the gap-free glyph
+    pub fn detect() -> Self {
+        if cfg!(windows) && std::env::var_os("WT_SESSION").is_none() {
+            HalfBlock::Full
+        } else {
+            HalfBlock::Upper
+        }
+    }
+
+    /// Parse a config file value: "upper", "lower", or "full"
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "upper" => Ok(HalfBlock::Upper),
+            "lower" => Ok(HalfBlock::Lower),
+            "full" => Ok(HalfBlock::Full),
+            _ => Err(anyhow!(
+                "Unknown half-block glyph {:?} (expected \"auto\", \"upper\", \"lower\", or \"full\")",
+                name
+            )),
+        }
+    }
+
+    pub fn glyph(self) -> char {
+        match self {
+            HalfBlock::Upper => '▀',
+            HalfBlock::Lower => '▄',
+            HalfBlock::Full => '█',
+        }
+    }
+}
+
+/// Characters allowed in terminal output and exports
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Charset {
+    #[default]
+    Unicode,
+    /// Glyphs that exist in IBM code page 437, for DOS-style consoles and BBS art
+    Cp437,
+    /// 7-bit ASCII only, for legacy consoles and fonts without block glyphs
+    Ascii,
+}
+
+impl Charset {
+    /// Parse a config file value: "unicode", "cp437", or "ascii-only"
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "unicode" => Ok(Charset::Unicode),
+            "cp437" => Ok(Charset::Cp437),
+            "ascii-only" | "ascii" => Ok(Charset::Ascii),
+            _ => Err(anyhow!(
+                "Unknown charset {:?} (expected \"unicode\", \"cp437\", or \"ascii-only\")",
+                name
+            )),
+        }
+    }
+
+    pub fn supports(self, ch: char) -> bool {
+        match self {
+            Charset::Unicode => true,
+            Charset::Cp437 => ch.is_ascii() || CP437_HIGH.contains(ch),
+            Charset::Ascii => ch.is_ascii(),
+        }
+    }
+
+    /// `ch`, or the closest character the charset has
+    pub fn glyph(self, ch: char) -> char {
+        if self.supports(ch) {
+            return ch;
+        }
+        // Prefer a CP437 block over plain ASCII where one fits
+        if self == Charset::Cp437 {
+            match ch {
+                '▁' | '▂' | '▃' => return '▄',
+                '▅' | '▆' | '▇' => return '█',
+                _ => {}
+            }
+        }
+        match ch {
+            '│' | '║' | '┃' => '|',
+            '─' | '═' | '━' => '-',
+            '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' => '+',
+            '╔' | '╗' | '╚' | '╝' | '╠' | '╣' | '╦' | '╩' | '╬' => '+',
+            '▁' | '▂' => '_',
+            '▃' | '▄' | '░' => '.',
+            '▅' | '▒' => ':',
+            '▆' | '▓' => '=',
+            '▇' | '█' | '■' | '▀' | '▌' | '▐' => '#',
+            // Braille spinner frames
+            '\u{2800}'..='\u{28ff}' => '*',
+            '…' => '.',
+            '·' | '∙' => '.',
+            '•' => '*',
+            _ => '?',
+        }
+    }
+
+    /// `text` with unsupported characters replaced, one for one
+    pub fn text(self, text: &str) -> String {
+        if self == Charset::Unicode {
+            return text.to_string();
+        }
+        text.chars().map(|ch| self.glyph(ch)).collect()
+    }
+
+    /// Border symbols for ratatui blocks
+    pub fn border_set(self) -> border::Set {
+        match self {
+            Charset::Ascii => ASCII_BORDER,
+            _ => border::PLAIN,
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Cell grids: a rendered frame as terminal characters and colors, decoupled from the GPU's
+//! packed output and from how it is written out, so overlays can be composed onto it first
+
+use crossterm::style::{Color, Colored};
+
+use super::charset::{Charset, HalfBlock};
+use super::output::{blend, get_char, half_block_cell, half_block_edge_cell, unpack_data, RenderMode};
+use super::palette::Palette;
+
+/// An RGB color
+pub type Rgb = (u8, u8, u8);
+
+/// One terminal cell: a character and its colors (None = the terminal's default)
+/// RGB colors are matched to the palette on output; named colors (from themes) are the terminal's own
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self { ch: ' ', fg: None, bg: None }
+    }
+}
+
+impl Cell {
+    /// Overall color the cell shows: both colors of a block glyph, otherwise its background,
+    /// otherwise its foreground (None for an uncolored cell)
+    pub fn tone(&self) -> Option<Rgb> {
+        match (rgb_of(self.fg), rgb_of(self.bg)) {
+            (Some(fg), Some(bg)) if matches!(self.ch, '▀' | '▄' | '█') => Some(blend(fg, bg)),
+            (fg, bg) => bg.or(fg),
+        }
+    }
+}
+
+/// A cell color for an RGB value
+pub fn rgb((r, g, b): Rgb) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+fn rgb_of(color: Option<Color>) -> Option<Rgb> {
+    match color {
+        Some(Color::Rgb { r, g, b }) => Some((r, g, b)),
+        _ => None,
+    }
+}
+
+/// A grid of cells in row-major order
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Frame {
+    cols: u16,
+    rows: u16,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    /// A blank grid
+    pub fn new(cols: u16, rows: u16) -> Self {
+        Self {
+            cols,
+            rows,
+            cells: vec![Cell::default(); cols as usize * rows as usize],
+        }
+    }
+
+    /// Cells for packed GPU output (0xRRGGBBCC per pixel) shown in `mode`: one cell per pixel,
+    /// or per two pixel rows in the half-block modes
+    pub fn from_packed(data: &[u32], cols: u32, rows: u32, mode: RenderMode, charset: Charset, half_block: HalfBlock) -> Self {
+        let pixel = |col: u32, row: u32| data.get((row * cols + col) as usize).map_or((0, 0, 0, 0), |&p| unpack_data(p));
+        let term_rows = if mode.is_half_block() { rows / 2 } else { rows };
+        let mut frame = Self::new(cols as u16, term_rows as u16);
+        for row in 0..frame.rows as u32 {
+            for col in 0..frame.cols as u32 {
+                let cell = match mode {
+                    RenderMode::PlainAscii => Cell {
+                        ch: get_char(pixel(col, row).3, charset),
+                        ..Cell::default()
+                    },
+                    RenderMode::ColoredAscii => {
+                        let (r, g, b, char_index) = pixel(col, row);
+                        Cell { ch: get_char(char_index, charset), fg: Some(rgb((r, g, b))), bg: None }
+                    }
+                    RenderMode::HalfBlock | RenderMode::HalfBlockEdges => {
+                        let (tr, tg, tb, top_char) = pixel(col, row * 2);
+                        let (br, bg, bb, bottom_char) = pixel(col, row * 2 + 1);
+                        let edge = (mode == RenderMode::HalfBlockEdges)
+                            .then(|| half_block_edge_cell(charset, ((tr, tg, tb), top_char), ((br, bg, bb), bottom_char)))
+                            .flatten();
+                        match edge {
+                            Some((ch, ink, background)) => Cell { ch, fg: Some(rgb(ink)), bg: Some(rgb(background)) },
+                            None => {
+                                let (ch, fg, bg) = half_block_cell(half_block, charset, (tr, tg, tb), (br, bg, bb));
+                                Cell { ch, fg: fg.map(rgb), bg: Some(rgb(bg)) }
+                            }
+                        }
+                    }
+                };
+                frame.cells[(row * cols + col) as usize] = cell;
+            }
+        }
+        frame
+    }
+
+    pub fn cols(&self) -> u16 {
+        self.cols
+    }
+
+    pub fn rows(&self) -> u16 {
+        self.rows
+    }
+
+    /// One row of cells
+    pub fn row(&self, row: u16) -> &[Cell] {
+        let start = row as usize * self.cols as usize;
+        &self.cells[start..start + self.cols as usize]
+    }
+
+    /// Copy `source` onto this grid with its top-left cell at (col, row), clipped to the grid
+    pub fn blit(&mut self, source: &Frame, col: u16, row: u16) {
+        let width = source.cols.min(self.cols.saturating_sub(col));
+        let height = source.rows.min(self.rows.saturating_sub(row));
+        for y in 0..height {
+            let start = (row + y) as usize * self.cols as usize + col as usize;
+            self.cells[start..start + width as usize].copy_from_slice(&source.row(y)[..width as usize]);
+        }
+    }
+
+    /// Write a line of text from (col, row), cut off at the right edge
+    /// `bg` goes behind the characters written over cells without a background of their own
+    pub fn overlay_text(&mut self, col: u16, row: u16, text: &str, fg: Option<Color>, bg: Option<Color>) {
+        if row >= self.rows {
+            return;
+        }
+        for (x, ch) in (col..self.cols).zip(text.chars()) {
+            let cell = &mut self.cells[row as usize * self.cols as usize + x as usize];
+            *cell = Cell { ch, fg, bg: cell.bg.or(bg) };
+        }
+    }
+
+    /// Dim a region for overlay text to sit on: each cell becomes a space over its tone mixed
+    /// with `panel` (`opacity` of the tone is kept), and uncolored cells are left blank
+    pub fn shade(&mut self, col: u16, row: u16, cols: u16, rows: u16, panel: Rgb, opacity: f32) {
+        let mix = |tone: u8, panel: u8| (tone as f32 * opacity + panel as f32 * (1.0 - opacity)) as u8;
+        for y in row..row.saturating_add(rows).min(self.rows) {
+            for x in col..col.saturating_add(cols).min(self.cols) {
+                let cell = &mut self.cells[y as usize * self.cols as usize + x as usize];
+                let backdrop = cell.tone().map(|tone| rgb((mix(tone.0, panel.0), mix(tone.1, panel.1), mix(tone.2, panel.2))));
+                *cell = Cell { bg: backdrop, ..Cell::default() };
+            }
+        }
+    }
+
+    /// The part of the grid `cols` x `rows` from (col, row), clipped to the grid
+    pub fn crop(&self, col: u16, row: u16, cols: u16, rows: u16) -> Frame {
+        let cols = cols.min(self.cols.saturating_sub(col));
+        let rows = rows.min(self.rows.saturating_sub(row));
+        let mut cropped = Frame::new(cols, rows);
+        for y in 0..rows {
+            let start = (row + y) as usize * self.cols as usize + col as usize;
+            cropped.cells[y as usize * cols as usize..(y as usize + 1) * cols as usize]
+                .copy_from_slice(&self.cells[start..start + cols as usize]);
+        }
+        cropped
+    }
+
+    /// The grid as text, colored with ANSI codes matched to `palette` where cells have colors
+    /// Each row ends in a newline, after a reset if the row set any colors
+    pub fn to_text(&self, palette: &Palette) -> String {
+        let mut output = String::new();
+        for row in 0..self.rows {
+            // Colors the terminal has at this point in the row
+            let mut fg: Option<String> = None;
+            let mut bg = false;
+            let mut styled = false;
+            for cell in self.row(row) {
+                if let Some(background) = cell.bg {
+                    // Cells with a background (the half blocks) set both colors each time
+                    if let Some(color) = cell.fg {
+                        let code = sgr(palette, color, false);
+                        output.push_str(&format!("\x1b[{}m", code));
+                        fg = Some(code);
+                    }
+                    output.push_str(&format!("\x1b[{}m", sgr(palette, background, true)));
+                    bg = true;
+                    styled = true;
+                } else {
+                    if bg {
+                        output.push_str("\x1b[49m");
+                        bg = false;
+                    }
+                    let cell_fg = cell.fg.map(|color| sgr(palette, color, false));
+                    if cell_fg != fg {
+                        output.push_str(&format!("\x1b[{}m", cell_fg.as_deref().unwrap_or("39")));
+                        fg = cell_fg;
+                        styled = true;
+                    }
+                }
+                output.push(cell.ch);
+            }
+            if styled {
+                output.push_str("\x1b[0m");
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// SGR parameters for a cell color: RGB matched to the palette, named colors as they are
+fn sgr(palette: &Palette, color: Color, background: bool) -> String {
+    match color {
+        Color::Rgb { r, g, b } => palette.sgr(r, g, b, background),
+        color if background => Colored::BackgroundColor(color).to_string(),
+        color => Colored::ForegroundColor(color).to_string(),
+    }
+}
@@ -0,0 +1,197 @@
+//! Text HUD: labels, boxes, and progress bars composed onto the screen's Frame over the 3D view,
+//! each over a dimmed backdrop of the frame behind it; the status bar and GPU info panel are
+//! drawn the same way, and so are labels attached to points on the model
+
+use crossterm::style::Color;
+
+use super::charset::Charset;
+use super::frame::{rgb, Frame, Rgb};
+use crate::gpu::LabelAnchor;
+use crate::theme::Theme;
+
+/// Weight of the frame color behind HUD panels (the rest is the theme's panel background)
+const HUD_OPACITY: f32 = 0.35;
+
+/// One HUD element at content-area cell coordinates (row 0 is the first row of the 3D view);
+/// negative coordinates count back from the right and bottom edges (-1 is the last cell)
+#[derive(Clone, Debug, PartialEq)]
+pub enum HudItem {
+    /// A line of text
+    Label { col: i32, row: i32, text: String },
+    /// A bordered box with an optional title in its top border
+    Box { col: i32, row: i32, width: u16, height: u16, title: String },
+    /// A bar `width` cells wide, filled to `progress` (0 to 1)
+    Bar { col: i32, row: i32, width: u16, progress: f32 },
+}
+
+/// HUD items by name, drawn in the order they were first set
+#[derive(Clone, Debug, Default)]
+pub struct Hud {
+    items: Vec<(String, HudItem)>,
+    /// Labels attached to points on the model: (name, text, anchor)
+    labels: Vec<(String, String, LabelAnchor)>,
+}
+
+impl Hud {
+    /// Add an item, or replace the one with the same name in place
+    pub fn set(&mut self, name: &str, item: HudItem) {
+        match self.items.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, existing)) => *existing = item,
+            None => self.items.push((name.to_string(), item)),
+        }
+    }
+
+    /// Remove an item or label; false if there was none with this name
+    pub fn remove(&mut self, name: &str) -> bool {
+        let count = self.items.len() + self.labels.len();
+        self.items.retain(|(existing, _)| existing != name);
+        self.labels.retain(|(existing, _, _)| existing != name);
+        self.items.len() + self.labels.len() != count
+    }
+
+    /// Remove every item and label
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.labels.clear();
+    }
+
+    /// Attach a label to a point on the model, or move the one with the same name
+    pub fn set_label(&mut self, name: &str, text: &str, anchor: LabelAnchor) {
+        match self.labels.iter_mut().find(|(existing, _, _)| existing == name) {
+            Some((_, existing, existing_anchor)) => (*existing, *existing_anchor) = (text.to_string(), anchor),
+            None => self.labels.push((name.to_string(), text.to_string(), anchor)),
+        }
+    }
+
+    /// Labels to locate in the next frame: (text, anchor)
+    pub fn labels(&self) -> impl Iterator<Item = (String, LabelAnchor)> + '_ {
+        self.labels.iter().map(|(_, text, anchor)| (text.clone(), *anchor))
+    }
+
+    /// Draw every item onto `screen`, in the content area `area` (col, row, cols, rows)
+    pub(super) fn draw(&self, screen: &mut Frame, area: (u16, u16, u16, u16), theme: &Theme, charset: Charset) {
+        let (left, top, cols, rows) = area;
+        for (_, item) in &self.items {
+            match item {
+                HudItem::Label { col, row, text } => {
+                    let (col, row) = (left + place(*col, cols), top + place(*row, rows));
+                    let text = charset.text(text);
+                    shade(screen, (col, row, text.chars().count().min(cols as usize) as u16, 1), theme);
+                    screen.overlay_text(col, row, &text, theme_color(theme.panel_fg), theme_color(theme.panel_bg));
+                }
+                HudItem::Box { col, row, width, height, title } => {
+                    let (col, row) = (left + place(*col, cols), top + place(*row, rows));
+                    draw_box(screen, (col, row, (*width).min(cols), (*height).min(rows)), title, theme, charset);
+                }
+                HudItem::Bar { col, row, width, progress } => {
+                    let (col, row) = (left + place(*col, cols), top + place(*row, rows));
+                    let width = (*width).min(cols);
+                    let filled = (progress.clamp(0.0, 1.0) * width as f32).round() as usize;
+                    shade(screen, (col, row, width, 1), theme);
+                    let fill = charset.text(&"█".repeat(filled));
+                    let rest = charset.text(&"░".repeat(width as usize - filled));
+                    screen.overlay_text(col, row, &fill, theme_color(theme.value), theme_color(theme.panel_bg));
+                    screen.overlay_text(col + filled as u16, row, &rest, theme_color(theme.dim), theme_color(theme.panel_bg));
+                }
+            }
+        }
+    }
+}
+
+/// Draw located labels onto `screen` over the 3D view's pane `pane` (col, row, cols, rows):
+/// each as a marker on its point with the text beside it, fading out as the point is hidden
+/// Labels are given as (text, position in the pane (0 to 1, y down), visibility)
+pub(super) fn draw_labels(screen: &mut Frame, pane: (u16, u16, u16, u16), labels: &[(String, [f32; 2], f32)], theme: &Theme, charset: Charset) {
+    // The pane comes from the last rendered frame, which can be larger than the screen just
+    // after a resize or clean mode change
+    let (left, top, cols, rows) = pane;
+    let cols = cols.min(screen.cols().saturating_sub(left));
+    let rows = rows.min(screen.rows().saturating_sub(top));
+    if cols == 0 || rows == 0 {
+        return;
+    }
+    for (text, [u, v], visibility) in labels {
+        let col = ((u * cols as f32) as u16).min(cols - 1);
+        let row = ((v * rows as f32) as u16).min(rows - 1);
+        let width = (text.chars().count() as u16 + 2).min(cols);
+        // Text runs to the right of the point, or to its left where it would be cut off
+        let (start, text) = if col + width <= cols {
+            (col, format!("• {}", text))
+        } else {
+            ((col + 1).saturating_sub(width), format!("{} •", text))
+        };
+        let (col, row) = (left + start, top + row);
+        shade(screen, (col, row, width, 1), theme);
+        for (x, ch) in (col..col + width).zip(charset.text(&text).chars()) {
+            if x >= screen.cols() || row >= screen.rows() {
+                continue;
+            }
+            let backdrop = screen.row(row)[x as usize].bg.or(theme_color(theme.panel_bg));
+            let fg = faded(theme_color(theme.panel_fg), theme_color(theme.dim), backdrop, *visibility);
+            screen.overlay_text(x, row, &ch.to_string(), fg, theme_color(theme.panel_bg));
+        }
+    }
+}
+
+/// Label text color at `visibility` (0 to 1) over `backdrop`: RGB colors are mixed, others drop
+/// to the dim color once the label is mostly hidden
+fn faded(color: Option<Color>, dim: Option<Color>, backdrop: Option<Color>, visibility: f32) -> Option<Color> {
+    match (color, backdrop) {
+        (Some(Color::Rgb { r, g, b }), Some(Color::Rgb { r: br, g: bg, b: bb })) => {
+            let mix = |c: u8, under: u8| (c as f32 * visibility + under as f32 * (1.0 - visibility)) as u8;
+            Some(rgb((mix(r, br), mix(g, bg), mix(b, bb))))
+        }
+        _ if visibility < 0.5 => dim,
+        _ => color,
+    }
+}
+
+/// Cell offset of a HUD coordinate in an area `size` cells long (negative counts from the end)
+fn place(pos: i32, size: u16) -> u16 {
+    if pos < 0 {
+        size.saturating_sub(pos.unsigned_abs().min(size as u32) as u16)
+    } else {
+        pos.min(size as i32) as u16
+    }
+}
+
+/// A theme color for a cell (the terminal default is None)
+pub(super) fn theme_color(color: ratatui::style::Color) -> Option<Color> {
+    match color {
+        ratatui::style::Color::Reset => None,
+        color => Some(color.into()),
+    }
+}
+
+/// Dim a region (col, row, cols, rows) toward the theme's panel background
+pub(super) fn shade(screen: &mut Frame, rect: (u16, u16, u16, u16), theme: &Theme) {
+    let panel: Rgb = match theme.panel_bg {
+        ratatui::style::Color::Rgb(r, g, b) => (r, g, b),
+        _ => (0, 0, 0),
+    };
+    screen.shade(rect.0, rect.1, rect.2, rect.3, panel, HUD_OPACITY);
+}
+
+/// A bordered box over a dimmed region (col, row, cols, rows), with `title` in the top border
+fn draw_box(screen: &mut Frame, rect: (u16, u16, u16, u16), title: &str, theme: &Theme, charset: Charset) {
+    let (col, row, width, height) = rect;
+    if width < 2 || height < 2 {
+        return;
+    }
+    shade(screen, rect, theme);
+    let inner = width as usize - 2;
+    let title: String = title.chars().take(inner.saturating_sub(2)).collect();
+    let top = if title.is_empty() {
+        format!("┌{}┐", "─".repeat(inner))
+    } else {
+        format!("┌ {} {}┐", title, "─".repeat(inner - title.chars().count() - 2))
+    };
+    let (border, background) = (theme_color(theme.border), theme_color(theme.panel_bg));
+    screen.overlay_text(col, row, &charset.text(&top), border, background);
+    for y in row + 1..row + height - 1 {
+        screen.overlay_text(col, y, &charset.text("│"), border, background);
+        screen.overlay_text(col + width - 1, y, &charset.text("│"), border, background);
+    }
+    screen.overlay_text(col, row + height - 1, &charset.text(&format!("└{}┘", "─".repeat(inner))), border, background);
+}
+
@@ -1,3 +1,10 @@
+mod charset;
+mod frame;
+mod hud;
 mod output;
+mod palette;
 
-pub use output::{RenderMode, TerminalRenderer};
+pub use charset::{Charset, HalfBlock};
+pub use hud::{Hud, HudItem};
+pub use output::{edge_char, edge_ink, frame_to_text, half_block_edge, PanelCorner, ASCII_RAMP, EDGE_CHARS, RenderMode, TerminalRenderer, TextStyle};
+pub use palette::{ColorAdjust, ColorDepth, ColorVision, Palette};
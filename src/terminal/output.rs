@@ -1,6 +1,7 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute, queue,
     style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
     terminal::{
@@ -10,12 +11,21 @@ use crossterm::{
 };
 use std::io::{stdout, Stdout, Write};
 
+use super::charset::{Charset, HalfBlock};
+use super::frame::{Frame, Rgb};
+use super::hud::{self, shade, theme_color, Hud};
+use super::palette::Palette;
+use crate::theme::Theme;
+
 // Fill characters matching AcerolaFX (dark to bright)
-const ASCII_RAMP: &[char] = &[' ', '.', ';', 'c', 'o', 'P', 'O', '?', '@', '#'];
+pub const ASCII_RAMP: &[char] = &[' ', '.', ';', 'c', 'o', 'P', 'O', '?', '@', '#'];
 
 // Edge characters for direction-based edge rendering
 // Index 10 = vertical (|), 11 = horizontal (-), 12 = back (\), 13 = forward (/)
-const EDGE_CHARS: &[char] = &['|', '-', '\\', '/'];
+pub const EDGE_CHARS: &[char] = &['|', '-', '\\', '/'];
+
+/// Cell luma above which edge lines are drawn in black rather than white
+const EDGE_INK_THRESHOLD: f32 = 140.0;
 
 /// Render mode for terminal output
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -23,6 +33,8 @@ pub enum RenderMode {
     PlainAscii,
     ColoredAscii,
     HalfBlock,
+    /// Half blocks with the edge characters drawn over them
+    HalfBlockEdges,
 }
 
 impl RenderMode {
@@ -31,6 +43,21 @@ impl RenderMode {
             RenderMode::PlainAscii => "Plain ASCII",
             RenderMode::ColoredAscii => "Colored ASCII",
             RenderMode::HalfBlock => "Half Block",
+            RenderMode::HalfBlockEdges => "Half Block + Edges",
+        }
+    }
+
+    /// Parse a command-line value: "plain", "color", "half-block", or "half-block-edges"
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "plain" => Ok(RenderMode::PlainAscii),
+            "color" | "colour" => Ok(RenderMode::ColoredAscii),
+            "half-block" => Ok(RenderMode::HalfBlock),
+            "half-block-edges" => Ok(RenderMode::HalfBlockEdges),
+            _ => Err(anyhow!(
+                "Unknown render mode {:?} (expected \"plain\", \"color\", \"half-block\", or \"half-block-edges\")",
+                name
+            )),
         }
     }
 
@@ -38,21 +65,76 @@ impl RenderMode {
         match self {
             RenderMode::PlainAscii => RenderMode::ColoredAscii,
             RenderMode::ColoredAscii => RenderMode::HalfBlock,
-            RenderMode::HalfBlock => RenderMode::PlainAscii,
+            RenderMode::HalfBlock => RenderMode::HalfBlockEdges,
+            RenderMode::HalfBlockEdges => RenderMode::PlainAscii,
+        }
+    }
+
+    /// Whether each terminal cell shows two data rows as half blocks
+    pub fn is_half_block(&self) -> bool {
+        matches!(self, RenderMode::HalfBlock | RenderMode::HalfBlockEdges)
+    }
+}
+
+/// Screen corner for an overlay panel
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PanelCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl PanelCorner {
+    /// Parse a config file value: "top-left", "top-right", "bottom-left", "bottom-right" (or "tl", "tr", "bl", "br")
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "top-left" | "tl" => Ok(PanelCorner::TopLeft),
+            "top-right" | "tr" => Ok(PanelCorner::TopRight),
+            "bottom-left" | "bl" => Ok(PanelCorner::BottomLeft),
+            "bottom-right" | "br" => Ok(PanelCorner::BottomRight),
+            _ => Err(anyhow!(
+                "Unknown panel corner {:?} (expected \"top-left\", \"top-right\", \"bottom-left\", or \"bottom-right\")",
+                name
+            )),
         }
     }
+
+    fn is_left(self) -> bool {
+        matches!(self, PanelCorner::TopLeft | PanelCorner::BottomLeft)
+    }
+
+    fn is_top(self) -> bool {
+        matches!(self, PanelCorner::TopLeft | PanelCorner::TopRight)
+    }
 }
 
 pub struct TerminalRenderer {
     stdout: Stdout,
-    buffer: String,
     cols: u16,
     rows: u16,
+    /// Colors for the status bar, GPU info panel, and notices
+    theme: Theme,
+    /// Color matching for the terminal's color depth
+    palette: Palette,
+    /// Characters the terminal can show
+    charset: Charset,
+    /// Glyph for half-block mode
+    half_block: HalfBlock,
+    /// Whether row 0 is reserved for the status bar
+    show_status: bool,
+    /// Screen corner of the GPU info panel
+    gpu_panel: PanelCorner,
+    /// Show the GPU info panel as one line instead of four
+    gpu_panel_compact: bool,
+    /// Whether mouse events are reported (and the terminal's own selection is off)
+    mouse_capture: bool,
 }
 
 /// Unpack color and char index from packed u32
 /// Format: 0xRRGGBBCC where CC=char, BB=blue, GG=green, RR=red
-fn unpack_data(packed: u32) -> (u8, u8, u8, u8) {
+pub(super) fn unpack_data(packed: u32) -> (u8, u8, u8, u8) {
     let char_index = (packed & 0xFF) as u8;
     let b = ((packed >> 8) & 0xFF) as u8;
     let g = ((packed >> 16) & 0xFF) as u8;
@@ -60,18 +142,79 @@ fn unpack_data(packed: u32) -> (u8, u8, u8, u8) {
     (r, g, b, char_index)
 }
 
-/// Get ASCII character from index
-fn get_char(char_index: u8) -> char {
+/// Get the character for an index, limited to `charset`
+pub(super) fn get_char(char_index: u8, charset: Charset) -> char {
     let idx = char_index as usize;
-    if idx < ASCII_RAMP.len() {
+    let ch = if idx < ASCII_RAMP.len() {
         ASCII_RAMP[idx]
     } else if idx < ASCII_RAMP.len() + EDGE_CHARS.len() {
         EDGE_CHARS[idx - ASCII_RAMP.len()]
     } else {
         ' '
+    };
+    charset.glyph(ch)
+}
+
+/// Average of the two half-block pixel colors, for charsets without `▀`
+pub(super) fn blend(top: Rgb, bottom: Rgb) -> Rgb {
+    let avg = |a: u8, b: u8| ((a as u16 + b as u16) / 2) as u8;
+    (avg(top.0, bottom.0), avg(top.1, bottom.1), avg(top.2, bottom.2))
+}
+
+/// Character, foreground, and background for one half-block cell
+/// Falls back to a blended background-colored space when the charset lacks the glyph
+pub(super) fn half_block_cell(
+    glyph: HalfBlock,
+    charset: Charset,
+    top: Rgb,
+    bottom: Rgb,
+) -> (char, Option<Rgb>, Rgb) {
+    if !charset.supports(glyph.glyph()) {
+        return (' ', None, blend(top, bottom));
+    }
+    match glyph {
+        HalfBlock::Upper => ('▀', Some(top), bottom),
+        HalfBlock::Lower => ('▄', Some(bottom), top),
+        HalfBlock::Full => ('█', Some(blend(top, bottom)), blend(top, bottom)),
     }
 }
 
+/// Edge character index to draw over a half-block cell in half-block + edges mode: one of the
+/// two pixels found an edge and the other found none or the same one (pixels disagreeing on the
+/// direction are left as plain blocks)
+pub fn half_block_edge(top_index: u8, bottom_index: u8) -> Option<u8> {
+    let is_edge = |index: u8| (ASCII_RAMP.len()..ASCII_RAMP.len() + EDGE_CHARS.len()).contains(&(index as usize));
+    match (is_edge(top_index), is_edge(bottom_index)) {
+        (true, false) => Some(top_index),
+        (false, true) => Some(bottom_index),
+        (true, true) if top_index == bottom_index => Some(top_index),
+        _ => None,
+    }
+}
+
+/// Character for an edge index (10-13), which every charset can show
+pub fn edge_char(index: u8) -> char {
+    get_char(index, Charset::default())
+}
+
+/// Color of edge lines drawn over a cell: black on light cells, white on dark ones
+pub fn edge_ink(background: Rgb) -> Rgb {
+    let luma = 0.299 * background.0 as f32 + 0.587 * background.1 as f32 + 0.114 * background.2 as f32;
+    if luma > EDGE_INK_THRESHOLD {
+        (0, 0, 0)
+    } else {
+        (255, 255, 255)
+    }
+}
+
+/// Character, foreground, and background for one half-block + edges cell: the edge character
+/// in contrasting ink over the blended block colors, when the cell has an edge
+pub(super) fn half_block_edge_cell(charset: Charset, top: (Rgb, u8), bottom: (Rgb, u8)) -> Option<(char, Rgb, Rgb)> {
+    let edge = half_block_edge(top.1, bottom.1)?;
+    let background = blend(top.0, bottom.0);
+    Some((get_char(edge, charset), edge_ink(background), background))
+}
+
 impl TerminalRenderer {
     pub fn new() -> Result<Self> {
         let mut stdout = stdout();
@@ -83,15 +226,32 @@ impl TerminalRenderer {
 
         Ok(Self {
             stdout,
-            buffer: String::with_capacity((cols as usize + 1) * rows as usize * 20), // Extra for ANSI codes
             cols,
             rows,
+            theme: Theme::default(),
+            palette: Palette::default(),
+            charset: Charset::default(),
+            half_block: HalfBlock::default(),
+            show_status: true,
+            gpu_panel: PanelCorner::default(),
+            gpu_panel_compact: false,
+            mouse_capture: false,
         })
     }
 
-    /// Returns usable size for ASCII content (reserves row 0 for status bar)
+    /// Full terminal size in cells, including the status bar
+    pub fn size(&self) -> (u16, u16) {
+        (self.cols, self.rows)
+    }
+
+    /// Returns usable size for ASCII content (reserves row 0 for status bar when shown)
     pub fn content_size(&self) -> (u16, u16) {
-        (self.cols, self.rows.saturating_sub(1))
+        (self.cols, self.rows.saturating_sub(self.top_row()))
+    }
+
+    /// First terminal row of the content area
+    fn top_row(&self) -> u16 {
+        self.show_status as u16
     }
 
     pub fn check_resize(&mut self) -> Result<bool> {
@@ -99,7 +259,6 @@ impl TerminalRenderer {
         if new_cols != self.cols || new_rows != self.rows {
             self.cols = new_cols;
             self.rows = new_rows;
-            self.buffer = String::with_capacity((new_cols as usize + 1) * new_rows as usize * 20);
             execute!(self.stdout, Clear(ClearType::All))?;
             Ok(true)
         } else {
@@ -107,304 +266,544 @@ impl TerminalRenderer {
         }
     }
 
-    /// Render using current mode, with optional mask region to skip
-    /// mask: Option<(start_col, start_row, width, height)> in terminal coordinates
-    pub fn render(&mut self, data: &[u32], cols: u32, rows: u32, mode: RenderMode, mask: Option<(u16, u16, u16, u16)>) -> Result<()> {
-        match mode {
-            RenderMode::PlainAscii => self.render_plain_ascii(data, cols, rows, mask),
-            RenderMode::ColoredAscii => self.render_colored_ascii(data, cols, rows, mask),
-            RenderMode::HalfBlock => self.render_half_block(data, cols, rows, mask),
+    /// Cells for packed GPU output in `mode`, with this renderer's charset and half-block glyph
+    pub fn to_frame(&self, data: &[u32], cols: u32, rows: u32, mode: RenderMode) -> Frame {
+        Frame::from_packed(data, cols, rows, mode, self.charset, self.half_block)
+    }
+
+    /// A blank frame the size of the terminal, for composing the screen before `present`
+    pub fn screen(&self) -> Frame {
+        Frame::new(self.cols, self.rows)
+    }
+
+    /// The content area below the status bar: (col, row, cols, rows)
+    fn content_area(&self) -> (u16, u16, u16, u16) {
+        let (cols, rows) = self.content_size();
+        (0, self.top_row(), cols, rows)
+    }
+
+    /// Put the 3D view's frame in the screen's content area
+    pub fn draw_content(&self, screen: &mut Frame, content: &Frame) {
+        screen.blit(content, 0, self.top_row());
+    }
+
+    /// Put the split view in the content area: both panes side by side with a divider line
+    pub fn draw_split(&self, screen: &mut Frame, left: &Frame, right: &Frame) {
+        let divider = self.charset.glyph('│').to_string();
+        for row in self.top_row()..self.rows {
+            screen.overlay_text(left.cols(), row, &divider, None, None);
         }
+        self.draw_content(screen, left);
+        screen.blit(right, left.cols() + 1, self.top_row());
     }
 
-    /// Check if a terminal position is inside the mask region
-    fn is_masked(&self, col: u16, row: u16, mask: Option<(u16, u16, u16, u16)>) -> bool {
-        if let Some((mask_col, mask_row, mask_w, mask_h)) = mask {
-            col >= mask_col && col < mask_col + mask_w && row >= mask_row && row < mask_row + mask_h
-        } else {
-            false
+    /// Put the raw render preview (half-block cells) in a corner, one cell in from the edges
+    /// It sits on the GPU panel's side, in the other vertical corner, so the two never overlap
+    pub fn draw_preview(&self, screen: &mut Frame, preview: &Frame) {
+        let (content_cols, content_rows) = self.content_size();
+        let width = preview.cols().min(content_cols.saturating_sub(2));
+        let height = preview.rows().min(content_rows.saturating_sub(2));
+        if width == 0 || height == 0 {
+            return;
         }
+        let col = if self.gpu_panel.is_left() {
+            1
+        } else {
+            content_cols - width - 1
+        };
+        let row = if self.gpu_panel.is_top() {
+            self.top_row() + content_rows - height - 1
+        } else {
+            self.top_row() + 1
+        };
+        screen.blit(&preview.crop(0, 0, width, height), col, row);
+    }
+
+    /// Draw HUD items over the content area
+    pub fn draw_hud(&self, screen: &mut Frame, hud: &Hud) {
+        hud.draw(screen, self.content_area(), &self.theme, self.charset);
     }
 
-    /// Plain ASCII mode - no colors
-    pub fn render_plain_ascii(&mut self, data: &[u32], cols: u32, rows: u32, mask: Option<(u16, u16, u16, u16)>) -> Result<()> {
-        let max_rows = rows.min(self.rows.saturating_sub(1) as u32);
-        let max_cols = cols.min(self.cols as u32);
-
-        queue!(self.stdout, MoveTo(0, 1))?;
-
-        for row in 0..max_rows {
-            let term_row = row as u16 + 1; // +1 for status bar
-            for col in 0..max_cols {
-                let term_col = col as u16;
-                if self.is_masked(term_col, term_row, mask) {
-                    queue!(self.stdout, Print(' '))?;
-                } else {
-                    let idx = (row * cols + col) as usize;
-                    if idx < data.len() {
-                        let (_, _, _, char_index) = unpack_data(data[idx]);
-                        queue!(self.stdout, Print(get_char(char_index)))?;
-                    }
+    /// Draw labels located in the 3D view's main pane (`pane_size` cells, at the content area's
+    /// top left) over it
+    pub fn draw_labels(&self, screen: &mut Frame, labels: &[(String, [f32; 2], f32)], pane_size: (u16, u16)) {
+        let (col, row, ..) = self.content_area();
+        hud::draw_labels(screen, (col, row, pane_size.0, pane_size.1), labels, &self.theme, self.charset);
+    }
+
+    /// Write a composed screen to the terminal; colors are matched to the palette and only sent
+    /// when they change
+    pub fn present(&mut self, screen: &Frame) -> Result<()> {
+        let rows = screen.rows().min(self.rows);
+        let cols = screen.cols().min(self.cols) as usize;
+        // Colors the terminal has set (None = its default)
+        let mut fg: Option<Color> = None;
+        let mut bg: Option<Color> = None;
+        for row in 0..rows {
+            queue!(self.stdout, MoveTo(0, row))?;
+            for cell in &screen.row(row)[..cols] {
+                let cell_fg = cell.fg.map(|color| self.palette.resolve(color));
+                let cell_bg = cell.bg.map(|color| self.palette.resolve(color));
+                if cell_fg != fg {
+                    queue!(self.stdout, SetForegroundColor(cell_fg.unwrap_or(Color::Reset)))?;
+                    fg = cell_fg;
                 }
-            }
-            if row < max_rows - 1 {
-                queue!(self.stdout, Print("\r\n"))?;
+                if cell_bg != bg {
+                    queue!(self.stdout, SetBackgroundColor(cell_bg.unwrap_or(Color::Reset)))?;
+                    bg = cell_bg;
+                }
+                queue!(self.stdout, Print(cell.ch))?;
             }
         }
-
+        queue!(self.stdout, ResetColor)?;
         self.stdout.flush()?;
         Ok(())
     }
 
-    /// Colored ASCII mode - ANSI 24-bit color
-    pub fn render_colored_ascii(&mut self, data: &[u32], cols: u32, rows: u32, mask: Option<(u16, u16, u16, u16)>) -> Result<()> {
-        let max_rows = rows.min(self.rows.saturating_sub(1) as u32);
-        let max_cols = cols.min(self.cols as u32);
-
-        queue!(self.stdout, MoveTo(0, 1))?;
-
-        let mut last_color: Option<(u8, u8, u8)> = None;
-
-        for row in 0..max_rows {
-            let term_row = row as u16 + 1; // +1 for status bar
-            for col in 0..max_cols {
-                let term_col = col as u16;
-                if self.is_masked(term_col, term_row, mask) {
-                    queue!(self.stdout, ResetColor, Print(' '))?;
-                    last_color = None;
-                } else {
-                    let idx = (row * cols + col) as usize;
-                    if idx < data.len() {
-                        let (r, g, b, char_index) = unpack_data(data[idx]);
-                        let ch = get_char(char_index);
-
-                        // Only change color if different from last
-                        if last_color != Some((r, g, b)) {
-                            queue!(self.stdout, SetForegroundColor(Color::Rgb { r, g, b }))?;
-                            last_color = Some((r, g, b));
-                        }
-                        queue!(self.stdout, Print(ch))?;
-                    }
-                }
-            }
-            if row < max_rows - 1 {
-                queue!(self.stdout, Print("\r\n"))?;
+    /// Generate frame as ANSI-colored string (for clipboard export)
+    pub fn frame_to_ansi_string(&self, data: &[u32], cols: u32, rows: u32, mode: RenderMode) -> String {
+        let (content_cols, content_rows) = self.content_size();
+        self.to_frame(data, cols, rows, mode).crop(0, 0, content_cols, content_rows).to_text(&self.palette)
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    pub fn set_charset(&mut self, charset: Charset) {
+        self.charset = charset;
+    }
+
+    pub fn set_half_block(&mut self, half_block: HalfBlock) {
+        self.half_block = half_block;
+    }
+
+    pub fn set_gpu_panel(&mut self, corner: PanelCorner, compact: bool) {
+        self.gpu_panel = corner;
+        self.gpu_panel_compact = compact;
+    }
+
+    /// Report mouse clicks as events (the terminal's text selection then needs a modifier, often Shift)
+    pub fn set_mouse_capture(&mut self, capture: bool) -> Result<()> {
+        if capture != self.mouse_capture {
+            if capture {
+                execute!(self.stdout, EnableMouseCapture)?;
+            } else {
+                execute!(self.stdout, DisableMouseCapture)?;
             }
+            self.mouse_capture = capture;
         }
+        Ok(())
+    }
 
-        queue!(self.stdout, ResetColor)?;
+    /// Where a terminal cell falls in the 3D view's main pane (`pane_size` cells, at the content
+    /// area's top left): its center as 0 to 1 across the pane (y down), or None outside the pane
+    pub fn pane_position(&self, column: u16, row: u16, pane_size: (u16, u16)) -> Option<[f32; 2]> {
+        let row = row.checked_sub(self.top_row())?;
+        (column < pane_size.0 && row < pane_size.1)
+            .then(|| [(column as f32 + 0.5) / pane_size.0 as f32, (row as f32 + 0.5) / pane_size.1 as f32])
+    }
+
+    /// Show or hide the status bar; hiding it gives its row to the content area
+    pub fn set_show_status(&mut self, show: bool) {
+        if show != self.show_status {
+            self.show_status = show;
+            let _ = execute!(self.stdout, Clear(ClearType::All));
+        }
+    }
+
+    /// Draw the status bar: model name on the left, mode and FPS centered, key hints on the right
+    pub fn draw_status(&self, screen: &mut Frame, model: &str, fps: f32, mode: &str) {
+        if !self.show_status {
+            return;
+        }
+        let center = format!("{} | {:.1} FPS", mode, fps);
+        let hints = "1-4: modes | c: config | g: gpu | esc: quit";
+        let status = status_line(self.cols as usize, model, &center, hints);
+        screen.overlay_text(0, 0, &self.charset.text(&status), theme_color(self.theme.status_fg), theme_color(self.theme.status_bg));
+    }
+
+    /// Replace the whole screen with centered lines of text, for when nothing else can be drawn
+    /// Lines are cut to fit; every row is rewritten so the screen doesn't need clearing
+    pub fn render_placeholder(&mut self, lines: &[&str]) -> Result<()> {
+        let width = self.cols as usize;
+        let first = (self.rows as usize).saturating_sub(lines.len()) / 2;
+        for row in 0..self.rows {
+            let line = (row as usize).checked_sub(first).and_then(|i| lines.get(i)).unwrap_or(&"");
+            let text: String = line.chars().take(width).collect();
+            let pad = (width - text.chars().count()) / 2;
+            let padded = format!("{:pad$}{:<rest$}", "", text, pad = pad, rest = width - pad);
+            queue!(self.stdout, MoveTo(0, row), ResetColor, Print(self.charset.text(&padded)))?;
+        }
         self.stdout.flush()?;
         Ok(())
     }
 
-    /// Half-block mode - uses ▀ with fg/bg colors for 2x vertical resolution
-    pub fn render_half_block(&mut self, data: &[u32], cols: u32, rows: u32, mask: Option<(u16, u16, u16, u16)>) -> Result<()> {
-        let max_rows = (rows / 2).min(self.rows.saturating_sub(1) as u32);
-        let max_cols = cols.min(self.cols as u32);
-
-        queue!(self.stdout, MoveTo(0, 1))?;
-
-        for term_row in 0..max_rows {
-            let actual_term_row = term_row as u16 + 1; // +1 for status bar
-            let top_row = term_row * 2;
-            let bottom_row = top_row + 1;
-
-            for col in 0..max_cols {
-                let term_col = col as u16;
-                if self.is_masked(term_col, actual_term_row, mask) {
-                    queue!(self.stdout, ResetColor, Print(' '))?;
-                } else {
-                    let top_idx = (top_row * cols + col) as usize;
-                    let bottom_idx = (bottom_row * cols + col) as usize;
-
-                    // Get colors for top and bottom pixels
-                    let (tr, tg, tb, _) = if top_idx < data.len() {
-                        unpack_data(data[top_idx])
-                    } else {
-                        (0, 0, 0, 0)
-                    };
-
-                    let (br, bg, bb, _) = if bottom_idx < data.len() && bottom_row < rows {
-                        unpack_data(data[bottom_idx])
-                    } else {
-                        (0, 0, 0, 0)
-                    };
-
-                    // ▀ (upper half block): foreground = top color, background = bottom color
-                    queue!(
-                        self.stdout,
-                        SetForegroundColor(Color::Rgb { r: tr, g: tg, b: tb }),
-                        SetBackgroundColor(Color::Rgb { r: br, g: bg, b: bb }),
-                        Print('▀')
-                    )?;
-                }
-            }
+    /// Draw a boxed one-line notice centered near the top (e.g. loading progress)
+    pub fn render_notice(&mut self, text: &str) -> Result<()> {
+        let inner = text.chars().count().min(self.cols.saturating_sub(4) as usize);
+        let text: String = text.chars().take(inner).collect();
+        let border = "─".repeat(inner + 2);
+        let col = self.cols.saturating_sub(inner as u16 + 4) / 2;
+        queue!(
+            self.stdout,
+            SetForegroundColor(self.theme.panel_fg.into()),
+            SetBackgroundColor(self.theme.panel_bg.into()),
+            MoveTo(col, 2),
+            Print(self.charset.text(&format!("┌{}┐", border))),
+            MoveTo(col, 3),
+            Print(self.charset.text(&format!("│ {} │", text))),
+            MoveTo(col, 4),
+            Print(self.charset.text(&format!("└{}┘", border))),
+            ResetColor
+        )?;
+        self.stdout.flush()?;
+        Ok(())
+    }
 
-            queue!(self.stdout, ResetColor)?;
-            if term_row < max_rows - 1 {
-                queue!(self.stdout, Print("\r\n"))?;
-            }
+    /// Draw a centered dialog box of lines with one highlighted (e.g. the export dialog)
+    pub fn render_dialog(&mut self, title: &str, lines: &[String], highlight: usize) -> Result<()> {
+        let max_inner = self.cols.saturating_sub(4) as usize;
+        let inner = lines
+            .iter()
+            .map(|l| l.chars().count())
+            .chain(std::iter::once(title.chars().count() + 2))
+            .max()
+            .unwrap_or(0)
+            .min(max_inner);
+        let col = self.cols.saturating_sub(inner as u16 + 4) / 2;
+        let title: String = title.chars().take(inner.saturating_sub(2)).collect();
+        let top_fill = "─".repeat(inner - title.chars().count());
+
+        queue!(
+            self.stdout,
+            SetForegroundColor(self.theme.panel_fg.into()),
+            SetBackgroundColor(self.theme.panel_bg.into()),
+            MoveTo(col, 2),
+            Print(self.charset.text(&format!("┌ {} {}┐", title, top_fill)))
+        )?;
+        for (i, line) in lines.iter().enumerate() {
+            let line: String = line.chars().take(inner).collect();
+            let fg = if i == highlight { self.theme.selection } else { self.theme.panel_fg };
+            queue!(
+                self.stdout,
+                MoveTo(col, 3 + i as u16),
+                Print(self.charset.text("│ ")),
+                SetForegroundColor(fg.into()),
+                Print(self.charset.text(&format!("{:<width$}", line, width = inner))),
+                SetForegroundColor(self.theme.panel_fg.into()),
+                Print(self.charset.text(" │"))
+            )?;
         }
+        queue!(
+            self.stdout,
+            MoveTo(col, 3 + lines.len() as u16),
+            Print(self.charset.text(&format!("└{}┘", "─".repeat(inner + 2)))),
+            ResetColor
+        )?;
+        self.stdout.flush()?;
+        Ok(())
+    }
 
-        queue!(self.stdout, ResetColor)?;
+    /// Draw a caption box centered above the bottom edge (e.g. narration from a demo script)
+    /// `opacity` fades the text and panel in from black (0 = invisible, 1 = fully shown)
+    pub fn render_caption(&mut self, text: &str, opacity: f32) -> Result<()> {
+        const TEXT: (f32, f32, f32) = (240.0, 240.0, 235.0);
+        const PANEL: (f32, f32, f32) = (24.0, 24.0, 36.0);
+        if opacity <= 0.0 {
+            return Ok(());
+        }
+        let fade = |(r, g, b): (f32, f32, f32)| Color::Rgb {
+            r: (r * opacity) as u8,
+            g: (g * opacity) as u8,
+            b: (b * opacity) as u8,
+        };
+
+        let max_inner = self.cols.saturating_sub(4) as usize;
+        let lines: Vec<String> = text
+            .lines()
+            .take(self.rows.saturating_sub(4) as usize)
+            .map(|l| l.chars().take(max_inner).collect())
+            .collect();
+        let inner = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let width = inner + 4;
+        let col = self.cols.saturating_sub(width as u16) / 2;
+        // One padding row above and below the text, bottom edge one row above the screen edge
+        let top = self.rows.saturating_sub(lines.len() as u16 + 3);
+
+        queue!(self.stdout, SetForegroundColor(fade(TEXT)), SetBackgroundColor(fade(PANEL)))?;
+        queue!(self.stdout, MoveTo(col, top), Print(" ".repeat(width)))?;
+        for (i, line) in lines.iter().enumerate() {
+            // Center each line within the panel
+            let pad = inner - line.chars().count();
+            let left = 2 + pad / 2;
+            let right = 2 + pad - pad / 2;
+            queue!(
+                self.stdout,
+                MoveTo(col, top + 1 + i as u16),
+                Print(format!("{}{}{}", " ".repeat(left), self.charset.text(line), " ".repeat(right)))
+            )?;
+        }
+        queue!(
+            self.stdout,
+            MoveTo(col, top + 1 + lines.len() as u16),
+            Print(" ".repeat(width)),
+            ResetColor
+        )?;
         self.stdout.flush()?;
         Ok(())
     }
 
-    /// Generate frame as ANSI-colored string (for clipboard export)
-    pub fn frame_to_ansi_string(&self, data: &[u32], cols: u32, rows: u32, mode: RenderMode) -> String {
-        match mode {
-            RenderMode::PlainAscii => self.frame_to_plain_string(data, cols, rows),
-            RenderMode::ColoredAscii => self.frame_to_colored_string(data, cols, rows),
-            RenderMode::HalfBlock => self.frame_to_halfblock_string(data, cols, rows),
+    /// Draw a boxed multi-line message near the top of the screen (e.g. shader compile errors)
+    /// Long messages are truncated to fit the terminal
+    pub fn render_error_overlay(&mut self, title: &str, message: &str) -> Result<()> {
+        let max_inner = self.cols.saturating_sub(4) as usize;
+        let max_lines = self.rows.saturating_sub(6) as usize;
+        let lines: Vec<String> = message
+            .lines()
+            .take(max_lines)
+            .map(|l| l.replace('\t', "    ").chars().take(max_inner).collect())
+            .collect();
+        let inner = lines
+            .iter()
+            .map(|l| l.chars().count())
+            .chain(std::iter::once(title.chars().count() + 2))
+            .max()
+            .unwrap_or(0)
+            .min(max_inner);
+        let col = self.cols.saturating_sub(inner as u16 + 4) / 2;
+
+        let title: String = title.chars().take(inner.saturating_sub(2)).collect();
+        let top_fill = "─".repeat(inner - title.chars().count());
+        queue!(
+            self.stdout,
+            ResetColor,
+            SetForegroundColor(Color::Red),
+            MoveTo(col, 2),
+            Print(self.charset.text(&format!("┌ {} {}┐", title, top_fill)))
+        )?;
+        for (i, line) in lines.iter().enumerate() {
+            queue!(
+                self.stdout,
+                MoveTo(col, 3 + i as u16),
+                Print(self.charset.text(&format!("│ {:<width$} │", line, width = inner)))
+            )?;
         }
+        queue!(
+            self.stdout,
+            MoveTo(col, 3 + lines.len() as u16),
+            Print(self.charset.text(&format!("└{}┘", "─".repeat(inner + 2)))),
+            ResetColor
+        )?;
+        self.stdout.flush()?;
+        Ok(())
     }
 
-    fn frame_to_plain_string(&self, data: &[u32], cols: u32, rows: u32) -> String {
-        let max_rows = rows.min(self.rows.saturating_sub(1) as u32);
-        let max_cols = cols.min(self.cols as u32);
-        let mut output = String::new();
+    /// Draw the frame profiler panel in the top-left corner
+    /// Each (label, ms) stage gets a row and a segment of the stacked bar, colored alike
+    pub fn render_profiler(&mut self, stages: &[(&str, f32)]) -> Result<()> {
+        const PALETTE: &[Color] = &[
+            Color::Blue,
+            Color::Cyan,
+            Color::Green,
+            Color::Yellow,
+            Color::Magenta,
+            Color::Red,
+        ];
+        const BAR_WIDTH: usize = 30;
 
-        for row in 0..max_rows {
-            for col in 0..max_cols {
-                let idx = (row * cols + col) as usize;
-                if idx < data.len() {
-                    let (_, _, _, char_index) = unpack_data(data[idx]);
-                    output.push(get_char(char_index));
-                }
-            }
-            output.push('\n');
-        }
-        output
-    }
-
-    fn frame_to_colored_string(&self, data: &[u32], cols: u32, rows: u32) -> String {
-        let max_rows = rows.min(self.rows.saturating_sub(1) as u32);
-        let max_cols = cols.min(self.cols as u32);
-        let mut output = String::new();
-        let mut last_color: Option<(u8, u8, u8)> = None;
-
-        for row in 0..max_rows {
-            for col in 0..max_cols {
-                let idx = (row * cols + col) as usize;
-                if idx < data.len() {
-                    let (r, g, b, char_index) = unpack_data(data[idx]);
-                    let ch = get_char(char_index);
-
-                    if last_color != Some((r, g, b)) {
-                        // ANSI 24-bit color: ESC[38;2;R;G;Bm
-                        output.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
-                        last_color = Some((r, g, b));
-                    }
-                    output.push(ch);
-                }
-            }
-            output.push_str("\x1b[0m\n"); // Reset at end of line
-            last_color = None;
+        let total: f32 = stages.iter().map(|&(_, ms)| ms).sum();
+        let mut row = 1;
+        queue!(
+            self.stdout,
+            ResetColor,
+            MoveTo(0, row),
+            Print(format!(" {:<14}{:>8.2} ms {:>5.0} fps ", "Frame", total, 1000.0 / total.max(0.001)))
+        )?;
+        row += 1;
+
+        for (i, &(label, ms)) in stages.iter().enumerate() {
+            let share = if total > 0.0 { ms / total * 100.0 } else { 0.0 };
+            queue!(
+                self.stdout,
+                MoveTo(0, row),
+                SetForegroundColor(PALETTE[i % PALETTE.len()]),
+                Print(self.charset.text(" ■ ")),
+                ResetColor,
+                Print(format!("{:<12}{:>8.2} ms {:>4.0}%     ", label, ms, share))
+            )?;
+            row += 1;
         }
-        output
-    }
-
-    fn frame_to_halfblock_string(&self, data: &[u32], cols: u32, rows: u32) -> String {
-        let max_rows = (rows / 2).min(self.rows.saturating_sub(1) as u32);
-        let max_cols = cols.min(self.cols as u32);
-        let mut output = String::new();
-
-        for term_row in 0..max_rows {
-            let top_row = term_row * 2;
-            let bottom_row = top_row + 1;
-
-            for col in 0..max_cols {
-                let top_idx = (top_row * cols + col) as usize;
-                let bottom_idx = (bottom_row * cols + col) as usize;
-
-                let (tr, tg, tb, _) = if top_idx < data.len() {
-                    unpack_data(data[top_idx])
-                } else {
-                    (0, 0, 0, 0)
-                };
-
-                let (br, bg, bb, _) = if bottom_idx < data.len() && bottom_row < rows {
-                    unpack_data(data[bottom_idx])
-                } else {
-                    (0, 0, 0, 0)
-                };
-
-                // ANSI: fg=top, bg=bottom, char=▀
-                output.push_str(&format!(
-                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m▀",
-                    tr, tg, tb, br, bg, bb
-                ));
+
+        // Stacked bar: segment widths proportional to each stage's share of the frame
+        queue!(self.stdout, MoveTo(0, row), Print(" "))?;
+        let mut drawn = 0;
+        let mut elapsed = 0.0;
+        for (i, &(_, ms)) in stages.iter().enumerate() {
+            elapsed += ms;
+            let end = if total > 0.0 {
+                ((elapsed / total) * BAR_WIDTH as f32).round() as usize
+            } else {
+                0
+            };
+            let width = end.saturating_sub(drawn);
+            if width > 0 {
+                queue!(
+                    self.stdout,
+                    SetForegroundColor(PALETTE[i % PALETTE.len()]),
+                    Print(self.charset.text(&"█".repeat(width)))
+                )?;
+                drawn += width;
             }
-            output.push_str("\x1b[0m\n");
         }
-        output
-    }
-
-    pub fn render_status(&mut self, fps: f32, mode: &str) -> Result<()> {
-        let status = format!(" {} | {:.1} FPS | 1-3: modes | c: config | g: gpu | q: quit ", mode, fps);
-        execute!(
+        queue!(
             self.stdout,
-            MoveTo(0, 0),
             ResetColor,
-            Print(&status)
+            Print(" ".repeat(BAR_WIDTH - drawn.min(BAR_WIDTH) + 1))
         )?;
+
+        self.stdout.flush()?;
         Ok(())
     }
 
-    /// Calculate the mask region for GPU info display
-    /// Returns (start_col, start_row, width, height) in terminal coordinates
-    pub fn gpu_info_mask(&self, gpu_name: &str) -> (u16, u16, u16, u16) {
-        const NUM_LINES: u16 = 4;
-        // Estimate max line length based on GPU name + fixed formatting
-        let max_len = (gpu_name.len() + 12).max(30) as u16; // "      GPU: " prefix + name
-        let start_row = self.rows.saturating_sub(NUM_LINES + 1);
-        let start_col = self.cols.saturating_sub(max_len + 1);
-        (start_col, start_row, max_len + 1, NUM_LINES)
+    /// Draw GPU/performance info in the configured corner, over a dimmed backdrop that takes in
+    /// a one-cell margin on the screen-edge side
+    /// Uses fixed-width formatting so labels stay in place while values change
+    pub fn draw_gpu_info(
+        &self,
+        screen: &mut Frame,
+        gpu_name: &str,
+        gpu_time_ms: f32,
+        render_res: (u32, u32),
+        pipeline_res: (u32, u32),
+        extra_lines: &[String],
+    ) {
+        let lines = self.gpu_info_lines(gpu_name, gpu_time_ms, render_res, pipeline_res, extra_lines);
+        let (col, row, width, height) = self.gpu_panel_rect(&lines);
+        shade(screen, (col.saturating_sub(self.gpu_panel.is_left() as u16), row, width + 1, height), &self.theme);
+        for (i, line) in lines.iter().enumerate() {
+            // Padded to the panel width so values of varying length overwrite each other
+            let padded = self.charset.text(&format!("{:>width$}", line, width = width as usize));
+            screen.overlay_text(col, row + i as u16, &padded, theme_color(self.theme.panel_fg), theme_color(self.theme.panel_bg));
+        }
     }
 
-    /// Render GPU/performance info in bottom right corner
-    /// Uses fixed-width formatting so labels stay in place while values change
-    pub fn render_gpu_info(
-        &mut self,
+    /// Panel text: four labelled lines, or one line in compact mode, then `extra_lines`
+    fn gpu_info_lines(
+        &self,
         gpu_name: &str,
         gpu_time_ms: f32,
         render_res: (u32, u32),
         pipeline_res: (u32, u32),
-    ) -> Result<()> {
-        // Format each line with fixed-width values (right-aligned numbers)
-        let lines = [
-            format!("      GPU: {}", gpu_name),
-            format!("  GPU Time: {:>6.2} ms", gpu_time_ms),
-            format!("   Render: {:>4} x {:>4} px", render_res.0, render_res.1),
-            format!(" Pipeline: {:>4} x {:>4} cells", pipeline_res.0, pipeline_res.1),
-        ];
+        extra_lines: &[String],
+    ) -> Vec<String> {
+        let mut lines = if self.gpu_panel_compact {
+            vec![format!(
+                "{} | {:>6.2} ms | {:>4}x{:<4} px | {:>3}x{:<3} cells",
+                gpu_name, gpu_time_ms, render_res.0, render_res.1, pipeline_res.0, pipeline_res.1
+            )]
+        } else {
+            vec![
+                format!("      GPU: {}", gpu_name),
+                format!("  GPU Time: {:>6.2} ms", gpu_time_ms),
+                format!("   Render: {:>4} x {:>4} px", render_res.0, render_res.1),
+                format!(" Pipeline: {:>4} x {:>4} cells", pipeline_res.0, pipeline_res.1),
+            ]
+        };
+        lines.extend_from_slice(extra_lines);
+        lines
+    }
 
-        // Find the longest line to align everything to the right
-        let max_len = lines.iter().map(|l| l.len()).max().unwrap_or(0) as u16;
+    /// Where the panel's text goes: (start_col, start_row, width, height)
+    /// A row and column are left clear between the panel and the screen edges
+    fn gpu_panel_rect(&self, lines: &[String]) -> (u16, u16, u16, u16) {
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16;
+        let height = lines.len() as u16;
+        let col = if self.gpu_panel.is_left() {
+            1
+        } else {
+            self.cols.saturating_sub(width + 1)
+        };
+        let row = if self.gpu_panel.is_top() {
+            self.top_row() + 1
+        } else {
+            self.rows.saturating_sub(height + 1)
+        };
+        (col, row, width, height)
+    }
+}
 
-        // Draw from bottom up, leaving last row clear
-        let start_row = self.rows.saturating_sub(lines.len() as u16 + 1);
-        let start_col = self.cols.saturating_sub(max_len + 1);
+/// Lay out a status bar exactly `width` characters wide
+/// `center` is kept longest: on narrow terminals the hints go first, then `left` is shortened
+fn status_line(width: usize, left: &str, center: &str, right: &str) -> String {
+    let len = |s: &str| s.chars().count();
+    // One space of padding at each end and at least two between segments
+    let fits = |l: usize, r: usize| {
+        let gaps = 2 + if l > 0 { 2 } else { 0 } + if r > 0 { 2 } else { 0 };
+        l + len(center) + r + gaps <= width
+    };
+
+    let right = if fits(len(left), len(right)) { right } else { "" };
+    let left = if fits(len(left), len(right)) {
+        left.to_string()
+    } else {
+        // Shorten the model name with an ellipsis, dropping it if only a stub would remain
+        let room = width.saturating_sub(len(center) + 4);
+        if room >= 4 {
+            let mut short: String = left.chars().take(room - 1).collect();
+            short.push('…');
+            short
+        } else {
+            String::new()
+        }
+    };
 
-        for (i, line) in lines.iter().enumerate() {
-            // Pad line to max_len for consistent clearing
-            let padded = format!("{:>width$}", line, width = max_len as usize);
-            queue!(
-                self.stdout,
-                MoveTo(start_col, start_row + i as u16),
-                ResetColor,
-                Print(&padded)
-            )?;
+    let mut line: Vec<char> = vec![' '; width];
+    let mut put = |start: usize, text: &str| {
+        for (i, ch) in text.chars().enumerate() {
+            if let Some(cell) = line.get_mut(start + i) {
+                *cell = ch;
+            }
         }
+    };
+    put(1, &left);
+    let right_start = width.saturating_sub(len(right) + 1);
+    put(right_start, right);
+    // Centered on the bar, nudged aside if that would overlap the other segments
+    let lowest = if left.is_empty() { 1 } else { len(&left) + 3 };
+    let highest = if right.is_empty() { width } else { right_start.saturating_sub(1) }
+        .saturating_sub(len(center) + 1);
+    let center_start = (width.saturating_sub(len(center)) / 2).min(highest).max(lowest);
+    put(center_start, center);
+    line.into_iter().collect()
+}
 
-        self.stdout.flush()?;
-        Ok(())
-    }
+/// Colors and glyphs for frame text, matching the live view's settings
+pub struct TextStyle<'a> {
+    pub palette: &'a Palette,
+    pub charset: Charset,
+    pub half_block: HalfBlock,
+}
+
+/// Convert a frame to text (ANSI-colored for the color modes)
+/// `limit` is the (cols, rows) terminal area the output is clipped to
+/// Colors are matched to the style's palette and characters limited to its charset, so exports look the
+/// same as the live view
+pub fn frame_to_text(data: &[u32], cols: u32, rows: u32, mode: RenderMode, limit: (u16, u16), style: &TextStyle) -> String {
+    Frame::from_packed(data, cols, rows, mode, style.charset, style.half_block)
+        .crop(0, 0, limit.0, limit.1)
+        .to_text(style.palette)
 }
 
 impl Drop for TerminalRenderer {
     fn drop(&mut self) {
+        if self.mouse_capture {
+            let _ = execute!(self.stdout, DisableMouseCapture);
+        }
         let _ = execute!(self.stdout, ResetColor, Show, LeaveAlternateScreen);
         let _ = disable_raw_mode();
     }
@@ -0,0 +1,338 @@
+use anyhow::{anyhow, Result};
+use crossterm::style::Color;
+use std::cell::OnceCell;
+
+/// Bits per channel of the nearest-color lookup table
+const LUT_BITS: u32 = 5;
+
+/// Color capability of the output terminal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit RGB escapes
+    #[default]
+    TrueColor,
+    /// xterm 256-color palette (6x6x6 cube + gray ramp)
+    Ansi256,
+    /// The 16 standard ANSI colors
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Guess from $COLORTERM and $TERM
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        if colorterm.contains("truecolor") || colorterm.contains("24bit") {
+            return ColorDepth::TrueColor;
+        }
+        let term = std::env::var("TERM").unwrap_or_default();
+        if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else if term.is_empty() || term.contains("direct") {
+            // Windows terminals don't set TERM and support 24-bit color
+            ColorDepth::TrueColor
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// Parse a config file value: "truecolor", "256", or "16"
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" => Ok(ColorDepth::TrueColor),
+            "256" => Ok(ColorDepth::Ansi256),
+            "16" => Ok(ColorDepth::Ansi16),
+            _ => Err(anyhow!(
+                "Unknown color depth {:?} (expected \"auto\", \"truecolor\", \"256\", or \"16\")",
+                name
+            )),
+        }
+    }
+}
+
+/// Maps frame colors to what the terminal can show
+/// Reduced palettes use nearest-color matching in OKLab, which keeps hue and lightness
+/// far better than matching raw RGB
+pub struct Palette {
+    depth: ColorDepth,
+    /// Multiplier on OKLab chroma before matching (> 1 favors saturated palette entries)
+    chroma_boost: f32,
+    /// Nearest palette index per quantized RGB, built on first use
+    lut: OnceCell<Vec<u8>>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new(ColorDepth::TrueColor, 1.0)
+    }
+}
+
+impl Palette {
+    pub fn new(depth: ColorDepth, chroma_boost: f32) -> Self {
+        Self {
+            depth,
+            chroma_boost: chroma_boost.max(0.0),
+            lut: OnceCell::new(),
+        }
+    }
+
+    /// Foreground/background color for an RGB value
+    pub fn color(&self, r: u8, g: u8, b: u8) -> Color {
+        match self.depth {
+            ColorDepth::TrueColor => Color::Rgb { r, g, b },
+            ColorDepth::Ansi256 => Color::AnsiValue(self.nearest(r, g, b)),
+            ColorDepth::Ansi16 => ANSI16_NAMED[self.nearest(r, g, b) as usize],
+        }
+    }
+
+    /// A cell color for the terminal: RGB matched to the palette, named colors (the terminal's own) as they are
+    pub fn resolve(&self, color: Color) -> Color {
+        match color {
+            Color::Rgb { r, g, b } => self.color(r, g, b),
+            color => color,
+        }
+    }
+
+    /// SGR parameters for an exported escape sequence, e.g. "38;2;255;0;0" or "91"
+    pub fn sgr(&self, r: u8, g: u8, b: u8, background: bool) -> String {
+        match self.depth {
+            ColorDepth::TrueColor => {
+                format!("{};2;{};{};{}", if background { 48 } else { 38 }, r, g, b)
+            }
+            ColorDepth::Ansi256 => {
+                format!("{};5;{}", if background { 48 } else { 38 }, self.nearest(r, g, b))
+            }
+            ColorDepth::Ansi16 => {
+                let index = self.nearest(r, g, b);
+                let base = match (background, index < 8) {
+                    (false, true) => 30,
+                    (false, false) => 90 - 8,
+                    (true, true) => 40,
+                    (true, false) => 100 - 8,
+                };
+                format!("{}", base + index as u32)
+            }
+        }
+    }
+
+    /// Palette index closest to the color
+    fn nearest(&self, r: u8, g: u8, b: u8) -> u8 {
+        let lut = self.lut.get_or_init(|| self.build_lut());
+        let shift = 8 - LUT_BITS;
+        let key = ((r as usize >> shift) << (2 * LUT_BITS))
+            | ((g as usize >> shift) << LUT_BITS)
+            | (b as usize >> shift);
+        lut[key]
+    }
+
+    fn build_lut(&self) -> Vec<u8> {
+        // Match against the cube and gray ramp only in 256-color mode:
+        // the first 16 entries follow the terminal's theme and can't be relied on
+        let (first, entries): (u8, Vec<[f32; 3]>) = match self.depth {
+            ColorDepth::Ansi16 => (0, (0..16).map(|i| oklab(xterm_rgb(i))).collect()),
+            _ => (16, (16..=255).map(|i| oklab(xterm_rgb(i))).collect()),
+        };
+
+        let levels = 1u32 << LUT_BITS;
+        let step = 255.0 / (levels - 1) as f32;
+        let mut lut = Vec::with_capacity((levels * levels * levels) as usize);
+        for r in 0..levels {
+            for g in 0..levels {
+                for b in 0..levels {
+                    let rgb = [r, g, b].map(|c| (c as f32 * step).round() as u8);
+                    let [l, a, b] = oklab(rgb);
+                    let target = [l, a * self.chroma_boost, b * self.chroma_boost];
+                    let best = entries
+                        .iter()
+                        .enumerate()
+                        .map(|(i, e)| {
+                            let d = (e[0] - target[0]).powi(2)
+                                + (e[1] - target[1]).powi(2)
+                                + (e[2] - target[2]).powi(2);
+                            (i, d)
+                        })
+                        .min_by(|x, y| x.1.total_cmp(&y.1))
+                        .map_or(0, |(i, _)| i);
+                    lut.push(first + best as u8);
+                }
+            }
+        }
+        lut
+    }
+}
+
+/// Saturation/brightness/contrast (and a colorblind correction) applied to cell colors before they
+/// reach the terminal or an export, for terminals that show the raw colors too dark or too garish
+/// (1 = unchanged)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorAdjust {
+    /// Multiplier on the distance from gray (0 = grayscale)
+    pub saturation: f32,
+    /// Multiplier on every channel
+    pub brightness: f32,
+    /// Multiplier on the distance from mid-gray
+    pub contrast: f32,
+    /// Color deficiency the colors are corrected for, applied last
+    pub vision: ColorVision,
+}
+
+impl Default for ColorAdjust {
+    fn default() -> Self {
+        Self {
+            saturation: 1.0,
+            brightness: 1.0,
+            contrast: 1.0,
+            vision: ColorVision::Normal,
+        }
+    }
+}
+
+impl ColorAdjust {
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Adjust packed 0xRRGGBBCC cells in place (the character byte is kept)
+    pub fn apply(&self, cells: &mut [u32]) {
+        if self.is_identity() {
+            return;
+        }
+        for cell in cells {
+            let channel = |shift: u32| ((*cell >> shift) & 0xFF) as f32 / 255.0;
+            let rgb = [channel(24), channel(16), channel(8)];
+            let luma = 0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2];
+            let rgb = rgb.map(|c| {
+                let c = luma + (c - luma) * self.saturation;
+                (c * self.brightness - 0.5) * self.contrast + 0.5
+            });
+            let [r, g, b] = self.vision.daltonize(rgb).map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u32);
+            *cell = (r << 24) | (g << 16) | (b << 8) | (*cell & 0xFF);
+        }
+    }
+}
+
+/// Color vision deficiency the colored modes are corrected for
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorVision {
+    #[default]
+    Normal,
+    /// Weak or missing green cones (the most common form)
+    Deuteranopia,
+    /// Weak or missing red cones
+    Protanopia,
+}
+
+impl ColorVision {
+    /// Parse a config file value: "normal", "deuteranopia", or "protanopia"
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "normal" => Ok(ColorVision::Normal),
+            "deuteranopia" => Ok(ColorVision::Deuteranopia),
+            "protanopia" => Ok(ColorVision::Protanopia),
+            _ => Err(anyhow!(
+                "Unknown color vision {:?} (expected \"normal\", \"deuteranopia\", or \"protanopia\")",
+                name
+            )),
+        }
+    }
+
+    /// Daltonize an RGB color: the difference the deficiency can't see (simulated in LMS cone
+    /// space) is moved into the channels it can, so red/green contrasts become lightness and
+    /// blue contrasts
+    fn daltonize(self, rgb: [f32; 3]) -> [f32; 3] {
+        const RGB_TO_LMS: [[f32; 3]; 3] = [
+            [17.8824, 43.5161, 4.11935],
+            [3.45565, 27.1554, 3.86714],
+            [0.0299566, 0.184309, 1.46709],
+        ];
+        const LMS_TO_RGB: [[f32; 3]; 3] = [
+            [0.080944, -0.130504, 0.116721],
+            [-0.010249, 0.054019, -0.113615],
+            [-0.000365, -0.004122, 0.693511],
+        ];
+        let mul = |m: &[[f32; 3]; 3], v: [f32; 3]| m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2]);
+
+        let [l, m, s] = mul(&RGB_TO_LMS, rgb);
+        let seen = match self {
+            ColorVision::Normal => return rgb,
+            ColorVision::Deuteranopia => [l, 0.494207 * l + 1.24827 * s, s],
+            ColorVision::Protanopia => [2.02344 * m - 2.52581 * s, m, s],
+        };
+        let seen = mul(&LMS_TO_RGB, seen);
+        let error = [rgb[0] - seen[0], rgb[1] - seen[1], rgb[2] - seen[2]];
+        [rgb[0], rgb[1] + 0.7 * error[0] + error[1], rgb[2] + 0.7 * error[0] + error[2]]
+    }
+}
+
+/// crossterm colors for ANSI indices 0-15 (emitted as SGR 30-37/90-97)
+const ANSI16_NAMED: [Color; 16] = [
+    Color::Black,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::DarkYellow,
+    Color::DarkBlue,
+    Color::DarkMagenta,
+    Color::DarkCyan,
+    Color::Grey,
+    Color::DarkGrey,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+/// RGB of an xterm palette entry (the 16 system colors use xterm's defaults)
+fn xterm_rgb(index: u8) -> [u8; 3] {
+    const SYSTEM: [[u8; 3]; 16] = [
+        [0, 0, 0],
+        [205, 0, 0],
+        [0, 205, 0],
+        [205, 205, 0],
+        [0, 0, 238],
+        [205, 0, 205],
+        [0, 205, 205],
+        [229, 229, 229],
+        [127, 127, 127],
+        [255, 0, 0],
+        [0, 255, 0],
+        [255, 255, 0],
+        [92, 92, 255],
+        [255, 0, 255],
+        [0, 255, 255],
+        [255, 255, 255],
+    ];
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=15 => SYSTEM[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            [CUBE[(i / 36) as usize], CUBE[(i / 6 % 6) as usize], CUBE[(i % 6) as usize]]
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            [level, level, level]
+        }
+    }
+}
+
+/// sRGB to OKLab (L, a, b)
+fn oklab(rgb: [u8; 3]) -> [f32; 3] {
+    let [r, g, b] = rgb.map(|c| {
+        let c = c as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    });
+    let l = (0.41222147 * r + 0.53633254 * g + 0.05144599 * b).cbrt();
+    let m = (0.2119035 * r + 0.6806995 * g + 0.10739696 * b).cbrt();
+    let s = (0.08830246 * r + 0.28171884 * g + 0.6299787 * b).cbrt();
+    [
+        0.21045426 * l + 0.7936178 * m - 0.00407205 * s,
+        1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+        0.02590404 * l + 0.78277177 * m - 0.80867577 * s,
+    ]
+}
@@ -1,26 +1,91 @@
+mod audio;
+mod calibrate;
+mod caption;
+mod capture;
+mod cli;
 mod config;
+mod convert;
+mod export;
+mod gallery;
 mod gpu;
 mod model;
+mod profiler;
+mod recording;
+mod render_thread;
+mod scene;
+mod script;
+#[cfg(test)]
+mod snapshot;
+mod stats;
 mod terminal;
+mod theme;
+mod tile;
+mod timeline;
+mod video;
 
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use crossterm::cursor::Hide;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
 use crossterm::execute;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
-use std::io::stdout;
-use std::path::Path;
+use std::io::{stdout, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use arboard::Clipboard;
-use config::{run_config_ui, ConfigState};
-use gpu::{AsciiPipeline, HeadlessGpu};
-use model::load_model;
-use terminal::{RenderMode, TerminalRenderer};
+use audio::AudioInput;
+use caption::{Captions, MAX_PRESETS};
+use capture::LoopCapture;
+use cli::{CliArgs, GalleryArgs};
+use export::{DialogAction, ExportDialog, ExportTarget};
+use config::{
+    default_config_path, get_skybox_display_name, get_style_display_name, run_config_ui, run_onboarding, ConfigFile,
+    ConfigState, SkyboxSidecar, StarterAssets,
+};
+use gpu::{framing_distance, AsciiShaders, LabelAnchor, LightingMode, RotationMode, ShaderWatcher, ACCUMULATE_SAMPLES, CAMERA_DISTANCE, DEFAULT_EXPOSURE, DEFAULT_GAMMA};
+use model::{discover_models, get_model_display_name, load_model, LoadOptions, ModelData, ModelStats, Progress};
+use profiler::{FrameProfiler, Stage};
+use recording::Recorder;
+use render_thread::{CameraPose, FrameRequest, FrameResult, RenderThread};
+use script::{ScriptCommand, ScriptReply, ScriptRequest, ScriptRunner, ATTRACT_PLAYLIST};
+use stats::SessionStats;
+use scene::{
+    build_clock_model, build_life_model, build_marquee_model, build_text_model, build_water_model, instance_transforms, load_solar_bodies, local_time_of_day,
+    BuiltinScene, CsgPlayground, MarqueePath, PhysicsSim, PlantScene, SolarSystem, SystemGauges, Terrain, write_primitive_models,
+};
+use terminal::{ColorDepth, HalfBlock, Hud, Palette, RenderMode, TerminalRenderer};
+use tile::TileLink;
+use timeline::{Timeline, SCRUB_STEP};
+use video::VideoSource;
 
 const MODELS_DIR: &str = "assets/models";
 const SKYBOXES_DIR: &str = "assets/skyboxes";
+const STYLES_DIR: &str = "assets/styles";
+const CAPTIONS_FILE: &str = "assets/captions.txt";
+
+/// Target frame time (~30 fps), also the fixed time step in deterministic mode
+const FRAME_TIME: Duration = Duration::from_millis(33);
+
+/// Time of day shown by the clock scene in deterministic mode (10:10:00)
+const DETERMINISTIC_TIME_OF_DAY: f32 = 10.0 * 3600.0 + 10.0 * 60.0;
+
+/// How often the model and skybox directories are re-scanned for new files
+const ASSET_SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long the stats export result stays on screen
+const STATS_NOTICE_TIME: Duration = Duration::from_secs(3);
+
+/// Smallest terminal (columns, rows) that renders; below it a resize notice is shown
+const MIN_TERMINAL_SIZE: (u16, u16) = (40, 12);
+
+/// How long changed settings (tone mapping, lighting) stay in the status bar
+const STATUS_FLASH_TIME: Duration = Duration::from_secs(2);
+
+/// Fastest rotation speed with `reduced_motion` (the config menu's speed is capped to it)
+const REDUCED_MOTION_SPEED: f32 = 0.5;
 
 /// Application mode
 enum AppMode {
@@ -36,9 +101,9 @@ struct ManualControls {
     rotation: (f32, f32),
     /// Angular velocity (pitch/sec, yaw/sec)
     velocity: (f32, f32),
-    /// Camera zoom distance
+    /// Camera zoom distance (also the auto-rotation camera distance)
     zoom: f32,
-    /// Default zoom distance
+    /// Default zoom distance: the camera distance fitted to the model
     default_zoom: f32,
 }
 
@@ -48,8 +113,8 @@ impl ManualControls {
             active: false,
             rotation: (0.0, 0.0),
             velocity: (0.0, 0.0),
-            zoom: 4.0,
-            default_zoom: 4.0,
+            zoom: CAMERA_DISTANCE,
+            default_zoom: CAMERA_DISTANCE,
         }
     }
 
@@ -76,15 +141,26 @@ impl ManualControls {
         self.active = true;
     }
 
-    /// Adjust zoom
-    fn zoom_in(&mut self) {
+    /// Adjust zoom; `keep_auto` leaves auto rotation running (the Orbit camera follows the zoom)
+    fn zoom_in(&mut self, keep_auto: bool) {
         self.zoom = (self.zoom - 0.15).max(1.5);
-        self.active = true;
+        self.active |= !keep_auto;
     }
 
-    fn zoom_out(&mut self) {
+    fn zoom_out(&mut self, keep_auto: bool) {
         self.zoom = (self.zoom + 0.15).min(15.0);
-        self.active = true;
+        self.active |= !keep_auto;
+    }
+
+    /// Make `distance` the default zoom and zoom to it
+    fn frame(&mut self, distance: f32) {
+        self.default_zoom = distance;
+        self.zoom = distance;
+    }
+
+    /// Orbit radius scale for the current zoom (the configured orbit is for the unfitted distance)
+    fn orbit_scale(&self) -> f32 {
+        self.zoom / CAMERA_DISTANCE
     }
 
     /// Update physics (apply velocity to rotation, apply damping)
@@ -113,106 +189,734 @@ impl ManualControls {
     }
 }
 
+/// Animation clock: wall time normally, frame index times a fixed step when deterministic
+struct FrameClock {
+    deterministic: bool,
+    start: Instant,
+    last_frame: Instant,
+    frame_index: u64,
+}
+
+impl FrameClock {
+    fn new(deterministic: bool) -> Self {
+        Self {
+            deterministic,
+            start: Instant::now(),
+            last_frame: Instant::now(),
+            frame_index: 0,
+        }
+    }
+
+    /// Seconds since the first frame
+    fn elapsed(&self) -> f32 {
+        if self.deterministic {
+            self.frame_index as f32 * FRAME_TIME.as_secs_f32()
+        } else {
+            self.start.elapsed().as_secs_f32()
+        }
+    }
+
+    /// Seconds since the previous frame
+    fn frame_dt(&self) -> f32 {
+        if self.deterministic {
+            FRAME_TIME.as_secs_f32()
+        } else {
+            self.last_frame.elapsed().as_secs_f32()
+        }
+    }
+
+    /// Time of day for the clock scene
+    fn time_of_day(&self) -> f32 {
+        if self.deterministic {
+            DETERMINISTIC_TIME_OF_DAY + self.elapsed()
+        } else {
+            local_time_of_day()
+        }
+    }
+
+    /// Sleep out the rest of the frame budget and advance to the next frame
+    fn finish_frame(&mut self) {
+        let frame_time = self.last_frame.elapsed();
+        if frame_time < FRAME_TIME {
+            std::thread::sleep(FRAME_TIME - frame_time);
+        }
+        self.last_frame = Instant::now();
+        self.frame_index += 1;
+    }
+}
+
+/// Longest a frame's pose is sampled ahead of its request (seconds)
+const MAX_PACING_LEAD: f32 = 0.25;
+
+/// Output pacing for a GPU slower than the terminal: each request is posed for the time its
+/// frame is expected to reach the screen (the smoothed request-to-readback latency ahead), and a
+/// frame that comes back early is held until then, so rotation keeps its speed and even spacing
+/// instead of stuttering behind the clock
+/// The simulation itself steps every terminal tick, independent of GPU completion, but only
+/// rendered frames are shown: nothing is interpolated between them
+struct FramePacer {
+    enabled: bool,
+    /// Smoothed request-to-readback time (seconds)
+    latency: f32,
+    /// Request time and presentation time of the frame being rendered
+    in_flight: Option<(f32, f32)>,
+    /// A rendered frame waiting for its presentation time
+    held: Option<(f32, FrameResult)>,
+}
+
+impl FramePacer {
+    /// Disabled pacing (deterministic runs) poses and presents every frame at its request time
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            latency: 0.0,
+            in_flight: None,
+            held: None,
+        }
+    }
+
+    /// How far ahead of now the next request should be posed
+    fn lead(&self) -> f32 {
+        if self.enabled {
+            self.latency.min(MAX_PACING_LEAD)
+        } else {
+            0.0
+        }
+    }
+
+    /// Record a request made at `now`, posed `lead` seconds ahead
+    fn submitted(&mut self, now: f32, lead: f32) {
+        self.in_flight = Some((now, now + lead));
+    }
+
+    /// Take in a frame read back at `now`
+    fn arrived(&mut self, now: f32, frame: FrameResult) {
+        let (requested, present_at) = self.in_flight.take().unwrap_or((now, now));
+        let sample = now - requested;
+        self.latency = if self.latency == 0.0 {
+            sample
+        } else {
+            self.latency * 0.9 + sample * 0.1
+        };
+        self.held = Some((present_at, frame));
+    }
+
+    /// The held frame, once its presentation time has come (within half a tick)
+    fn take_due(&mut self, now: f32) -> Option<FrameResult> {
+        let due = self
+            .held
+            .as_ref()
+            .is_some_and(|(present_at, _)| !self.enabled || *present_at <= now + FRAME_TIME.as_secs_f32() / 2.0);
+        if due {
+            self.held.take().map(|(_, frame)| frame)
+        } else {
+            None
+        }
+    }
+}
+
+/// Settings for the right-hand pane of the side-by-side comparison view
+/// The left pane always uses the current render mode and configured lighting
+#[derive(Clone, Copy)]
+struct SplitView {
+    render_mode: RenderMode,
+    lighting_mode: LightingMode,
+}
+
+impl SplitView {
+    /// Label shown in the status bar, e.g. "Half Block/Toon"
+    fn label(&self) -> String {
+        format!("{}/{}", self.render_mode.name(), self.lighting_mode.name())
+    }
+}
+
+/// Raw render preview grid for a pane: about a quarter of its width, same aspect ratio,
+/// two pixel rows per terminal row
+fn preview_dims(pane_width: u16, term_rows: u16) -> (u32, u32) {
+    let cols = (pane_width / 4).clamp(12, 48).min(pane_width) as u32;
+    let rows = ((cols as f32 * term_rows as f32 / pane_width as f32).round() as u32).max(1);
+    (cols, rows * 2)
+}
+
+/// Width in terminal columns of each pane (split view reserves one column for the divider)
+fn pane_cols(term_cols: u16, split: bool) -> u16 {
+    if split {
+        term_cols.saturating_sub(1) / 2
+    } else {
+        term_cols
+    }
+}
+
 /// Calculate pipeline dimensions and pixel size based on render mode
+/// `quality` multiplies the base pixels per cell on each axis (1, 2, or 4)
 /// Returns (data_cols, data_rows, pixels_per_cell_x, pixels_per_cell_y)
-fn get_pipeline_dims(term_cols: u16, term_rows: u16, mode: RenderMode) -> (u32, u32, u32, u32) {
+fn get_pipeline_dims(term_cols: u16, term_rows: u16, mode: RenderMode, quality: u32) -> (u32, u32, u32, u32) {
     match mode {
         RenderMode::PlainAscii | RenderMode::ColoredAscii => {
             // Each terminal cell = one data cell, rendered at 8x16 (char aspect ratio)
-            (term_cols as u32, term_rows as u32, 8, 16)
+            (term_cols as u32, term_rows as u32, 8 * quality, 16 * quality)
         }
-        RenderMode::HalfBlock => {
+        RenderMode::HalfBlock | RenderMode::HalfBlockEdges => {
             // Each terminal row displays 2 data rows
             // Each "pixel" is square (8x8) since ▀ splits the cell in half vertically
-            (term_cols as u32, term_rows as u32 * 2, 8, 8)
+            (term_cols as u32, term_rows as u32 * 2, 8 * quality, 8 * quality)
+        }
+    }
+}
+
+/// Render mode of the main pane: the stereogram is plain characters whatever the chosen mode
+fn main_pane_mode(mode: RenderMode, stereogram: bool) -> RenderMode {
+    if stereogram {
+        RenderMode::PlainAscii
+    } else {
+        mode
+    }
+}
+
+/// Whether the main pane draws double-wide cells: only the one-character-per-cell modes have
+/// cells to widen (half blocks are already square)
+fn double_wide_active(mode: RenderMode, double_wide: bool) -> bool {
+    double_wide && !mode.is_half_block()
+}
+
+/// Pipeline dimensions of the main pane; double-wide cells are square, two terminal columns
+/// by one row, so the render size stays the same as a normal pane
+fn main_pane_dims(term_cols: u16, term_rows: u16, mode: RenderMode, quality: u32, double_wide: bool) -> (u32, u32, u32, u32) {
+    if double_wide_active(mode, double_wide) {
+        let (cols, rows, px_x, px_y) = get_pipeline_dims((term_cols / 2).max(1), term_rows, mode, quality);
+        (cols, rows, px_x * 2, px_y)
+    } else {
+        get_pipeline_dims(term_cols, term_rows, mode, quality)
+    }
+}
+
+/// Identifies what geometry is loaded (built-in scene, model file, and text string)
+type Selection = (Option<BuiltinScene>, Option<std::path::PathBuf>, String);
+
+/// The geometry selection described by a config
+fn selection(config: &ConfigState) -> Selection {
+    match config.scene {
+        Some(BuiltinScene::Text) => (config.scene, None, config.text.clone()),
+        // The selected model is the solar system's central body
+        Some(BuiltinScene::SolarSystem) => (config.scene, config.model_path.clone(), String::new()),
+        Some(_) => (config.scene, None, String::new()),
+        None => (None, config.model_path.clone(), String::new()),
+    }
+}
+
+/// Display name of a selection, for the status bar and loading notice
+fn selection_label(selection: &Selection) -> String {
+    match selection {
+        (Some(scene), _, _) => scene.name().to_string(),
+        (None, Some(path), _) => get_model_display_name(path),
+        (None, None, _) => "model".to_string(),
+    }
+}
+
+/// Whether a selection is built on the loader thread (live scenes are rebuilt per frame instead)
+fn is_background_load(selection: &Selection) -> bool {
+    matches!(selection, (Some(BuiltinScene::Text | BuiltinScene::Terrain | BuiltinScene::Water | BuiltinScene::Life | BuiltinScene::SolarSystem), _, _) | (None, Some(_), _))
+}
+
+/// Build geometry for a model file or 3D text selection (runs on the loader thread)
+fn load_selection(selection: &Selection, options: &LoadOptions, progress: &Progress) -> Result<ModelData> {
+    match selection {
+        (Some(BuiltinScene::Text), _, text) => {
+            build_text_model(text).context("Failed to build 3D text")
+        }
+        (Some(BuiltinScene::Terrain), _, _) => Ok(Terrain::generate().build_model()),
+        (Some(BuiltinScene::Water), _, _) => Ok(build_water_model()),
+        (Some(BuiltinScene::Life), _, _) => Ok(build_life_model()),
+        (Some(BuiltinScene::SolarSystem), central, _) => {
+            load_solar_bodies(central.as_deref(), &discover_models(Path::new(MODELS_DIR)), options, progress)
         }
+        (None, Some(path), _) => load_model(path, options, progress)
+            .with_context(|| format!("Failed to load model {}", get_model_display_name(path))),
+        _ => Err(anyhow!("Selection has no geometry to load")),
     }
 }
 
-/// Load a model and update GPU geometry
-fn load_model_into_gpu(gpu: &mut HeadlessGpu, path: &Path) -> Result<()> {
-    let model_data = load_model(path)?;
-    gpu.set_geometry(&model_data.vertices, &model_data.indices);
+/// A model or 3D text being built on a worker thread while the previous model keeps rendering
+struct PendingLoad {
+    selection: Selection,
+    label: String,
+    started: Instant,
+    receiver: Receiver<Result<ModelData>>,
+    /// Latest stage and completion reported by the loader
+    progress: Arc<Mutex<Option<(&'static str, f32)>>>,
+}
+
+impl PendingLoad {
+    fn spawn(selection: Selection, options: LoadOptions) -> Self {
+        let label = selection_label(&selection);
+
+        let (sender, receiver) = mpsc::channel();
+        let job = selection.clone();
+        let progress = Arc::new(Mutex::new(None));
+        let reporter = Arc::clone(&progress);
+        std::thread::spawn(move || {
+            let report = move |stage: &'static str, done: f32| {
+                if let Ok(mut slot) = reporter.lock() {
+                    *slot = Some((stage, done));
+                }
+            };
+            // The receiver is dropped if the selection changed again; the result is discarded
+            let _ = sender.send(load_selection(&job, &options, &report));
+        });
+
+        Self {
+            selection,
+            label,
+            started: Instant::now(),
+            receiver,
+            progress,
+        }
+    }
+
+    /// The finished result, if the worker is done (or once it is, when `block` is set)
+    fn poll(&self, block: bool) -> Option<Result<ModelData>> {
+        if block {
+            return Some(
+                self.receiver
+                    .recv()
+                    .unwrap_or_else(|_| Err(anyhow!("Model loader thread stopped unexpectedly"))),
+            );
+        }
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                Some(Err(anyhow!("Model loader thread stopped unexpectedly")))
+            }
+        }
+    }
+
+    /// Spinner line shown while loading
+    fn status(&self) -> String {
+        const SPINNER: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+        let elapsed = self.started.elapsed();
+        let frame = (elapsed.as_millis() / 100) as usize % SPINNER.len();
+        let stage = match *self.progress.lock().unwrap_or_else(|e| e.into_inner()) {
+            Some(("Reading", _)) | None => String::new(),
+            Some((stage, done)) => format!("{} {:.0}% ", stage, done * 100.0),
+        };
+        format!(
+            "{} Loading {}... {}{:.1}s",
+            SPINNER[frame],
+            self.label,
+            stage,
+            elapsed.as_secs_f32()
+        )
+    }
+}
+
+/// Read a style shader selected in the config UI
+fn load_style(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to load style {}", get_style_display_name(path)))
+}
+
+/// Apply a demo script command (everything except `wait`, which the main loop times)
+fn apply_script_command(
+    command: ScriptCommand,
+    config: &mut ConfigState,
+    captions: &mut Captions,
+    hud: &mut Hud,
+    now: f32,
+) -> Result<(), String> {
+    match command {
+        ScriptCommand::SetModel(name) => {
+            if !config.select_by_name(&name) {
+                return Err(format!("No built-in scene or model named {:?}", name));
+            }
+        }
+        ScriptCommand::SetText(text) => {
+            config.scene = Some(BuiltinScene::Text);
+            config.text = text;
+        }
+        ScriptCommand::SetLighting(name) => {
+            config.lighting_mode = *LightingMode::all()
+                .iter()
+                .find(|mode| mode.name().eq_ignore_ascii_case(&name))
+                .ok_or_else(|| format!("No lighting mode named {:?}", name))?;
+        }
+        ScriptCommand::Orbit(speed) => {
+            config.rotation_mode = RotationMode::Orbit;
+            config.rotation_speed = speed.clamp(0.1, 3.0);
+        }
+        ScriptCommand::Caption(text, duration) => captions.show(&text, now, duration),
+        ScriptCommand::Marquee(text, path) => {
+            config.marquee = match path {
+                Some(name) => *MarqueePath::all()
+                    .iter()
+                    .find(|path| path.name().eq_ignore_ascii_case(&name))
+                    .ok_or_else(|| format!("No marquee path named {:?}", name))?,
+                None if text.is_empty() => MarqueePath::Off,
+                None if config.marquee == MarqueePath::Off => MarqueePath::Ring,
+                None => config.marquee,
+            };
+            config.marquee_text = text;
+        }
+        ScriptCommand::Hud(name, Some(item)) => hud.set(&name, item),
+        ScriptCommand::Hud(name, None) => {
+            if !hud.remove(&name) {
+                return Err(format!("No HUD item named {:?}", name));
+            }
+        }
+        ScriptCommand::HudPin(name, text, point) => hud.set_label(&name, &text, LabelAnchor { point: point.into(), radius: 0.0, part: None }),
+        ScriptCommand::ClearHud => hud.clear(),
+        ScriptCommand::Wait(_) => {}
+    }
     Ok(())
 }
 
+/// Format an error and its causes, one per line
+fn error_chain(error: &anyhow::Error) -> String {
+    error
+        .chain()
+        .map(|cause| cause.to_string())
+        .collect::<Vec<_>>()
+        .join("\n  caused by: ")
+}
+
+/// Info panel lines describing the loaded model (bounding box overlay)
+fn model_stats_lines(stats: Option<&ModelStats>) -> Vec<String> {
+    match stats {
+        Some(stats) => {
+            let mut lines = vec![
+                format!("Triangles: {:>10}", stats.triangles),
+                format!(" Vertices: {:>10}", stats.vertices),
+            ];
+            if let Some(before) = stats.welded_from {
+                lines.push(format!("   Welded: {:>10} -> {}", before, stats.vertices));
+            }
+            lines.push(format!("     Size: {:.3} x {:.3} x {:.3}", stats.size[0], stats.size[1], stats.size[2]));
+            lines
+        }
+        None => vec!["    Model: built-in cube".to_string()],
+    }
+}
+
 fn main() -> Result<()> {
+    let Some(args) = CliArgs::parse()? else {
+        return Ok(());
+    };
+
+    // Checked before the terminal switches to the alternate screen so errors stay readable
+    let config_file = match args.config.clone().or_else(default_config_path) {
+        Some(path) => ConfigFile::load(&path)?,
+        None => ConfigFile::default(),
+    };
+    if let Some(ref convert_args) = args.convert {
+        return convert::run(convert_args, &config_file);
+    }
+    if let Some(ref calibrate_args) = args.calibrate {
+        return calibrate::run(calibrate_args);
+    }
+    if let Some(ref gallery_args) = args.gallery {
+        return gallery::run(gallery_args);
+    }
+    if let Some(ref path) = args.play {
+        return recording::play(path, &config_file);
+    }
+    // First run (no config file and nothing in assets/models): a setup wizard explains the
+    // controls, writes an initial config, and offers something to look at besides the built-ins
+    let first_run = args.config.is_none()
+        && !args.deterministic
+        && !args.screensaver
+        && args.tile.is_none()
+        && args.script.is_none()
+        && args.model_url.is_none()
+        && stdout().is_terminal()
+        && discover_models(Path::new(MODELS_DIR)).is_empty();
+    let config_file = match default_config_path().filter(|path| first_run && !path.exists()) {
+        Some(path) => {
+            match run_onboarding(&path, &config_file.theme, config_file.charset)? {
+                // A failed download leaves the built-in scenes, so it isn't fatal
+                StarterAssets::Gallery => {
+                    if let Err(e) = gallery::run(&GalleryArgs::default()) {
+                        eprintln!("{}", error_chain(&e));
+                    }
+                }
+                StarterAssets::Primitives => {
+                    let names = write_primitive_models(Path::new(MODELS_DIR))?;
+                    eprintln!("Wrote {} to {}", names.join(", "), MODELS_DIR);
+                }
+                StarterAssets::None => {}
+            }
+            ConfigFile::load(&path)?
+        }
+        None => config_file,
+    };
+    let mut recorder = args.record.as_deref().map(Recorder::create).transpose()?;
+    let mut shader_watcher = args.shader_dir.as_deref().map(ShaderWatcher::new).transpose()?;
+    let mut script = match args.script.as_deref() {
+        Some(path) => Some(ScriptRunner::spawn(path)?),
+        None if args.screensaver => Some(ScriptRunner::from_source(ATTRACT_PLAYLIST.to_string(), "attract playlist")?),
+        None => None,
+    };
+    let mut audio_input = args.audio.then(AudioInput::open).transpose()?;
+    if let Some(ref input) = audio_input {
+        eprintln!("Audio input: {}", input.device_name());
+    }
+    // Downloaded before the terminal is taken over so curl's progress bar is visible
+    let downloaded_model = args.model_url.as_deref().map(model::fetch_model).transpose()?;
+    let video = args.video.as_deref().map(VideoSource::open).transpose()?;
+    if let Some(ref video) = video {
+        eprintln!("Video input: {}", video.name());
+    }
+
     env_logger::init();
     eprintln!("Starting terminal demo...");
 
     // Initialize terminal renderer
     let mut term = TerminalRenderer::new()?;
     eprintln!("Terminal initialized");
+    term.set_theme(config_file.theme.clone());
+    term.set_charset(config_file.charset);
+    term.set_half_block(config_file.half_block.unwrap_or_else(HalfBlock::detect));
+    // Clean mode (Space) hides the status bar and GPU panel; the screensaver starts in it
+    let mut clean = args.screensaver;
+    term.set_show_status(config_file.status_bar && !clean);
+    term.set_gpu_panel(config_file.gpu_panel, config_file.gpu_panel_compact);
+    term.set_mouse_capture(config_file.mouse)?;
+    term.set_palette(Palette::new(
+        config_file.color_depth.unwrap_or_else(ColorDepth::detect),
+        config_file.chroma_boost,
+    ));
     let (term_cols, term_rows) = term.content_size();
+    let quality = args.quality.unwrap_or(config_file.quality);
+    let load_options = LoadOptions {
+        weld: args.weld || config_file.weld,
+        ambient_occlusion: config_file.ambient_occlusion,
+        crease_angle: config_file.crease_angle,
+    };
 
     // Initialize config state
     let mut config = ConfigState::new();
     config.refresh_models(Path::new(MODELS_DIR));
     config.refresh_skyboxes(Path::new(SKYBOXES_DIR));
+    config.refresh_styles(Path::new(STYLES_DIR));
+    if let Some(path) = downloaded_model {
+        config.add_downloaded_model(path);
+    }
 
     // Current render mode
     let mut render_mode = RenderMode::PlainAscii;
-    let mut prev_mode = render_mode;
 
     // GPU info display toggle
     let mut show_gpu_info = true;
 
+    // Per-stage frame profiler overlay (Shift+G)
+    let mut show_profiler = false;
+    let mut profiler = FrameProfiler::new();
+
+    // Bounding box + model statistics toggle
+    let mut show_bounds = false;
+    let mut model_stats: Option<ModelStats> = None;
+    let mut marquee_shown = (MarqueePath::Off, String::new());
+
+    // Side-by-side comparison (None = single view)
+    let mut split_view: Option<SplitView> = None;
+
+    // Picture-in-picture of the raw render, before ASCII conversion
+    let mut show_preview = false;
+
+    // Random-character stereogram of the depth buffer in place of the main pane
+    let mut stereogram = false;
+
+    // Part names drawn on the model, and the labels for the loaded model's parts
+    let mut show_part_labels = false;
+    let mut part_labels: Vec<(String, LabelAnchor)> = Vec::new();
+
+    // Part selected by clicking it, and a click waiting to be looked up in the next frame's part
+    // IDs (in the main pane, whose size in cells is kept from the last screen drawn)
+    let mut selected_part: Option<usize> = None;
+    let mut pending_pick: Option<[f32; 2]> = None;
+    let mut main_pane = (0, 0);
+
+    // Each ASCII cell drawn twice so cells are square instead of twice as tall as wide
+    let mut double_wide = config_file.double_wide;
+
     // App mode
     let mut app_mode = AppMode::Rendering;
 
+    // Asset load errors reopen the config UI with the user's unapplied choices
+    let mut config_error: Option<String> = None;
+    let mut config_draft: Option<ConfigState> = None;
+
     // Manual control state
     let mut controls = ManualControls::new();
+    // Bounding radius the camera is fitted to (None = the default distance), the first geometry of
+    // a new selection still to be measured, and a fit waiting for the frame size (Home asks again)
+    let mut model_radius: Option<f32> = None;
+    let mut frame_geometry = false;
+    let mut refit = false;
+    // Last pose requested, and the animation time held while the view stays still so accumulated
+    // renders converge (with `accumulate`)
+    let mut last_pose: Option<CameraPose> = None;
+    let mut still_time: Option<f32> = None;
 
-    // Calculate initial pipeline dimensions based on mode
-    let (pipe_cols, pipe_rows, px_x, px_y) = get_pipeline_dims(term_cols, term_rows, render_mode);
-    let render_width = pipe_cols * px_x;
-    let render_height = pipe_rows * px_y;
-
-    // Initialize headless GPU
-    eprintln!("Creating HeadlessGpu...");
-    let mut gpu = pollster::block_on(HeadlessGpu::new(render_width, render_height))?;
-    eprintln!("HeadlessGpu created");
-
-    // Load initial model if available
-    if let Some(ref model_path) = config.model_path {
-        eprintln!("Loading model: {:?}", model_path);
-        if let Err(e) = load_model_into_gpu(&mut gpu, model_path) {
-            eprintln!("Failed to load model: {}", e);
-        }
-    }
+    // CPU/RAM sampler for the system gauges scene
+    // (frozen in deterministic mode so the output doesn't depend on the machine's load)
+    let mut gauges = if args.deterministic {
+        SystemGauges::frozen()
+    } else {
+        SystemGauges::new()
+    };
+
+    // Operation and primitives of the CSG playground scene
+    let mut csg = CsgPlayground::new();
 
-    // Initialize edge-aware ASCII pipeline
-    eprintln!("Creating AsciiPipeline...");
-    let mut pipeline = AsciiPipeline::new(
-        &gpu.device,
-        pipe_cols,
-        pipe_rows,
-        render_width,
-        render_height,
-    )?;
-    eprintln!("AsciiPipeline created");
+    // Preset of the L-system plant scene
+    let mut plant = PlantScene::new();
 
-    let start_time = Instant::now();
-    let mut last_frame = Instant::now();
+    // Bodies of the solar system scene, placed on their orbits every frame
+    let mut solar = SolarSystem::new();
+
+    // Heightfield the terrain flyover camera follows
+    let terrain = Terrain::generate();
+
+    // Rigid-body simulation for the Physics rotation mode
+    let mut physics = PhysicsSim::new();
+
+    // Exploded view: manual amount, or a looping animation when enabled
+    let mut explode = 0.0f32;
+    let mut explode_anim = false;
+
+    // Live tone mapping (+/- exposure, </> gamma), flashed in the status bar when changed
+    let mut exposure = DEFAULT_EXPOSURE;
+    let mut gamma = DEFAULT_GAMMA;
+    let mut tone_changed: Option<Instant> = None;
+    // Saturation/brightness/contrast of the cell colors (5/6, 7/8, 9/0), flashed when changed
+    let mut color_adjust = config_file.color_adjust;
+    let mut color_changed: Option<Instant> = None;
+    // When L / O last changed the lighting / rotation mode
+    let mut lighting_changed: Option<Instant> = None;
+    let mut rotation_changed: Option<Instant> = None;
+
+    // Animation clock for auto rotation and the explode animation, scrubbed with , and .
+    // (/ cycles the playback direction; both flash the position in the status bar)
+    let mut timeline = Timeline::new();
+    let mut timeline_changed: Option<Instant> = None;
+
+    // Demo script state: a pending wait (deadline in animation time) and errors
+    let mut script_wait: Option<(f32, ScriptReply)> = None;
+    let mut script_error: Option<String> = None;
+
+    // Caption overlay, set from F1-F9 presets or demo scripts
+    let mut captions = Captions::new();
+    captions.load_presets(Path::new(CAPTIONS_FILE));
+    // Labels, boxes, and bars demo scripts draw over the 3D view
+    let mut hud = Hud::default();
+
+    // Extra rotation accumulated from the bass in audio-reactive mode (seconds of spin)
+    let mut audio_spin = 0.0f32;
+
+    // The GPU lives on its own thread so input and the status bar stay responsive
+    // when a heavy model drops the render rate
+    let mut render = RenderThread::spawn(get_pipeline_dims(term_cols.max(1), term_rows.max(1), render_mode, quality))?;
+    let skybox_rotation = config_file.skybox_rotation / 360.0;
+    let tumble = config_file.tumble;
+    // A tiled instance looks from its fixed side and follows the first one started
+    let mut tile = args.tile.map(TileLink::open).transpose()?;
+    let view_turn = tile.as_ref().map(|t| t.view.turn()).unwrap_or_default();
+    render.call(move |r| {
+        r.gpu.set_skybox_rotation(skybox_rotation);
+        r.gpu.set_tumble(tumble);
+        r.gpu.set_view_turn(view_turn);
+        Ok(())
+    })?;
+
+    // Frame timings for --stats and the M hotkey, plus the last export result to show
+    let mut session_stats =
+        SessionStats::new(render.gpu_name(), render.max_texture_dimension(), (term_cols, term_rows));
+    // A fresh checkout has no models of its own; point at the gallery instead of an empty list
+    // (not in deterministic runs, whose screens shouldn't depend on the directory)
+    let mut stats_notice: Option<(String, Instant)> = (config.available_models.is_empty() && !args.deterministic)
+        .then(|| ("No models yet: `ascii-3d gallery` downloads a sample set".to_string(), Instant::now()));
+
+    // One-rotation loop being captured (K)
+    let mut loop_capture: Option<LoopCapture> = None;
+
+    // Export dialog (X); while open it takes all keys
+    let mut export_dialog: Option<ExportDialog> = None;
+
+    // Files dropped into assets/models or assets/skyboxes show up without reopening the config UI
+    let mut last_asset_scan = Instant::now();
+
+    // Last frame read back from the render thread, redrawn until the next one arrives
+    let mut last_frame: Option<FrameResult> = None;
+
+    // Geometry waiting to be sent with the next frame request (newer geometry replaces it)
+    let mut pending_geometry: Option<ModelData> = None;
+
+    // Shader hot-reload: the watcher's first poll swaps in the on-disk sources
+    // A style shader from the config UI replaces the final pass of whichever set is active
+    let mut ascii_shaders = AsciiShaders::builtin();
+    let mut style_shader: Option<String> = None;
+    let mut shader_error: Option<String> = None;
+
+    let mut clock = FrameClock::new(args.deterministic);
+    let mut pacer = FramePacer::new(!args.deterministic);
     let mut frame_count = 0u32;
     let mut fps = 0.0f32;
     let mut fps_update_time = Instant::now();
 
-    // Track current model path for change detection
-    let mut current_model_path = config.model_path.clone();
+    // Track the loaded model/scene selection for change detection
+    // The built-in cube is loaded until the initial model finishes loading in the background
+    let mut current_selection: Selection = (None, None, String::new());
+    let mut pending_load: Option<PendingLoad> = None;
 
     loop {
         match app_mode {
             AppMode::Rendering => {
+                profiler.mark();
+
                 // Handle input - process all pending events for responsive controls
                 let mut should_quit = false;
                 let mut copy_to_clipboard = false;
+                let mut write_stats = false;
+                let mut start_capture: Option<PathBuf> = None;
+                let mut pending_export: Option<(ExportTarget, Option<PathBuf>)> = None;
                 while event::poll(Duration::from_millis(0))? {
-                    if let Event::Key(key_event) = event::read()? {
+                    let event = event::read()?;
+                    if let Event::Mouse(mouse) = event {
+                        if mouse.kind == MouseEventKind::Down(MouseButton::Left) && export_dialog.is_none() {
+                            pending_pick = term.pane_position(mouse.column, mouse.row, main_pane).or(pending_pick);
+                        }
+                        continue;
+                    }
+                    if let Event::Key(key_event) = event {
+                        // The screensaver exits on any key, so no key reaches a setting or a file write
+                        if args.screensaver {
+                            should_quit |= key_event.kind == KeyEventKind::Press;
+                            continue;
+                        }
+                        if let Some(ref mut dialog) = export_dialog {
+                            if key_event.kind == KeyEventKind::Press {
+                                match dialog.handle_key(key_event.code) {
+                                    DialogAction::None => {}
+                                    DialogAction::Close => export_dialog = None,
+                                    DialogAction::Export(ExportTarget::GifLoop, path) => {
+                                        start_capture = path;
+                                        export_dialog = None;
+                                    }
+                                    DialogAction::Export(target, path) => {
+                                        pending_export = Some((target, path));
+                                        export_dialog = None;
+                                    }
+                                }
+                            }
+                            continue;
+                        }
+
                         // Handle Press and Repeat for smooth controls
                         if key_event.kind == KeyEventKind::Press
                             || key_event.kind == KeyEventKind::Repeat
                         {
+                            // In Orbit mode zooming moves the orbiting camera in and out
+                            let orbiting = config.rotation_mode == RotationMode::Orbit && !controls.active;
                             match key_event.code {
                                 // WASD for rotation (thruster-style)
                                 KeyCode::Char('w') | KeyCode::Char('W') => controls.thrust(-1.0, 0.0),
@@ -220,8 +924,8 @@ fn main() -> Result<()> {
                                 KeyCode::Char('a') | KeyCode::Char('A') => controls.thrust(0.0, -1.0),
                                 KeyCode::Char('d') | KeyCode::Char('D') => controls.thrust(0.0, 1.0),
                                 // Q/E for zoom
-                                KeyCode::Char('e') | KeyCode::Char('E') => controls.zoom_in(),
-                                KeyCode::Char('q') | KeyCode::Char('Q') => controls.zoom_out(),
+                                KeyCode::Char('e') | KeyCode::Char('E') => controls.zoom_in(orbiting),
+                                KeyCode::Char('q') | KeyCode::Char('Q') => controls.zoom_out(orbiting),
                                 _ => {}
                             }
                         }
@@ -233,18 +937,157 @@ fn main() -> Result<()> {
                                 KeyCode::Char('1') => render_mode = RenderMode::PlainAscii,
                                 KeyCode::Char('2') => render_mode = RenderMode::ColoredAscii,
                                 KeyCode::Char('3') => render_mode = RenderMode::HalfBlock,
-                                KeyCode::Char('g') | KeyCode::Char('G') => show_gpu_info = !show_gpu_info,
+                                KeyCode::Char('4') => render_mode = RenderMode::HalfBlockEdges,
+                                KeyCode::Char('g') => show_gpu_info = !show_gpu_info,
+                                // Shift+G toggles the frame profiler
+                                KeyCode::Char('G') => {
+                                    show_profiler = !show_profiler;
+                                    // Start fresh: unprofiled frames don't split out GPU time
+                                    profiler = FrameProfiler::new();
+                                }
+                                // I toggles the bounding box and model statistics
+                                KeyCode::Char('i') | KeyCode::Char('I') => show_bounds = !show_bounds,
                                 // R to reset view
                                 KeyCode::Char('r') | KeyCode::Char('R') => controls.reset(),
+                                // Home fits the camera to the model again (after a resize or zooming)
+                                KeyCode::Home => refit = true,
                                 // F to copy frame to clipboard
                                 KeyCode::Char('f') | KeyCode::Char('F') => copy_to_clipboard = true,
                                 KeyCode::Char('c') | KeyCode::Char('C') => {
                                     // Refresh model and skybox lists before opening config
                                     config.refresh_models(Path::new(MODELS_DIR));
                                     config.refresh_skyboxes(Path::new(SKYBOXES_DIR));
+                                    config.refresh_styles(Path::new(STYLES_DIR));
+                                    captions.load_presets(Path::new(CAPTIONS_FILE));
                                     app_mode = AppMode::Config;
                                 }
+                                // F1-F9 show or hide caption presets
+                                KeyCode::F(n) if (1..=MAX_PRESETS as u8).contains(&n) => {
+                                    captions.toggle_preset(n as usize - 1, clock.elapsed());
+                                }
                                 KeyCode::Tab => render_mode = render_mode.next(),
+                                KeyCode::Char('l') | KeyCode::Char('L') => {
+                                    config.lighting_mode = config.lighting_mode.next();
+                                    lighting_changed = Some(Instant::now());
+                                }
+                                KeyCode::Char('o') | KeyCode::Char('O') => {
+                                    config.rotation_mode = config.rotation_mode.next();
+                                    while config_file.reduced_motion && config.rotation_mode.drifts() {
+                                        config.rotation_mode = config.rotation_mode.next();
+                                    }
+                                    if config.rotation_mode == RotationMode::Physics {
+                                        physics.reset();
+                                    }
+                                    rotation_changed = Some(Instant::now());
+                                }
+                                // P toggles the picture-in-picture preview of the raw render
+                                KeyCode::Char('p') | KeyCode::Char('P') => show_preview = !show_preview,
+                                // ' toggles the part name labels
+                                KeyCode::Char('\'') => show_part_labels = !show_part_labels,
+                                KeyCode::Char('y') | KeyCode::Char('Y') => stereogram = !stereogram,
+                                KeyCode::Char('t') | KeyCode::Char('T') => double_wide = !double_wide,
+                                // Space toggles clean mode: the content area takes the status bar's row
+                                KeyCode::Char(' ') => {
+                                    clean = !clean;
+                                    term.set_show_status(config_file.status_bar && !clean);
+                                }
+                                // V toggles split view, B/N cycle the right pane's mode/lighting
+                                KeyCode::Char('v') | KeyCode::Char('V') => {
+                                    split_view = match split_view {
+                                        Some(_) => None,
+                                        None => Some(SplitView {
+                                            render_mode: render_mode.next(),
+                                            lighting_mode: config.lighting_mode,
+                                        }),
+                                    };
+                                }
+                                KeyCode::Char('b') | KeyCode::Char('B') => {
+                                    if let Some(ref mut split) = split_view {
+                                        split.render_mode = split.render_mode.next();
+                                    }
+                                }
+                                KeyCode::Char('n') | KeyCode::Char('N') => {
+                                    if let Some(ref mut split) = split_view {
+                                        split.lighting_mode = split.lighting_mode.next();
+                                    }
+                                }
+                                // [ / ] adjust the exploded view, Z animates it
+                                KeyCode::Char('[') => {
+                                    explode_anim = false;
+                                    explode = (explode - 0.1).max(0.0);
+                                }
+                                KeyCode::Char(']') => {
+                                    explode_anim = false;
+                                    explode = (explode + 0.1).min(1.5);
+                                }
+                                KeyCode::Char('z') | KeyCode::Char('Z') => explode_anim = !explode_anim,
+                                KeyCode::Char(',') => {
+                                    timeline.scrub(-SCRUB_STEP);
+                                    timeline_changed = Some(Instant::now());
+                                }
+                                KeyCode::Char('.') => {
+                                    timeline.scrub(SCRUB_STEP);
+                                    timeline_changed = Some(Instant::now());
+                                }
+                                KeyCode::Char('/') => {
+                                    config.playback = config.playback.next();
+                                    timeline_changed = Some(Instant::now());
+                                }
+                                KeyCode::Char('m') | KeyCode::Char('M') => write_stats = true,
+                                // K captures exactly one rotation period as a seamless loop (again to cancel)
+                                KeyCode::Char('k') | KeyCode::Char('K') => {
+                                    if loop_capture.take().is_some() {
+                                        stats_notice = Some(("Loop capture cancelled".to_string(), Instant::now()));
+                                    } else {
+                                        start_capture = Some(args.loop_file.clone().unwrap_or_else(capture::default_path));
+                                    }
+                                }
+                                // J/H/U cycle the CSG playground's operation, base and tool shapes
+                                KeyCode::Char('j') | KeyCode::Char('J') if config.scene == Some(BuiltinScene::Csg) => {
+                                    csg.operation = csg.operation.next();
+                                }
+                                // J picks the L-system plant's next preset
+                                KeyCode::Char('j') | KeyCode::Char('J') if config.scene == Some(BuiltinScene::Plant) => {
+                                    plant.next_preset();
+                                }
+                                // J toggles the solar system's orbit trails
+                                KeyCode::Char('j') | KeyCode::Char('J') if config.scene == Some(BuiltinScene::SolarSystem) => {
+                                    solar.trails = !solar.trails;
+                                }
+                                KeyCode::Char('h') | KeyCode::Char('H') if config.scene == Some(BuiltinScene::Csg) => {
+                                    csg.base = csg.base.next();
+                                }
+                                KeyCode::Char('u') | KeyCode::Char('U') if config.scene == Some(BuiltinScene::Csg) => {
+                                    csg.tool = csg.tool.next();
+                                }
+                                // X opens the export dialog
+                                KeyCode::Char('x') | KeyCode::Char('X') => export_dialog = Some(ExportDialog::new()),
+                                KeyCode::Char('+') | KeyCode::Char('=') => {
+                                    exposure = (exposure * 1.1).min(10.0);
+                                    tone_changed = Some(Instant::now());
+                                }
+                                KeyCode::Char('-') | KeyCode::Char('_') => {
+                                    exposure = (exposure / 1.1).max(0.1);
+                                    tone_changed = Some(Instant::now());
+                                }
+                                KeyCode::Char('>') => {
+                                    gamma = (gamma + 0.05).min(3.0);
+                                    tone_changed = Some(Instant::now());
+                                }
+                                KeyCode::Char('<') => {
+                                    gamma = (gamma - 0.05).max(0.2);
+                                    tone_changed = Some(Instant::now());
+                                }
+                                KeyCode::Char(key @ '5'..='9') | KeyCode::Char(key @ '0') => {
+                                    let (value, min) = match key {
+                                        '5' | '6' => (&mut color_adjust.saturation, 0.0),
+                                        '7' | '8' => (&mut color_adjust.brightness, 0.1),
+                                        _ => (&mut color_adjust.contrast, 0.1),
+                                    };
+                                    let step = if matches!(key, '6' | '8' | '0') { 0.1 } else { -0.1 };
+                                    *value = ((*value + step) * 10.0).round().clamp(min * 10.0, 40.0) / 10.0;
+                                    color_changed = Some(Instant::now());
+                                }
                                 _ => {}
                             }
                         }
@@ -253,111 +1096,476 @@ fn main() -> Result<()> {
                 if should_quit {
                     break;
                 }
+                if let Some(path) = start_capture {
+                    if controls.active {
+                        stats_notice = Some(("Loop capture needs auto-rotation (R)".to_string(), Instant::now()));
+                    } else {
+                        // Start from the pose on screen (captures play forward over one period)
+                        let start = timeline.time(audio_spin) / config.rotation_speed;
+                        match LoopCapture::new(path, config.rotation_mode, config.rotation_speed, config_file.tumble, main_pane_mode(render_mode, stereogram), start) {
+                            Ok(capture) => loop_capture = Some(capture),
+                            Err(e) => stats_notice = Some((format!("Can't capture a loop: {}", e), Instant::now())),
+                        }
+                    }
+                }
+                profiler.record(Stage::Input);
+
+                // Band levels from the audio input (None without --audio)
+                let audio_levels = audio_input.as_mut().map(|input| input.update());
+                let levels = audio_levels.unwrap_or_default();
 
                 // Update manual controls physics
-                let frame_dt = last_frame.elapsed().as_secs_f32();
+                let frame_dt = clock.frame_dt();
                 controls.update(frame_dt);
+                audio_spin += frame_dt * levels.spin_boost();
 
-                // Check for terminal resize or mode change
-                let mode_changed = render_mode != prev_mode;
-                let resized = term.check_resize()?;
-
-                if resized || mode_changed {
-                    let (new_term_cols, new_term_rows) = term.content_size();
-                    let (new_pipe_cols, new_pipe_rows, new_px_x, new_px_y) =
-                        get_pipeline_dims(new_term_cols, new_term_rows, render_mode);
-                    let new_width = new_pipe_cols * new_px_x;
-                    let new_height = new_pipe_rows * new_px_y;
-                    gpu.resize(new_width, new_height);
-                    pipeline.resize(
-                        &gpu.device,
-                        new_pipe_cols,
-                        new_pipe_rows,
-                        new_width,
-                        new_height,
-                    );
-                    prev_mode = render_mode;
+                // Reduced motion holds whatever the menu or a script picked to a slow single axis
+                if config_file.reduced_motion {
+                    if config.rotation_mode.drifts() {
+                        config.rotation_mode = RotationMode::AxisY;
+                    }
+                    config.rotation_speed = config.rotation_speed.min(REDUCED_MOTION_SPEED);
+                }
+
+                // Advance the physics demo (paused while under manual control)
+                if config.rotation_mode == RotationMode::Physics && !controls.active {
+                    physics.step(frame_dt * config.rotation_speed * (1.0 + levels.spin_boost()));
+                }
+
+                // Resizes are applied by the render thread when the requested grid changes
+                if term.check_resize()? {
+                    session_stats.record_resize(term.content_size());
+                }
+
+                // Recompile edited shaders; errors keep the last good pipelines running
+                if let Some(reloaded) = shader_watcher.as_mut().and_then(|w| w.poll()) {
+                    let result = reloaded.and_then(|shaders| {
+                        let styled = shaders.clone().with_style(style_shader.as_deref());
+                        render.call(move |r| r.reload_shaders(styled))?;
+                        ascii_shaders = shaders;
+                        Ok(())
+                    });
+                    shader_error = result.err().map(|e| error_chain(&e));
                 }
 
-                let elapsed = start_time.elapsed().as_secs_f32();
+                let elapsed = clock.elapsed();
+
+                // Run the demo script up to its next wait; commands take effect this frame
+                if script_wait.as_ref().is_some_and(|(deadline, _)| elapsed >= *deadline) {
+                    if let Some((_, reply)) = script_wait.take() {
+                        reply.finish(Ok(()));
+                    }
+                }
+                while script_wait.is_none() {
+                    let Some(ref runner) = script else { break };
+                    match runner.poll() {
+                        Ok(Some(ScriptRequest {
+                            command: ScriptCommand::Wait(seconds),
+                            reply,
+                        })) => script_wait = Some((elapsed + seconds.max(0.0), reply)),
+                        Ok(Some(ScriptRequest { command, reply })) => {
+                            reply.finish(apply_script_command(command, &mut config, &mut captions, &mut hud, elapsed));
+                        }
+                        Ok(None) => break,
+                        Err(error) => {
+                            script_error = error.map(|e| error_chain(&e.context("Script failed")));
+                            script = None;
+                        }
+                    }
+                }
+                timeline.advance(elapsed, config.rotation_speed, config.playback);
+                if let Some(ref mut tile) = tile {
+                    tile.sync(&mut timeline, &mut config);
+                }
+
+                // Start a background load when the selection changes (or cancel a stale one)
+                let wanted = selection(&config);
+                if wanted == current_selection {
+                    pending_load = None;
+                } else if is_background_load(&wanted)
+                    && pending_load.as_ref().is_none_or(|p| p.selection != wanted)
+                {
+                    pending_load = Some(PendingLoad::spawn(wanted.clone(), load_options));
+                }
 
-                // Time GPU operations
-                let gpu_start = Instant::now();
+                // Swap in the finished model, or report the failure in the config UI
+                // Deterministic runs wait for the load so every frame shows the same geometry
+                if let Some(pending) = pending_load.take() {
+                    match pending.poll(args.deterministic) {
+                        None => pending_load = Some(pending),
+                        Some(Ok(model_data)) => {
+                            model_stats = Some(model_data.stats());
+                            config.set_parts(model_data.part_names.clone());
+                            part_labels = model_data.part_labels();
+                            selected_part = None;
+                            frame_geometry = true;
+                            if pending.selection.0 == Some(BuiltinScene::SolarSystem) {
+                                // The bodies are placed on their orbits by the live scene rebuild below
+                                solar.set_bodies(&model_data);
+                            } else {
+                                pending_geometry = Some(model_data);
+                            }
+                            current_selection = pending.selection;
+                        }
+                        Some(Err(e)) => {
+                            // The screensaver has no one to fix the selection: it stays on the loaded one
+                            if !args.screensaver {
+                                config_error = Some(error_chain(&e));
+                                config_draft = Some(config.clone());
+                                app_mode = AppMode::Config;
+                            }
+                            // Fall back to the selection that is actually loaded
+                            config.scene = current_selection.0;
+                            config.model_path = current_selection.1.clone();
+                            if current_selection.0 == Some(BuiltinScene::Text) {
+                                config.text = current_selection.2.clone();
+                            }
+                        }
+                    }
+                }
+
+                // Raymarched scenes have no mesh: the GPU draws them from the selection alone
+                if wanted.0.is_some_and(|s| s.sdf().is_some()) && current_selection != wanted {
+                    model_stats = None;
+                    config.set_parts(Vec::new());
+                    part_labels.clear();
+                    selected_part = None;
+                    current_selection = wanted.clone();
+                    pending_load = None;
+                    model_radius = None;
+                    refit = true;
+                }
 
-                // Render 3D scene - use manual controls if active, otherwise auto rotation
-                let render_cmd = if controls.active {
-                    gpu.render_manual(
-                        controls.rotation.0,
-                        controls.rotation.1,
-                        controls.zoom,
-                        config.lighting_mode,
-                    )
+                // Rebuild live scenes (clock hands, gauge levels, growing plants)
+                if let Some(scene) = config.scene.filter(|s| s.is_animated()) {
+                    let switched = current_selection != wanted;
+                    let rebuilt = match scene {
+                        BuiltinScene::Clock => Some(build_clock_model(clock.time_of_day())),
+                        BuiltinScene::Csg => Some(csg.build_model(elapsed)),
+                        BuiltinScene::Plant => Some(plant.build_model(elapsed)),
+                        // Only once its bodies have loaded for this selection
+                        BuiltinScene::SolarSystem if !switched => Some(solar.build_model(elapsed)),
+                        BuiltinScene::SystemInfo if gauges.update() || switched => {
+                            Some(gauges.build_model())
+                        }
+                        _ => None,
+                    };
+                    if let Some(model_data) = rebuilt {
+                        model_stats = Some(model_data.stats());
+                        // Parts of live scenes move between rebuilds
+                        part_labels = model_data.part_labels();
+                        if switched {
+                            config.set_parts(model_data.part_names.clone());
+                            selected_part = None;
+                            frame_geometry = true;
+                        }
+                        pending_geometry = Some(model_data);
+                        current_selection = wanted;
+                        pending_load = None;
+                    }
+                }
+
+                // Fit the camera to the first geometry of a new selection (for the solar system that
+                // is the first rebuild after its bodies load)
+                if frame_geometry {
+                    if let Some(ref geometry) = pending_geometry {
+                        model_radius = Some(geometry.radius);
+                        refit = true;
+                        frame_geometry = false;
+                    }
+                }
+
+                // Rebuild the marquee when its path or text changes
+                let marquee_wanted = (config.marquee, config.marquee_text.clone());
+                if marquee_wanted != marquee_shown {
+                    match build_marquee_model(&config.marquee_text, config.marquee) {
+                        Ok(model_data) => render.call(move |r| {
+                            r.gpu.set_marquee(&model_data.vertices, &model_data.indices);
+                            Ok(())
+                        })?,
+                        Err(e) => {
+                            config_error = Some(error_chain(&e.context("Failed to build the marquee")));
+                            config_draft = Some(config.clone());
+                            config.marquee = MarqueePath::Off;
+                            app_mode = AppMode::Config;
+                        }
+                    }
+                    marquee_shown = marquee_wanted;
+                }
+
+                // Animated explode eases between assembled and fully exploded
+                let explode_amount = if explode_anim {
+                    0.5 - 0.5 * timeline.time(0.0).cos()
                 } else {
-                    gpu.render_with_rotation(
-                        elapsed,
-                        config.rotation_mode,
-                        config.rotation_speed,
-                        config.lighting_mode,
-                    )
+                    explode
                 };
-                gpu.queue.submit(std::iter::once(render_cmd));
-
-                // Update pipeline bind groups with color and depth textures
-                pipeline.update_bind_groups(
-                    &gpu.device,
-                    &gpu.queue,
-                    gpu.render_texture_view(),
-                    gpu.depth_texture_view(),
-                );
-
-                // Run edge-aware compute pipeline
-                let mut encoder = gpu
-                    .device
-                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                        label: Some("Pipeline Encoder"),
+
+                // A collapsed terminal (e.g. a zoomed-away tmux pane) has no cells to render into,
+                // and a tiny one only shows a garbled postage stamp: pause the GPU and say what size
+                // is needed until the terminal is resized
+                let (term_cols, term_rows) = term.content_size();
+                let (full_cols, full_rows) = term.size();
+                if full_cols < MIN_TERMINAL_SIZE.0
+                    || full_rows < MIN_TERMINAL_SIZE.1
+                    || term_cols == 0
+                    || term_rows == 0
+                    || pane_cols(term_cols, split_view.is_some()) == 0
+                {
+                    let needed = format!("Needs {} x {}, now {} x {}", MIN_TERMINAL_SIZE.0, MIN_TERMINAL_SIZE.1, full_cols, full_rows);
+                    term.render_placeholder(&["Terminal too small", &needed, "", "Resize to continue (Esc quits)"])?;
+                    clock.finish_frame();
+                    continue;
+                }
+
+                // Fitting the camera depends on the shape of the main pane
+                if std::mem::take(&mut refit) {
+                    let pane_width = pane_cols(term_cols, split_view.is_some());
+                    let (cols, rows, px_x, px_y) =
+                        main_pane_dims(pane_width, term_rows, main_pane_mode(render_mode, stereogram), quality, double_wide);
+                    let aspect = (cols * px_x) as f32 / (rows * px_y).max(1) as f32;
+                    controls.frame(model_radius.map_or(CAMERA_DISTANCE, |radius| framing_distance(radius, aspect)));
+                }
+
+                // Request the next frame once the previous one has been read back
+                let capture_done = loop_capture.as_ref().is_some_and(|capture| capture.is_complete());
+                if render.is_idle() && !capture_done {
+                    // Pose the frame for when it should reach the screen (manual input isn't predicted)
+                    // Loop captures render their own evenly spaced times instead
+                    let capture_time = loop_capture.as_mut().map(|capture| {
+                        capture.awaiting = true;
+                        capture.next_time()
                     });
+                    let lead = if capture_time.is_some() { 0.0 } else { pacer.lead() };
+                    // Render 3D scene - use manual controls if active, otherwise auto rotation
+                    let pose = if controls.active {
+                        CameraPose::Manual {
+                            pitch: controls.rotation.0,
+                            yaw: controls.rotation.1,
+                            zoom: controls.zoom,
+                        }
+                    } else if current_selection.0 == Some(BuiltinScene::Terrain) {
+                        // The flyover replaces the rotation mode
+                        let time = capture_time.unwrap_or(elapsed + lead);
+                        CameraPose::View(terrain.flyover_view(time, config.rotation_speed))
+                    } else if config.rotation_mode == RotationMode::Physics {
+                        CameraPose::Model {
+                            model: physics.predicted_model_matrix(lead),
+                            distance: controls.zoom,
+                        }
+                    } else if let Some(time) = capture_time {
+                        CameraPose::Rotation {
+                            time,
+                            mode: config.rotation_mode,
+                            speed: config.rotation_speed,
+                            distance: controls.zoom,
+                            orbit: config_file.orbit.zoomed(controls.orbit_scale()),
+                        }
+                    } else {
+                        // The timeline already runs at the playback speed
+                        CameraPose::Rotation {
+                            time: timeline.time(audio_spin + lead),
+                            mode: config.rotation_mode,
+                            speed: 1.0,
+                            distance: controls.zoom,
+                            orbit: config_file.orbit.zoomed(controls.orbit_scale()),
+                        }
+                    };
+                    // Static mode never moves the model; other poses are still once they repeat
+                    let still = config_file.accumulate
+                        && capture_time.is_none()
+                        && (matches!(pose, CameraPose::Rotation { mode: RotationMode::Static, .. }) || last_pose == Some(pose));
+                    last_pose = Some(pose);
+                    if !still {
+                        still_time = None;
+                    }
+                    let pane_width = pane_cols(term_cols, split_view.is_some());
+                    let split = split_view.map(|split| {
+                        let (cols, rows, _, _) =
+                            get_pipeline_dims(pane_width, term_rows, split.render_mode, quality);
+                        let suppress = config_file.suppress_background
+                            && split.render_mode == RenderMode::PlainAscii;
+                        ((cols, rows), split.lighting_mode, suppress)
+                    });
+                    let preview = show_preview.then(|| preview_dims(pane_width, term_rows));
+                    render.submit(FrameRequest {
+                        pose,
+                        lighting: config.lighting_mode,
+                        time: capture_time.unwrap_or_else(|| if still { *still_time.get_or_insert(elapsed + lead) } else { elapsed + lead }),
+                        explode: explode_amount,
+                        show_bounds,
+                        part_visibility: config.part_visibility(),
+                        light_intensity: levels.light_intensity(),
+                        exposure: exposure * levels.exposure_scale(),
+                        gamma,
+                        tonemap: config_file.tonemap,
+                        motion_blur: config_file.motion_blur,
+                        accumulate: config_file.accumulate,
+                        edge_algorithm: config_file.edge_algorithm,
+                        depth_edge_threshold: config_file.depth_edge_threshold,
+                        object_edges: config_file.object_edges,
+                        interior_edges: config_file.interior_edges,
+                        outline_width: config_file.outline_width,
+                        ink_outlines: config_file.ink_outlines,
+                        fill_levels: config_file.fill_levels,
+                        edge_chars: !config_file.reduced_motion,
+                        ramp: config_file.ramp,
+                        depth_weight: config_file.depth_weight,
+                        // Colored modes keep the backdrop
+                        suppress_background: config_file.suppress_background
+                            && render_mode == RenderMode::PlainAscii,
+                        dims: main_pane_dims(pane_width, term_rows, main_pane_mode(render_mode, stereogram), quality, double_wide),
+                        split,
+                        preview,
+                        stereogram,
+                        double_wide: double_wide_active(main_pane_mode(render_mode, stereogram), double_wide),
+                        geometry: pending_geometry.take(),
+                        video_frame: video.as_ref().and_then(|video| video.take_frame()),
+                        show_model: !args.video_only,
+                        sdf: current_selection.0.and_then(|s| s.sdf()),
+                        wave: current_selection.0 == Some(BuiltinScene::Water),
+                        life: current_selection.0 == Some(BuiltinScene::Life),
+                        selected_part,
+                        pick: pending_pick.take(),
+                        // Labels would break up the stereogram's pattern
+                        labels: if stereogram {
+                            Vec::new()
+                        } else {
+                            hud.labels().chain(part_labels.iter().filter(|_| show_part_labels).cloned()).collect()
+                        },
+                        profile: show_profiler,
+                    })?;
+                    pacer.submitted(elapsed, lead);
+                }
 
-                pipeline.dispatch(&mut encoder);
-                pipeline.copy_to_staging(&mut encoder);
+                // Deterministic runs wait for the frame so each tick renders exactly one
+                let frame_arrived = match render.poll(args.deterministic)? {
+                    Some(mut frame) => {
+                        // Adjusted once here, so the terminal, recordings and exports all agree
+                        color_adjust.apply(&mut frame.cells);
+                        if let Some((ref mut data, _, _)) = frame.split {
+                            color_adjust.apply(data);
+                        }
+                        for &(stage, time) in &frame.stage_times {
+                            profiler.add(stage, time);
+                        }
+                        frame_count += 1;
+                        session_stats.record_frame(
+                            frame.gpu_time_ms,
+                            frame.cols,
+                            frame.rows,
+                            frame.render_size,
+                            render_mode,
+                        );
+                        if let Some(capture) = loop_capture.as_mut().filter(|capture| capture.awaiting) {
+                            capture.push(&frame.cells, frame.cols, frame.rows);
+                        }
+                        // Clicking the background clears the selection
+                        if let Some(picked) = frame.picked {
+                            selected_part = picked;
+                        }
+                        pacer.arrived(clock.elapsed(), frame);
+                        true
+                    }
+                    None => false,
+                };
+                if let Some(frame) = pacer.take_due(clock.elapsed()) {
+                    if let Some(ref mut recorder) = recorder {
+                        recorder.write_frame(clock.elapsed(), &frame.cells, frame.cols, frame.rows, main_pane_mode(render_mode, stereogram))?;
+                    }
+                    last_frame = Some(frame);
+                }
 
-                gpu.queue.submit(std::iter::once(encoder.finish()));
+                // (skipped in deterministic runs, whose screens shouldn't depend on the directory)
+                if !args.deterministic && last_asset_scan.elapsed() >= ASSET_SCAN_INTERVAL {
+                    last_asset_scan = Instant::now();
+                    let added = config.rescan_assets(Path::new(MODELS_DIR), Path::new(SKYBOXES_DIR));
+                    if !added.is_empty() {
+                        let message = format!("New in the config menu (C): {}", added.join(", "));
+                        stats_notice = Some((message, Instant::now()));
+                    }
+                }
+
+                // A failed or finished video is reported like an export result
+                if let Some(message) = video.as_ref().and_then(|video| video.take_error()) {
+                    stats_notice = Some((message, Instant::now()));
+                }
 
-                // Read results (includes GPU sync)
-                let ascii_data = pollster::block_on(pipeline.read_results(&gpu.device))?;
+                if let Some(capture) = loop_capture.take_if(|capture| capture.is_complete()) {
+                    let (frames, _) = capture.progress();
+                    let message = match capture.write(|cells, cols, rows| term.frame_to_ansi_string(cells, cols, rows, main_pane_mode(render_mode, stereogram))) {
+                        Ok(()) => format!("Loop of {} frames written to {}", frames, capture.path().display()),
+                        Err(e) => error_chain(&e),
+                    };
+                    stats_notice = Some((message, Instant::now()));
+                }
 
-                let gpu_time_ms = gpu_start.elapsed().as_secs_f32() * 1000.0;
+                if write_stats {
+                    let path = args.stats.clone().unwrap_or_else(|| session_stats.default_path());
+                    let message = match session_stats.write(&path) {
+                        Ok(()) => format!("Stats written to {}", path.display()),
+                        Err(e) => error_chain(&e),
+                    };
+                    stats_notice = Some((message, Instant::now()));
+                }
 
-                // Calculate mask region if GPU info is shown
-                let mask = if show_gpu_info {
-                    Some(term.gpu_info_mask(gpu.gpu_name()))
+                // Model statistics are appended to the info panel while the bounding box is shown
+                let mut info_lines = if show_bounds && current_selection.0.is_some_and(|s| s.sdf().is_some()) {
+                    vec!["    Model: raymarched SDF".to_string()]
+                } else if show_bounds {
+                    model_stats_lines(model_stats.as_ref())
                 } else {
-                    None
+                    Vec::new()
                 };
+                if let Some(samples) = last_frame.as_ref().map(|f| f.samples).filter(|&samples| samples > 0) {
+                    info_lines.push(format!("  Samples: {:>10}", format!("{}/{}", samples, ACCUMULATE_SAMPLES)));
+                }
+                if last_frame.as_ref().is_some_and(|f| f.clamped) {
+                    info_lines.push(format!(
+                        "     Note: scaled to {} px GPU limit",
+                        render.max_texture_dimension()
+                    ));
+                }
+                let show_info_panel = !clean && (show_gpu_info || show_bounds);
 
-                // Render to terminal using current mode
-                term.render(
-                    &ascii_data,
-                    pipeline.cols(),
-                    pipeline.rows(),
-                    render_mode,
-                    mask,
-                )?;
-
-                // Copy frame to clipboard if requested
-                if copy_to_clipboard {
-                    let ansi_string = term.frame_to_ansi_string(
-                        &ascii_data,
-                        pipeline.cols(),
-                        pipeline.rows(),
-                        render_mode,
-                    );
-                    if let Ok(mut clipboard) = Clipboard::new() {
-                        let _ = clipboard.set_text(ansi_string);
+                // Compose the screen using current mode: the frame, then the overlays over it
+                // The frame may lag a mode change by one request; its own grid is drawn as-is
+                profiler.mark();
+                let mut screen = term.screen();
+                if let Some(ref frame) = last_frame {
+                    let cells = term.to_frame(&frame.cells, frame.cols, frame.rows, main_pane_mode(render_mode, stereogram));
+                    // Composite the right pane next to a divider
+                    match (split_view, frame.split.as_ref()) {
+                        (Some(split), Some((data, cols, rows))) => {
+                            term.draw_split(&mut screen, &cells, &term.to_frame(data, *cols, *rows, split.render_mode))
+                        }
+                        _ => term.draw_content(&mut screen, &cells),
+                    }
+                    main_pane = (cells.cols(), cells.rows());
+                    term.draw_labels(&mut screen, &frame.labels, main_pane);
+
+                    if let (true, Some((data, cols, rows))) = (show_preview, frame.preview.as_ref()) {
+                        term.draw_preview(&mut screen, &term.to_frame(data, *cols, *rows, RenderMode::HalfBlock));
+                    }
+
+                    // Copy frame to clipboard if requested
+                    if copy_to_clipboard {
+                        let ansi_string =
+                            term.frame_to_ansi_string(&frame.cells, frame.cols, frame.rows, main_pane_mode(render_mode, stereogram));
+                        if let Ok(mut clipboard) = Clipboard::new() {
+                            let _ = clipboard.set_text(ansi_string);
+                        }
                     }
                 }
+                if let Some((target, path)) = pending_export {
+                    let result = match last_frame.as_ref() {
+                        Some(frame) => export::export_frame(target, path.as_deref(), frame, main_pane_mode(render_mode, stereogram), &term),
+                        None => Err(anyhow!("No frame to export yet")),
+                    };
+                    let message = result.unwrap_or_else(|e| error_chain(&e));
+                    stats_notice = Some((message, Instant::now()));
+                }
 
-                // Update FPS
-                frame_count += 1;
+                // Update FPS (frames rendered, not terminal ticks)
                 if fps_update_time.elapsed() >= Duration::from_secs(1) {
                     fps = frame_count as f32 / fps_update_time.elapsed().as_secs_f32();
                     frame_count = 0;
@@ -365,28 +1573,124 @@ fn main() -> Result<()> {
                 }
 
                 // Show mode name with manual indicator
-                let mode_display = if controls.active {
+                let mut mode_display = if controls.active {
                     format!("{} [Manual]", render_mode.name())
                 } else {
                     render_mode.name().to_string()
                 };
-                term.render_status(fps, &mode_display)?;
-                if show_gpu_info {
-                    term.render_gpu_info(
-                        gpu.gpu_name(),
-                        gpu_time_ms,
-                        gpu.render_size(),
-                        (pipeline.cols(), pipeline.rows()),
-                    )?;
+                if let Some(name) = selected_part.and_then(|part| config.part_names.get(part)) {
+                    mode_display = format!("{} [Part: {}]", mode_display, name);
+                }
+                if let Some(ref tile) = tile {
+                    let role = if tile.is_leader() { "leading" } else { "following" };
+                    mode_display = format!("{} [Tile {} {}]", mode_display, tile.view.name(), role);
+                }
+                if lighting_changed.is_some_and(|t| t.elapsed() < STATUS_FLASH_TIME) {
+                    mode_display = format!("{} [{} Lighting]", mode_display, config.lighting_mode.name());
+                }
+                if rotation_changed.is_some_and(|t| t.elapsed() < STATUS_FLASH_TIME) {
+                    mode_display = format!("{} [{} Rotation]", mode_display, config.rotation_mode.name());
+                }
+                if timeline_changed.is_some_and(|t| t.elapsed() < STATUS_FLASH_TIME) {
+                    mode_display = format!("{} [Time {:.2}s {}]", mode_display, timeline.time(0.0), config.playback.name());
+                }
+                if stereogram {
+                    mode_display = format!("{} [Stereogram]", mode_display);
+                }
+                if double_wide_active(main_pane_mode(render_mode, stereogram), double_wide) {
+                    mode_display = format!("{} [Double Wide]", mode_display);
+                }
+                if let Some(split) = split_view {
+                    mode_display = format!("{} vs {}", mode_display, split.label());
+                }
+                if config.scene == Some(BuiltinScene::Csg) {
+                    mode_display = format!("{} [{}: {}]", mode_display, csg.operation.name(), csg.label());
+                }
+                if let Some(population) = last_frame.as_ref().and_then(|f| f.life_population) {
+                    mode_display = format!("{} [Life: {} alive]", mode_display, population);
+                }
+                if config.scene == Some(BuiltinScene::SolarSystem) {
+                    mode_display = format!("{} [Trails: {}]", mode_display, if solar.trails { "on" } else { "off" });
+                }
+                if config.scene == Some(BuiltinScene::Plant) {
+                    mode_display = format!("{} [Plant: {}]", mode_display, plant.name());
+                }
+                if let Some((captured, total)) = loop_capture.as_ref().map(|capture| capture.progress()) {
+                    mode_display = format!("{} [Loop {}/{}]", mode_display, captured, total);
+                }
+                if explode_amount > 0.0 {
+                    mode_display = format!("{} [Explode {:.0}%]", mode_display, explode_amount * 100.0);
                 }
+                if let Some(levels) = audio_levels {
+                    mode_display = format!("{} [Audio {}]", mode_display, levels.meter());
+                }
+                if tone_changed.is_some_and(|t| t.elapsed() < STATUS_FLASH_TIME) {
+                    mode_display = format!("{} [Exposure {:.2} Gamma {:.2}]", mode_display, exposure, gamma);
+                }
+                if color_changed.is_some_and(|t| t.elapsed() < STATUS_FLASH_TIME) {
+                    mode_display = format!(
+                        "{} [Saturation {:.1} Brightness {:.1} Contrast {:.1}]",
+                        mode_display, color_adjust.saturation, color_adjust.brightness, color_adjust.contrast
+                    );
+                }
+                term.draw_hud(&mut screen, &hud);
+                term.draw_status(&mut screen, &selection_label(&current_selection), fps, &mode_display);
+                if show_info_panel {
+                    if let Some(ref frame) = last_frame {
+                        term.draw_gpu_info(
+                            &mut screen,
+                            render.gpu_name(),
+                            frame.gpu_time_ms,
+                            frame.render_size,
+                            (frame.cols, frame.rows),
+                            &info_lines,
+                        );
+                    }
+                }
+                term.present(&screen)?;
 
-                // Frame timing (target ~30 fps to reduce CPU usage)
-                let frame_time = last_frame.elapsed();
-                let target_frame_time = Duration::from_millis(33);
-                if frame_time < target_frame_time {
-                    std::thread::sleep(target_frame_time - frame_time);
+                if let Some(ref pending) = pending_load {
+                    term.render_notice(&pending.status())?;
+                } else if let Some((ref message, shown)) = stats_notice {
+                    if shown.elapsed() < STATS_NOTICE_TIME {
+                        term.render_notice(message)?;
+                    }
+                }
+
+                if let Some((text, opacity)) = captions.visible(elapsed) {
+                    term.render_caption(text, opacity)?;
+                }
+
+                if let Some(ref error) = shader_error {
+                    term.render_error_overlay("Shader error", error)?;
+                }
+
+                if let Some(ref dialog) = export_dialog {
+                    let (lines, selected) = dialog.lines();
+                    term.render_dialog("Export", &lines, selected)?;
+                }
+
+                if let Some(ref error) = script_error {
+                    term.render_error_overlay("Script error", error)?;
+                }
+
+                if show_profiler {
+                    let stages: Vec<(&str, f32)> = profiler
+                        .stages()
+                        .into_iter()
+                        .map(|(stage, ms)| (stage.name(), ms))
+                        .collect();
+                    term.render_profiler(&stages)?;
+                }
+                profiler.record(Stage::Emit);
+
+                // Averages advance per rendered frame so GPU stages line up with their frame
+                if frame_arrived {
+                    profiler.end_frame();
                 }
-                last_frame = Instant::now();
+
+                // Frame timing (target ~30 fps to reduce CPU usage)
+                clock.finish_frame();
             }
 
             AppMode::Config => {
@@ -397,7 +1701,8 @@ fn main() -> Result<()> {
                 ratatui_terminal.clear()?;
 
                 // Run config UI (blocks until user applies or cancels)
-                let result = run_config_ui(&mut ratatui_terminal, config.clone())?;
+                let draft = config_draft.take().unwrap_or_else(|| config.clone());
+                let result = run_config_ui(&mut ratatui_terminal, draft, config_error.take(), &config_file.theme, config_file.charset)?;
 
                 // Restore terminal state
                 drop(ratatui_terminal);
@@ -406,42 +1711,110 @@ fn main() -> Result<()> {
                 execute!(stdout(), Hide)?;
 
                 // Clear and redraw
-                term.check_resize()?;
-
-                if let Some(new_config) = result {
-                    // Check if model changed
-                    if new_config.model_path != current_model_path {
-                        if let Some(ref model_path) = new_config.model_path {
-                            if let Err(e) = load_model_into_gpu(&mut gpu, model_path) {
-                                eprintln!("Failed to load model: {}", e);
-                            } else {
-                                current_model_path = new_config.model_path.clone();
-                            }
-                        }
-                    }
+                if term.check_resize()? {
+                    session_stats.record_resize(term.content_size());
+                }
+
+                if let Some(mut new_config) = result {
+                    // Model, scene, and text changes are picked up by the background loader
 
                     // Check if skybox changed
                     if new_config.skybox_path != config.skybox_path {
-                        match &new_config.skybox_path {
+                        match new_config.skybox_path.clone() {
                             Some(skybox_path) => {
-                                if let Err(e) = gpu.set_skybox(skybox_path) {
-                                    eprintln!("Failed to load skybox: {}", e);
+                                let name = get_skybox_display_name(&skybox_path);
+                                let result = SkyboxSidecar::load(&skybox_path).and_then(|sidecar| {
+                                    render.call(move |r| {
+                                        r.gpu.set_skybox(&skybox_path, sidecar.projection, sidecar.rotation / 360.0)
+                                    })
+                                });
+                                if let Err(e) = result {
+                                    let e = e.context(format!("Failed to load skybox {}", name));
+                                    // Reopen the config UI with the user's choices so another file can be picked
+                                    config_error = Some(error_chain(&e));
+                                    config_draft = Some(new_config.clone());
+                                    // The previous skybox stays on screen
+                                    new_config.skybox_path = config.skybox_path.clone();
                                 }
                             }
                             None => {
-                                gpu.clear_skybox();
+                                render.call(|r| {
+                                    r.gpu.clear_skybox();
+                                    Ok(())
+                                })?;
                             }
                         }
                     }
 
+                    // Check if the ASCII style changed
+                    if new_config.style_path != config.style_path {
+                        let result = new_config
+                            .style_path
+                            .as_deref()
+                            .map(load_style)
+                            .transpose()
+                            .and_then(|style| {
+                                let styled = ascii_shaders.clone().with_style(style.as_deref());
+                                render
+                                    .call(move |r| r.reload_shaders(styled))
+                                    .context("Style shader failed to compile")?;
+                                style_shader = style;
+                                Ok(())
+                            });
+                        if let Err(e) = result {
+                            config_error = Some(error_chain(&e));
+                            config_draft = Some(new_config.clone());
+                            // The previous style stays active
+                            new_config.style_path = config.style_path.clone();
+                        }
+                    }
+
+                    let (background, top, bottom) = (
+                        new_config.background,
+                        new_config.gradient_top,
+                        new_config.gradient_bottom,
+                    );
+                    let (skybox_intensity, skybox_blur) = (new_config.skybox_intensity, new_config.skybox_blur);
+                    let instances =
+                        instance_transforms(new_config.instance_pattern, new_config.instance_count);
+                    render.call(move |r| {
+                        r.gpu.set_background(background);
+                        r.gpu.set_gradient(top, bottom);
+                        r.gpu.set_skybox_look(skybox_intensity, skybox_blur);
+                        r.gpu.set_instances(&instances);
+                        Ok(())
+                    })?;
+
+                    // Drop the model in fresh when switching into the physics demo
+                    if new_config.rotation_mode == RotationMode::Physics
+                        && config.rotation_mode != RotationMode::Physics
+                    {
+                        physics.reset();
+                    }
+
                     config = new_config;
                 }
 
-                // Return to rendering mode
-                app_mode = AppMode::Rendering;
+                // Return to rendering mode unless an error needs attention
+                app_mode = if config_error.is_some() {
+                    AppMode::Config
+                } else {
+                    AppMode::Rendering
+                };
             }
         }
     }
 
+    // Written once the terminal is restored so the result can be reported
+    drop(term);
+    if let (Some(recorder), Some(path)) = (recorder, args.record.as_ref()) {
+        recorder.finish()?;
+        eprintln!("Recording written to {}", path.display());
+    }
+    if let Some(ref path) = args.stats {
+        session_stats.write(path)?;
+        eprintln!("Session stats written to {}", path.display());
+    }
+
     Ok(())
 }
@@ -0,0 +1,212 @@
+use anyhow::{anyhow, Result};
+use ratatui::style::Color;
+use std::str::FromStr;
+
+use crate::terminal::ColorVision;
+
+/// Colors for the UI chrome: status bar, GPU info panel, notices, and the config menu
+/// `Color::Reset` means the terminal's default color
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// Config menu border
+    pub border: Color,
+    /// Label and list border of the focused config section
+    pub focus: Color,
+    /// Labels of unfocused sections and error text
+    pub label: Color,
+    /// Value of the focused section (text, selectors, slider)
+    pub value: Color,
+    /// Values of unfocused sections
+    pub inactive: Color,
+    /// Unfocused borders and hints
+    pub dim: Color,
+    /// Selected rotation mode
+    pub selection: Color,
+    /// Selected lighting mode
+    pub selection_alt: Color,
+    /// Apply button
+    pub ok: Color,
+    /// Cancel button and error borders
+    pub error: Color,
+    /// Error popup background
+    pub popup_bg: Color,
+    pub status_fg: Color,
+    pub status_bg: Color,
+    /// GPU info panel and notices
+    pub panel_fg: Color,
+    pub panel_bg: Color,
+}
+
+/// Names of the built-in themes, in the order they are documented
+pub const THEME_NAMES: &[&str] = &["classic", "nord", "amber", "mono"];
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl Theme {
+    /// The original look: ANSI colors, terminal-default status bar and panel
+    fn classic() -> Self {
+        Self {
+            border: Color::Cyan,
+            focus: Color::Yellow,
+            label: Color::White,
+            value: Color::Cyan,
+            inactive: Color::Gray,
+            dim: Color::DarkGray,
+            selection: Color::Green,
+            selection_alt: Color::Magenta,
+            ok: Color::Green,
+            error: Color::Red,
+            popup_bg: Color::Black,
+            status_fg: Color::Reset,
+            status_bg: Color::Reset,
+            panel_fg: Color::Reset,
+            panel_bg: Color::Reset,
+        }
+    }
+
+    /// Built-in theme by name (case-insensitive)
+    pub fn builtin(name: &str) -> Option<Self> {
+        let rgb = |r, g, b| Color::Rgb(r, g, b);
+        let theme = match name.to_ascii_lowercase().as_str() {
+            "classic" => Self::classic(),
+            "nord" => Self {
+                border: rgb(0x88, 0xc0, 0xd0),
+                focus: rgb(0xeb, 0xcb, 0x8b),
+                label: rgb(0xe5, 0xe9, 0xf0),
+                value: rgb(0x8f, 0xbc, 0xbb),
+                inactive: rgb(0xd8, 0xde, 0xe9),
+                dim: rgb(0x4c, 0x56, 0x6a),
+                selection: rgb(0xa3, 0xbe, 0x8c),
+                selection_alt: rgb(0xb4, 0x8e, 0xad),
+                ok: rgb(0xa3, 0xbe, 0x8c),
+                error: rgb(0xbf, 0x61, 0x6a),
+                popup_bg: rgb(0x2e, 0x34, 0x40),
+                status_fg: rgb(0xec, 0xef, 0xf4),
+                status_bg: rgb(0x3b, 0x42, 0x52),
+                panel_fg: rgb(0xd8, 0xde, 0xe9),
+                panel_bg: rgb(0x2e, 0x34, 0x40),
+            },
+            "amber" => {
+                let bright = rgb(0xff, 0xb0, 0x00);
+                let mid = rgb(0xcc, 0x88, 0x00);
+                let dark = rgb(0x66, 0x44, 0x00);
+                Self {
+                    border: mid,
+                    focus: bright,
+                    label: mid,
+                    value: bright,
+                    inactive: mid,
+                    dim: dark,
+                    selection: bright,
+                    selection_alt: bright,
+                    ok: bright,
+                    error: rgb(0xff, 0x55, 0x00),
+                    popup_bg: Color::Black,
+                    status_fg: Color::Black,
+                    status_bg: mid,
+                    panel_fg: bright,
+                    panel_bg: Color::Black,
+                }
+            }
+            "mono" => Self {
+                border: Color::White,
+                focus: Color::White,
+                label: Color::Gray,
+                value: Color::White,
+                inactive: Color::Gray,
+                dim: Color::DarkGray,
+                selection: Color::White,
+                selection_alt: Color::White,
+                ok: Color::White,
+                error: Color::White,
+                popup_bg: Color::Black,
+                status_fg: Color::Black,
+                status_bg: Color::Gray,
+                panel_fg: Color::White,
+                panel_bg: Color::Black,
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Swap the colors that tell states apart (focus, selections, Apply/Cancel) for ones from the
+    /// Okabe-Ito palette a color deficiency can still separate: sky blue against orange, with
+    /// yellow or vermillion, and no pure red for protanopia, which sees it as near-black
+    pub fn for_color_vision(self, vision: ColorVision) -> Self {
+        let rgb = |r, g, b| Color::Rgb(r, g, b);
+        let sky_blue = rgb(0x56, 0xb4, 0xe9);
+        let orange = rgb(0xe6, 0x9f, 0x00);
+        let yellow = rgb(0xf0, 0xe4, 0x42);
+        let (alarm, accent) = match vision {
+            ColorVision::Normal => return self,
+            ColorVision::Deuteranopia => (rgb(0xd5, 0x5e, 0x00), orange),
+            ColorVision::Protanopia => (orange, yellow),
+        };
+        Self {
+            focus: yellow,
+            value: sky_blue,
+            selection: sky_blue,
+            selection_alt: accent,
+            ok: sky_blue,
+            error: alarm,
+            ..self
+        }
+    }
+
+    /// Build a theme from a config file `[theme]` table:
+    /// `base` picks a built-in theme, other keys override single colors
+    /// Colors are names (`"cyan"`, `"lightblue"`, `"reset"`), `"#rrggbb"`, or 256-color indices
+    pub fn from_table(table: &toml::Table) -> Result<Self> {
+        let mut theme = match table.get("base") {
+            Some(base) => {
+                let name = base.as_str().ok_or_else(|| anyhow!("theme.base must be a string"))?;
+                Self::builtin(name).ok_or_else(|| {
+                    anyhow!("Unknown theme {:?} (built-in themes: {})", name, THEME_NAMES.join(", "))
+                })?
+            }
+            None => Self::default(),
+        };
+
+        for (key, value) in table {
+            if key == "base" {
+                continue;
+            }
+            let slot = theme
+                .color_mut(key)
+                .ok_or_else(|| anyhow!("Unknown theme color {:?}", key))?;
+            let text = value
+                .as_str()
+                .ok_or_else(|| anyhow!("theme.{} must be a string", key))?;
+            *slot = Color::from_str(text)
+                .map_err(|_| anyhow!("Invalid color {:?} for theme.{}", text, key))?;
+        }
+        Ok(theme)
+    }
+
+    /// The color slot for a config file key
+    fn color_mut(&mut self, key: &str) -> Option<&mut Color> {
+        Some(match key {
+            "border" => &mut self.border,
+            "focus" => &mut self.focus,
+            "label" => &mut self.label,
+            "value" => &mut self.value,
+            "inactive" => &mut self.inactive,
+            "dim" => &mut self.dim,
+            "selection" => &mut self.selection,
+            "selection_alt" => &mut self.selection_alt,
+            "ok" => &mut self.ok,
+            "error" => &mut self.error,
+            "popup_bg" => &mut self.popup_bg,
+            "status_fg" => &mut self.status_fg,
+            "status_bg" => &mut self.status_bg,
+            "panel_fg" => &mut self.panel_fg,
+            "panel_bg" => &mut self.panel_bg,
+            _ => return None,
+        })
+    }
+}
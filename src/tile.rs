@@ -0,0 +1,322 @@
+//! Multi-terminal tiling (`--tile VIEW`): instances in separate terminals or tmux panes link up
+//! over a loopback socket and show the same scene from different fixed cameras. The first one
+//! started leads: each frame it sends its animation clock, rotation mode, and selection to the
+//! others, which follow it instead of their own clocks. A follower opens with `HANDSHAKE` and
+//! the leader answers with it, so a different program on the port (or a different protocol
+//! version) is caught before any state is exchanged
+
+use anyhow::{anyhow, Context, Result};
+use glam::Mat4;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use crate::config::ConfigState;
+use crate::gpu::RotationMode;
+use crate::timeline::{Playback, Timeline};
+
+/// Loopback port the leading instance listens on
+const TILE_PORT: u16 = 47310;
+
+/// First line each side sends: the protocol and its version (bumped when messages change)
+const HANDSHAKE: &str = "ascii-3d-tile 1";
+
+/// How long either side waits for the other's handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often a follower whose leader went away tries to open the link again
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Fixed camera of a tiled instance
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TileView {
+    Front,
+    Top,
+    Side,
+    Perspective,
+}
+
+impl TileView {
+    /// Parse a `--tile` value: "front", "top", "side", or "perspective"
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "front" => Ok(TileView::Front),
+            "top" => Ok(TileView::Top),
+            "side" => Ok(TileView::Side),
+            "perspective" => Ok(TileView::Perspective),
+            _ => Err(anyhow!(
+                "Unknown tile view {:?} (expected \"front\", \"top\", \"side\", or \"perspective\")",
+                name
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TileView::Front => "Front",
+            TileView::Top => "Top",
+            TileView::Side => "Side",
+            TileView::Perspective => "Perspective",
+        }
+    }
+
+    /// Rotation applied to the world before the camera sees it, so the fixed camera on +Z looks
+    /// at the scene from this view's side (front leaves it as it is)
+    pub fn turn(&self) -> Mat4 {
+        match self {
+            TileView::Front => Mat4::IDENTITY,
+            // +Y (the top) turned toward the camera
+            TileView::Top => Mat4::from_rotation_x(90f32.to_radians()),
+            // +X (the right side) turned toward the camera
+            TileView::Side => Mat4::from_rotation_y(-90f32.to_radians()),
+            // From above the front-right corner, like an isometric drawing
+            TileView::Perspective => Mat4::from_rotation_x(35.26f32.to_radians()) * Mat4::from_rotation_y(-45f32.to_radians()),
+        }
+    }
+}
+
+enum Role {
+    /// Owns the clock and sends it to every follower (and to connections once their handshake
+    /// arrives)
+    Leader { listener: TcpListener, followers: Vec<TcpStream>, joining: Vec<Handshake> },
+    /// Waiting for the leader to answer this instance's handshake
+    Joining(Handshake),
+    /// Reads the leader's clock (with any partial line received so far)
+    Follower { reader: BufReader<TcpStream>, line: String },
+    /// The leader went away; the link is opened again once `retry` passes
+    Lost { retry: Instant },
+}
+
+/// The other side's handshake line, read without blocking across frames
+struct Handshake {
+    reader: BufReader<TcpStream>,
+    line: String,
+    started: Instant,
+}
+
+enum HandshakeState {
+    Pending,
+    Done,
+    /// What came instead of the handshake (empty if nothing did)
+    Failed(String),
+}
+
+impl Handshake {
+    fn new(stream: TcpStream) -> std::io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        Ok(Self { reader: BufReader::new(stream), line: String::new(), started: Instant::now() })
+    }
+
+    /// Read whatever has arrived; the reader keeps anything sent right behind the handshake
+    fn poll(&mut self) -> HandshakeState {
+        loop {
+            if self.line.ends_with('\n') {
+                return match self.line.trim_end() {
+                    HANDSHAKE => HandshakeState::Done,
+                    other => HandshakeState::Failed(other.to_string()),
+                };
+            }
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return HandshakeState::Failed(std::mem::take(&mut self.line)),
+                Ok(_) => {}
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    return if self.started.elapsed() < HANDSHAKE_TIMEOUT {
+                        HandshakeState::Pending
+                    } else {
+                        HandshakeState::Failed(std::mem::take(&mut self.line))
+                    };
+                }
+                Err(_) => return HandshakeState::Failed(std::mem::take(&mut self.line)),
+            }
+        }
+    }
+}
+
+/// This instance's link to the other tiled instances
+pub struct TileLink {
+    pub view: TileView,
+    role: Role,
+    /// Selection last received from the leader (a follower's own changes stand until the next one)
+    followed_selection: String,
+}
+
+impl TileLink {
+    /// Lead if no other instance is listening yet, otherwise follow the one that is (waiting
+    /// for its handshake, so something else on the port is reported here)
+    pub fn open(view: TileView) -> Result<Self> {
+        let mut role = connect()?;
+        if let Role::Joining(handshake) = &mut role {
+            loop {
+                match handshake.poll() {
+                    HandshakeState::Pending => std::thread::sleep(Duration::from_millis(10)),
+                    HandshakeState::Done => break,
+                    HandshakeState::Failed(reply) => {
+                        return Err(anyhow!(
+                            "Port {} is in use by something other than a tiled ascii-3d instance of this version (expected {:?} in reply, got {:?})",
+                            TILE_PORT,
+                            HANDSHAKE,
+                            reply.trim_end()
+                        ));
+                    }
+                }
+            }
+        }
+        let mut link = Self {
+            view,
+            role,
+            followed_selection: String::new(),
+        };
+        link.finish_joining();
+        Ok(link)
+    }
+
+    pub fn is_leader(&self) -> bool {
+        matches!(self.role, Role::Leader { .. })
+    }
+
+    /// Exchange this frame's state: a leader sends its clock, rotation mode, and selection to
+    /// new and existing followers; a follower takes on the latest ones it received
+    pub fn sync(&mut self, timeline: &mut Timeline, config: &mut ConfigState) {
+        // One of the followers of a leader that quit takes over, the others follow it
+        // Nothing here waits on the socket: handshakes are picked up over the following frames
+        if let Role::Lost { retry } = self.role {
+            if Instant::now() >= retry {
+                self.role = connect().unwrap_or_else(|_| lost());
+            }
+        }
+        self.finish_joining();
+        match &mut self.role {
+            Role::Leader { listener, followers, joining } => {
+                while let Ok((stream, _)) = listener.accept() {
+                    // Small frequent messages are sent right away, and a follower that stops
+                    // reading is dropped rather than stalling this instance
+                    if let Ok(handshake) = stream.set_nodelay(true).and_then(|()| Handshake::new(stream)) {
+                        joining.push(handshake);
+                    }
+                }
+                // Answer the connections that sent the handshake; anything else is turned away
+                let mut index = 0;
+                while index < joining.len() {
+                    match joining[index].poll() {
+                        HandshakeState::Pending => index += 1,
+                        HandshakeState::Done => {
+                            let stream = joining.swap_remove(index).reader.into_inner();
+                            if (&stream).write_all(format!("{}\n", HANDSHAKE).as_bytes()).is_ok() {
+                                followers.push(stream);
+                            }
+                        }
+                        HandshakeState::Failed(_) => {
+                            joining.swap_remove(index);
+                        }
+                    }
+                }
+                let message = format!(
+                    "{} {} {} {} {}\n",
+                    timeline.position(),
+                    config.rotation_speed,
+                    Playback::all().iter().position(|&p| p == config.playback).unwrap_or(0),
+                    RotationMode::all().iter().position(|&m| m == config.rotation_mode).unwrap_or(0),
+                    selection_name(config),
+                );
+                // A follower that went away is dropped
+                followers.retain_mut(|stream| stream.write_all(message.as_bytes()).is_ok());
+            }
+            Role::Follower { reader, line } => {
+                let mut latest = None;
+                loop {
+                    match reader.read_line(line) {
+                        Ok(0) => {
+                            self.role = lost();
+                            break;
+                        }
+                        Ok(_) if line.ends_with('\n') => latest = Some(std::mem::take(line)),
+                        Ok(_) => {}
+                        Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                        Err(_) => {
+                            self.role = lost();
+                            break;
+                        }
+                    }
+                }
+                if let Some(message) = latest {
+                    self.follow(message.trim_end(), timeline, config);
+                }
+            }
+            Role::Joining(_) | Role::Lost { .. } => {}
+        }
+    }
+
+    /// Follow the leader once it has answered the handshake, or give up on it
+    fn finish_joining(&mut self) {
+        let Role::Joining(handshake) = &mut self.role else {
+            return;
+        };
+        match handshake.poll() {
+            HandshakeState::Pending => {}
+            HandshakeState::Done => {
+                let Role::Joining(handshake) = std::mem::replace(&mut self.role, lost()) else {
+                    unreachable!();
+                };
+                self.role = Role::Follower { reader: handshake.reader, line: String::new() };
+            }
+            HandshakeState::Failed(_) => self.role = lost(),
+        }
+    }
+
+    /// Apply one message from the leader (malformed ones are ignored)
+    fn follow(&mut self, message: &str, timeline: &mut Timeline, config: &mut ConfigState) {
+        let mut fields = message.splitn(5, ' ');
+        let (Some(position), Some(speed), Some(playback), Some(mode), Some(selection)) =
+            (fields.next(), fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return;
+        };
+        let (Ok(position), Ok(speed), Ok(playback), Ok(mode)) =
+            (position.parse(), speed.parse(), playback.parse::<usize>(), mode.parse::<usize>())
+        else {
+            return;
+        };
+        timeline.set_position(position);
+        config.rotation_speed = speed;
+        config.playback = Playback::all().get(playback).copied().unwrap_or_default();
+        config.rotation_mode = RotationMode::all().get(mode).copied().unwrap_or_default();
+        if selection != self.followed_selection {
+            config.select_by_name(selection);
+            self.followed_selection = selection.to_string();
+        }
+    }
+}
+
+/// Name the leader sends for the current selection, which `select_by_name` finds again
+fn selection_name(config: &ConfigState) -> String {
+    match (config.scene, &config.model_path) {
+        (Some(scene), _) => scene.name().to_string(),
+        (None, Some(path)) => path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+        (None, None) => String::new(),
+    }
+}
+
+/// A link that went away, opened again after `RECONNECT_INTERVAL`
+fn lost() -> Role {
+    Role::Lost { retry: Instant::now() + RECONNECT_INTERVAL }
+}
+
+/// Listen on the tile port, or connect to the instance already listening on it and send the
+/// handshake (its answer is read later, without blocking)
+fn connect() -> Result<Role> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, TILE_PORT));
+    match TcpListener::bind(address) {
+        Ok(listener) => {
+            listener.set_nonblocking(true).context("Failed to set up the tile socket")?;
+            Ok(Role::Leader { listener, followers: Vec::new(), joining: Vec::new() })
+        }
+        Err(e) if e.kind() == ErrorKind::AddrInUse => {
+            let mut stream = TcpStream::connect(address)
+                .with_context(|| format!("Failed to join the tiled instances on port {}", TILE_PORT))?;
+            stream.write_all(format!("{}\n", HANDSHAKE).as_bytes())
+                .with_context(|| format!("Failed to join the tiled instances on port {}", TILE_PORT))?;
+            Ok(Role::Joining(Handshake::new(stream).context("Failed to set up the tile socket")?))
+        }
+        Err(e) => Err(e).with_context(|| format!("Failed to open the tile socket on port {}", TILE_PORT)),
+    }
+}
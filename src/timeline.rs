@@ -0,0 +1,106 @@
+//! Animation timeline: the clock auto rotation and the exploded-view animation run on, advanced
+//! from the wall clock at the playback speed (forward, backward, or back and forth) and scrubbed
+//! from the keyboard
+
+/// Animation time one swing of ping-pong playback covers before turning back
+/// (half a turn of a single-axis rotation at 1x)
+const PING_PONG_SPAN: f32 = std::f32::consts::PI;
+
+/// Animation time one scrub key press moves the timeline
+pub const SCRUB_STEP: f32 = 0.25;
+
+/// Direction the timeline plays in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Playback {
+    #[default]
+    Forward,
+    Reverse,
+    /// Forward, then backward over the same stretch, and so on
+    PingPong,
+}
+
+impl Playback {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Playback::Forward => "Forward",
+            Playback::Reverse => "Reverse",
+            Playback::PingPong => "Ping-pong",
+        }
+    }
+
+    pub fn all() -> &'static [Playback] {
+        &[Playback::Forward, Playback::Reverse, Playback::PingPong]
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Playback::Forward => Playback::Reverse,
+            Playback::Reverse => Playback::PingPong,
+            Playback::PingPong => Playback::Forward,
+        }
+    }
+}
+
+/// Animation clock advanced by the wall clock
+pub struct Timeline {
+    /// Animation time played so far (in seconds at 1x; ping-pong folds it into its swing)
+    position: f32,
+    /// Wall-clock time of the last advance
+    last_elapsed: Option<f32>,
+    speed: f32,
+    playback: Playback,
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self {
+            position: 0.0,
+            last_elapsed: None,
+            speed: 1.0,
+            playback: Playback::default(),
+        }
+    }
+
+    /// Play from the last advance up to wall-clock time `elapsed`
+    pub fn advance(&mut self, elapsed: f32, speed: f32, playback: Playback) {
+        let dt = self.last_elapsed.map_or(0.0, |last| (elapsed - last).max(0.0));
+        self.last_elapsed = Some(elapsed);
+        self.speed = speed;
+        self.playback = playback;
+        self.position += dt * self.velocity();
+    }
+
+    /// Move the timeline by `delta` seconds of animation time
+    pub fn scrub(&mut self, delta: f32) {
+        self.position += delta;
+    }
+
+    /// Animation time played so far, before ping-pong folds it (for tiled instances to follow)
+    pub fn position(&self) -> f32 {
+        self.position
+    }
+
+    /// Jump to another instance's position; advancing carries on from it
+    pub fn set_position(&mut self, position: f32) {
+        self.position = position;
+    }
+
+    /// Animation time advanced per wall-clock second
+    fn velocity(&self) -> f32 {
+        match self.playback {
+            Playback::Reverse => -self.speed,
+            Playback::Forward | Playback::PingPong => self.speed,
+        }
+    }
+
+    /// Animation time `lead` wall-clock seconds from now (frames are shown a little after they
+    /// are requested)
+    pub fn time(&self, lead: f32) -> f32 {
+        let time = self.position + lead * self.velocity();
+        match self.playback {
+            // Triangle wave between 0 and the span
+            Playback::PingPong => PING_PONG_SPAN - (time.rem_euclid(2.0 * PING_PONG_SPAN) - PING_PONG_SPAN).abs(),
+            Playback::Forward | Playback::Reverse => time,
+        }
+    }
+}
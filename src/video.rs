@@ -0,0 +1,124 @@
+use anyhow::{anyhow, Context, Result};
+use image::RgbaImage;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+/// Size frames are scaled (and letterboxed) to by ffmpeg, so every frame has a known byte length
+pub const VIDEO_WIDTH: u32 = 640;
+pub const VIDEO_HEIGHT: u32 = 360;
+
+/// Newest decoded frame, and why decoding stopped once it has
+#[derive(Default)]
+struct Shared {
+    frame: Option<RgbaImage>,
+    error: Option<String>,
+}
+
+/// Live video decoded by an `ffmpeg` child process: a webcam, a video file, or a stream URL
+/// Frames are read on a background thread; only the newest one is kept
+pub struct VideoSource {
+    name: String,
+    child: Child,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl VideoSource {
+    /// Start decoding `source`: `webcam` (the first camera), `webcam:N` / `webcam:NAME`, or a
+    /// file path or URL (files loop and play at their own frame rate)
+    pub fn open(source: &str) -> Result<Self> {
+        let mut command = Command::new("ffmpeg");
+        command.args(["-hide_banner", "-loglevel", "error", "-nostdin"]);
+        command.args(input_args(source)?);
+        let filter = format!(
+            "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2",
+            w = VIDEO_WIDTH,
+            h = VIDEO_HEIGHT
+        );
+        command.args(["-an", "-vf", &filter, "-pix_fmt", "rgba", "-f", "rawvideo", "-"]);
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to start ffmpeg (--video needs ffmpeg on the PATH)")?;
+
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let reader_shared = Arc::clone(&shared);
+        std::thread::Builder::new()
+            .name("video".to_string())
+            .spawn(move || {
+                let mut buffer = vec![0u8; (VIDEO_WIDTH * VIDEO_HEIGHT * 4) as usize];
+                while stdout.read_exact(&mut buffer).is_ok() {
+                    let frame = RgbaImage::from_raw(VIDEO_WIDTH, VIDEO_HEIGHT, buffer.clone());
+                    reader_shared.lock().unwrap().frame = frame;
+                }
+                // ffmpeg has exited; its last error line says why
+                let mut log = String::new();
+                let _ = stderr.read_to_string(&mut log);
+                let reason = log.lines().last().unwrap_or("end of stream").trim().to_string();
+                reader_shared.lock().unwrap().error = Some(format!("Video stopped: {}", reason));
+            })?;
+
+        Ok(Self {
+            name: source.to_string(),
+            child,
+            shared,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The newest frame, if one arrived since the last call
+    pub fn take_frame(&self) -> Option<RgbaImage> {
+        self.shared.lock().unwrap().frame.take()
+    }
+
+    /// Why decoding stopped, reported once
+    pub fn take_error(&self) -> Option<String> {
+        self.shared.lock().unwrap().error.take()
+    }
+}
+
+impl Drop for VideoSource {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// ffmpeg input arguments for a source name
+fn input_args(source: &str) -> Result<Vec<String>> {
+    let camera = match source.strip_prefix("webcam") {
+        Some("") => None,
+        Some(rest) if rest.starts_with(':') => rest.strip_prefix(':'),
+        _ => return Ok(file_args(source)),
+    };
+    let (format, input) = if cfg!(target_os = "linux") {
+        let device = match camera {
+            Some(n) if n.parse::<u32>().is_ok() => format!("/dev/video{}", n),
+            Some(path) => path.to_string(),
+            None => "/dev/video0".to_string(),
+        };
+        ("v4l2", device)
+    } else if cfg!(target_os = "macos") {
+        ("avfoundation", camera.unwrap_or("0").to_string())
+    } else if cfg!(windows) {
+        let name = camera.ok_or_else(|| {
+            anyhow!("On Windows, name the camera: --video \"webcam:Integrated Camera\" (list them with ffmpeg -list_devices true -f dshow -i dummy)")
+        })?;
+        ("dshow", format!("video={}", name))
+    } else {
+        return Err(anyhow!("Webcam capture is not supported on this platform"));
+    };
+    Ok(vec!["-f".to_string(), format.to_string(), "-i".to_string(), input])
+}
+
+/// Play a file (or URL) in real time, looping
+fn file_args(path: &str) -> Vec<String> {
+    ["-re", "-stream_loop", "-1", "-i", path].into_iter().map(String::from).collect()
+}